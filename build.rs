@@ -0,0 +1,116 @@
+//! Build script — capture build metadata for `envvault version`.
+//!
+//! Exposes these compile-time environment variables to the crate:
+//! - `ENVVAULT_GIT_COMMIT`  — short commit hash, `-dirty` suffix if the tree
+//!   had uncommitted changes, or `unknown` outside a git checkout (e.g. when
+//!   building from a crates.io tarball)
+//! - `ENVVAULT_BUILD_DATE`  — UTC build date (`YYYY-MM-DD`); honours
+//!   `SOURCE_DATE_EPOCH` for reproducible builds
+//! - `ENVVAULT_TARGET`      — target triple
+//! - `ENVVAULT_FEATURES`    — comma-separated enabled cargo features, or `none`
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn main() {
+    println!("cargo:rustc-env=ENVVAULT_GIT_COMMIT={}", git_commit());
+    println!("cargo:rustc-env=ENVVAULT_BUILD_DATE={}", build_date());
+    println!(
+        "cargo:rustc-env=ENVVAULT_TARGET={}",
+        env::var("TARGET").unwrap_or_else(|_| "unknown".into())
+    );
+    println!("cargo:rustc-env=ENVVAULT_FEATURES={}", features());
+
+    // Only re-run when the checked-out commit or the index changes.
+    // Watching a missing path would force a re-run on every build.
+    for path in [".git/HEAD", ".git/index"] {
+        if Path::new(path).exists() {
+            println!("cargo:rerun-if-changed={path}");
+        }
+    }
+    if let Some(head_ref) = head_ref() {
+        println!("cargo:rerun-if-changed=.git/{head_ref}");
+    }
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+}
+
+/// Short commit hash with a `-dirty` suffix, or `unknown`.
+fn git_commit() -> String {
+    let Some(hash) = git(&["rev-parse", "--short", "HEAD"]) else {
+        return "unknown".into();
+    };
+
+    let dirty = git(&["status", "--porcelain", "--untracked-files=no"])
+        .is_some_and(|status| !status.is_empty());
+
+    if dirty {
+        format!("{hash}-dirty")
+    } else {
+        hash
+    }
+}
+
+/// The ref HEAD points at (e.g. `refs/heads/main`), if any.
+fn head_ref() -> Option<String> {
+    let head = std::fs::read_to_string(".git/HEAD").ok()?;
+    let head_ref = head.strip_prefix("ref: ")?.trim().to_string();
+    Path::new(".git")
+        .join(&head_ref)
+        .exists()
+        .then_some(head_ref)
+}
+
+/// Run git and return trimmed stdout, or `None` on any failure.
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8(output.stdout).ok()?.trim().to_string())
+}
+
+/// UTC build date as `YYYY-MM-DD`.
+fn build_date() -> String {
+    let secs = env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        });
+
+    // Days since 1970-01-01 to a civil date (Howard Hinnant's algorithm).
+    let z = (secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Enabled cargo features, sorted and comma-separated.
+fn features() -> String {
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(key, _)| {
+            key.strip_prefix("CARGO_FEATURE_")
+                .map(|name| name.to_lowercase().replace('_', "-"))
+        })
+        .filter(|name| name != "default")
+        .collect();
+    features.sort();
+
+    if features.is_empty() {
+        "none".into()
+    } else {
+        features.join(",")
+    }
+}
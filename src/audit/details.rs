@@ -0,0 +1,217 @@
+//! Structured `details` payloads for audit entries.
+//!
+//! Writers store a small JSON object in the `details` column so tooling can
+//! pull out fields like `count` or `source` without parsing prose.  Rows
+//! written before this change hold plain text; the readers below accept
+//! both.
+//!
+//! Field names live in the constructors here so commands cannot drift.
+
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+/// JSON object stored in an audit entry's `details` column.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditDetails(Map<String, Value>);
+
+impl AuditDetails {
+    /// `set`: whether the secret was added or updated.
+    pub fn set(existed: bool) -> Self {
+        Self::default().with("action", if existed { "updated" } else { "added" })
+    }
+
+    /// `import`: number of secrets written and the file they came from.
+    pub fn import(count: usize, source: &str) -> Self {
+        Self::default().with("count", count).with("source", source)
+    }
+
+    /// `set-many`: how many secrets were added and how many updated.
+    pub fn set_many(added: usize, updated: usize) -> Self {
+        Self::default()
+            .with("added", added)
+            .with("updated", updated)
+    }
+
+    /// `export`: number of secrets, output format, and the backup vault
+    /// used as the baseline for `--changed-since`, if any.
+    pub fn export(count: usize, format: &str, changed_since: Option<&str>) -> Self {
+        let details = Self::default().with("count", count).with("format", format);
+        match changed_since {
+            Some(backup) => details.with("changed_since", backup),
+            None => details,
+        }
+    }
+
+    /// `export --all-envs`: environments and secrets written, the output
+    /// format, and the destination directory.
+    pub fn export_all(environments: usize, count: usize, format: &str, output_dir: &str) -> Self {
+        Self::default()
+            .with("environments", environments)
+            .with("count", count)
+            .with("format", format)
+            .with("output_dir", output_dir)
+    }
+
+    /// `rotate-secret`: where the new value came from and whether a
+    /// pre-update hook ran.
+    pub fn rotate_secret(source: &str, hook: bool) -> Self {
+        Self::default()
+            .with("action", "rotated")
+            .with("source", source)
+            .with("hook", hook)
+    }
+
+    /// `rename`: the old and new secret names, and whether `--force`
+    /// replaced an existing secret called `to`.
+    pub fn rename(from: &str, to: &str, replaced: bool) -> Self {
+        let details = Self::default().with("from", from).with("to", to);
+        if replaced {
+            details.with("replaced", true)
+        } else {
+            details
+        }
+    }
+
+    /// `rotate-key`: number of secrets re-encrypted.
+    pub fn rotate(count: usize) -> Self {
+        Self::default().with("count", count)
+    }
+
+    /// `edit`: how many secrets were added, removed, and changed.
+    pub fn edit(added: usize, removed: usize, changed: usize) -> Self {
+        Self::default()
+            .with("added", added)
+            .with("removed", removed)
+            .with("changed", changed)
+    }
+
+    /// `env-clone`: number of secrets copied between two environments.
+    pub fn env_clone(count: usize, from: &str, to: &str) -> Self {
+        Self::default()
+            .with("count", count)
+            .with("from", from)
+            .with("to", to)
+    }
+
+    /// Record the `--merge-strategy` an `import` used.
+    pub fn strategy(self, strategy: &str) -> Self {
+        self.with("strategy", strategy)
+    }
+
+    /// Mark the entry as one stage of a cross-vault write: `plan` is
+    /// logged before anything is written, then `applied` or `aborted`.
+    pub fn stage(self, stage: &str) -> Self {
+        self.with("stage", stage)
+    }
+
+    /// `init-failed`: the phase that failed after the vault was created,
+    /// and whether the half-initialized vault was removed.
+    pub fn init_failed(phase: &str, removed: bool) -> Self {
+        Self::default()
+            .with("phase", phase)
+            .with("removed", removed)
+    }
+
+    /// `repair`: number of future-dated timestamps clamped to now.
+    pub fn repair(fixed_timestamps: usize) -> Self {
+        Self::default().with("fixed_timestamps", fixed_timestamps)
+    }
+
+    /// `diff`: the two environments compared.
+    pub fn diff(from: &str, to: &str) -> Self {
+        Self::default().with("from", from).with("to", to)
+    }
+
+    /// `diff --against-file`: the environment and the plaintext file.
+    pub fn diff_file(from: &str, file: &str) -> Self {
+        Self::default().with("from", from).with("file", file)
+    }
+
+    fn with(mut self, field: &str, value: impl Into<Value>) -> Self {
+        self.0.insert(field.to_string(), value.into());
+        self
+    }
+}
+
+/// Serializes as compact JSON — the form stored in the database.
+impl fmt::Display for AuditDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Value::Object(self.0.clone()))
+    }
+}
+
+/// Parse a stored `details` value: JSON objects are returned as-is, and
+/// legacy plain-text rows become a JSON string.
+pub fn details_value(raw: &str) -> Value {
+    match serde_json::from_str::<Value>(raw) {
+        Ok(value @ Value::Object(_)) => value,
+        _ => Value::String(raw.to_string()),
+    }
+}
+
+/// Render a stored `details` value for the terminal.
+///
+/// JSON objects become compact `key=value` pairs; legacy plain-text rows
+/// are shown unchanged.
+pub fn render_details(raw: &str) -> String {
+    let Value::Object(fields) = details_value(raw) else {
+        return raw.to_string();
+    };
+
+    fields
+        .iter()
+        .map(|(key, value)| match value {
+            Value::String(s) => format!("{key}={s}"),
+            other => format!("{key}={other}"),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constructors_serialize_to_compact_json() {
+        assert_eq!(
+            AuditDetails::import(3, ".env").to_string(),
+            r#"{"count":3,"source":".env"}"#
+        );
+        assert_eq!(
+            AuditDetails::set(false).to_string(),
+            r#"{"action":"added"}"#
+        );
+        assert_eq!(
+            AuditDetails::export(2, "json", Some("old.vault")).to_string(),
+            r#"{"changed_since":"old.vault","count":2,"format":"json"}"#
+        );
+    }
+
+    #[test]
+    fn structured_details_render_as_key_value_pairs() {
+        let stored = AuditDetails::env_clone(5, "dev", "staging").to_string();
+        assert_eq!(render_details(&stored), "count=5 from=dev to=staging");
+    }
+
+    #[test]
+    fn legacy_details_render_unchanged() {
+        assert_eq!(
+            render_details("3 secrets re-encrypted"),
+            "3 secrets re-encrypted"
+        );
+        // Valid JSON that is not an object is still treated as legacy text.
+        assert_eq!(render_details("42"), "42");
+    }
+
+    #[test]
+    fn details_value_passes_objects_through() {
+        let stored = AuditDetails::rotate(7).to_string();
+        assert_eq!(details_value(&stored), serde_json::json!({ "count": 7 }));
+        assert_eq!(
+            details_value("vault created"),
+            Value::String("vault created".into())
+        );
+    }
+}
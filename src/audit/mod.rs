@@ -5,16 +5,30 @@
 //!
 //! Designed for graceful degradation: if the database can't be opened or
 //! written to, operations silently continue without logging.
+//!
+//! The schema version lives in SQLite's `user_version` pragma.  `open`
+//! applies the [`MIGRATIONS`] the database hasn't seen, in one
+//! transaction.  A database from a newer envvault is opened read-only:
+//! queries work, but nothing is logged or purged.
+//!
+//! Several envvault processes may share the database (say `run` in one
+//! terminal and `set` in another), so it uses WAL journaling and a busy
+//! timeout, and `log` retries briefly before dropping an entry.
+
+mod details;
 
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use chrono::{DateTime, Utc};
-use rusqlite::Connection;
+use rusqlite::{Connection, ErrorCode, TransactionBehavior};
 use serde::Serialize;
 
 use crate::cli::Cli;
 use crate::errors::{EnvVaultError, Result};
 
+pub use details::{details_value, render_details, AuditDetails};
+
 /// A single audit log entry.
 #[derive(Debug, Clone)]
 pub struct AuditEntry {
@@ -29,6 +43,9 @@ pub struct AuditEntry {
 }
 
 /// Serializable audit entry for JSON/CSV export.
+///
+/// Structured `details` are passed through as a JSON object; legacy
+/// plain-text details become a JSON string.
 #[derive(Debug, Clone, Serialize, serde::Deserialize)]
 pub struct AuditEntryExport {
     pub id: i64,
@@ -36,7 +53,7 @@ pub struct AuditEntryExport {
     pub operation: String,
     pub environment: String,
     pub key_name: Option<String>,
-    pub details: Option<String>,
+    pub details: Option<serde_json::Value>,
     pub user: Option<String>,
     pub pid: Option<i64>,
 }
@@ -49,16 +66,49 @@ impl From<&AuditEntry> for AuditEntryExport {
             operation: e.operation.clone(),
             environment: e.environment.clone(),
             key_name: e.key_name.clone(),
-            details: e.details.clone(),
+            details: e.details.as_deref().map(details_value),
             user: e.user.clone(),
             pid: e.pid,
         }
     }
 }
 
+/// Schema migrations, oldest first.  `MIGRATIONS[n]` upgrades a database
+/// at version `n` to `n + 1`; append new steps, never edit old ones.
+const MIGRATIONS: &[&str] = &[
+    // v1: the original table.
+    "CREATE TABLE IF NOT EXISTS audit_log (
+        id          INTEGER PRIMARY KEY AUTOINCREMENT,
+        timestamp   TEXT NOT NULL,
+        operation   TEXT NOT NULL,
+        environment TEXT NOT NULL,
+        key_name    TEXT,
+        details     TEXT
+    );",
+    // v2 (0.5.0): who ran the command, and a timestamp index.
+    "ALTER TABLE audit_log ADD COLUMN user TEXT;
+     ALTER TABLE audit_log ADD COLUMN pid INTEGER;
+     CREATE INDEX IF NOT EXISTS idx_audit_timestamp ON audit_log(timestamp);",
+];
+
+/// Audit schema version this build reads and writes.
+pub const SCHEMA_VERSION: i64 = MIGRATIONS.len() as i64;
+
+/// How long SQLite waits for another process's lock before giving up.
+const BUSY_TIMEOUT: Duration = Duration::from_millis(2000);
+
+/// Attempts `log` makes when the database is still busy after
+/// [`BUSY_TIMEOUT`].
+const LOG_ATTEMPTS: u32 = 3;
+
+/// Pause between `log` attempts (multiplied by the attempt number).
+const LOG_RETRY_DELAY: Duration = Duration::from_millis(50);
+
 /// SQLite-backed audit log.
 pub struct AuditLog {
     conn: Connection,
+    /// Schema version found on disk (after any migrations ran).
+    schema_version: i64,
 }
 
 impl AuditLog {
@@ -68,48 +118,103 @@ impl AuditLog {
     /// treat this as "audit logging unavailable" and continue normally.
     pub fn open(vault_dir: &Path) -> Option<Self> {
         let db_path = vault_dir.join("audit.db");
-        let conn = Connection::open(&db_path).ok()?;
+        let mut conn = Connection::open(&db_path).ok()?;
 
-        // Set restrictive permissions on the audit database (owner-only).
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let perms = std::fs::Permissions::from_mode(0o600);
-            let _ = std::fs::set_permissions(&db_path, perms);
+        // Owner-only before any sidecar exists: SQLite creates the -wal
+        // and -shm files with the main database's permissions.
+        restrict_permissions(&db_path);
+
+        conn.busy_timeout(BUSY_TIMEOUT).ok()?;
+        // WAL lets readers and a writer work at the same time.  Some
+        // filesystems can't do it; the default journal still works there.
+        let _ = conn.query_row("PRAGMA journal_mode = WAL", [], |row| {
+            row.get::<_, String>(0)
+        });
+
+        let schema_version = Self::migrate(&mut conn).ok()?;
+
+        // Sidecars left behind by an older run may predate the chmod.
+        restrict_permissions(&db_path);
+
+        Some(Self {
+            conn,
+            schema_version,
+        })
+    }
+
+    /// Bring the database up to [`SCHEMA_VERSION`] and return the version
+    /// it ends up at.  Newer databases are left untouched.
+    fn migrate(conn: &mut Connection) -> rusqlite::Result<i64> {
+        let version = Self::detect_version(conn)?;
+        if version >= SCHEMA_VERSION {
+            return Ok(version);
         }
 
-        // Create the table if it doesn't exist.
-        conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS audit_log (
-                id          INTEGER PRIMARY KEY AUTOINCREMENT,
-                timestamp   TEXT NOT NULL,
-                operation   TEXT NOT NULL,
-                environment TEXT NOT NULL,
-                key_name    TEXT,
-                details     TEXT
-            );",
-        )
-        .ok()?;
+        // Take the write lock up front and look again: another process
+        // may have migrated while we waited for it.
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let mut version = Self::detect_version(&tx)?;
+        if version >= SCHEMA_VERSION {
+            return Ok(version);
+        }
+        while version < SCHEMA_VERSION {
+            tx.execute_batch(MIGRATIONS[version as usize])?;
+            version += 1;
+        }
+        tx.pragma_update(None, "user_version", version)?;
+        tx.commit()?;
+        Ok(version)
+    }
 
-        // Run idempotent schema migration for v0.5.0 (user, pid, index).
-        Self::migrate_v5(&conn);
+    /// The stored `user_version`, or for databases written before it was
+    /// tracked, the version their columns match.
+    fn detect_version(conn: &Connection) -> rusqlite::Result<i64> {
+        let version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if version > 0 {
+            return Ok(version);
+        }
 
-        Some(Self { conn })
+        let mut stmt = conn.prepare("SELECT name FROM pragma_table_info('audit_log')")?;
+        let columns: Vec<String> = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(if columns.is_empty() {
+            0
+        } else if columns.iter().any(|c| c == "pid") {
+            2
+        } else {
+            1
+        })
     }
 
-    /// Idempotent migration: add user/pid columns and timestamp index.
-    ///
-    /// SQLite's `ALTER TABLE ADD COLUMN` errors if the column already exists,
-    /// so we silently ignore those errors to make this safe to run every time.
-    fn migrate_v5(conn: &Connection) {
-        let _ = conn.execute_batch("ALTER TABLE audit_log ADD COLUMN user TEXT;");
-        let _ = conn.execute_batch("ALTER TABLE audit_log ADD COLUMN pid INTEGER;");
-        let _ = conn.execute_batch(
-            "CREATE INDEX IF NOT EXISTS idx_audit_timestamp ON audit_log(timestamp);",
-        );
+    /// Schema version of the database.
+    pub fn schema_version(&self) -> i64 {
+        self.schema_version
+    }
+
+    /// Whether the database comes from a newer envvault.  Such databases
+    /// are never written to.
+    pub fn is_read_only(&self) -> bool {
+        self.schema_version > SCHEMA_VERSION
+    }
+
+    /// Read the schema version of `<vault_dir>/audit.db` without creating
+    /// or migrating it.  `None` if there is no readable database.
+    pub fn stored_schema_version(vault_dir: &Path) -> Option<i64> {
+        let db_path = Self::db_path(vault_dir);
+        if !db_path.exists() {
+            return None;
+        }
+        let conn =
+            Connection::open_with_flags(&db_path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+                .ok()?;
+        Self::detect_version(&conn).ok()
     }
 
     /// Record an operation. Fire-and-forget — errors are silently ignored.
+    ///
+    /// A database still locked after the busy timeout gets a few more
+    /// tries before the entry is dropped.
     pub fn log(
         &self,
         operation: &str,
@@ -117,16 +222,27 @@ impl AuditLog {
         key_name: Option<&str>,
         details: Option<&str>,
     ) {
+        if self.is_read_only() {
+            return;
+        }
         let now = Utc::now().to_rfc3339();
         let user = std::env::var("USER")
             .or_else(|_| std::env::var("LOGNAME"))
             .ok();
         let pid = std::process::id() as i64;
-        let _ = self.conn.execute(
-            "INSERT INTO audit_log (timestamp, operation, environment, key_name, details, user, pid)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            rusqlite::params![now, operation, environment, key_name, details, user, pid],
-        );
+        for attempt in 1..=LOG_ATTEMPTS {
+            let result = self.conn.execute(
+                "INSERT INTO audit_log (timestamp, operation, environment, key_name, details, user, pid)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                rusqlite::params![now, operation, environment, key_name, details, user, pid],
+            );
+            match result {
+                Err(ref e) if attempt < LOG_ATTEMPTS && is_busy(e) => {
+                    std::thread::sleep(LOG_RETRY_DELAY * attempt);
+                }
+                _ => return,
+            }
+        }
     }
 
     /// Query recent audit entries.
@@ -134,31 +250,67 @@ impl AuditLog {
     /// - `limit`: maximum number of entries to return (most recent first).
     /// - `since`: if provided, only return entries newer than this timestamp.
     pub fn query(&self, limit: usize, since: Option<DateTime<Utc>>) -> Result<Vec<AuditEntry>> {
+        self.query_env(limit, since, None)
+    }
+
+    /// Like [`AuditLog::query`], restricted to one environment when
+    /// `environment` is given.
+    pub fn query_env(
+        &self,
+        limit: usize,
+        since: Option<DateTime<Utc>>,
+        environment: Option<&str>,
+    ) -> Result<Vec<AuditEntry>> {
+        self.query_filtered(limit, since, environment, None)
+    }
+
+    /// Entries recorded against the secret `key` (the `key_name` column),
+    /// most recent first, across every environment.
+    pub fn query_by_key(&self, key: &str, limit: usize) -> Result<Vec<AuditEntry>> {
+        self.query_filtered(limit, None, None, Some(key))
+    }
+
+    fn query_filtered(
+        &self,
+        limit: usize,
+        since: Option<DateTime<Utc>>,
+        environment: Option<&str>,
+        key: Option<&str>,
+    ) -> Result<Vec<AuditEntry>> {
         let limit_i64 = i64::try_from(limit).unwrap_or(i64::MAX);
-        let (sql, params): (&str, Vec<Box<dyn rusqlite::types::ToSql>>) = match since {
-            Some(ref ts) => (
-                "SELECT id, timestamp, operation, environment, key_name, details, user, pid
-                 FROM audit_log
-                 WHERE timestamp >= ?1
-                 ORDER BY id DESC
-                 LIMIT ?2",
-                vec![
-                    Box::new(ts.to_rfc3339()) as Box<dyn rusqlite::types::ToSql>,
-                    Box::new(limit_i64),
-                ],
-            ),
-            None => (
-                "SELECT id, timestamp, operation, environment, key_name, details, user, pid
-                 FROM audit_log
-                 ORDER BY id DESC
-                 LIMIT ?1",
-                vec![Box::new(limit_i64) as Box<dyn rusqlite::types::ToSql>],
-            ),
+        let mut conditions = Vec::new();
+        let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+        if let Some(ref ts) = since {
+            params.push(Box::new(ts.to_rfc3339()));
+            conditions.push(format!("timestamp >= ?{}", params.len()));
+        }
+        if let Some(env) = environment {
+            params.push(Box::new(env.to_string()));
+            conditions.push(format!("environment = ?{}", params.len()));
+        }
+        if let Some(key) = key {
+            params.push(Box::new(key.to_string()));
+            conditions.push(format!("key_name = ?{}", params.len()));
+        }
+        params.push(Box::new(limit_i64));
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
         };
+        let sql = format!(
+            "SELECT id, timestamp, operation, environment, key_name, details, user, pid
+             FROM audit_log
+             {where_clause}
+             ORDER BY id DESC
+             LIMIT ?{}",
+            params.len()
+        );
 
         let mut stmt = self
             .conn
-            .prepare(sql)
+            .prepare(&sql)
             .map_err(|e| EnvVaultError::AuditError(format!("query prepare: {e}")))?;
 
         let params_refs: Vec<&dyn rusqlite::types::ToSql> = params.iter().map(|p| &**p).collect();
@@ -193,6 +345,12 @@ impl AuditLog {
     /// Delete audit entries older than the given timestamp.
     /// Returns the number of entries deleted.
     pub fn purge(&self, before: DateTime<Utc>) -> Result<usize> {
+        if self.is_read_only() {
+            return Err(EnvVaultError::AuditError(format!(
+                "audit.db uses schema v{} but this envvault only understands v{SCHEMA_VERSION} — upgrade envvault to purge it",
+                self.schema_version
+            )));
+        }
         let count = self
             .conn
             .execute(
@@ -209,17 +367,56 @@ impl AuditLog {
     }
 }
 
+/// Whether an error means another connection holds the lock.
+fn is_busy(error: &rusqlite::Error) -> bool {
+    matches!(
+        error.sqlite_error_code(),
+        Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
+}
+
+/// Make the database and its `-wal` / `-shm` sidecars owner-only.
+fn restrict_permissions(db_path: &Path) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        for suffix in ["", "-wal", "-shm"] {
+            let mut path = db_path.as_os_str().to_owned();
+            path.push(suffix);
+            if Path::new(&path).exists() {
+                let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = db_path;
+}
+
 /// Convenience helper: log an audit event using the CLI context.
 ///
 /// Opens the audit database, logs the event, and silently ignores any errors.
 /// This is safe to call from any command — it never fails the parent operation.
 pub fn log_audit(cli: &Cli, op: &str, key: Option<&str>, details: Option<&str>) {
-    let vault_dir = match std::env::current_dir() {
-        Ok(cwd) => cwd.join(&cli.vault_dir),
+    let vault_dir = match crate::cli::resolve_vault_dir(cli) {
+        Ok(dir) => dir,
         Err(_) => return,
     };
 
+    // Never create a vault dir just to hold an audit DB — that would
+    // split the history across stray directories.
+    if !vault_dir.is_dir() {
+        return;
+    }
+
     if let Some(audit) = AuditLog::open(&vault_dir) {
+        if audit.is_read_only() {
+            crate::cli::output::warning(&format!(
+                "Not logging '{op}': audit.db uses schema v{} and this envvault only understands v{SCHEMA_VERSION}.",
+                audit.schema_version()
+            ));
+            return;
+        }
         audit.log(op, &cli.env, key, details);
     }
 }
@@ -290,6 +487,58 @@ mod tests {
         assert_eq!(entries.len(), 3);
     }
 
+    #[test]
+    fn query_env_filters_by_environment() {
+        let dir = TempDir::new().unwrap();
+        let audit = AuditLog::open(dir.path()).unwrap();
+
+        audit.log("set", "dev", Some("A"), None);
+        audit.log("set", "prod", Some("B"), None);
+        audit.log("delete", "prod", Some("C"), None);
+
+        let prod = audit.query_env(10, None, Some("prod")).unwrap();
+        assert_eq!(prod.len(), 2);
+        assert!(prod.iter().all(|e| e.environment == "prod"));
+        assert_eq!(prod[0].operation, "delete");
+
+        let past = Utc::now() - chrono::Duration::hours(1);
+        assert_eq!(
+            audit.query_env(1, Some(past), Some("prod")).unwrap().len(),
+            1
+        );
+        assert!(audit
+            .query_env(10, None, Some("staging"))
+            .unwrap()
+            .is_empty());
+        assert_eq!(audit.query_env(10, None, None).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn query_by_key_returns_only_that_key() {
+        let dir = TempDir::new().unwrap();
+        let audit = AuditLog::open(dir.path()).unwrap();
+
+        audit.log("set", "dev", Some("DB_URL"), Some("added"));
+        audit.log("set", "dev", Some("API_KEY"), Some("added"));
+        audit.log("set", "prod", Some("DB_URL"), Some("added"));
+        audit.log("export", "dev", None, None);
+        audit.log("rename", "dev", Some("DB_URL"), None);
+        audit.log("delete", "dev", Some("DB_URL_OLD"), None);
+
+        let history = audit.query_by_key("DB_URL", 10).unwrap();
+        assert_eq!(history.len(), 3);
+        assert!(history
+            .iter()
+            .all(|e| e.key_name.as_deref() == Some("DB_URL")));
+        // Most recent first, across environments.
+        assert_eq!(history[0].operation, "rename");
+        assert_eq!(history[1].environment, "prod");
+        assert_eq!(history[2].environment, "dev");
+
+        assert_eq!(audit.query_by_key("DB_URL", 1).unwrap().len(), 1);
+        assert!(audit.query_by_key("MISSING", 10).unwrap().is_empty());
+    }
+
     #[test]
     fn query_with_since_filter() {
         let dir = TempDir::new().unwrap();
@@ -345,16 +594,138 @@ mod tests {
         );
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn wal_sidecars_are_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let audit = AuditLog::open(dir.path()).unwrap();
+        audit.log("set", "dev", Some("KEY"), None);
+
+        let mode: String = audit
+            .conn
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode, "wal");
+
+        for sidecar in ["audit.db-wal", "audit.db-shm"] {
+            let path = dir.path().join(sidecar);
+            assert!(path.exists(), "{sidecar} should exist in WAL mode");
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600, "{sidecar} should be owner-only");
+        }
+    }
+
     #[test]
-    fn migrate_v5_is_idempotent() {
+    fn concurrent_writers_lose_no_entries() {
+        const THREADS: usize = 8;
+        const PER_THREAD: usize = 25;
+
         let dir = TempDir::new().unwrap();
-        // Open twice — migration runs both times, second should not error.
-        let audit1 = AuditLog::open(dir.path());
-        assert!(audit1.is_some());
+        // Every thread opens its own connection, like separate processes.
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let path = dir.path().to_path_buf();
+                std::thread::spawn(move || {
+                    let audit = AuditLog::open(&path).expect("open under contention");
+                    for i in 0..PER_THREAD {
+                        audit.log("set", "dev", Some(&format!("T{t}_K{i}")), None);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let audit = AuditLog::open(dir.path()).unwrap();
+        let entries = audit.query(usize::MAX, None).unwrap();
+        assert_eq!(entries.len(), THREADS * PER_THREAD);
+    }
+
+    #[test]
+    fn reopening_a_current_database_is_a_no_op() {
+        let dir = TempDir::new().unwrap();
+        let audit1 = AuditLog::open(dir.path()).unwrap();
+        assert_eq!(audit1.schema_version(), SCHEMA_VERSION);
         drop(audit1);
 
-        let audit2 = AuditLog::open(dir.path());
-        assert!(audit2.is_some());
+        let audit2 = AuditLog::open(dir.path()).unwrap();
+        assert_eq!(audit2.schema_version(), SCHEMA_VERSION);
+        assert!(!audit2.is_read_only());
+    }
+
+    fn user_version(dir: &Path) -> i64 {
+        Connection::open(dir.join("audit.db"))
+            .unwrap()
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn v1_database_is_migrated_and_keeps_rows() {
+        let dir = TempDir::new().unwrap();
+        {
+            let conn = Connection::open(dir.path().join("audit.db")).unwrap();
+            conn.execute_batch(MIGRATIONS[0]).unwrap();
+            conn.execute(
+                "INSERT INTO audit_log (timestamp, operation, environment, key_name, details)
+                 VALUES ('2024-01-01T00:00:00+00:00', 'init', 'dev', NULL, 'vault created')",
+                [],
+            )
+            .unwrap();
+        }
+        assert_eq!(AuditLog::stored_schema_version(dir.path()), Some(1));
+
+        let audit = AuditLog::open(dir.path()).unwrap();
+        assert_eq!(audit.schema_version(), SCHEMA_VERSION);
+        assert_eq!(user_version(dir.path()), SCHEMA_VERSION);
+
+        audit.log("set", "dev", Some("KEY"), None);
+        let entries = audit.query(10, None).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].operation, "init");
+        assert_eq!(entries[1].details.as_deref(), Some("vault created"));
+        assert!(entries[1].pid.is_none());
+        assert!(entries[0].pid.is_some());
+    }
+
+    #[test]
+    fn untracked_v2_database_is_stamped_without_rerunning_alters() {
+        let dir = TempDir::new().unwrap();
+        {
+            // What 0.5.0 left behind: all columns, but user_version 0.
+            let conn = Connection::open(dir.path().join("audit.db")).unwrap();
+            conn.execute_batch(MIGRATIONS[0]).unwrap();
+            conn.execute_batch(MIGRATIONS[1]).unwrap();
+        }
+
+        let audit = AuditLog::open(dir.path()).unwrap();
+        assert_eq!(audit.schema_version(), 2);
+        assert_eq!(user_version(dir.path()), 0, "nothing to migrate");
+    }
+
+    #[test]
+    fn newer_database_is_read_only() {
+        let dir = TempDir::new().unwrap();
+        let audit = AuditLog::open(dir.path()).unwrap();
+        audit.log("init", "dev", None, None);
+        audit
+            .conn
+            .pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+        drop(audit);
+
+        let audit = AuditLog::open(dir.path()).unwrap();
+        assert!(audit.is_read_only());
+        assert_eq!(audit.schema_version(), SCHEMA_VERSION + 1);
+
+        // Reads still work; writes are refused.
+        audit.log("set", "dev", Some("KEY"), None);
+        assert_eq!(audit.query(10, None).unwrap().len(), 1);
+        assert!(audit.purge(Utc::now()).is_err());
+        assert_eq!(user_version(dir.path()), SCHEMA_VERSION + 1);
     }
 
     #[test]
@@ -4,6 +4,13 @@
 //!   envvault audit               # show last 50 entries
 //!   envvault audit --last 20     # show last 20
 //!   envvault audit --since 7d    # entries from last 7 days
+//!   envvault audit --env prod    # only the prod environment
+//!
+//! Without an explicit `--env`, entries from every environment are shown
+//! (and exported).
+//!
+//! `envvault history <KEY>` shows the entries recorded against one secret
+//! (see [`execute_history`]).
 
 use crate::cli::Cli;
 use crate::errors::{EnvVaultError, Result};
@@ -14,8 +21,7 @@ pub fn execute(cli: &Cli, last: usize, since: Option<&str>) -> Result<()> {
     use crate::audit::AuditLog;
     use crate::cli::output;
 
-    let cwd = std::env::current_dir()?;
-    let vault_dir = cwd.join(&cli.vault_dir);
+    let vault_dir = crate::cli::resolve_vault_dir(cli)?;
 
     let audit = AuditLog::open(&vault_dir)
         .ok_or_else(|| EnvVaultError::AuditError("failed to open audit database".into()))?;
@@ -25,7 +31,7 @@ pub fn execute(cli: &Cli, last: usize, since: Option<&str>) -> Result<()> {
         None => None,
     };
 
-    let entries = audit.query(last, since_dt)?;
+    let entries = audit.query_env(last, since_dt, env_filter(cli))?;
 
     if entries.is_empty() {
         output::info("No audit entries found.");
@@ -37,12 +43,51 @@ pub fn execute(cli: &Cli, last: usize, since: Option<&str>) -> Result<()> {
     Ok(())
 }
 
+/// The environment to restrict to: only one named with `--env`.
+#[cfg(feature = "audit-log")]
+fn env_filter(cli: &Cli) -> Option<&str> {
+    cli.env_explicit.then_some(cli.env.as_str())
+}
+
 /// Execute the `audit` command — stub when audit-log is disabled.
 #[cfg(not(feature = "audit-log"))]
 pub fn execute(_cli: &Cli, _last: usize, _since: Option<&str>) -> Result<()> {
-    Err(EnvVaultError::AuditError(
-        "audit log not available — rebuild with `cargo build --features audit-log`".into(),
-    ))
+    Err(EnvVaultError::FeatureDisabled(crate::features::AUDIT_LOG))
+}
+
+// ---------------------------------------------------------------------------
+// Per-secret history
+// ---------------------------------------------------------------------------
+
+/// Execute the `history` command: the audit entries recorded against
+/// `key` (set, delete, rename, ...), most recent first.  Renames are
+/// recorded under the old name.
+#[cfg(feature = "audit-log")]
+pub fn execute_history(cli: &Cli, key: &str, last: usize) -> Result<()> {
+    use crate::audit::AuditLog;
+    use crate::cli::output;
+
+    crate::vault::VaultStore::validate_secret_name(key)?;
+    let vault_dir = crate::cli::resolve_vault_dir(cli)?;
+
+    let audit = AuditLog::open(&vault_dir)
+        .ok_or_else(|| EnvVaultError::AuditError("failed to open audit database".into()))?;
+
+    let entries = audit.query_by_key(key, last)?;
+    if entries.is_empty() {
+        output::info(&format!("No history for '{key}'"));
+        return Ok(());
+    }
+
+    print_audit_table(&entries);
+
+    Ok(())
+}
+
+/// History stub when audit-log is disabled.
+#[cfg(not(feature = "audit-log"))]
+pub fn execute_history(_cli: &Cli, _key: &str, _last: usize) -> Result<()> {
+    Err(EnvVaultError::FeatureDisabled(crate::features::AUDIT_LOG))
 }
 
 // ---------------------------------------------------------------------------
@@ -55,14 +100,13 @@ pub fn execute_export(cli: &Cli, format: &str, output: Option<&str>) -> Result<(
     use crate::audit::{AuditEntryExport, AuditLog};
     use crate::cli::output as out;
 
-    let cwd = std::env::current_dir()?;
-    let vault_dir = cwd.join(&cli.vault_dir);
+    let vault_dir = crate::cli::resolve_vault_dir(cli)?;
 
     let audit = AuditLog::open(&vault_dir)
         .ok_or_else(|| EnvVaultError::AuditError("failed to open audit database".into()))?;
 
     // Query all entries (no limit).
-    let entries = audit.query(i64::MAX as usize, None)?;
+    let entries = audit.query_env(i64::MAX as usize, None, env_filter(cli))?;
 
     if entries.is_empty() {
         out::info("No audit entries to export.");
@@ -98,9 +142,7 @@ pub fn execute_export(cli: &Cli, format: &str, output: Option<&str>) -> Result<(
 /// Export stub when audit-log is disabled.
 #[cfg(not(feature = "audit-log"))]
 pub fn execute_export(_cli: &Cli, _format: &str, _output: Option<&str>) -> Result<()> {
-    Err(EnvVaultError::AuditError(
-        "audit log not available — rebuild with `cargo build --features audit-log`".into(),
-    ))
+    Err(EnvVaultError::FeatureDisabled(crate::features::AUDIT_LOG))
 }
 
 /// Format audit entries as CSV.
@@ -115,7 +157,10 @@ fn format_as_csv(entries: &[crate::audit::AuditEntryExport]) -> String {
             csv_escape(&e.operation),
             csv_escape(&e.environment),
             csv_escape(e.key_name.as_deref().unwrap_or("")),
-            csv_escape(e.details.as_deref().unwrap_or("")),
+            csv_escape(&e.details.as_ref().map_or(String::new(), |d| match d {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })),
             csv_escape(e.user.as_deref().unwrap_or("")),
             e.pid.map_or(String::new(), |p| p.to_string()),
         ));
@@ -143,8 +188,7 @@ pub fn execute_purge(cli: &Cli, older_than: &str) -> Result<()> {
     use crate::audit::AuditLog;
     use crate::cli::output as out;
 
-    let cwd = std::env::current_dir()?;
-    let vault_dir = cwd.join(&cli.vault_dir);
+    let vault_dir = crate::cli::resolve_vault_dir(cli)?;
 
     let audit = AuditLog::open(&vault_dir)
         .ok_or_else(|| EnvVaultError::AuditError("failed to open audit database".into()))?;
@@ -163,15 +207,17 @@ pub fn execute_purge(cli: &Cli, older_than: &str) -> Result<()> {
 /// Purge stub when audit-log is disabled.
 #[cfg(not(feature = "audit-log"))]
 pub fn execute_purge(_cli: &Cli, _older_than: &str) -> Result<()> {
-    Err(EnvVaultError::AuditError(
-        "audit log not available — rebuild with `cargo build --features audit-log`".into(),
-    ))
+    Err(EnvVaultError::FeatureDisabled(crate::features::AUDIT_LOG))
 }
 
-/// Parse a human-friendly duration string like "7d", "24h", "30m".
+/// Parse a human-friendly duration string like "7d", "24h", "30m" into
+/// the time that long ago.
 pub fn parse_duration(input: &str) -> Result<chrono::DateTime<chrono::Utc>> {
-    use chrono::Utc;
+    Ok(chrono::Utc::now() - parse_span(input)?)
+}
 
+/// Parse a human-friendly duration string like "7d", "24h", "30m".
+pub fn parse_span(input: &str) -> Result<chrono::Duration> {
     let input = input.trim();
 
     let (num_str, unit) = if let Some(s) = input.strip_suffix('d') {
@@ -192,38 +238,40 @@ pub fn parse_duration(input: &str) -> Result<chrono::DateTime<chrono::Utc>> {
         ))
     })?;
 
-    let duration = match unit {
-        'd' => chrono::Duration::days(num),
-        'h' => chrono::Duration::hours(num),
-        'm' => chrono::Duration::minutes(num),
+    let span = match unit {
+        'd' => chrono::Duration::try_days(num),
+        'h' => chrono::Duration::try_hours(num),
+        'm' => chrono::Duration::try_minutes(num),
         _ => unreachable!(),
     };
-
-    Ok(Utc::now() - duration)
+    span.ok_or_else(|| {
+        EnvVaultError::CommandFailed(format!("invalid duration '{input}' — out of range"))
+    })
 }
 
 /// Print audit entries in a formatted table.
 #[cfg(feature = "audit-log")]
 pub fn print_audit_table(entries: &[crate::audit::AuditEntry]) {
-    use comfy_table::{ContentArrangement, Table};
     use console::style;
 
-    let mut table = Table::new();
-    table.set_content_arrangement(ContentArrangement::Dynamic);
+    let mut table = crate::cli::output::table();
     table.set_header(vec!["Time", "Operation", "Environment", "Key", "Details"]);
 
     for entry in entries {
-        let time = entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string();
+        let time = crate::cli::output::format_time(entry.timestamp);
         let op = colorize_operation(&entry.operation);
         let key = entry.key_name.as_deref().unwrap_or("-");
-        let details = entry.details.as_deref().unwrap_or("-");
+        let details = entry
+            .details
+            .as_deref()
+            .map_or_else(|| "-".to_string(), crate::audit::render_details);
 
         table.add_row(vec![
             time,
             op,
             entry.environment.clone(),
             key.to_string(),
-            details.to_string(),
+            details,
         ]);
     }
 
@@ -241,7 +289,7 @@ fn colorize_operation(op: &str) -> String {
 
     match op {
         "init" | "env-clone" => style(op).green().to_string(),
-        "set" | "edit" => style(op).blue().to_string(),
+        "set" | "set-many" | "edit" => style(op).blue().to_string(),
         "delete" | "env-delete" => style(op).red().to_string(),
         "rotate-key" => style(op).yellow().to_string(),
         "export" | "import" => style(op).cyan().to_string(),
@@ -283,6 +331,13 @@ mod tests {
         assert!(parse_duration("d").is_err());
     }
 
+    #[test]
+    fn parse_span_is_the_length_itself() {
+        assert_eq!(parse_span("30d").unwrap(), chrono::Duration::days(30));
+        assert_eq!(parse_span(" 2h ").unwrap(), chrono::Duration::hours(2));
+        assert!(parse_span("99999999999999d").is_err());
+    }
+
     #[cfg(feature = "audit-log")]
     #[test]
     fn colorize_operation_returns_string() {
@@ -349,6 +404,32 @@ mod tests {
         assert_eq!(parsed[1].operation, "set");
     }
 
+    #[cfg(feature = "audit-log")]
+    #[test]
+    fn export_json_passes_structured_details_through() {
+        use crate::audit::{AuditDetails, AuditEntryExport, AuditLog};
+        let dir = tempfile::TempDir::new().unwrap();
+        let audit = AuditLog::open(dir.path()).unwrap();
+
+        audit.log("init", "dev", None, Some("vault created"));
+        let details = AuditDetails::import(3, ".env").to_string();
+        audit.log("import", "dev", None, Some(&details));
+
+        let entries = audit.query(100, None).unwrap();
+        let exports: Vec<AuditEntryExport> = entries.iter().map(AuditEntryExport::from).collect();
+        let json: serde_json::Value = serde_json::to_value(&exports).unwrap();
+
+        assert_eq!(json[0]["details"]["count"], 3);
+        assert_eq!(json[0]["details"]["source"], ".env");
+        assert_eq!(json[1]["details"], "vault created");
+
+        let csv = format_as_csv(&exports);
+        assert!(
+            csv.contains(r#""{""count"":3,""source"":"".env""}""#),
+            "{csv}"
+        );
+    }
+
     #[cfg(feature = "audit-log")]
     #[test]
     fn export_csv_format() {
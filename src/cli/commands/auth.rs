@@ -42,15 +42,20 @@ pub fn execute_keyring(cli: &Cli, delete: bool) -> Result<()> {
     #[cfg(not(feature = "keyring-store"))]
     {
         let _ = (cli, delete);
-        Err(EnvVaultError::KeyringError(
-            "keyring support not compiled — rebuild with `cargo build --features keyring-store`"
-                .into(),
+        Err(EnvVaultError::FeatureDisabled(
+            crate::features::KEYRING_STORE,
         ))
     }
 }
 
 /// Execute `envvault auth keyfile-generate` — create a new random keyfile.
-pub fn execute_keyfile_generate(cli: &Cli, keyfile_path: Option<&str>) -> Result<()> {
+///
+/// The keyfile is added to `.gitignore` unless `no_gitignore` is set.
+pub fn execute_keyfile_generate(
+    cli: &Cli,
+    keyfile_path: Option<&str>,
+    no_gitignore: bool,
+) -> Result<()> {
     let cwd = std::env::current_dir()?;
 
     let path = match keyfile_path {
@@ -63,7 +68,11 @@ pub fn execute_keyfile_generate(cli: &Cli, keyfile_path: Option<&str>) -> Result
     let path_display = path.display();
     output::success(&format!("Keyfile generated at {path_display}"));
     output::warning("Keep this file secret! Anyone with it can help unlock your vault.");
-    output::tip("Add the keyfile path to .gitignore to prevent accidental commits.");
+
+    if no_gitignore {
+        output::tip("Add the keyfile path to .gitignore to prevent accidental commits.");
+        return Ok(());
+    }
 
     // Auto-patch .gitignore for the keyfile.
     let relative = path.strip_prefix(&cwd).map_or_else(
@@ -92,7 +101,7 @@ mod tests {
             assert!(result.is_err());
             let msg = result.unwrap_err().to_string();
             assert!(
-                msg.contains("keyring support not compiled"),
+                msg.contains("built without `keyring-store`"),
                 "unexpected error: {msg}"
             );
         }
@@ -111,10 +120,12 @@ mod tests {
             dir.path().to_str().unwrap(),
             "auth",
             "keyfile-generate",
+            "--no-gitignore",
             kf_path.to_str().unwrap(),
         ]);
 
-        super::execute_keyfile_generate(&cli, Some(kf_path.to_str().unwrap())).unwrap();
+        // --no-gitignore keeps the test from patching the crate's own .gitignore.
+        super::execute_keyfile_generate(&cli, Some(kf_path.to_str().unwrap()), true).unwrap();
 
         assert!(kf_path.exists(), "keyfile should be created");
         let data = std::fs::read(&kf_path).unwrap();
@@ -7,9 +7,10 @@
 //!   envvault completions powershell
 
 use std::io;
+use std::path::{Path, PathBuf};
 
 use clap::CommandFactory;
-use clap_complete::{generate, Shell};
+use clap_complete::{generate, generate_to, Shell};
 
 use crate::cli::Cli;
 use crate::errors::{EnvVaultError, Result};
@@ -22,8 +23,34 @@ pub fn execute(shell: &str) -> Result<()> {
     Ok(())
 }
 
+/// Every shell we can generate completions for.
+pub const SUPPORTED_SHELLS: [Shell; 5] = [
+    Shell::Bash,
+    Shell::Zsh,
+    Shell::Fish,
+    Shell::PowerShell,
+    Shell::Elvish,
+];
+
+/// Write the completion script for `shell` into `dir`.
+///
+/// Returns the path of the written file (named per the shell's convention,
+/// e.g. `envvault.bash`, `_envvault`).
+pub fn write_to_dir(shell: Shell, dir: &Path) -> Result<PathBuf> {
+    let mut cmd = Cli::command();
+    Ok(generate_to(shell, &mut cmd, "envvault", dir)?)
+}
+
+/// The completion script for `shell`.
+pub fn render(shell: Shell) -> Vec<u8> {
+    let mut cmd = Cli::command();
+    let mut script = Vec::new();
+    generate(shell, &mut cmd, "envvault", &mut script);
+    script
+}
+
 /// Parse a shell name string into a `Shell` enum.
-fn parse_shell(name: &str) -> Result<Shell> {
+pub fn parse_shell(name: &str) -> Result<Shell> {
     match name.to_lowercase().as_str() {
         "bash" => Ok(Shell::Bash),
         "zsh" => Ok(Shell::Zsh),
@@ -0,0 +1,265 @@
+//! `envvault config check` — validate `.envvault.toml` without opening a vault.
+//!
+//! Loads the project config (and the global config, if present), prints the
+//! effective settings, and reports:
+//! - errors: unparsable TOML, weak Argon2 params, invalid environment
+//!   names, custom scan patterns or a `key_policy.pattern` that are not
+//!   valid regexes
+//! - warnings: unknown keys (typos or unsupported tables), Argon2 params
+//!   below the recommended defaults, missing files referenced by the config
+//!
+//! Exits non-zero when any error is found.
+
+use std::path::Path;
+
+use console::style;
+
+use crate::cli::{output, validate_env_name};
+use crate::config::{GlobalConfig, Settings};
+use crate::errors::{EnvVaultError, Result};
+
+/// Problems found while checking a config.
+#[derive(Debug, Default)]
+pub struct ConfigReport {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Execute `envvault config check`.
+pub fn execute() -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let config_path = Settings::config_path(&cwd);
+
+    let mut report = ConfigReport::default();
+
+    if config_path.exists() {
+        output::info(&format!("Checking {}", config_path.display()));
+    } else {
+        output::info(&format!(
+            "No {} found — using defaults",
+            config_path.display()
+        ));
+    }
+
+    match Settings::load_with_unknown_keys(&cwd) {
+        Ok((settings, unknown)) => {
+            for key in unknown {
+                report
+                    .warnings
+                    .push(format!("unknown key '{key}' is ignored"));
+            }
+            let checked = check_settings(&settings, &cwd);
+            report.errors.extend(checked.errors);
+            report.warnings.extend(checked.warnings);
+
+            print_effective_settings(&settings)?;
+        }
+        Err(e) => report.errors.push(e.to_string()),
+    }
+
+    check_global_config(&mut report);
+
+    for warning in &report.warnings {
+        output::warning(warning);
+    }
+    for error in &report.errors {
+        output::error(error);
+    }
+
+    if report.errors.is_empty() {
+        output::success(&format!("Config OK ({} warning(s))", report.warnings.len()));
+        Ok(())
+    } else {
+        Err(EnvVaultError::ConfigError(format!(
+            "{} error(s) found in config",
+            report.errors.len()
+        )))
+    }
+}
+
+/// Validate the values of already-parsed settings.
+///
+/// Relative paths in the config are resolved against `project_dir`.
+pub fn check_settings(settings: &Settings, project_dir: &Path) -> ConfigReport {
+    let mut report = ConfigReport::default();
+
+    let params = settings.argon2_params();
+    if let Err(EnvVaultError::KeyDerivationFailed(msg)) =
+        crate::crypto::kdf::validate_params(&params)
+    {
+        report.errors.push(msg);
+    } else {
+        for shortfall in params.below_recommended() {
+            report.warnings.push(format!(
+                "argon2 params weaker than recommended: {shortfall}"
+            ));
+        }
+    }
+
+    if settings.vault_dir.trim().is_empty() {
+        report.errors.push("vault_dir cannot be empty".into());
+    }
+
+    if let Err(e) = validate_env_name(&settings.default_environment) {
+        report.errors.push(format!("default_environment: {e}"));
+    }
+
+    if let Some(ref allowed) = settings.allowed_environments {
+        if allowed.is_empty() {
+            report
+                .errors
+                .push("allowed_environments is empty — no environment could be used".into());
+        }
+        for name in allowed {
+            if let Err(e) = validate_env_name(name) {
+                report.errors.push(format!("allowed_environments: {e}"));
+            }
+        }
+        if !allowed.contains(&settings.default_environment) {
+            report.errors.push(format!(
+                "default_environment '{}' is not in allowed_environments",
+                settings.default_environment
+            ));
+        }
+    }
+
+    if let Some(ref keyfile) = settings.keyfile_path {
+        if !project_dir.join(keyfile).exists() {
+            report
+                .warnings
+                .push(format!("keyfile_path '{keyfile}' does not exist"));
+        }
+    }
+
+    if settings.editor.as_deref().is_some_and(str::is_empty) {
+        report
+            .warnings
+            .push("editor is empty — $VISUAL / $EDITOR will be used".into());
+    }
+
+    for pattern in &settings.secret_scanning.custom_patterns {
+        if let Err(e) = regex::Regex::new(&pattern.regex) {
+            report.errors.push(format!(
+                "secret_scanning.custom_patterns '{}': invalid regex: {e}",
+                pattern.name
+            ));
+        }
+    }
+
+    if let Err(e) = crate::cli::key_policy::KeyPolicy::from_settings(&settings.key_policy) {
+        report.errors.push(e.to_string());
+    }
+
+    if let Some(ref gitleaks) = settings.secret_scanning.gitleaks_config {
+        if !project_dir.join(gitleaks).exists() {
+            report.warnings.push(format!(
+                "secret_scanning.gitleaks_config '{gitleaks}' does not exist"
+            ));
+        }
+    }
+
+    report
+}
+
+/// `GlobalConfig::load` silently falls back to defaults, so parse errors
+/// in the global file are surfaced here instead.
+fn check_global_config(report: &mut ConfigReport) {
+    let Some(path) = GlobalConfig::config_path() else {
+        return;
+    };
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return;
+    };
+
+    if let Err(e) = toml::from_str::<GlobalConfig>(&contents) {
+        report
+            .errors
+            .push(format!("Failed to parse {}: {e}", path.display()));
+    }
+}
+
+/// Print the resolved settings as TOML.
+fn print_effective_settings(settings: &Settings) -> Result<()> {
+    let rendered = toml::to_string_pretty(settings)
+        .map_err(|e| EnvVaultError::SerializationError(format!("settings: {e}")))?;
+
+    println!("\n{}", style("Effective settings:").bold());
+    for line in rendered.lines() {
+        if line.is_empty() {
+            println!();
+        } else {
+            println!("  {line}");
+        }
+    }
+    println!();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CustomPattern;
+
+    #[test]
+    fn default_settings_pass() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let report = check_settings(&Settings::default(), tmp.path());
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    }
+
+    #[test]
+    fn invalid_values_are_errors() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut settings = Settings {
+            argon2_memory_kib: 1024,
+            default_environment: "Prod".into(),
+            allowed_environments: Some(vec!["dev".into()]),
+            ..Settings::default()
+        };
+        settings
+            .secret_scanning
+            .custom_patterns
+            .push(CustomPattern {
+                name: "broken".into(),
+                regex: "(unclosed".into(),
+            });
+        settings.key_policy.pattern = Some("[A-Z".into());
+
+        let report = check_settings(&settings, tmp.path());
+        let all = report.errors.join("\n");
+        assert!(all.contains("memory_kib"), "{all}");
+        assert!(all.contains("default_environment"), "{all}");
+        assert!(all.contains("not in allowed_environments"), "{all}");
+        assert!(all.contains("invalid regex"), "{all}");
+        assert!(all.contains("key_policy.pattern"), "{all}");
+    }
+
+    #[test]
+    fn missing_referenced_files_are_warnings() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut settings = Settings {
+            keyfile_path: Some("missing.key".into()),
+            ..Settings::default()
+        };
+        settings.secret_scanning.gitleaks_config = Some("missing.toml".into());
+
+        let report = check_settings(&settings, tmp.path());
+        assert!(report.errors.is_empty());
+        assert_eq!(report.warnings.len(), 2);
+    }
+
+    #[test]
+    fn weak_but_valid_kdf_params_are_warnings() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let settings = Settings {
+            argon2_memory_kib: 16_384,
+            argon2_iterations: 2,
+            ..Settings::default()
+        };
+
+        let report = check_settings(&settings, tmp.path());
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(report.warnings.len(), 2, "{:?}", report.warnings);
+    }
+}
@@ -0,0 +1,122 @@
+//! `envvault config show` — print the effective settings.
+//!
+//! Values are layered lowest to highest:
+//! 1. built-in defaults
+//! 2. global config (`~/.config/envvault/config.toml`) — `editor`, `keyfile_path`
+//! 3. project config (`.envvault.toml`)
+//! 4. CLI flags — `--vault-dir`, `--keyfile`
+
+use crate::cli::Cli;
+use crate::config::{GlobalConfig, Settings};
+use crate::errors::{EnvVaultError, Result};
+
+/// Execute `envvault config show`.
+pub fn execute(cli: &Cli, json: bool) -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let project = Settings::load(&cwd)?;
+    let global = GlobalConfig::load();
+    let settings = effective_settings(project, &global, cli);
+
+    if json {
+        let rendered = serde_json::to_string_pretty(&settings)
+            .map_err(|e| EnvVaultError::SerializationError(format!("settings: {e}")))?;
+        println!("{rendered}");
+        return Ok(());
+    }
+
+    let rendered = toml::to_string_pretty(&settings)
+        .map_err(|e| EnvVaultError::SerializationError(format!("settings: {e}")))?;
+
+    // Comment header so the output stays valid TOML when redirected.
+    println!("# Effective settings, lowest to highest precedence:");
+    println!("#   defaults");
+    if let Some(path) = GlobalConfig::config_path().filter(|p| p.exists()) {
+        println!("#   {}", path.display());
+    }
+    let project_path = Settings::config_path(&cwd);
+    if project_path.exists() {
+        println!("#   {}", project_path.display());
+    }
+    println!("#   command-line flags");
+    println!();
+    print!("{rendered}");
+
+    Ok(())
+}
+
+/// Layer the global config and CLI flags over the project settings.
+///
+/// Mirrors the lookups the commands themselves do: the global config only
+/// fills `editor`, `keyfile_path`, and `update.registry_url` when the
+/// project leaves them unset, and `--vault-dir` / `--keyfile` always win.
+pub fn effective_settings(mut settings: Settings, global: &GlobalConfig, cli: &Cli) -> Settings {
+    if settings.editor.is_none() {
+        settings.editor.clone_from(&global.editor);
+    }
+    if settings.keyfile_path.is_none() {
+        settings.keyfile_path.clone_from(&global.keyfile_path);
+    }
+    if settings.update.registry_url.is_none() {
+        settings
+            .update
+            .registry_url
+            .clone_from(&global.update.registry_url);
+    }
+
+    settings.vault_dir.clone_from(&cli.vault_dir);
+    if cli.keyfile.is_some() {
+        settings.keyfile_path.clone_from(&cli.keyfile);
+    }
+
+    settings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn global() -> GlobalConfig {
+        GlobalConfig {
+            editor: Some("nano".into()),
+            keyfile_path: Some("/global.key".into()),
+            ..GlobalConfig::default()
+        }
+    }
+
+    #[test]
+    fn global_fills_unset_project_values() {
+        let cli = Cli::try_parse_from(["envvault", "config", "show"]).unwrap();
+        let project = Settings {
+            editor: Some("vim".into()),
+            ..Settings::default()
+        };
+
+        let settings = effective_settings(project, &global(), &cli);
+        assert_eq!(settings.editor.as_deref(), Some("vim"));
+        assert_eq!(settings.keyfile_path.as_deref(), Some("/global.key"));
+    }
+
+    #[test]
+    fn cli_flags_override_config() {
+        let cli = Cli::try_parse_from([
+            "envvault",
+            "--vault-dir",
+            "secrets",
+            "--keyfile",
+            "cli.key",
+            "config",
+            "show",
+            "--json",
+        ])
+        .unwrap();
+        let project = Settings {
+            keyfile_path: Some("project.key".into()),
+            ..Settings::default()
+        };
+
+        let settings = effective_settings(project, &global(), &cli);
+        assert_eq!(settings.vault_dir, "secrets");
+        assert_eq!(settings.keyfile_path.as_deref(), Some("cli.key"));
+    }
+}
@@ -1,42 +1,332 @@
 //! `envvault delete` — remove a secret from the vault.
+//!
+//! Without `--force` the vault is opened first so the confirmation can say
+//! what is about to be destroyed: timestamps, value length, and encoding.
+//! Secrets updated within `delete_confirm_recent_hours` (default 24) are
+//! probably still in use, so for those the key name must be typed instead
+//! of answering y/N.
+//!
+//! A key containing `*` or `?` is a case-sensitive glob (`LEGACY_*`): every
+//! matching secret is listed, confirmed once, and deleted in one save, with
+//! one audit entry per key.  A glob that matches nothing is an error.
 
+use chrono::{DateTime, Utc};
 use dialoguer::Confirm;
+use zeroize::Zeroize;
 
+use super::search::glob_match_case_sensitive;
 use crate::cli::output;
 use crate::cli::{load_keyfile, prompt_password_for_vault, vault_path, Cli};
 use crate::errors::{EnvVaultError, Result};
+use crate::vault::secret::SecretMetadata;
 use crate::vault::VaultStore;
 
+/// Default for `delete_confirm_recent_hours` in `.envvault.toml`.
+pub const DEFAULT_RECENT_HOURS: u64 = 24;
+
 /// Execute the `delete` command.
 pub fn execute(cli: &Cli, key: &str, force: bool) -> Result<()> {
-    let path = vault_path(cli)?;
-
-    // Unless --force is set, ask for confirmation before deleting.
-    if !force {
-        let confirmed = Confirm::new()
-            .with_prompt(format!("Delete secret '{key}'?"))
-            .default(false)
-            .interact()
-            .map_err(|e| EnvVaultError::CommandFailed(format!("confirm prompt: {e}")))?;
-
-        if !confirmed {
-            output::info("Cancelled.");
-            return Ok(());
-        }
+    if is_glob(key) {
+        return execute_glob(cli, key, force);
     }
 
+    let path = vault_path(cli)?;
+
     // Open the vault (requires password).
     let keyfile = load_keyfile(cli)?;
     let vault_id = path.to_string_lossy();
     let password = prompt_password_for_vault(Some(&vault_id))?;
     let mut store = VaultStore::open(&path, password.as_bytes(), keyfile.as_deref())?;
+    crate::cli::warn_rotation_due(&store);
+
+    // Unless --force is set, show what is being deleted and confirm.
+    if !force && !confirm_delete(&store, key, &cli.env)? {
+        output::info("Cancelled.");
+        return Ok(());
+    }
 
     // Delete the secret and save.
     store.delete_secret(key)?;
+    crate::cli::warn_clock_skew(&store);
     store.save()?;
 
     crate::audit::log_audit(cli, "delete", Some(key), None);
+    crate::cli::run_post_write_hook("delete", &cli.env);
     output::success(&format!("Deleted secret '{key}'"));
 
     Ok(())
 }
+
+/// Delete every secret matching the glob `pattern`.
+fn execute_glob(cli: &Cli, pattern: &str, force: bool) -> Result<()> {
+    let path = vault_path(cli)?;
+
+    let keyfile = load_keyfile(cli)?;
+    let vault_id = path.to_string_lossy();
+    let password = prompt_password_for_vault(Some(&vault_id))?;
+    let mut store = VaultStore::open(&path, password.as_bytes(), keyfile.as_deref())?;
+    crate::cli::warn_rotation_due(&store);
+
+    let matches = matching_secrets(&store.list_secrets(), pattern);
+    if matches.is_empty() {
+        return Err(EnvVaultError::CommandFailed(format!(
+            "no secrets in {} match '{pattern}'",
+            cli.env
+        )));
+    }
+
+    if !force && !confirm_delete_many(&matches, pattern, &cli.env)? {
+        output::info("Cancelled.");
+        return Ok(());
+    }
+
+    for meta in &matches {
+        store.delete_secret(&meta.name)?;
+    }
+    crate::cli::warn_clock_skew(&store);
+    store.save()?;
+
+    for meta in &matches {
+        crate::audit::log_audit(cli, "delete", Some(&meta.name), None);
+    }
+    crate::cli::run_post_write_hook("delete", &cli.env);
+    output::success(&format!(
+        "Deleted {} secret{} matching '{pattern}'",
+        matches.len(),
+        if matches.len() == 1 { "" } else { "s" }
+    ));
+
+    Ok(())
+}
+
+/// List the matched secrets and ask for confirmation.
+///
+/// If any of them was updated recently the pattern must be typed.
+fn confirm_delete_many(matches: &[SecretMetadata], pattern: &str, env: &str) -> Result<bool> {
+    let now = Utc::now();
+    let threshold = recent_hours();
+    println!(
+        "{} secret{} in {env} match '{pattern}':",
+        matches.len(),
+        if matches.len() == 1 { "" } else { "s" }
+    );
+    for meta in matches {
+        println!(
+            "  {:<32} updated {}",
+            meta.name,
+            describe_age(meta.updated_at, now)
+        );
+    }
+
+    let recent: Vec<&str> = matches
+        .iter()
+        .filter(|m| requires_typed_name(m.updated_at, now, threshold))
+        .map(|m| m.name.as_str())
+        .collect();
+    if recent.is_empty() {
+        Confirm::new()
+            .with_prompt(format!("Delete {} secrets?", matches.len()))
+            .default(false)
+            .interact()
+            .map_err(|e| EnvVaultError::CommandFailed(format!("confirm prompt: {e}")))
+    } else {
+        output::warning(&format!(
+            "{} updated recently and may still be in use.",
+            recent.join(", ")
+        ));
+        crate::cli::confirm_by_typing(pattern, &format!("Type '{pattern}' to delete them"))
+    }
+}
+
+/// Whether a `delete` key argument is a glob rather than a name.
+pub fn is_glob(key: &str) -> bool {
+    key.contains(['*', '?'])
+}
+
+/// The secrets whose names match `pattern`, sorted by name.
+pub fn matching_secrets(secrets: &[SecretMetadata], pattern: &str) -> Vec<SecretMetadata> {
+    let mut matches: Vec<SecretMetadata> = secrets
+        .iter()
+        .filter(|m| glob_match_case_sensitive(pattern, &m.name))
+        .cloned()
+        .collect();
+    matches.sort_by(|a, b| a.name.cmp(&b.name));
+    matches
+}
+
+/// Print the secret's details and ask for confirmation.
+fn confirm_delete(store: &VaultStore, key: &str, env: &str) -> Result<bool> {
+    let meta = store
+        .list_secrets()
+        .into_iter()
+        .find(|m| m.name == key)
+        .ok_or_else(|| EnvVaultError::SecretNotFound(key.to_string()))?;
+    let mut value = store.get_secret(key)?;
+    let value_len = value.chars().count();
+    value.zeroize();
+
+    let now = Utc::now();
+    println!("Secret '{key}' in {env}:");
+    for line in confirmation_details(&meta, value_len, now) {
+        println!("  {line}");
+    }
+
+    if requires_typed_name(meta.updated_at, now, recent_hours()) {
+        output::warning(&format!(
+            "'{key}' was updated recently and may still be in use."
+        ));
+        crate::cli::confirm_by_typing(key, &format!("Type '{key}' to delete it"))
+    } else {
+        Confirm::new()
+            .with_prompt(format!("Delete secret '{key}'?"))
+            .default(false)
+            .interact()
+            .map_err(|e| EnvVaultError::CommandFailed(format!("confirm prompt: {e}")))
+    }
+}
+
+/// `delete_confirm_recent_hours` from `.envvault.toml`.
+fn recent_hours() -> u64 {
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| crate::config::Settings::load(&cwd).ok())
+        .map_or(DEFAULT_RECENT_HOURS, |s| s.delete_confirm_recent_hours)
+}
+
+/// Lines shown above the confirmation prompt.
+pub fn confirmation_details(
+    meta: &SecretMetadata,
+    value_len: usize,
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let mut lines = vec![
+        format!("created   {}", meta.created_at.format("%Y-%m-%d %H:%M:%S")),
+        format!(
+            "updated   {} ({})",
+            meta.updated_at.format("%Y-%m-%d %H:%M:%S"),
+            describe_age(meta.updated_at, now)
+        ),
+        format!(
+            "value     {value_len} character{}",
+            if value_len == 1 { "" } else { "s" }
+        ),
+    ];
+    if let Some(ref encoding) = meta.encoding {
+        lines.push(format!("encoding  {encoding}"));
+    }
+    lines
+}
+
+/// Whether a secret last updated at `updated_at` needs its name typed.
+///
+/// A threshold of 0 turns the check off.  Future-dated timestamps count
+/// as recent.
+pub fn requires_typed_name(
+    updated_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+    threshold_hours: u64,
+) -> bool {
+    if threshold_hours == 0 {
+        return false;
+    }
+    let age = now.signed_duration_since(updated_at).num_seconds();
+    u64::try_from(age).map_or(true, |age| age < threshold_hours.saturating_mul(3600))
+}
+
+/// Rough age such as "3 hours ago" or "12 days ago".
+fn describe_age(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let age = now.signed_duration_since(at);
+    let (count, unit) = if age.num_seconds() < 0 {
+        return "in the future".to_string();
+    } else if age.num_minutes() < 1 {
+        return "just now".to_string();
+    } else if age.num_hours() < 1 {
+        (age.num_minutes(), "minute")
+    } else if age.num_days() < 1 {
+        (age.num_hours(), "hour")
+    } else {
+        (age.num_days(), "day")
+    };
+    format!("{count} {unit}{} ago", if count == 1 { "" } else { "s" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn meta(updated_at: DateTime<Utc>) -> SecretMetadata {
+        named("API_KEY", updated_at)
+    }
+
+    fn named(name: &str, updated_at: DateTime<Utc>) -> SecretMetadata {
+        SecretMetadata {
+            name: name.into(),
+            created_at: updated_at - Duration::days(30),
+            updated_at,
+            encoding: Some("base64".into()),
+            access_count: None,
+            last_accessed: None,
+            expires_at: None,
+            description: None,
+            tags: Vec::new(),
+            order: None,
+        }
+    }
+
+    #[test]
+    fn details_show_timestamps_length_and_encoding() {
+        let now = Utc::now();
+        let lines = confirmation_details(&meta(now - Duration::hours(3)), 42, now);
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("created   "));
+        assert!(lines[1].ends_with("(3 hours ago)"), "{}", lines[1]);
+        assert_eq!(lines[2], "value     42 characters");
+        assert_eq!(lines[3], "encoding  base64");
+
+        let mut plain = meta(now - Duration::days(1));
+        plain.encoding = None;
+        let lines = confirmation_details(&plain, 1, now);
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].ends_with("(1 day ago)"), "{}", lines[1]);
+        assert_eq!(lines[2], "value     1 character");
+    }
+
+    #[test]
+    fn typed_name_required_only_within_threshold() {
+        let now = Utc::now();
+        assert!(requires_typed_name(now - Duration::hours(23), now, 24));
+        assert!(!requires_typed_name(now - Duration::hours(25), now, 24));
+        assert!(requires_typed_name(now - Duration::hours(25), now, 48));
+        // Future-dated timestamps are treated as recent.
+        assert!(requires_typed_name(now + Duration::hours(1), now, 24));
+        // 0 disables the check; huge values do not overflow.
+        assert!(!requires_typed_name(now, now, 0));
+        assert!(requires_typed_name(
+            now - Duration::days(3650),
+            now,
+            u64::MAX
+        ));
+    }
+
+    #[test]
+    fn globs_match_case_sensitively_in_name_order() {
+        assert!(is_glob("LEGACY_*"));
+        assert!(is_glob("KEY_?"));
+        assert!(!is_glob("LEGACY_TOKEN"));
+
+        let now = Utc::now();
+        let secrets = [
+            named("LEGACY_TOKEN", now),
+            named("API_KEY", now),
+            named("LEGACY_DB", now),
+            named("legacy_lower", now),
+        ];
+        let names: Vec<String> = matching_secrets(&secrets, "LEGACY_*")
+            .into_iter()
+            .map(|m| m.name)
+            .collect();
+        assert_eq!(names, ["LEGACY_DB", "LEGACY_TOKEN"]);
+        assert!(matching_secrets(&secrets, "NOPE_*").is_empty());
+    }
+}
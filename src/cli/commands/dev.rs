@@ -0,0 +1,58 @@
+//! `envvault dev` — generate test fixtures (only with the `dev-tools` feature).
+//!
+//! Subcommands:
+//! - `envvault dev gen-vault`   — vault with seeded pseudo-random secrets
+//! - `envvault dev gen-corrupt` — small vault damaged in a standard way
+//!
+//! Both use the fast KDF profile, so the output is only fit for tests.
+//! The heavy lifting lives in `crate::devtools`.
+
+use std::path::Path;
+
+use crate::cli::output;
+use crate::cli::Cli;
+use crate::devtools::{self, CorruptMode, GenVaultOptions};
+use crate::errors::Result;
+
+/// Execute `envvault dev gen-vault`.
+pub fn execute_gen_vault(
+    cli: &Cli,
+    secrets: usize,
+    value_size: usize,
+    password: &str,
+    seed: u64,
+    out: &str,
+) -> Result<()> {
+    let options = GenVaultOptions {
+        secrets,
+        value_size,
+        environment: cli.env.clone(),
+        password: password.to_string(),
+        seed,
+    };
+
+    devtools::generate_vault(Path::new(out), &options)?;
+
+    output::success(&format!(
+        "Generated '{}' vault with {secrets} secrets at {out}",
+        cli.env
+    ));
+    Ok(())
+}
+
+/// Execute `envvault dev gen-corrupt`.
+pub fn execute_gen_corrupt(mode: &str, password: &str, out: &str) -> Result<()> {
+    let mode: CorruptMode = mode.parse()?;
+    let path = Path::new(out);
+
+    let options = GenVaultOptions {
+        secrets: 1,
+        password: password.to_string(),
+        ..GenVaultOptions::default()
+    };
+    devtools::generate_vault(path, &options)?;
+    devtools::corrupt_vault(path, mode)?;
+
+    output::success(&format!("Generated vault with {mode} corruption at {out}"));
+    Ok(())
+}
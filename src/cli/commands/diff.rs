@@ -3,11 +3,18 @@
 //! Usage:
 //!   envvault diff staging              # compare dev (default) vs staging
 //!   envvault --env prod diff staging --show-values
+//!   envvault diff --against-file teammate.env   # vault vs a plaintext .env
+//!
+//! `--against-file` parses the file like `import` does and compares it
+//! with the current environment's vault without importing anything: keys
+//! only in the file show as added, keys only in the vault as removed.
 
 use std::collections::BTreeSet;
+use std::path::Path;
 
 use zeroize::Zeroize;
 
+use crate::cli::env_parser::{read_env_file, warn_if_crlf};
 use crate::cli::output;
 use crate::cli::{load_keyfile, prompt_password_for_vault, Cli};
 use crate::errors::{EnvVaultError, Result};
@@ -23,8 +30,7 @@ pub struct DiffResult {
 
 /// Execute the `diff` command.
 pub fn execute(cli: &Cli, target_env: &str, show_values: bool) -> Result<()> {
-    let cwd = std::env::current_dir()?;
-    let vault_dir = cwd.join(&cli.vault_dir);
+    let vault_dir = crate::cli::resolve_vault_dir(cli)?;
 
     let env = &cli.env;
     let source_path = vault_dir.join(format!("{env}.vault"));
@@ -69,7 +75,7 @@ pub fn execute(cli: &Cli, target_env: &str, show_values: bool) -> Result<()> {
         cli,
         "diff",
         None,
-        Some(&format!("compared {env} vs {target_env}")),
+        Some(&crate::audit::AuditDetails::diff(env, target_env).to_string()),
     );
 
     // Print results.
@@ -93,6 +99,49 @@ pub fn execute(cli: &Cli, target_env: &str, show_values: bool) -> Result<()> {
     Ok(())
 }
 
+/// Execute `diff --against-file <path>`: compare the current environment's
+/// vault with a plaintext `.env` file.
+pub fn execute_against_file(cli: &Cli, file: &str, show_values: bool) -> Result<()> {
+    let file_path = Path::new(file);
+    if !file_path.exists() {
+        return Err(EnvVaultError::CommandFailed(format!(
+            "file not found: {}",
+            file_path.display()
+        )));
+    }
+    let vault_path = crate::cli::vault_path(cli)?;
+
+    let parsed = read_env_file(file_path)?;
+    warn_if_crlf(file_path, &parsed);
+    let mut file_secrets = parsed.secrets;
+
+    let keyfile = load_keyfile(cli)?;
+    let vault_id = vault_path.to_string_lossy();
+    let password = prompt_password_for_vault(Some(&vault_id))?;
+    let store = VaultStore::open(&vault_path, password.as_bytes(), keyfile.as_deref())?;
+    let mut vault_secrets = store.get_all_secrets()?;
+
+    let diff = compute_diff(&vault_secrets, &file_secrets);
+
+    crate::audit::log_audit(
+        cli,
+        "diff",
+        None,
+        Some(&crate::audit::AuditDetails::diff_file(&cli.env, file).to_string()),
+    );
+
+    print_diff(cli, file, &diff, &vault_secrets, &file_secrets, show_values);
+
+    for v in vault_secrets.values_mut() {
+        v.zeroize();
+    }
+    for v in file_secrets.values_mut() {
+        v.zeroize();
+    }
+
+    Ok(())
+}
+
 /// Compare two secret maps and categorize keys.
 pub fn compute_diff(
     source: &std::collections::HashMap<String, String>,
@@ -130,10 +179,11 @@ pub fn compute_diff(
     }
 }
 
-/// Print the diff results with colored output.
+/// Print the diff results with colored output.  `target_name` names what the
+/// current environment is compared with: an environment or a file.
 fn print_diff(
     cli: &Cli,
-    target_env: &str,
+    target_name: &str,
     diff: &DiffResult,
     source: &std::collections::HashMap<String, String>,
     target: &std::collections::HashMap<String, String>,
@@ -145,7 +195,7 @@ fn print_diff(
         "\n{} {} vs {}",
         style("Diff:").bold(),
         style(&cli.env).cyan(),
-        style(target_env).cyan()
+        style(target_name).cyan()
     );
     println!();
 
@@ -178,10 +228,11 @@ fn print_diff(
     for key in &diff.changed {
         if show_values {
             println!(
-                "  {} {} = {} → {}",
+                "  {} {} = {} {} {}",
                 style("~").yellow().bold(),
                 style(key).yellow(),
                 style(&source[key]).red(),
+                output::Symbol::Arrow.glyph(),
                 style(&target[key]).green()
             );
         } else {
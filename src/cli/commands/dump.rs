@@ -0,0 +1,197 @@
+//! `envvault dump` — print every decrypted secret in one go.
+//!
+//! Built for pipelines such as `envvault dump --json | jq '.DATABASE_URL'`.
+//! Stdout carries nothing but the dump itself; the password prompt,
+//! warnings, and errors all go to stderr so `jq` never chokes.
+//!
+//! Output shapes:
+//! - default:                  `KEY=value` lines (like `printenv`)
+//! - `--json`:                 `{"KEY":"value",...}` (compact, one line)
+//! - `--json --with-metadata`: `{"KEY":{"value":"...","created_at":"...","updated_at":"..."},...}`
+//!   (plus `"encoding"` for secrets that carry an encoding hint)
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use zeroize::Zeroize;
+
+use crate::cli::{load_keyfile, prompt_password_for_vault, vault_path, Cli};
+use crate::errors::{EnvVaultError, Result};
+use crate::vault::{SecretMetadata, VaultStore};
+
+/// One secret in the `--with-metadata` shape.
+#[derive(Serialize)]
+struct DumpEntry<'a> {
+    value: &'a str,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    encoding: Option<&'a str>,
+}
+
+/// Execute the `dump` command.
+pub fn execute(cli: &Cli, json: bool, with_metadata: bool) -> Result<()> {
+    let path = vault_path(cli)?;
+    let keyfile = load_keyfile(cli)?;
+
+    let vault_id = path.to_string_lossy();
+    let password = prompt_password_for_vault(Some(&vault_id))?;
+    let mut store = match VaultStore::open(&path, password.as_bytes(), keyfile.as_deref()) {
+        Ok(store) => store,
+        Err(e) => {
+            #[cfg(feature = "audit-log")]
+            crate::audit::log_auth_failure(cli, &e.to_string());
+            return Err(e);
+        }
+    };
+    crate::cli::warn_rotation_due(&store);
+    crate::cli::enable_access_tracking(&mut store);
+
+    // Sort by key for deterministic output.
+    let mut secrets: BTreeMap<String, String> = store.get_all_secrets()?.into_iter().collect();
+    crate::cli::persist_access(&mut store);
+
+    let mut content = if !json {
+        format_as_lines(&secrets)
+    } else if with_metadata {
+        format_as_json_with_metadata(&secrets, &store.list_secrets())?
+    } else {
+        format_as_json(&secrets)?
+    };
+
+    // A single write keeps the document contiguous on stdout.
+    let write_result = io::stdout().write_all(content.as_bytes());
+
+    if write_result.is_ok() {
+        crate::audit::log_read_audit(
+            cli,
+            "dump",
+            None,
+            Some(&format!("{} secrets", secrets.len())),
+        );
+    }
+
+    // Zeroize plaintext secrets before returning.
+    for v in secrets.values_mut() {
+        v.zeroize();
+    }
+    content.zeroize();
+
+    write_result.map_err(EnvVaultError::from)
+}
+
+/// Format secrets as `KEY=value` lines, unquoted.
+fn format_as_lines(secrets: &BTreeMap<String, String>) -> String {
+    use std::fmt::Write;
+    let mut out = String::new();
+    for (key, value) in secrets {
+        let _ = writeln!(out, "{key}={value}");
+    }
+    out
+}
+
+/// Format secrets as a compact JSON object followed by a newline.
+fn format_as_json(secrets: &BTreeMap<String, String>) -> Result<String> {
+    let mut out = serde_json::to_string(secrets)
+        .map_err(|e| EnvVaultError::SerializationError(format!("JSON dump: {e}")))?;
+    out.push('\n');
+    Ok(out)
+}
+
+/// Format secrets as a compact JSON object of `{value, created_at, updated_at}`.
+fn format_as_json_with_metadata(
+    secrets: &BTreeMap<String, String>,
+    metadata: &[SecretMetadata],
+) -> Result<String> {
+    let entries: BTreeMap<&str, DumpEntry<'_>> = metadata
+        .iter()
+        .filter_map(|m| {
+            secrets.get(&m.name).map(|value| {
+                (
+                    m.name.as_str(),
+                    DumpEntry {
+                        value,
+                        created_at: m.created_at,
+                        updated_at: m.updated_at,
+                        encoding: m.encoding.as_deref(),
+                    },
+                )
+            })
+        })
+        .collect();
+
+    let mut out = serde_json::to_string(&entries)
+        .map_err(|e| EnvVaultError::SerializationError(format!("JSON dump: {e}")))?;
+    out.push('\n');
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> BTreeMap<String, String> {
+        let mut secrets = BTreeMap::new();
+        secrets.insert("B".into(), "two words".into());
+        secrets.insert("A".into(), "line1\nline2".into());
+        secrets
+    }
+
+    #[test]
+    fn json_dump_is_a_single_compact_line() {
+        let out = format_as_json(&sample()).unwrap();
+        assert_eq!(out.matches('\n').count(), 1);
+        assert!(out.ends_with('\n'));
+
+        let parsed: BTreeMap<String, String> = serde_json::from_str(&out).unwrap();
+        assert_eq!(parsed, sample());
+    }
+
+    #[test]
+    fn json_dump_with_metadata_nests_values() {
+        let now = Utc::now();
+        let metadata = vec![
+            SecretMetadata {
+                name: "A".into(),
+                created_at: now,
+                updated_at: now,
+                encoding: Some("base64".into()),
+                access_count: None,
+                last_accessed: None,
+                expires_at: None,
+                description: None,
+                tags: Vec::new(),
+                order: None,
+            },
+            SecretMetadata {
+                name: "B".into(),
+                created_at: now,
+                updated_at: now,
+                encoding: None,
+                access_count: None,
+                last_accessed: None,
+                expires_at: None,
+                description: None,
+                tags: Vec::new(),
+                order: None,
+            },
+        ];
+
+        let out = format_as_json_with_metadata(&sample(), &metadata).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(parsed["B"]["value"], "two words");
+        assert!(parsed["A"]["created_at"].is_string());
+        assert!(parsed["A"]["updated_at"].is_string());
+        assert_eq!(parsed["A"]["encoding"], "base64");
+        assert!(parsed["B"].get("encoding").is_none());
+    }
+
+    #[test]
+    fn line_dump_is_unquoted() {
+        let mut secrets = BTreeMap::new();
+        secrets.insert("KEY".into(), "has space".into());
+        assert_eq!(format_as_lines(&secrets), "KEY=has space\n");
+    }
+}
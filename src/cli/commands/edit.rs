@@ -2,46 +2,64 @@
 //!
 //! Decrypts all secrets to a temporary file, launches `$VISUAL` / `$EDITOR` / `vi`,
 //! and applies any changes back to the vault on save.
+//!
+//! Editors may leave plaintext behind: vim writes swap files and `file~`
+//! backups, emacs writes `.#file` locks and `#file#` autosaves, and many
+//! editors save by writing a new file and renaming it over the old one.
+//! After the editor exits, the temp file *and* any such siblings are
+//! overwritten and removed, and vim/nvim are launched with swap, backup,
+//! and undo files disabled unless `editor_no_swapfile = false`.
+//!
+//! Names added in the editor must satisfy the project's `[key_policy]`;
+//! if any do not, nothing is written and every offending name is listed.
 
 use std::collections::HashMap;
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
 
 use zeroize::Zeroize;
 
-use crate::cli::env_parser::parse_env_line;
-use crate::cli::output;
+use crate::cli::commands::export::{ordered_keys, KeyOrder};
+use crate::cli::env_parser::parse_env_content;
+use crate::cli::{key_policy, output};
 use crate::cli::{load_keyfile, prompt_password_for_vault, vault_path, Cli};
 use crate::errors::{EnvVaultError, Result};
 use crate::vault::VaultStore;
 
 /// Execute the `edit` command.
-pub fn execute(cli: &Cli) -> Result<()> {
+pub fn execute(cli: &Cli, sort: &str) -> Result<()> {
+    let order = KeyOrder::parse(sort)?;
+    let policy = key_policy::load(cli)?;
     let path = vault_path(cli)?;
 
     let keyfile = load_keyfile(cli)?;
     let vault_id = path.to_string_lossy();
     let password = prompt_password_for_vault(Some(&vault_id))?;
     let mut store = VaultStore::open(&path, password.as_bytes(), keyfile.as_deref())?;
+    crate::cli::warn_rotation_due(&store);
 
     let mut secrets = store.get_all_secrets()?;
 
     // Write secrets to a temp file in KEY=VALUE format.
-    let tmp_path = write_temp_file(&secrets)?;
+    let tmp_path = write_temp_file(&secrets, &ordered_keys(&store, order))?;
+    let identity_before = file_identity(&tmp_path);
 
     // Find the editor.
     let editor = find_editor();
 
     // Launch editor.
-    let status = Command::new(&editor)
-        .arg(&tmp_path)
-        .status()
-        .map_err(|e| EnvVaultError::EditorError(format!("failed to launch '{editor}': {e}")))?;
+    let status = match launch_editor(&editor, &tmp_path, no_swapfile_enabled()) {
+        Ok(status) => status,
+        Err(e) => {
+            cleanup_edit_files(&tmp_path, identity_before);
+            return Err(e);
+        }
+    };
 
     if !status.success() {
-        secure_delete(&tmp_path);
+        cleanup_edit_files(&tmp_path, identity_before);
         for v in secrets.values_mut() {
             v.zeroize();
         }
@@ -52,17 +70,35 @@ pub fn execute(cli: &Cli) -> Result<()> {
     }
 
     // Parse the edited file.
-    let mut edited_content = fs::read_to_string(&tmp_path)
-        .map_err(|e| EnvVaultError::EditorError(format!("failed to read edited file: {e}")))?;
+    let read_result = fs::read_to_string(&tmp_path)
+        .map_err(|e| EnvVaultError::EditorError(format!("failed to read edited file: {e}")));
+
+    // Securely wipe and delete the temp file and editor leftovers immediately.
+    cleanup_edit_files(&tmp_path, identity_before);
 
-    // Securely wipe and delete temp file immediately.
-    secure_delete(&tmp_path);
+    let mut edited_content = read_result?;
 
     let mut new_secrets = parse_edited_content(&edited_content);
 
     // Zeroize the raw edited content — no longer needed.
     edited_content.zeroize();
 
+    // New names must satisfy the key policy; report them all at once.
+    if let Some(ref policy) = policy {
+        let mut added: Vec<&str> = new_secrets
+            .keys()
+            .filter(|key| !secrets.contains_key(*key))
+            .map(String::as_str)
+            .collect();
+        added.sort_unstable();
+        if let Err(e) = policy.enforce(added) {
+            for v in secrets.values_mut().chain(new_secrets.values_mut()) {
+                v.zeroize();
+            }
+            return Err(e);
+        }
+    }
+
     // Compute and apply changes.
     let (added, removed, changed) = apply_changes(&mut store, &secrets, &new_secrets)?;
 
@@ -79,16 +115,16 @@ pub fn execute(cli: &Cli) -> Result<()> {
         return Ok(());
     }
 
+    crate::cli::warn_clock_skew(&store);
     store.save()?;
 
     crate::audit::log_audit(
         cli,
         "edit",
         None,
-        Some(&format!(
-            "{added} added, {removed} removed, {changed} changed"
-        )),
+        Some(&crate::audit::AuditDetails::edit(added, removed, changed).to_string()),
     );
+    crate::cli::run_post_write_hook("edit", &cli.env);
 
     output::success(&format!(
         "Edit complete: {added} added, {removed} removed, {changed} changed"
@@ -97,11 +133,15 @@ pub fn execute(cli: &Cli) -> Result<()> {
     Ok(())
 }
 
-/// Write secrets to a temp file in KEY=VALUE format.
-/// Returns the path to the temp file.
-fn write_temp_file(secrets: &HashMap<String, String>) -> Result<PathBuf> {
-    let mut sorted: Vec<(&String, &String)> = secrets.iter().collect();
-    sorted.sort_by_key(|(k, _)| *k);
+/// Write secrets to a temp file in KEY=VALUE format, in the order of
+/// `keys`.  Returns the path to the temp file.
+///
+/// Lines always end in `\n`, on Windows too.
+fn write_temp_file(secrets: &HashMap<String, String>, keys: &[String]) -> Result<PathBuf> {
+    let sorted: Vec<(&String, &String)> = keys
+        .iter()
+        .filter_map(|key| secrets.get_key_value(key))
+        .collect();
 
     // Build a unique temp file path using PID + timestamp.
     let tmp_dir = std::env::temp_dir();
@@ -194,15 +234,53 @@ fn find_editor() -> String {
     "vi".to_string()
 }
 
+/// Whether vim/nvim should be launched without swap/backup/undo files.
+fn no_swapfile_enabled() -> bool {
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| crate::config::Settings::load(&cwd).ok())
+        .map_or(true, |settings| settings.editor_no_swapfile)
+}
+
+/// Extra arguments that stop vim/nvim from writing plaintext copies.
+///
+/// `-n` disables the swap file, `-i NONE` skips viminfo/shada, and the
+/// `set` command turns off backup and persistent undo files.
+fn vim_hardening_args(editor: &str) -> &'static [&'static str] {
+    let name = Path::new(editor)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(editor);
+
+    match name {
+        "vim" | "nvim" | "gvim" | "mvim" => &[
+            "-n",
+            "-i",
+            "NONE",
+            "-c",
+            "set nobackup nowritebackup noundofile",
+        ],
+        _ => &[],
+    }
+}
+
+/// Launch the editor on `path` and wait for it to exit.
+fn launch_editor(editor: &str, path: &Path, no_swapfile: bool) -> Result<ExitStatus> {
+    let mut cmd = Command::new(editor);
+    if no_swapfile {
+        cmd.args(vim_hardening_args(editor));
+    }
+    cmd.arg(path)
+        .status()
+        .map_err(|e| EnvVaultError::EditorError(format!("failed to launch '{editor}': {e}")))
+}
+
 /// Parse edited content back into a key-value map.
+///
+/// Editors that save with CRLF (e.g. Notepad) are handled: line endings
+/// are normalized before parsing, so no value picks up a `\r`.
 pub fn parse_edited_content(content: &str) -> HashMap<String, String> {
-    let mut map = HashMap::new();
-    for line in content.lines() {
-        if let Some((key, value)) = parse_env_line(line) {
-            map.insert(key.to_string(), value.to_string());
-        }
-    }
-    map
+    parse_env_content(content).secrets
 }
 
 /// Apply changes between old and new secrets. Returns (added, removed, changed) counts.
@@ -241,13 +319,80 @@ fn apply_changes(
     Ok((added, removed, changed))
 }
 
+/// Device and inode of a file, used to detect editors that save by
+/// replacing the file rather than writing it in place.
+#[cfg(unix)]
+fn file_identity(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    fs::metadata(path).ok().map(|m| (m.dev(), m.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_identity(_path: &Path) -> Option<(u64, u64)> {
+    None
+}
+
+/// Securely delete the temp file and any editor artifacts next to it.
+///
+/// `identity_before` is the temp file's identity when it was written; if
+/// the editor replaced the file, the original blocks were never
+/// overwritten and zeroing the new file cannot reach them.
+fn cleanup_edit_files(tmp_path: &Path, identity_before: Option<(u64, u64)>) {
+    let identity_after = file_identity(tmp_path);
+    if identity_before.is_some() && identity_after.is_some() && identity_before != identity_after {
+        output::debug(&format!(
+            "editor replaced {} with a new file; overwrite-before-delete may not reach the original data",
+            tmp_path.display()
+        ));
+    }
+
+    for artifact in find_editor_artifacts(tmp_path) {
+        output::debug(&format!("removing editor artifact {}", artifact.display()));
+        secure_delete(&artifact);
+    }
+
+    secure_delete(tmp_path);
+}
+
+/// Find editor leftovers for `path` in its directory: vim swap files
+/// (`.name.swp`, `.name.swo`, ...), backups (`name~`), and emacs lock
+/// and autosave files (`.#name`, `#name#`).
+fn find_editor_artifacts(path: &Path) -> Vec<PathBuf> {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name().and_then(|n| n.to_str())) else {
+        return Vec::new();
+    };
+
+    let swap_prefix = format!(".{name}.sw");
+    let backup = format!("{name}~");
+    let lock = format!(".#{name}");
+    let autosave = format!("#{name}#");
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry.file_name().to_str().is_some_and(|candidate| {
+                candidate.starts_with(&swap_prefix)
+                    || candidate == backup
+                    || candidate == lock
+                    || candidate == autosave
+            })
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
+
 /// Overwrite a file's contents with zeros before deleting it.
 /// This reduces the chance of secret recovery from disk.
+/// Symlinks (e.g. emacs lock files) are removed without following them.
 /// Best-effort: failures are silently ignored.
-fn secure_delete(path: &PathBuf) {
-    if let Ok(metadata) = fs::metadata(path) {
+pub(crate) fn secure_delete(path: &Path) {
+    if let Ok(metadata) = fs::symlink_metadata(path) {
         let len = metadata.len() as usize;
-        if len > 0 {
+        if metadata.is_file() && len > 0 {
             if let Ok(mut file) = fs::OpenOptions::new().write(true).open(path) {
                 let zeros = vec![0u8; len];
                 let _ = file.write_all(&zeros);
@@ -291,17 +436,108 @@ mod tests {
         secrets.insert("A".into(), "1".into());
         secrets.insert("B".into(), "has space".into());
 
-        let tmp_path = write_temp_file(&secrets).unwrap();
+        let keys = ["B".to_string(), "A".to_string()];
+        let tmp_path = write_temp_file(&secrets, &keys).unwrap();
         let content = fs::read_to_string(&tmp_path).unwrap();
         assert!(content.contains("A=1"));
         assert!(content.contains("B=\"has space\""));
+        // Keys come in the order given.
+        assert!(content.find("B=").unwrap() < content.find("A=").unwrap());
         let _ = fs::remove_file(&tmp_path);
     }
 
+    #[test]
+    fn vim_gets_hardening_args_other_editors_do_not() {
+        assert!(vim_hardening_args("vim").contains(&"-n"));
+        assert!(vim_hardening_args("/usr/bin/nvim").contains(&"-n"));
+        assert!(vim_hardening_args("nano").is_empty());
+        assert!(vim_hardening_args("code").is_empty());
+    }
+
+    #[test]
+    fn find_editor_artifacts_matches_only_siblings_of_the_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("edit.env");
+        for name in [
+            "edit.env",
+            "edit.env~",
+            ".edit.env.swp",
+            ".edit.env.swo",
+            ".#edit.env",
+            "#edit.env#",
+            "other.env~",
+            ".other.env.swp",
+        ] {
+            fs::write(dir.path().join(name), "SECRET=1").unwrap();
+        }
+
+        let mut found: Vec<String> = find_editor_artifacts(&file)
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            [
+                "#edit.env#",
+                ".#edit.env",
+                ".edit.env.swo",
+                ".edit.env.swp",
+                "edit.env~"
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cleanup_handles_editor_that_replaces_file_and_leaves_backup() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("edit.env");
+        fs::write(&file, "A=1\n").unwrap();
+        let identity_before = file_identity(&file);
+
+        // An "editor" that keeps a backup and saves via a new inode.
+        let script = dir.path().join("fake-editor.sh");
+        fs::write(
+            &script,
+            "#!/bin/sh\nprintf 'A=2\\n' > \"$1.new\"\ncp \"$1\" \"$1~\"\nmv \"$1.new\" \"$1\"\n",
+        )
+        .unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let status = launch_editor(script.to_str().unwrap(), &file, true).unwrap();
+        assert!(status.success());
+        assert_ne!(file_identity(&file), identity_before);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "A=2\n");
+        assert!(dir.path().join("edit.env~").exists());
+
+        cleanup_edit_files(&file, identity_before);
+
+        assert!(!file.exists());
+        assert!(!dir.path().join("edit.env~").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn secure_delete_does_not_follow_symlinks() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let target = dir.path().join("target");
+        fs::write(&target, "keep me").unwrap();
+        let link = dir.path().join(".#edit.env");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        secure_delete(&link);
+
+        assert!(!link.exists());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "keep me");
+    }
+
     #[test]
     fn write_temp_file_sets_permissions() {
         let secrets = HashMap::new();
-        let tmp_path = write_temp_file(&secrets).unwrap();
+        let tmp_path = write_temp_file(&secrets, &[]).unwrap();
 
         #[cfg(unix)]
         {
@@ -1,38 +1,47 @@
 //! `envvault env clone` — clone an environment's secrets to a new vault.
+//!
+//! With `--from-file`, the new vault is seeded from a file instead of the
+//! current environment, using the same parsers as `envvault import`.
 
+use std::path::Path;
+
+use chrono::Utc;
 use zeroize::Zeroize;
 
+use crate::audit::AuditDetails;
 use crate::cli::output;
 use crate::cli::{
     load_keyfile, prompt_new_password, prompt_password_for_vault, validate_env_name, Cli,
 };
 use crate::config::Settings;
+use crate::crypto::Cipher;
 use crate::errors::{EnvVaultError, Result};
-use crate::vault::VaultStore;
+use crate::vault::{SecretMetadata, VaultStore};
 
 /// Execute `envvault env clone <target>`.
-pub fn execute(cli: &Cli, target: &str, new_password: bool) -> Result<()> {
+pub fn execute(cli: &Cli, target: &str, new_password: bool, from_file: Option<&str>) -> Result<()> {
     validate_env_name(target)?;
 
-    let cwd = std::env::current_dir()?;
-    let vault_dir = cwd.join(&cli.vault_dir);
+    let vault_dir = crate::cli::resolve_vault_dir(cli)?;
     let env = &cli.env;
     let source_path = vault_dir.join(format!("{env}.vault"));
     let target_path = vault_dir.join(format!("{target}.vault"));
 
-    if !source_path.exists() {
-        return Err(EnvVaultError::EnvironmentNotFound(cli.env.clone()));
-    }
     if target_path.exists() {
         return Err(EnvVaultError::VaultAlreadyExists(target_path));
     }
+    if let Some(file) = from_file {
+        return clone_from_file(cli, target, &target_path, Path::new(file));
+    }
+    if !source_path.exists() {
+        return Err(EnvVaultError::EnvironmentNotFound(cli.env.clone()));
+    }
 
-    // Open source vault and decrypt all secrets.
+    // Open the source vault.
     let keyfile = load_keyfile(cli)?;
     let vault_id = source_path.to_string_lossy();
     let password = prompt_password_for_vault(Some(&vault_id))?;
     let source = VaultStore::open(&source_path, password.as_bytes(), keyfile.as_deref())?;
-    let mut secrets = source.get_all_secrets()?;
 
     // Determine the target password.
     let target_pw = if new_password {
@@ -42,43 +51,139 @@ pub fn execute(cli: &Cli, target: &str, new_password: bool) -> Result<()> {
         password
     };
 
-    // Create the target vault with the same (or new) password.
-    let settings = Settings::load(&cwd)?;
-    let mut target_store = VaultStore::create(
-        &target_path,
-        target_pw.as_bytes(),
-        target,
-        Some(&settings.argon2_params()),
-        keyfile.as_deref(),
-    )?;
-
-    // Copy all secrets.
-    let count = secrets.len();
-    for (name, value) in &secrets {
-        target_store.set_secret(name, value)?;
+    // Stage the target vault with the same (or new) password and the
+    // source's cipher, copying all secrets as bytes (streamed values stay
+    // chunked) and keeping when each was first introduced.  Nothing is
+    // written until the one save.
+    let settings = Settings::load(&std::env::current_dir()?)?;
+    let count = source.secret_count();
+    let applied = apply_staged(cli, env, target, count, || {
+        let mut target_store = VaultStore::create_staged(
+            &target_path,
+            target_pw.as_bytes(),
+            target,
+            Some(&settings.argon2_params()),
+            keyfile.as_deref(),
+            source.cipher(),
+        )?;
+        for meta in source.list_secrets() {
+            target_store.copy_secret_from(&source, &clone_metadata(meta))?;
+        }
+        target_store.save()
+    });
+    applied?;
+
+    crate::cli::run_post_write_hook("env-clone", target);
+
+    output::success(&format!(
+        "Cloned {} secrets from '{}' to '{}' environment",
+        count, cli.env, target
+    ));
+
+    Ok(())
+}
+
+/// Create the `target` vault at `target_path` and import `file` into it.
+///
+/// There is no source vault to borrow a password from, so the new vault's
+/// password is chosen the way `init` chooses one.  The file is parsed
+/// before anything is created, so a bad file leaves no vault behind.
+fn clone_from_file(cli: &Cli, target: &str, target_path: &Path, file: &Path) -> Result<()> {
+    if !file.exists() {
+        return Err(EnvVaultError::CommandFailed(format!(
+            "import file not found: {}",
+            file.display()
+        )));
     }
-    target_store.save()?;
+    let mut secrets = super::import_cmd::parse_import_file(file, None, None, None)?;
+    if secrets.is_empty() {
+        return Err(EnvVaultError::CommandFailed(format!(
+            "no secrets found in {} — nothing to create '{target}' from",
+            file.display()
+        )));
+    }
+
+    let keyfile = load_keyfile(cli)?;
+    output::info(&format!("Choose a password for the '{target}' vault."));
+    let password = prompt_new_password()?;
+    let settings = Settings::load(&std::env::current_dir()?)?;
+    let count = secrets.len();
+    let source = file.display().to_string();
+    let applied = apply_staged(cli, &source, target, count, || {
+        let mut store = VaultStore::create_staged(
+            target_path,
+            password.as_bytes(),
+            target,
+            Some(&settings.argon2_params()),
+            keyfile.as_deref(),
+            Cipher::default(),
+        )?;
+        let mut progress = crate::progress::phase(crate::progress::IMPORT, count);
+        for (key, value) in &secrets {
+            store.set_secret(key, value)?;
+            progress.inc();
+        }
+        drop(progress);
+        store.save()
+    });
 
-    // Zeroize plaintext secrets.
     for value in secrets.values_mut() {
         value.zeroize();
     }
+    applied?;
 
-    crate::audit::log_audit(
-        cli,
-        "env-clone",
-        None,
-        Some(&format!("{count} secrets, {env} -> {target}")),
-    );
+    crate::cli::run_post_write_hook("env-clone", target);
 
     output::success(&format!(
-        "Cloned {} secrets from '{}' to '{}' environment",
-        count, cli.env, target
+        "Created '{target}' environment with {count} secrets from {source}"
     ));
 
     Ok(())
 }
 
+/// Run `write`, which stages the whole target vault and saves it once,
+/// between a `plan` audit entry and an `applied` or `aborted` one, so the
+/// audit trail shows what was attempted even when it failed.
+fn apply_staged(
+    cli: &Cli,
+    from: &str,
+    target: &str,
+    count: usize,
+    write: impl FnOnce() -> Result<()>,
+) -> Result<()> {
+    let details = AuditDetails::env_clone(count, from, target);
+    let log = |stage: &str| {
+        let entry = details.clone().stage(stage).to_string();
+        crate::audit::log_audit(cli, "env-clone", None, Some(&entry));
+    };
+
+    log("plan");
+    match write() {
+        Ok(()) => {
+            log("applied");
+            Ok(())
+        }
+        Err(e) => {
+            log("aborted");
+            Err(EnvVaultError::CommandFailed(format!(
+                "could not create '{target}': {e} — nothing was applied"
+            )))
+        }
+    }
+}
+
+/// Metadata for the copy of a secret in a cloned vault: the original
+/// `created_at` and encoding hint, a fresh `updated_at`, and no access
+/// stats (nothing has read the clone yet).
+fn clone_metadata(meta: SecretMetadata) -> SecretMetadata {
+    SecretMetadata {
+        updated_at: Utc::now(),
+        access_count: None,
+        last_accessed: None,
+        ..meta
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,17 +221,25 @@ mod tests {
 
         let mut target =
             VaultStore::create(&staging_path, b"testpassword1", "staging", None, None).unwrap();
-        for (k, v) in &source_secrets {
-            target.set_secret(k, v).unwrap();
+        for meta in source.list_secrets() {
+            let value = &source_secrets[&meta.name];
+            target
+                .set_secret_with_metadata(&clone_metadata(meta), value)
+                .unwrap();
         }
         target.save().unwrap();
 
-        // Verify target has the same secrets.
+        // Verify target has the same secrets and original created_at.
         let reopened = VaultStore::open(&staging_path, b"testpassword1", None).unwrap();
         let target_secrets = reopened.get_all_secrets().unwrap();
         assert_eq!(target_secrets.len(), 2);
         assert_eq!(target_secrets["DB_URL"], "postgres://localhost");
         assert_eq!(target_secrets["API_KEY"], "secret123");
+        for (before, after) in source.list_secrets().iter().zip(reopened.list_secrets()) {
+            assert_eq!(before.name, after.name);
+            assert_eq!(before.created_at, after.created_at);
+            assert!(after.updated_at >= before.updated_at);
+        }
     }
 
     #[test]
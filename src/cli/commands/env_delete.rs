@@ -12,8 +12,7 @@ use crate::errors::{EnvVaultError, Result};
 pub fn execute(cli: &Cli, name: &str, force: bool) -> Result<()> {
     validate_env_name(name)?;
 
-    let cwd = std::env::current_dir()?;
-    let vault_dir = cwd.join(&cli.vault_dir);
+    let vault_dir = crate::cli::resolve_vault_dir(cli)?;
     let vault_path = vault_dir.join(format!("{name}.vault"));
 
     if !vault_path.exists() {
@@ -28,7 +27,12 @@ pub fn execute(cli: &Cli, name: &str, force: bool) -> Result<()> {
         return Ok(());
     }
 
-    if !force {
+    if !force && crate::cli::needs_confirm_word(name) {
+        if !crate::cli::confirm_env_word(name, "delete the vault")? {
+            output::info("Cancelled.");
+            return Ok(());
+        }
+    } else if !force {
         let confirmed = Confirm::new()
             .with_prompt(format!(
                 "Delete environment '{name}'? This cannot be undone"
@@ -2,7 +2,7 @@
 
 use std::fs;
 
-use comfy_table::{ContentArrangement, Table};
+use chrono::{DateTime, Utc};
 use console::style;
 
 use crate::cli::output;
@@ -11,8 +11,7 @@ use crate::errors::Result;
 
 /// Execute `envvault env list`.
 pub fn execute(cli: &Cli) -> Result<()> {
-    let cwd = std::env::current_dir()?;
-    let vault_dir = cwd.join(&cli.vault_dir);
+    let vault_dir = crate::cli::resolve_vault_dir(cli)?;
 
     if !vault_dir.exists() {
         output::info("No vault directory found.");
@@ -29,9 +28,8 @@ pub fn execute(cli: &Cli) -> Result<()> {
         return Ok(());
     }
 
-    let mut table = Table::new();
-    table.set_content_arrangement(ContentArrangement::Dynamic);
-    table.set_header(vec!["Environment", "Size", "Active"]);
+    let mut table = output::table();
+    table.set_header(vec!["Environment", "Size", "Modified", "Active"]);
 
     for env in &envs {
         let active = if env.name == cli.env {
@@ -40,7 +38,15 @@ pub fn execute(cli: &Cli) -> Result<()> {
             String::new()
         };
 
-        table.add_row(vec![env.name.clone(), format_size(env.size), active]);
+        let modified = env
+            .modified
+            .map_or_else(|| "-".to_string(), output::format_time);
+        table.add_row(vec![
+            env.name.clone(),
+            format_size(env.size),
+            modified,
+            active,
+        ]);
     }
 
     output::info(&format!("{} environment(s) found:", envs.len()));
@@ -53,6 +59,8 @@ pub fn execute(cli: &Cli) -> Result<()> {
 pub struct EnvInfo {
     pub name: String,
     pub size: u64,
+    /// File modification time, if the filesystem reports one.
+    pub modified: Option<DateTime<Utc>>,
 }
 
 /// Scan a vault directory for `*.vault` files.
@@ -68,8 +76,16 @@ pub fn list_environments(vault_dir: &std::path::Path) -> Result<Vec<EnvInfo>> {
             if ext == "vault" {
                 if let Some(stem) = path.file_stem() {
                     let name = stem.to_string_lossy().to_string();
-                    let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
-                    envs.push(EnvInfo { name, size });
+                    let metadata = entry.metadata().ok();
+                    let size = metadata.as_ref().map_or(0, fs::Metadata::len);
+                    let modified = metadata
+                        .and_then(|m| m.modified().ok())
+                        .map(DateTime::<Utc>::from);
+                    envs.push(EnvInfo {
+                        name,
+                        size,
+                        modified,
+                    });
                 }
             }
         }
@@ -1,43 +1,281 @@
 //! `envvault export` — export secrets in various formats.
 //!
 //! Supported formats:
-//! - `env` (default): `.env` file format (KEY=value, one per line)
+//! - `env` (default): `.env` file format (KEY=value, one per line); keys
+//!   with an encoding hint get a `# encoding: <name>` comment above them
+//! - `dotenv-docker`: `KEY=value` lines with no quoting or escaping, for
+//!   `docker run --env-file`, whose parser keeps quotes as part of the
+//!   value.  Values containing a newline cannot be written and are an
+//!   error listing their keys
 //! - `json`: JSON object { "KEY": "value", ... }
+//! - `yaml`: flat YAML mapping (`KEY: value`, one per line); anything a
+//!   YAML parser would not read back as the same string is double-quoted
+//! - `k8s`: a Kubernetes `v1/Secret` manifest with base64 `data:` entries,
+//!   ready for `kubectl apply -f -`; `--name` sets `metadata.name`
+//!   (default `envvault-<env>`) and `--namespace` adds a namespace.
+//!   Secrets with an encoding hint contribute their decoded bytes
+//! - `recovery-doc`: markdown disaster-recovery page covering every
+//!   environment, built from headers only (see `recovery_doc`)
+//!
+//! `--clip` copies the output to the clipboard instead of stdout or a file,
+//! so nothing touches disk; the clipboard is cleared after
+//! `clipboard_clear_secs` (default 30).
+//!
+//! `--append` (with `--output`, env format) merges into an existing file
+//! instead of overwriting it: lines for keys the vault holds are replaced
+//! in place (kept as-is with `--no-clobber`), new keys are added at the
+//! end, and comments and other lines are left untouched.
+//!
+//! `--all-envs --output-dir <dir>` writes one `<env>.<ext>` file per vault
+//! in the vault directory (see [`execute_all_envs`]).
+//!
+//! `--changed-since <backup>` restricts the export to keys that were added
+//! or changed relative to a backup vault file (a delta config).
+//!
+//! `--group-by-prefix` (env format) starts the file with a comment naming
+//! the environment, export time, and envvault version, and puts a
+//! `# ── STRIPE ──` header above each group of keys sharing a prefix (the
+//! text before the first `--separator`, default `_`).  Import skips
+//! comments and blank lines, so the grouped file imports the same secrets.
+//!
+//! `--prefix DB_` exports only the keys starting with `DB_`, in any
+//! format.  If none match, the output is an empty but valid file.
+//!
+//! Keys are written in name order.  `--sort declared` writes them in
+//! declared order instead: secrets given a position with `set --order`
+//! first, then the rest in the order they were added (see
+//! [`VaultStore::declared_order`]).
 
-use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use zeroize::Zeroize;
+use chrono::Utc;
+use serde::{Serialize, Serializer};
+use zeroize::{Zeroize, Zeroizing};
 
+use crate::cli::commands::diff::compute_diff;
+use crate::cli::commands::get::{
+    clipboard_clear_secs, copy_to_clipboard, describe_clear, spawn_clipboard_clear,
+};
+use crate::cli::commands::recovery_doc;
+use crate::cli::env_parser::parse_env_line;
 use crate::cli::output;
 use crate::cli::{load_keyfile, prompt_password_for_vault, vault_path, Cli};
 use crate::errors::{EnvVaultError, Result};
 use crate::vault::VaultStore;
 
+/// How `--append` resolves keys present in both the file and the vault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppendMode {
+    /// Vault values replace the file's (default).
+    VaultWins,
+    /// The file's values are kept (`--no-clobber`).
+    FileWins,
+}
+
+/// `--name` / `--namespace` for the k8s format.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct K8sOptions<'a> {
+    /// `metadata.name`; `envvault-<env>` when unset.
+    pub name: Option<&'a str>,
+    /// `metadata.namespace`; omitted when unset.
+    pub namespace: Option<&'a str>,
+}
+
+impl K8sOptions<'_> {
+    /// The Secret's name for environment `env`, after checking it and the
+    /// namespace are valid Kubernetes names.
+    fn checked_name(&self, env: &str) -> Result<String> {
+        let name = self
+            .name
+            .map_or_else(|| format!("envvault-{env}"), str::to_string);
+        check_k8s_name("--name", &name, 253, true)?;
+        if let Some(namespace) = self.namespace {
+            check_k8s_name("--namespace", namespace, 63, false)?;
+        }
+        Ok(name)
+    }
+}
+
+/// Order of the exported keys (`--sort`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyOrder {
+    /// Alphabetical (default).
+    Name,
+    /// `set --order` positions first, then the order the secrets were added.
+    Declared,
+}
+
+impl KeyOrder {
+    /// Parse a `--sort` value.
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "name" => Ok(Self::Name),
+            "declared" => Ok(Self::Declared),
+            other => Err(EnvVaultError::CommandFailed(format!(
+                "unknown sort order '{other}' — supported: name, declared"
+            ))),
+        }
+    }
+}
+
+/// Names of the secrets in `store`, in `order`.
+pub(crate) fn ordered_keys(store: &VaultStore, order: KeyOrder) -> Vec<String> {
+    match order {
+        KeyOrder::Name => store.list_secrets().into_iter().map(|m| m.name).collect(),
+        KeyOrder::Declared => store.declared_order(),
+    }
+}
+
+/// Turn decrypted `secrets` into `(key, value)` pairs in `order`.
+fn ordered_secrets(
+    mut secrets: HashMap<String, String>,
+    store: &VaultStore,
+    order: KeyOrder,
+) -> Vec<(String, String)> {
+    ordered_keys(store, order)
+        .iter()
+        .filter_map(|name| secrets.remove_entry(name))
+        .collect()
+}
+
+/// Keep only the secrets whose key starts with `prefix`, wiping the rest.
+fn retain_prefix(secrets: &mut Vec<(String, String)>, prefix: &str) {
+    secrets.retain_mut(|(key, value)| {
+        let keep = key.starts_with(prefix);
+        if !keep {
+            value.zeroize();
+        }
+        keep
+    });
+}
+
 /// Execute the `export` command.
-pub fn execute(cli: &Cli, format: &str, output_path: Option<&str>) -> Result<()> {
+#[allow(clippy::too_many_arguments)]
+pub fn execute(
+    cli: &Cli,
+    format: &str,
+    output_path: Option<&str>,
+    clip: bool,
+    append: Option<AppendMode>,
+    changed_since: Option<&str>,
+    redact_names: bool,
+    group_by_prefix: Option<&str>,
+    sort: &str,
+    prefix: Option<&str>,
+    k8s: K8sOptions<'_>,
+) -> Result<()> {
+    let order = KeyOrder::parse(sort)?;
+    // Check the manifest's name before asking for a password.
+    let k8s_name = if format == "k8s" {
+        Some(k8s.checked_name(&cli.env)?)
+    } else if k8s.name.is_some() || k8s.namespace.is_some() {
+        return Err(EnvVaultError::CommandFailed(
+            "--name and --namespace only work with the k8s format".into(),
+        ));
+    } else {
+        None
+    };
+    if append.is_some() && format != "env" {
+        return Err(EnvVaultError::CommandFailed(
+            "--append only works with the env format".into(),
+        ));
+    }
+    if let Some(separator) = group_by_prefix {
+        if format != "env" {
+            return Err(EnvVaultError::CommandFailed(
+                "--group-by-prefix only works with the env format".into(),
+            ));
+        }
+        if separator.is_empty() {
+            return Err(EnvVaultError::CommandFailed(
+                "--separator cannot be empty".into(),
+            ));
+        }
+    }
+
+    if format == "recovery-doc" {
+        if changed_since.is_some() || clip || prefix.is_some() {
+            return Err(EnvVaultError::CommandFailed(
+                "--changed-since, --clip, and --prefix cannot be used with the recovery-doc format"
+                    .into(),
+            ));
+        }
+        return export_recovery_doc(cli, output_path, redact_names);
+    }
+
     let path = vault_path(cli)?;
 
     let keyfile = load_keyfile(cli)?;
     let vault_id = path.to_string_lossy();
     let password = prompt_password_for_vault(Some(&vault_id))?;
-    let store = VaultStore::open(&path, password.as_bytes(), keyfile.as_deref())?;
+    let mut store = VaultStore::open(&path, password.as_bytes(), keyfile.as_deref())?;
+    crate::cli::warn_rotation_due(&store);
+    crate::cli::enable_access_tracking(&mut store);
 
     // Decrypt all secrets.
-    let secrets = store.get_all_secrets()?;
+    let mut secrets = store.get_all_secrets()?;
+    crate::cli::persist_access(&mut store);
 
-    // Sort by key for deterministic output.
-    let mut sorted: BTreeMap<_, _> = secrets.into_iter().collect();
+    // Keep only the delta against a backup, if requested.
+    if let Some(backup) = changed_since {
+        let mut backup_secrets =
+            open_backup_secrets(Path::new(backup), password.as_bytes(), keyfile.as_deref())?;
+        secrets = retain_changed(secrets, &backup_secrets);
+        for v in backup_secrets.values_mut() {
+            v.zeroize();
+        }
+    }
 
-    // Format the output.
-    let mut content = match format {
-        "env" => format_as_env(&sorted),
-        "json" => format_as_json(&sorted)?,
-        other => {
-            return Err(EnvVaultError::CommandFailed(format!(
-                "unknown export format '{other}' — use 'env' or 'json'"
-            )));
+    // Put the keys in a deterministic order.
+    let mut sorted = ordered_secrets(secrets, &store, order);
+    if let Some(prefix) = prefix {
+        retain_prefix(&mut sorted, prefix);
+        if sorted.is_empty() {
+            output::warning(&format!(
+                "No secrets start with '{prefix}' — nothing to export"
+            ));
+        }
+    }
+
+    let encodings: HashMap<String, String> = store
+        .list_secrets()
+        .into_iter()
+        .filter_map(|m| m.encoding.map(|e| (m.name, e)))
+        .collect();
+
+    // Format the output, wiping the plaintext if that fails.
+    let formatted = match format {
+        "env" => Ok(match group_by_prefix {
+            Some(separator) => format_as_env_grouped(
+                &sorted,
+                &encodings,
+                separator,
+                &export_header(&cli.env, Utc::now()),
+            ),
+            None => format_as_env(&sorted, &encodings),
+        }),
+        "dotenv-docker" => format_as_docker_env(&sorted),
+        "json" => format_as_json(&sorted),
+        "yaml" => format_as_yaml(&sorted),
+        "k8s" => format_as_k8s(
+            &sorted,
+            &encodings,
+            k8s_name.as_deref().unwrap_or_default(),
+            k8s.namespace,
+        ),
+        other => Err(EnvVaultError::CommandFailed(format!(
+            "unknown export format '{other}' — use 'env', 'dotenv-docker', 'json', 'yaml', 'k8s', or 'recovery-doc'"
+        ))),
+    };
+    let mut content = match formatted {
+        Ok(content) => content,
+        Err(e) => {
+            for (_, v) in &mut sorted {
+                v.zeroize();
+            }
+            return Err(e);
         }
     };
 
@@ -45,11 +283,27 @@ pub fn execute(cli: &Cli, format: &str, output_path: Option<&str>) -> Result<()>
         cli,
         "export",
         None,
-        Some(&format!("{} secrets, format: {format}", sorted.len())),
+        Some(&crate::audit::AuditDetails::export(sorted.len(), format, changed_since).to_string()),
     );
 
-    // Write to file or stdout.
+    // Write to the clipboard, a file, or stdout.
     match output_path {
+        None if clip => {
+            if let Err(e) = copy_to_clipboard(&content) {
+                content.zeroize();
+                return Err(e);
+            }
+            let clear_secs = clipboard_clear_secs();
+            spawn_clipboard_clear(clear_secs);
+            output::success(&format!(
+                "Copied {} secrets to clipboard (format: {format}, {})",
+                sorted.len(),
+                describe_clear(clear_secs)
+            ));
+            output::warning(
+                "The clipboard is shared with every app on this machine — paste it promptly.",
+            );
+        }
         Some(dest) => {
             let dest_path = Path::new(dest);
 
@@ -63,16 +317,41 @@ pub fn execute(cli: &Cli, format: &str, output_path: Option<&str>) -> Result<()>
                 ));
             }
 
-            fs::write(dest_path, &content).map_err(|e| {
-                EnvVaultError::CommandFailed(format!("failed to write export file: {e}"))
-            })?;
+            match append.filter(|_| dest_path.exists()) {
+                Some(mode) => {
+                    let mut existing = fs::read_to_string(dest_path).map_err(|e| {
+                        EnvVaultError::CommandFailed(format!("failed to read {dest}: {e}"))
+                    })?;
+                    let (mut merged, stats) = merge_into_env(&existing, &sorted, &encodings, mode);
+                    existing.zeroize();
 
-            output::success(&format!(
-                "Exported {} secrets to {} (format: {})",
-                sorted.len(),
-                dest,
-                format
-            ));
+                    let result = fs::write(dest_path, &merged);
+                    merged.zeroize();
+                    result.map_err(|e| {
+                        EnvVaultError::CommandFailed(format!("failed to write export file: {e}"))
+                    })?;
+
+                    output::success(&format!(
+                        "Merged {} secrets into {dest} ({} updated, {} added, {} kept)",
+                        sorted.len(),
+                        stats.updated,
+                        stats.added,
+                        stats.kept
+                    ));
+                }
+                None => {
+                    fs::write(dest_path, &content).map_err(|e| {
+                        EnvVaultError::CommandFailed(format!("failed to write export file: {e}"))
+                    })?;
+
+                    output::success(&format!(
+                        "Exported {} secrets to {} (format: {})",
+                        sorted.len(),
+                        dest,
+                        format
+                    ));
+                }
+            }
         }
         None => {
             // Write to stdout (no success message, just raw output).
@@ -81,7 +360,7 @@ pub fn execute(cli: &Cli, format: &str, output_path: Option<&str>) -> Result<()>
     }
 
     // Zeroize plaintext secrets before returning.
-    for v in sorted.values_mut() {
+    for (_, v) in &mut sorted {
         v.zeroize();
     }
     content.zeroize();
@@ -89,42 +368,761 @@ pub fn execute(cli: &Cli, format: &str, output_path: Option<&str>) -> Result<()>
     Ok(())
 }
 
+/// Result of exporting one environment with `--all-envs`.
+struct EnvExport {
+    env: String,
+    file: Option<PathBuf>,
+    secrets: usize,
+    bytes: usize,
+}
+
+/// Execute `export --all-envs --output-dir <dir>`.
+///
+/// Opens every vault in the vault directory — reusing a password that
+/// already worked and prompting only when none does, like `diff` — and
+/// writes `<env>.env`, `<env>.json`, or `<env>.yaml` into `output_dir`.  Existing files
+/// are only replaced with `force`.  Files are owner-only (0o600) and a
+/// relative `output_dir` is added to `.gitignore`.  Vaults that need a
+/// keyfile we don't have are skipped with a warning.
+pub fn execute_all_envs(
+    cli: &Cli,
+    format: &str,
+    output_dir: &str,
+    force: bool,
+    sort: &str,
+    prefix: Option<&str>,
+) -> Result<()> {
+    let order = KeyOrder::parse(sort)?;
+    let ext = match format {
+        "env" | "json" | "yaml" => format,
+        other => {
+            return Err(EnvVaultError::CommandFailed(format!(
+                "--all-envs supports the env, json, and yaml formats, not '{other}'"
+            )));
+        }
+    };
+
+    let vault_dir = crate::cli::resolve_vault_dir(cli)?;
+    if !vault_dir.is_dir() {
+        return Err(EnvVaultError::CommandFailed(format!(
+            "no vault directory at {}",
+            vault_dir.display()
+        )));
+    }
+    let mut envs = crate::cli::commands::env_list::list_environments(&vault_dir)?;
+    envs.sort_by(|a, b| a.name.cmp(&b.name));
+    if envs.is_empty() {
+        output::info("No environments found.");
+        return Ok(());
+    }
+
+    // Refuse before decrypting anything if files would be clobbered.
+    let out_dir = Path::new(output_dir);
+    let targets: Vec<PathBuf> = envs
+        .iter()
+        .map(|env| out_dir.join(format!("{}.{ext}", env.name)))
+        .collect();
+    let existing: Vec<String> = targets
+        .iter()
+        .filter(|t| t.exists())
+        .map(|t| t.display().to_string())
+        .collect();
+    if !existing.is_empty() && !force {
+        return Err(EnvVaultError::CommandFailed(format!(
+            "refusing to overwrite {} — pass --force to replace",
+            existing.join(", ")
+        )));
+    }
+
+    fs::create_dir_all(out_dir)?;
+    if out_dir.is_relative() {
+        let entry = format!("{}/", output_dir.trim_end_matches('/'));
+        crate::cli::gitignore::patch_gitignore(&std::env::current_dir()?, &entry);
+    }
+
+    let keyfile = load_keyfile(cli)?;
+    let mut passwords: Vec<Zeroizing<String>> = Vec::new();
+    let mut results = Vec::with_capacity(envs.len());
+
+    let mut progress = crate::progress::phase(crate::progress::EXPORT, envs.len());
+    for (env, target) in envs.iter().zip(targets) {
+        let path = vault_dir.join(format!("{}.vault", env.name));
+        let Some(mut store) =
+            open_with_known_passwords(&path, &env.name, &mut passwords, keyfile.as_deref())?
+        else {
+            results.push(EnvExport {
+                env: env.name.clone(),
+                file: None,
+                secrets: 0,
+                bytes: 0,
+            });
+            progress.inc();
+            continue;
+        };
+
+        crate::cli::enable_access_tracking(&mut store);
+        let mut sorted = ordered_secrets(store.get_all_secrets()?, &store, order);
+        if let Some(prefix) = prefix {
+            retain_prefix(&mut sorted, prefix);
+        }
+        crate::cli::persist_access(&mut store);
+        let encodings: HashMap<String, String> = store
+            .list_secrets()
+            .into_iter()
+            .filter_map(|m| m.encoding.map(|e| (m.name, e)))
+            .collect();
+        let mut content = match ext {
+            "json" => format_as_json(&sorted)?,
+            "yaml" => format_as_yaml(&sorted)?,
+            _ => format_as_env(&sorted, &encodings),
+        };
+
+        let written = write_private_file(&target, content.as_bytes());
+        let export = EnvExport {
+            env: env.name.clone(),
+            file: Some(target),
+            secrets: sorted.len(),
+            bytes: content.len(),
+        };
+        for (_, v) in &mut sorted {
+            v.zeroize();
+        }
+        content.zeroize();
+        written?;
+        results.push(export);
+        progress.inc();
+    }
+    drop(progress);
+
+    let exported: Vec<&EnvExport> = results.iter().filter(|r| r.file.is_some()).collect();
+    let total: usize = exported.iter().map(|r| r.secrets).sum();
+    crate::audit::log_audit(
+        cli,
+        "export",
+        None,
+        Some(
+            &crate::audit::AuditDetails::export_all(exported.len(), total, format, output_dir)
+                .to_string(),
+        ),
+    );
+
+    print_all_envs_summary(&results);
+    output::success(&format!(
+        "Exported {} environment(s) to {output_dir} (format: {format})",
+        exported.len()
+    ));
+
+    let skipped: Vec<&str> = results
+        .iter()
+        .filter(|r| r.file.is_none())
+        .map(|r| r.env.as_str())
+        .collect();
+    if !skipped.is_empty() {
+        output::warning(&format!(
+            "SKIPPED {} keyfile-protected environment(s): {} — pass --keyfile to export them",
+            skipped.len(),
+            skipped.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// Open a vault with the first known password that works, prompting for
+/// a new one otherwise.  Returns `None` when the vault needs a keyfile we
+/// don't have (or a different one).
+fn open_with_known_passwords(
+    path: &Path,
+    env: &str,
+    passwords: &mut Vec<Zeroizing<String>>,
+    keyfile: Option<&[u8]>,
+) -> Result<Option<VaultStore>> {
+    for password in passwords.iter() {
+        match VaultStore::open(path, password.as_bytes(), keyfile) {
+            Ok(store) => return Ok(Some(store)),
+            Err(EnvVaultError::HmacMismatch | EnvVaultError::DecryptionFailed) => {}
+            Err(EnvVaultError::KeyfileError(_)) => return Ok(None),
+            Err(e) => return Err(e),
+        }
+    }
+
+    if !passwords.is_empty() {
+        output::info(&format!("Vault '{env}' uses a different password."));
+    }
+    let vault_id = path.to_string_lossy();
+    let password = prompt_password_for_vault(Some(&vault_id))?;
+    match VaultStore::open(path, password.as_bytes(), keyfile) {
+        Ok(store) => {
+            passwords.push(password);
+            Ok(Some(store))
+        }
+        Err(EnvVaultError::KeyfileError(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Write a plaintext export readable only by the owner.
+fn write_private_file(path: &Path, content: &[u8]) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path).map_err(|e| {
+        EnvVaultError::CommandFailed(format!("failed to write {}: {e}", path.display()))
+    })?;
+
+    // `mode` only applies to new files; tighten ones we're replacing.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+
+    std::io::Write::write_all(&mut file, content)?;
+    Ok(())
+}
+
+/// Print one row per environment: file, secret count, bytes written.
+fn print_all_envs_summary(results: &[EnvExport]) {
+    let mut table = output::table();
+    table.set_header(vec!["Environment", "File", "Secrets", "Bytes"]);
+    for r in results {
+        match r.file {
+            Some(ref file) => table.add_row(vec![
+                r.env.clone(),
+                file.display().to_string(),
+                r.secrets.to_string(),
+                r.bytes.to_string(),
+            ]),
+            None => table.add_row(vec![
+                r.env.clone(),
+                "skipped (keyfile required)".into(),
+                "-".into(),
+                "-".into(),
+            ]),
+        };
+    }
+    println!("{table}");
+}
+
+/// Write the recovery document for every environment in the vault dir.
+///
+/// Reads headers only, so no password is prompted for.
+fn export_recovery_doc(cli: &Cli, output_path: Option<&str>, redact_names: bool) -> Result<()> {
+    let vault_dir = crate::cli::resolve_vault_dir(cli)?;
+    if !vault_dir.exists() {
+        return Err(EnvVaultError::CommandFailed(format!(
+            "no vault directory at {}",
+            vault_dir.display()
+        )));
+    }
+
+    let records = recovery_doc::collect(&vault_dir, &cli.vault_dir)?;
+    let doc = recovery_doc::render(&records, &cli.vault_dir, Utc::now(), redact_names);
+
+    match output_path {
+        Some(dest) => {
+            fs::write(dest, &doc).map_err(|e| {
+                EnvVaultError::CommandFailed(format!("failed to write recovery doc: {e}"))
+            })?;
+            output::success(&format!(
+                "Wrote recovery document for {} environment(s) to {dest}",
+                records.len()
+            ));
+        }
+        None => print!("{doc}"),
+    }
+
+    crate::audit::log_audit(
+        cli,
+        "export",
+        None,
+        Some(&crate::audit::AuditDetails::export(records.len(), "recovery-doc", None).to_string()),
+    );
+
+    Ok(())
+}
+
+/// Decrypt every secret in a backup vault.
+///
+/// Tries the current vault's password first and prompts for the backup's
+/// own password if that fails, mirroring `diff`.
+fn open_backup_secrets(
+    backup_path: &Path,
+    password: &[u8],
+    keyfile: Option<&[u8]>,
+) -> Result<HashMap<String, String>> {
+    match VaultStore::open(backup_path, password, keyfile) {
+        Ok(backup) => backup.get_all_secrets(),
+        Err(EnvVaultError::HmacMismatch | EnvVaultError::DecryptionFailed) => {
+            output::info(&format!(
+                "Backup '{}' uses a different password.",
+                backup_path.display()
+            ));
+            let backup_id = backup_path.to_string_lossy();
+            let backup_pw = prompt_password_for_vault(Some(&backup_id))?;
+            VaultStore::open(backup_path, backup_pw.as_bytes(), keyfile)?.get_all_secrets()
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Keep only secrets that were added or changed relative to `backup`.
+/// Dropped plaintext values are zeroized.
+fn retain_changed(
+    mut current: HashMap<String, String>,
+    backup: &HashMap<String, String>,
+) -> HashMap<String, String> {
+    let diff = compute_diff(backup, &current);
+
+    let mut delta = HashMap::new();
+    for key in diff.added.into_iter().chain(diff.changed) {
+        if let Some(value) = current.remove(&key) {
+            delta.insert(key, value);
+        }
+    }
+
+    for v in current.values_mut() {
+        v.zeroize();
+    }
+    delta
+}
+
 /// Format secrets as `.env` file content.
-fn format_as_env(secrets: &BTreeMap<String, String>) -> String {
-    use std::fmt::Write;
+///
+/// Keys listed in `encodings` are preceded by a `# encoding: <name>` comment.
+/// Lines always end in `\n`, whatever the platform, and values never
+/// contain a raw `\r`.
+fn format_as_env(secrets: &[(String, String)], encodings: &HashMap<String, String>) -> String {
+    let mut out = String::new();
+    write_env_entries(&mut out, secrets.iter().map(|(k, v)| (k, v)), encodings);
+    out
+}
+
+/// Format secrets for `docker run --env-file`: `KEY=value` lines written
+/// verbatim.
+///
+/// Docker takes everything after the first `=` as the value, quotes
+/// included, and has no way to represent a newline, so values containing
+/// `\n` or `\r` are an error naming every such key.
+fn format_as_docker_env(secrets: &[(String, String)]) -> Result<String> {
+    let multiline: Vec<&str> = secrets
+        .iter()
+        .filter(|(_, value)| value.contains(['\n', '\r']))
+        .map(|(key, _)| key.as_str())
+        .collect();
+    if !multiline.is_empty() {
+        return Err(EnvVaultError::CommandFailed(format!(
+            "docker --env-file cannot hold values with newlines: {} — use another format for these keys",
+            multiline.join(", ")
+        )));
+    }
+
     let mut out = String::new();
     for (key, value) in secrets {
-        // Quote values that contain spaces, special chars, or are empty.
-        if value.is_empty()
-            || value.contains(' ')
-            || value.contains('#')
-            || value.contains('"')
-            || value.contains('\'')
-            || value.contains('\n')
-            || value.contains('$')
-        {
-            // Escape inner double quotes and newlines.
-            let escaped = value
-                .replace('\\', "\\\\")
-                .replace('"', "\\\"")
-                .replace('\n', "\\n");
-            let _ = writeln!(out, "{key}=\"{escaped}\"");
-        } else {
-            let _ = writeln!(out, "{key}={value}");
+        out.push_str(key);
+        out.push('=');
+        out.push_str(value);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Format secrets as `.env` content grouped by key prefix
+/// (`--group-by-prefix`).
+///
+/// `header` comes first.  Keys without `separator` (or starting with it)
+/// follow without a group header; each prefix group then gets a
+/// `# ── PREFIX ──` comment, in the order its first key appears in
+/// `secrets`.  Groups are separated by blank lines.
+fn format_as_env_grouped(
+    secrets: &[(String, String)],
+    encodings: &HashMap<String, String>,
+    separator: &str,
+    header: &str,
+) -> String {
+    let mut ungrouped: Vec<(&String, &String)> = Vec::new();
+    let mut groups: Vec<(&str, Vec<(&String, &String)>)> = Vec::new();
+    for (key, value) in secrets {
+        match key_prefix(key, separator) {
+            None => ungrouped.push((key, value)),
+            Some(prefix) => match groups.iter_mut().find(|(p, _)| *p == prefix) {
+                Some((_, entries)) => entries.push((key, value)),
+                None => groups.push((prefix, vec![(key, value)])),
+            },
         }
     }
+
+    let mut out = String::from(header);
+    if !ungrouped.is_empty() {
+        out.push('\n');
+        write_env_entries(&mut out, ungrouped, encodings);
+    }
+    for (prefix, entries) in groups {
+        out.push_str(&format!("\n# ── {prefix} ──\n"));
+        write_env_entries(&mut out, entries, encodings);
+    }
     out
 }
 
-/// Format secrets as a JSON object.
-fn format_as_json(secrets: &BTreeMap<String, String>) -> Result<String> {
-    serde_json::to_string_pretty(secrets)
+/// Write `KEY=value` lines, each preceded by its encoding comment if any.
+fn write_env_entries<'a>(
+    out: &mut String,
+    entries: impl IntoIterator<Item = (&'a String, &'a String)>,
+    encodings: &HashMap<String, String>,
+) {
+    use std::fmt::Write;
+    for (key, value) in entries {
+        if let Some(encoding) = encodings.get(key) {
+            let _ = writeln!(out, "# encoding: {encoding}");
+        }
+        let _ = writeln!(out, "{}", format_env_entry(key, value));
+    }
+}
+
+/// Text before the first `separator` in `key`, if any.
+fn key_prefix<'a>(key: &'a str, separator: &str) -> Option<&'a str> {
+    key.split_once(separator)
+        .map(|(prefix, _)| prefix)
+        .filter(|prefix| !prefix.is_empty())
+}
+
+/// Comment block at the top of a `--group-by-prefix` export.
+fn export_header(env: &str, now: chrono::DateTime<Utc>) -> String {
+    format!(
+        "# Exported from the '{env}' environment by envvault {}\n# at {}\n",
+        env!("CARGO_PKG_VERSION"),
+        now.format("%Y-%m-%d %H:%M:%S UTC")
+    )
+}
+
+/// Format one `KEY=value` line (without the newline).
+fn format_env_entry(key: &str, value: &str) -> String {
+    // Quote values that contain spaces, special chars, or are empty.
+    if value.is_empty()
+        || value.contains(' ')
+        || value.contains('#')
+        || value.contains('"')
+        || value.contains('\'')
+        || value.contains('\n')
+        || value.contains('\r')
+        || value.contains('$')
+    {
+        // Escape inner double quotes, newlines, and carriage returns.
+        let escaped = value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r");
+        format!("{key}=\"{escaped}\"")
+    } else {
+        format!("{key}={value}")
+    }
+}
+
+/// What `merge_into_env` did with the vault's keys.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct MergeStats {
+    updated: usize,
+    added: usize,
+    kept: usize,
+}
+
+/// Merge secrets into existing `.env` content.
+///
+/// Lines that parse to a vault key are rewritten with the vault value
+/// (keeping an `export ` prefix), or left alone under
+/// [`AppendMode::FileWins`].  Every other line, comments included, is
+/// copied unchanged.  Keys not already in the file are appended.
+fn merge_into_env(
+    existing: &str,
+    secrets: &[(String, String)],
+    encodings: &HashMap<String, String>,
+    mode: AppendMode,
+) -> (String, MergeStats) {
+    let mut out = String::with_capacity(existing.len());
+    let mut seen = std::collections::BTreeSet::new();
+    let mut stats = MergeStats::default();
+    let by_key: HashMap<&str, &str> = secrets
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+
+    for line in existing.lines() {
+        let vault_entry = parse_env_line(line).and_then(|(key, _)| by_key.get_key_value(key));
+
+        match vault_entry {
+            Some((key, value)) if mode == AppendMode::VaultWins => {
+                if line.trim_start().starts_with("export ") {
+                    out.push_str("export ");
+                }
+                out.push_str(&format_env_entry(key, value));
+                if seen.insert(*key) {
+                    stats.updated += 1;
+                }
+            }
+            Some((key, _)) => {
+                out.push_str(line);
+                if seen.insert(*key) {
+                    stats.kept += 1;
+                }
+            }
+            None => out.push_str(line),
+        }
+        out.push('\n');
+    }
+
+    let mut missing: Vec<(String, String)> = secrets
+        .iter()
+        .filter(|(key, _)| !seen.contains(key.as_str()))
+        .cloned()
+        .collect();
+    stats.added = missing.len();
+    let mut appended = format_as_env(&missing, encodings);
+    out.push_str(&appended);
+    appended.zeroize();
+    for (_, v) in &mut missing {
+        v.zeroize();
+    }
+
+    (out, stats)
+}
+
+/// `(key, value)` pairs serialized as a JSON object, keeping their order.
+struct OrderedMap<'a>(&'a [(String, String)]);
+
+impl Serialize for OrderedMap<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(k, v)| (k, v)))
+    }
+}
+
+/// Format secrets as a JSON object, keys in the order given.
+fn format_as_json(secrets: &[(String, String)]) -> Result<String> {
+    serde_json::to_string_pretty(&OrderedMap(secrets))
         .map_err(|e| EnvVaultError::SerializationError(format!("JSON export: {e}")))
 }
 
+/// Format secrets as a flat YAML mapping.
+///
+/// Keys and values are written plain when that is unambiguous, and as
+/// double-quoted scalars otherwise (see [`yaml_scalar`]), so every value
+/// reads back as the exact same string, newlines included.
+pub(crate) fn format_as_yaml(secrets: &[(String, String)]) -> Result<String> {
+    use std::fmt::Write;
+
+    let mut out = String::new();
+    for (key, value) in secrets {
+        let _ = writeln!(out, "{}: {}", yaml_scalar(key)?, yaml_scalar(value)?);
+    }
+    Ok(out)
+}
+
+/// Format secrets as a Kubernetes `v1/Secret` manifest named `name`
+/// (checked by [`K8sOptions::checked_name`]).
+///
+/// Values go under `data:` base64-encoded; a secret with an encoding hint
+/// contributes its decoded bytes.  Keys outside `[-._a-zA-Z0-9]` cannot
+/// be Secret data keys, so they are an error (all of them listed) rather
+/// than a manifest `kubectl` would reject.
+fn format_as_k8s(
+    secrets: &[(String, String)],
+    encodings: &HashMap<String, String>,
+    name: &str,
+    namespace: Option<&str>,
+) -> Result<String> {
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use base64::Engine;
+    use std::fmt::Write;
+
+    let invalid: Vec<&str> = secrets
+        .iter()
+        .map(|(key, _)| key.as_str())
+        .filter(|key| !is_k8s_data_key(key))
+        .collect();
+    if !invalid.is_empty() {
+        return Err(EnvVaultError::CommandFailed(format!(
+            "not valid Kubernetes Secret keys (allowed: letters, digits, '-', '_', '.'): {}",
+            invalid.join(", ")
+        )));
+    }
+
+    let mut out = String::from("apiVersion: v1\nkind: Secret\nmetadata:\n");
+    let _ = writeln!(out, "  name: {}", yaml_scalar(name)?);
+    if let Some(namespace) = namespace {
+        let _ = writeln!(out, "  namespace: {}", yaml_scalar(namespace)?);
+    }
+    out.push_str("type: Opaque\n");
+    if secrets.is_empty() {
+        out.push_str("data: {}\n");
+        return Ok(out);
+    }
+
+    out.push_str("data:\n");
+    for (key, value) in secrets {
+        let encoding = encodings
+            .get(key)
+            .map(|e| crate::vault::SecretEncoding::parse(e))
+            .transpose()?
+            .flatten();
+        let mut encoded = match encoding {
+            Some(encoding) => {
+                let mut bytes = encoding
+                    .decode(value)
+                    .map_err(|e| EnvVaultError::CommandFailed(format!("{key}: {e}")))?;
+                let encoded = BASE64.encode(&bytes);
+                bytes.zeroize();
+                encoded
+            }
+            None => BASE64.encode(value),
+        };
+        // A bare empty scalar would read back as `null`.
+        if encoded.is_empty() {
+            encoded.push_str("\"\"");
+        }
+        let _ = writeln!(out, "  {}: {encoded}", yaml_scalar(key)?);
+        encoded.zeroize();
+    }
+    Ok(out)
+}
+
+/// Whether `key` can be a key in a Kubernetes Secret's `data`.
+fn is_k8s_data_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= 253
+        && key != "."
+        && key != ".."
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-._".contains(c))
+}
+
+/// Check a Kubernetes object name (a DNS subdomain, or a DNS label when
+/// `dots` is false): lowercase letters, digits, `-`, starting and ending
+/// with a letter or digit.
+fn check_k8s_name(flag: &str, name: &str, max_len: usize, dots: bool) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= max_len
+        && name.chars().all(|c| {
+            c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || (dots && c == '.')
+        })
+        && name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name.ends_with(|c: char| c.is_ascii_alphanumeric());
+    if valid {
+        Ok(())
+    } else {
+        Err(EnvVaultError::CommandFailed(format!(
+            "{flag} '{name}' is not a valid Kubernetes name — use lowercase letters, digits, and '-'{}",
+            if dots { " or '.'" } else { "" }
+        )))
+    }
+}
+
+/// Render one string as a YAML scalar.
+///
+/// Plain scalars are limited to a conservative character set and must
+/// not look like a number, boolean, or null (YAML 1.1 or 1.2).  Anything
+/// else is double-quoted; JSON string escapes are valid YAML
+/// double-quoted escapes, so `serde_json` does the escaping.
+fn yaml_scalar(value: &str) -> Result<String> {
+    if yaml_plain_safe(value) {
+        return Ok(value.to_string());
+    }
+    serde_json::to_string(value)
+        .map_err(|e| EnvVaultError::SerializationError(format!("YAML export: {e}")))
+}
+
+fn yaml_plain_safe(value: &str) -> bool {
+    const RESERVED: &[&str] = &[
+        "true", "false", "yes", "no", "on", "off", "y", "n", "null", "~",
+    ];
+    let Some(first) = value.chars().next() else {
+        return false;
+    };
+    first.is_ascii_alphabetic()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./@+=,".contains(c))
+        && !RESERVED.iter().any(|r| value.eq_ignore_ascii_case(r))
+        // `.inf` / `.nan` start with a dot, so only the spelled-out
+        // forms need catching.
+        && !value.eq_ignore_ascii_case("nan")
+        && !value.eq_ignore_ascii_case("inf")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::BTreeMap;
+
+    fn pairs(secrets: &BTreeMap<String, String>) -> Vec<(String, String)> {
+        secrets.clone().into_iter().collect()
+    }
+
+    #[test]
+    fn retain_changed_keeps_added_and_changed_only() {
+        let backup: HashMap<String, String> = [("SAME", "1"), ("EDITED", "old"), ("GONE", "x")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let current: HashMap<String, String> = [("SAME", "1"), ("EDITED", "new"), ("NEW", "y")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+
+        let delta: BTreeMap<_, _> = retain_changed(current, &backup).into_iter().collect();
+        let pairs: Vec<(&str, &str)> = delta
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        assert_eq!(pairs, [("EDITED", "new"), ("NEW", "y")]);
+    }
+
+    #[test]
+    fn format_yaml_quotes_only_when_needed() {
+        let mut secrets = BTreeMap::new();
+        secrets.insert("PLAIN".into(), "postgres.example.com".into());
+        secrets.insert("URL".into(), "postgres://u:p@host:5432/db".into());
+        secrets.insert("HASH".into(), "abc #def".into());
+        secrets.insert("PEM".into(), "-----BEGIN-----\nline\n".into());
+        secrets.insert("FLAG".into(), "yes".into());
+        secrets.insert("PORT".into(), "5432".into());
+        secrets.insert("EMPTY".into(), String::new());
+        secrets.insert("N".into(), "*alias".into());
+
+        let output = format_as_yaml(&pairs(&secrets)).unwrap();
+        assert_eq!(
+            output,
+            "EMPTY: \"\"\n\
+             FLAG: \"yes\"\n\
+             HASH: \"abc #def\"\n\
+             \"N\": \"*alias\"\n\
+             PEM: \"-----BEGIN-----\\nline\\n\"\n\
+             PLAIN: postgres.example.com\n\
+             PORT: \"5432\"\n\
+             URL: \"postgres://u:p@host:5432/db\"\n"
+        );
+    }
+
+    #[test]
+    fn yaml_quoted_scalars_decode_to_the_original() {
+        for value in [
+            "a\"b\\c",
+            "tab\there",
+            "line1\nline2\r\n",
+            " lead",
+            "trail ",
+            "\u{1}",
+        ] {
+            let scalar = yaml_scalar(value).unwrap();
+            assert!(scalar.starts_with('"'), "{scalar}");
+            assert_eq!(serde_json::from_str::<String>(&scalar).unwrap(), value);
+        }
+    }
 
     #[test]
     fn format_env_simple_values() {
@@ -132,7 +1130,7 @@ mod tests {
         secrets.insert("A".into(), "hello".into());
         secrets.insert("B".into(), "world".into());
 
-        let output = format_as_env(&secrets);
+        let output = format_as_env(&pairs(&secrets), &HashMap::new());
         assert_eq!(output, "A=hello\nB=world\n");
     }
 
@@ -141,7 +1139,7 @@ mod tests {
         let mut secrets = BTreeMap::new();
         secrets.insert("KEY".into(), "has space".into());
 
-        let output = format_as_env(&secrets);
+        let output = format_as_env(&pairs(&secrets), &HashMap::new());
         assert_eq!(output, "KEY=\"has space\"\n");
     }
 
@@ -150,7 +1148,7 @@ mod tests {
         let mut secrets = BTreeMap::new();
         secrets.insert("EMPTY".into(), String::new());
 
-        let output = format_as_env(&secrets);
+        let output = format_as_env(&pairs(&secrets), &HashMap::new());
         assert_eq!(output, "EMPTY=\"\"\n");
     }
 
@@ -159,17 +1157,305 @@ mod tests {
         let mut secrets = BTreeMap::new();
         secrets.insert("KEY".into(), "price$100".into());
 
-        let output = format_as_env(&secrets);
+        let output = format_as_env(&pairs(&secrets), &HashMap::new());
         assert_eq!(output, "KEY=\"price$100\"\n");
     }
 
+    #[test]
+    fn format_env_uses_lf_and_escapes_carriage_returns() {
+        let mut secrets = BTreeMap::new();
+        secrets.insert("A".into(), "one".into());
+        secrets.insert("B".into(), "line\r\nbreak".into());
+
+        let output = format_as_env(&pairs(&secrets), &HashMap::new());
+        assert_eq!(output, "A=one\nB=\"line\\r\\nbreak\"\n");
+        assert!(!output.contains('\r'));
+    }
+
+    #[test]
+    fn format_env_annotates_encoded_keys() {
+        let mut secrets = BTreeMap::new();
+        secrets.insert("CERT".into(), "aGk=".into());
+        secrets.insert("PLAIN".into(), "x".into());
+        let encodings = HashMap::from([("CERT".to_string(), "base64".to_string())]);
+
+        let output = format_as_env(&pairs(&secrets), &encodings);
+        assert_eq!(output, "# encoding: base64\nCERT=aGk=\nPLAIN=x\n");
+    }
+
+    #[test]
+    fn grouped_env_has_header_and_prefix_sections() {
+        let secrets = BTreeMap::from([
+            ("AWS_KEY".to_string(), "a".to_string()),
+            ("AWS_REGION".to_string(), "eu-west-1".to_string()),
+            ("PORT".to_string(), "8080".to_string()),
+            ("STRIPE_KEY".to_string(), "sk".to_string()),
+            ("_HIDDEN".to_string(), "h".to_string()),
+        ]);
+        let out = format_as_env_grouped(&pairs(&secrets), &HashMap::new(), "_", "# header\n");
+        assert_eq!(
+            out,
+            "# header\n\
+             \nPORT=8080\n_HIDDEN=h\n\
+             \n# ── AWS ──\nAWS_KEY=a\nAWS_REGION=eu-west-1\n\
+             \n# ── STRIPE ──\nSTRIPE_KEY=sk\n"
+        );
+
+        // A custom separator changes the prefixes.
+        let dotted = BTreeMap::from([("app.db.url".to_string(), "x".to_string())]);
+        let out = format_as_env_grouped(&pairs(&dotted), &HashMap::new(), ".", "");
+        assert!(out.contains("# ── app ──\napp.db.url=x\n"), "{out}");
+    }
+
+    #[test]
+    fn formats_keep_the_given_key_order() {
+        let secrets = vec![
+            ("STRIPE_KEY".to_string(), "sk".to_string()),
+            ("PORT".to_string(), "8080".to_string()),
+            ("AWS_KEY".to_string(), "a".to_string()),
+            ("STRIPE_HOOK".to_string(), "wh".to_string()),
+        ];
+
+        assert_eq!(
+            format_as_env(&secrets, &HashMap::new()),
+            "STRIPE_KEY=sk\nPORT=8080\nAWS_KEY=a\nSTRIPE_HOOK=wh\n"
+        );
+        let json = format_as_json(&secrets).unwrap();
+        assert!(json.find("STRIPE_KEY").unwrap() < json.find("PORT").unwrap());
+        assert!(json.find("PORT").unwrap() < json.find("AWS_KEY").unwrap());
+
+        // Groups follow their first key; keys keep their order inside one.
+        let grouped = format_as_env_grouped(&secrets, &HashMap::new(), "_", "");
+        assert_eq!(
+            grouped,
+            "\nPORT=8080\n\
+             \n# ── STRIPE ──\nSTRIPE_KEY=sk\nSTRIPE_HOOK=wh\n\
+             \n# ── AWS ──\nAWS_KEY=a\n"
+        );
+    }
+
+    #[test]
+    fn prefix_filter_applies_to_every_format() {
+        let mut secrets = vec![
+            ("BACKEND_DB".to_string(), "postgres://db".to_string()),
+            ("FRONTEND_API".to_string(), "https://api".to_string()),
+            ("BACKEND_KEY".to_string(), "k".to_string()),
+        ];
+        retain_prefix(&mut secrets, "BACKEND_");
+
+        assert_eq!(
+            format_as_env(&secrets, &HashMap::new()),
+            "BACKEND_DB=postgres://db\nBACKEND_KEY=k\n"
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&format_as_json(&secrets).unwrap()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"BACKEND_DB": "postgres://db", "BACKEND_KEY": "k"})
+        );
+        assert!(!format_as_yaml(&secrets).unwrap().contains("FRONTEND"));
+    }
+
+    #[test]
+    fn prefix_with_no_match_gives_empty_but_valid_output() {
+        let mut secrets = vec![("FRONTEND_API".to_string(), "https://api".to_string())];
+        retain_prefix(&mut secrets, "BACKEND_");
+
+        assert!(secrets.is_empty());
+        assert_eq!(format_as_env(&secrets, &HashMap::new()), "");
+        assert_eq!(format_as_json(&secrets).unwrap(), "{}");
+        assert_eq!(format_as_yaml(&secrets).unwrap(), "");
+    }
+
+    #[test]
+    fn key_order_names_parse() {
+        assert_eq!(KeyOrder::parse("name").unwrap(), KeyOrder::Name);
+        assert_eq!(KeyOrder::parse("Declared").unwrap(), KeyOrder::Declared);
+        assert!(KeyOrder::parse("created").is_err());
+    }
+
+    #[test]
+    fn grouped_export_round_trips_through_import() {
+        let secrets = BTreeMap::from([
+            ("DB_HOST".to_string(), "localhost".to_string()),
+            ("DB_PASSWORD".to_string(), "p@ss word#1".to_string()),
+            ("STRIPE_SECRET".to_string(), "sk_live_123".to_string()),
+            ("STRIPE_EMPTY".to_string(), String::new()),
+            ("PORT".to_string(), "8080".to_string()),
+            ("TOKEN_B64".to_string(), "aGVsbG8=".to_string()),
+        ]);
+        let encodings = HashMap::from([("TOKEN_B64".to_string(), "base64".to_string())]);
+        let header = export_header("staging", Utc::now());
+        let out = format_as_env_grouped(&pairs(&secrets), &encodings, "_", &header);
+        assert!(out.starts_with("# Exported from the 'staging' environment by envvault "));
+
+        let imported = crate::cli::env_parser::parse_env_content(&out).secrets;
+        let expected: HashMap<String, String> = secrets.into_iter().collect();
+        assert_eq!(imported, expected);
+    }
+
+    fn sample_secrets() -> Vec<(String, String)> {
+        [("API_KEY", "from-vault"), ("NEW_KEY", "new value")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    const EXISTING: &str = "# shared settings\nPORT=8080\n\nexport API_KEY=old\n";
+
+    #[test]
+    fn merge_replaces_vault_keys_and_keeps_other_lines() {
+        let (merged, stats) = merge_into_env(
+            EXISTING,
+            &sample_secrets(),
+            &HashMap::new(),
+            AppendMode::VaultWins,
+        );
+        assert_eq!(
+            merged,
+            "# shared settings\nPORT=8080\n\nexport API_KEY=from-vault\nNEW_KEY=\"new value\"\n"
+        );
+        assert_eq!(
+            stats,
+            MergeStats {
+                updated: 1,
+                added: 1,
+                kept: 0
+            }
+        );
+    }
+
+    #[test]
+    fn merge_no_clobber_keeps_file_values() {
+        let (merged, stats) = merge_into_env(
+            EXISTING,
+            &sample_secrets(),
+            &HashMap::new(),
+            AppendMode::FileWins,
+        );
+        assert_eq!(merged, format!("{EXISTING}NEW_KEY=\"new value\"\n"));
+        assert_eq!(
+            stats,
+            MergeStats {
+                updated: 0,
+                added: 1,
+                kept: 1
+            }
+        );
+    }
+
+    #[test]
+    fn merge_terminates_a_final_line_without_newline() {
+        let (merged, _) = merge_into_env(
+            "PORT=8080",
+            &sample_secrets(),
+            &HashMap::new(),
+            AppendMode::VaultWins,
+        );
+        assert!(
+            merged.starts_with("PORT=8080\nAPI_KEY=from-vault\n"),
+            "{merged}"
+        );
+    }
+
     #[test]
     fn format_json_produces_valid_json() {
         let mut secrets = BTreeMap::new();
         secrets.insert("KEY".into(), "value".into());
 
-        let output = format_as_json(&secrets).unwrap();
+        let output = format_as_json(&pairs(&secrets)).unwrap();
         let parsed: BTreeMap<String, String> = serde_json::from_str(&output).unwrap();
         assert_eq!(parsed["KEY"], "value");
     }
+
+    #[test]
+    fn k8s_manifest_has_base64_data() {
+        let secrets = vec![
+            ("DB_URL".to_string(), "postgres://db".to_string()),
+            ("tls.key".to_string(), "aGk=".to_string()),
+            ("EMPTY".to_string(), String::new()),
+        ];
+        let encodings = HashMap::from([("tls.key".to_string(), "base64".to_string())]);
+
+        let manifest = format_as_k8s(&secrets, &encodings, "envvault-dev", Some("prod")).unwrap();
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&manifest).unwrap();
+        assert_eq!(parsed["apiVersion"], "v1");
+        assert_eq!(parsed["kind"], "Secret");
+        assert_eq!(parsed["type"], "Opaque");
+        assert_eq!(parsed["metadata"]["name"], "envvault-dev");
+        assert_eq!(parsed["metadata"]["namespace"], "prod");
+        assert_eq!(parsed["data"]["DB_URL"], "cG9zdGdyZXM6Ly9kYg==");
+        // Encoded secrets contribute their bytes, not their text.
+        assert_eq!(parsed["data"]["tls.key"], "aGk=");
+        assert_eq!(parsed["data"]["EMPTY"], "");
+
+        let empty = format_as_k8s(&[], &HashMap::new(), "app", None).unwrap();
+        assert!(!empty.contains("namespace"), "{empty}");
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&empty).unwrap();
+        assert!(parsed["data"].as_mapping().unwrap().is_empty());
+    }
+
+    #[test]
+    fn k8s_rejects_invalid_keys_and_names() {
+        let secrets = vec![
+            ("GOOD".to_string(), "1".to_string()),
+            ("HAS SPACE".to_string(), "2".to_string()),
+            ("a/b".to_string(), "3".to_string()),
+        ];
+        let err = format_as_k8s(&secrets, &HashMap::new(), "app", None)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("HAS SPACE, a/b"), "{err}");
+        assert!(!err.contains("GOOD"), "{err}");
+
+        let options = |name, namespace| K8sOptions { name, namespace };
+        assert_eq!(
+            options(None, None).checked_name("staging").unwrap(),
+            "envvault-staging"
+        );
+        assert!(options(Some("My_App"), None).checked_name("dev").is_err());
+        assert!(options(Some("app.v1"), None).checked_name("dev").is_ok());
+        assert!(options(None, Some("a.b")).checked_name("dev").is_err());
+        assert!(options(None, Some("-ns")).checked_name("dev").is_err());
+    }
+
+    /// Read an env file the way `docker run --env-file` does: skip blank
+    /// and `#` lines, split at the first `=`, keep the rest verbatim.
+    fn docker_env_file(content: &str) -> Vec<(String, String)> {
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn docker_env_writes_values_verbatim() {
+        let secrets = vec![
+            ("GREETING".to_string(), "hello world".to_string()),
+            ("QUOTED".to_string(), "\"as-is\"".to_string()),
+            (
+                "URL".to_string(),
+                "postgres://u:p@db/x?a=b#frag".to_string(),
+            ),
+            ("EMPTY".to_string(), String::new()),
+        ];
+
+        let out = format_as_docker_env(&secrets).unwrap();
+        assert!(out.contains("GREETING=hello world\n"), "{out}");
+        assert_eq!(docker_env_file(&out), secrets);
+    }
+
+    #[test]
+    fn docker_env_rejects_every_multiline_value() {
+        let secrets = vec![
+            ("CERT".to_string(), "line1\nline2".to_string()),
+            ("OK".to_string(), "fine".to_string()),
+            ("WIN".to_string(), "a\r\nb".to_string()),
+        ];
+        let err = format_as_docker_env(&secrets).unwrap_err().to_string();
+        assert!(err.contains("CERT, WIN"), "{err}");
+        assert!(!err.contains("OK"), "{err}");
+    }
 }
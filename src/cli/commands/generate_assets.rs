@@ -0,0 +1,69 @@
+//! `envvault generate-assets` — write completions and man pages for packaging.
+//!
+//! Usage:
+//!   envvault generate-assets --output dist/
+//!
+//! Layout:
+//!   <output>/completions/  — one script per supported shell
+//!   <output>/man/          — `envvault.1` plus one page per subcommand
+
+use std::fs;
+use std::path::Path;
+
+use clap::CommandFactory;
+
+use crate::cli::commands::completions;
+use crate::cli::output;
+use crate::cli::Cli;
+use crate::errors::Result;
+
+/// Execute the `generate-assets` command.
+pub fn execute(output_dir: &str) -> Result<()> {
+    let root = Path::new(output_dir);
+
+    let completion_count = write_completions(&root.join("completions"))?;
+    let man_count = write_man_pages(&root.join("man"))?;
+
+    output::success(&format!(
+        "Wrote {completion_count} completion scripts and {man_count} man pages to {output_dir}"
+    ));
+    Ok(())
+}
+
+/// Write completion scripts for every supported shell into `dir`.
+fn write_completions(dir: &Path) -> Result<usize> {
+    fs::create_dir_all(dir)?;
+    for shell in completions::SUPPORTED_SHELLS {
+        completions::write_to_dir(shell, dir)?;
+    }
+    Ok(completions::SUPPORTED_SHELLS.len())
+}
+
+/// Write man pages for the top-level command and each visible subcommand.
+fn write_man_pages(dir: &Path) -> Result<usize> {
+    fs::create_dir_all(dir)?;
+    clap_mangen::generate_to(Cli::command(), dir)?;
+    Ok(fs::read_dir(dir)?.count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn writes_completions_and_man_pages_into_subfolders() {
+        let dir = TempDir::new().unwrap();
+        execute(dir.path().to_str().unwrap()).unwrap();
+
+        let completions = dir.path().join("completions");
+        assert!(completions.join("envvault.bash").exists());
+        assert!(completions.join("_envvault").exists());
+        assert!(completions.join("envvault.fish").exists());
+
+        let man = dir.path().join("man");
+        assert!(man.join("envvault.1").exists());
+        assert!(man.join("envvault-init.1").exists());
+        assert!(man.join("envvault-env-clone.1").exists());
+    }
+}
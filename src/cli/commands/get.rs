@@ -1,18 +1,90 @@
-//! `envvault get` — retrieve and print a single secret's value.
+//! `envvault get` — retrieve and print secret values.
+//!
+//! With one key, stdout is exactly the stored value followed by one `\n`.
+//! With several keys, each is printed as a `KEY=value` line.  `-n` drops
+//! the final newline and `--print0` terminates every entry with a NUL
+//! byte instead, for `$(...)` captures and `xargs -0`.
+//!
+//! `--decode` writes the decoded bytes of a secret stored with an encoding
+//! hint (`set --encoding base64|hex`), with no trailing newline, so binary
+//! values can be piped straight to a file.
+//!
+//! `--format table` renders a Name/Value table for interactive use, with
+//! values masked unless `--reveal` is passed.
+//!
+//! `--copy` (alias `--clipboard`) puts the value on the clipboard instead
+//! of printing it, so it stays out of scrollback, and clears it again after
+//! `--clear-after <SECONDS>`, or `clipboard_clear_secs` (default 30) when
+//! that is not given.  Clipboard access needs the `clipboard` cargo
+//! feature.
 
+use std::io::Write;
+
+use console::style;
+use zeroize::Zeroizing;
+
+use crate::cli::output;
 use crate::cli::{load_keyfile, prompt_password_for_vault, vault_path, Cli};
 use crate::errors::{EnvVaultError, Result};
-use crate::vault::VaultStore;
+use crate::vault::{SecretEncoding, VaultStore};
+
+/// Default for `clipboard_clear_secs` in `.envvault.toml`.
+pub const DEFAULT_CLIPBOARD_CLEAR_SECS: u64 = 30;
+
+/// Output format for `get`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GetFormat {
+    /// Raw value (one key) or `KEY=value` lines (several keys).
+    Line,
+    /// Two-column Name/Value table.
+    Table,
+}
+
+impl GetFormat {
+    /// Parse a `--format` value.
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "line" => Ok(Self::Line),
+            "table" => Ok(Self::Table),
+            other => Err(EnvVaultError::CommandFailed(format!(
+                "unknown get format '{other}' — supported: line, table"
+            ))),
+        }
+    }
+}
 
 /// Execute the `get` command.
-pub fn execute(cli: &Cli, key: &str, clipboard: bool) -> Result<()> {
+#[allow(clippy::too_many_arguments)]
+pub fn execute(
+    cli: &Cli,
+    keys: &[String],
+    clipboard: bool,
+    no_newline: bool,
+    print0: bool,
+    format: &str,
+    reveal: bool,
+    decode: bool,
+    clear_after: Option<u64>,
+) -> Result<()> {
+    let format = GetFormat::parse(format)?;
+    if clipboard && keys.len() > 1 {
+        return Err(EnvVaultError::CommandFailed(
+            "--copy takes a single key".into(),
+        ));
+    }
+    if decode && (keys.len() > 1 || format == GetFormat::Table) {
+        return Err(EnvVaultError::CommandFailed(
+            "--decode takes a single key in line format".into(),
+        ));
+    }
+
     let path = vault_path(cli)?;
     let keyfile = load_keyfile(cli)?;
 
     // Open the vault (requires password).
     let vault_id = path.to_string_lossy();
     let password = prompt_password_for_vault(Some(&vault_id))?;
-    let store = match VaultStore::open(&path, password.as_bytes(), keyfile.as_deref()) {
+    let mut store = match VaultStore::open(&path, password.as_bytes(), keyfile.as_deref()) {
         Ok(store) => store,
         Err(e) => {
             #[cfg(feature = "audit-log")]
@@ -20,71 +92,284 @@ pub fn execute(cli: &Cli, key: &str, clipboard: bool) -> Result<()> {
             return Err(e);
         }
     };
+    crate::cli::warn_rotation_due(&store);
+    crate::cli::enable_access_tracking(&mut store);
 
-    // Decrypt the secret value.
-    let value = store.get_secret(key)?;
-
-    if clipboard {
-        copy_to_clipboard(&value)?;
-        crate::cli::output::success(&format!("Copied '{key}' to clipboard (clears in 30s)"));
+    // Decrypt every requested value before printing anything, so a
+    // missing key doesn't leave partial output behind.
+    let mut values = Vec::with_capacity(keys.len());
+    for key in keys {
+        values.push((key.as_str(), Zeroizing::new(store.get_secret(key)?)));
+    }
+    crate::cli::persist_access(&mut store);
+    crate::cli::warn_expired(&store, keys.iter().map(String::as_str));
 
-        // Spawn a background process to clear the clipboard after 30 seconds.
-        spawn_clipboard_clear();
+    if decode {
+        let (key, value) = &values[0];
+        let decoded = Zeroizing::new(decode_value(key, store.secret_encoding(key), value)?);
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(&decoded)?;
+        stdout.flush()?;
+    } else if clipboard {
+        let (key, value) = &values[0];
+        copy_to_clipboard(value)?;
+        let clear_secs = clear_after.unwrap_or_else(clipboard_clear_secs);
+        output::success(&format!(
+            "Copied {key} to clipboard ({})",
+            describe_clear(clear_secs)
+        ));
+        spawn_clipboard_clear(clear_secs);
+    } else if format == GetFormat::Table {
+        print_values_table(&values, reveal);
     } else {
-        println!("{value}");
+        // One write_all so the output can't interleave with stderr.
+        let buf = render_lines(&values, no_newline, print0);
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(&buf)?;
+        stdout.flush()?;
     }
 
     #[cfg(feature = "audit-log")]
-    crate::audit::log_read_audit(cli, "get", Some(key), None);
+    for key in keys {
+        crate::audit::log_read_audit(cli, "get", Some(key), None);
+    }
 
     Ok(())
 }
 
+/// Render values in line format.
+///
+/// A single value is written as-is; several become `KEY=value` entries.
+/// Each entry ends with [`value_terminator`], except that `no_newline`
+/// still separates multiple entries with `\n` and only drops the last one.
+pub fn render_lines(
+    values: &[(&str, Zeroizing<String>)],
+    no_newline: bool,
+    print0: bool,
+) -> Zeroizing<Vec<u8>> {
+    let single = values.len() == 1;
+    let capacity = values
+        .iter()
+        .map(|(key, value)| key.len() + value.len() + 2)
+        .sum();
+    let mut buf = Zeroizing::new(Vec::with_capacity(capacity));
+
+    for (i, (key, value)) in values.iter().enumerate() {
+        if !single {
+            buf.extend_from_slice(key.as_bytes());
+            buf.push(b'=');
+        }
+        buf.extend_from_slice(value.as_bytes());
+
+        let last = i + 1 == values.len();
+        buf.extend_from_slice(value_terminator(no_newline && last, print0));
+    }
+
+    buf
+}
+
+/// Decode `value` according to the secret's stored encoding hint.
+pub fn decode_value(key: &str, encoding: Option<&str>, value: &str) -> Result<Vec<u8>> {
+    let Some(name) = encoding else {
+        return Err(EnvVaultError::CommandFailed(format!(
+            "'{key}' has no encoding hint — set one with `envvault set {key} --encoding base64|hex`"
+        )));
+    };
+    match SecretEncoding::parse(name)? {
+        Some(encoding) => encoding.decode(value),
+        None => Ok(value.as_bytes().to_vec()),
+    }
+}
+
+/// Print a Name/Value table, masking values unless `reveal` is set.
+pub(crate) fn print_values_table(values: &[(&str, Zeroizing<String>)], reveal: bool) {
+    let mut table = output::table();
+    table.set_header(vec!["Name", "Value"]);
+
+    for (key, value) in values {
+        table.add_row(vec![
+            style(key).cyan().bold().to_string(),
+            output::mask_value(value, reveal),
+        ]);
+    }
+
+    println!("{table}");
+    if !reveal {
+        output::tip("Pass --reveal to show the values.");
+    }
+}
+
+/// Bytes written after the value: `\n` by default, nothing with
+/// `--no-newline`, or NUL with `--print0`.
+pub fn value_terminator(no_newline: bool, print0: bool) -> &'static [u8] {
+    if print0 {
+        b"\0"
+    } else if no_newline {
+        b""
+    } else {
+        b"\n"
+    }
+}
+
 /// Copy a value to the system clipboard using arboard.
-fn copy_to_clipboard(value: &str) -> Result<()> {
-    let mut clip = arboard::Clipboard::new()
-        .map_err(|e| EnvVaultError::ClipboardError(format!("failed to access clipboard: {e}")))?;
+///
+/// arboard keeps its own copy; the caller still owns (and should zeroize)
+/// `value`.
+#[cfg(feature = "clipboard")]
+pub fn copy_to_clipboard(value: &str) -> Result<()> {
+    let mut clip = arboard::Clipboard::new().map_err(|e| {
+        EnvVaultError::ClipboardError(format!(
+            "failed to access clipboard: {e} — print the value without --copy instead"
+        ))
+    })?;
     clip.set_text(value)
         .map_err(|e| EnvVaultError::ClipboardError(format!("failed to copy to clipboard: {e}")))?;
     Ok(())
 }
 
-/// Spawn a detached background process to clear the clipboard after 30 seconds.
+#[cfg(not(feature = "clipboard"))]
+pub fn copy_to_clipboard(_value: &str) -> Result<()> {
+    Err(EnvVaultError::FeatureDisabled(crate::features::CLIPBOARD))
+}
+
+/// `clipboard_clear_secs` from `.envvault.toml`.
+pub fn clipboard_clear_secs() -> u64 {
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| crate::config::Settings::load(&cwd).ok())
+        .map_or(DEFAULT_CLIPBOARD_CLEAR_SECS, |s| s.clipboard_clear_secs)
+}
+
+/// "clears in 30s", or a reminder when auto-clear is off.
+pub fn describe_clear(secs: u64) -> String {
+    if secs == 0 {
+        "not cleared automatically".to_string()
+    } else {
+        format!("clears in {secs}s")
+    }
+}
+
+/// Spawn a detached background process to clear the clipboard after
+/// `secs` seconds; 0 does nothing.
 ///
 /// Best-effort: if it fails, we just warn — the secret was already copied.
 #[cfg(unix)]
-fn spawn_clipboard_clear() {
+pub fn spawn_clipboard_clear(secs: u64) {
     use std::process::{Command, Stdio};
 
+    if secs == 0 {
+        return;
+    }
+
     // Try xclip first, fall back to xsel, then pbcopy (macOS).
-    let clear_cmd = "sleep 30 && \
+    let clear_cmd = format!(
+        "sleep {secs} && \
         (printf '' | xclip -selection clipboard 2>/dev/null || \
          xsel --clipboard --delete 2>/dev/null || \
-         printf '' | pbcopy 2>/dev/null || true)";
+         printf '' | pbcopy 2>/dev/null || true)"
+    );
 
     let result = Command::new("sh")
-        .args(["-c", clear_cmd])
+        .args(["-c", &clear_cmd])
         .stdin(Stdio::null())
         .stdout(Stdio::null())
         .stderr(Stdio::null())
         .spawn();
 
     if result.is_err() {
-        crate::cli::output::warning("Could not schedule clipboard auto-clear");
+        output::warning("Could not schedule clipboard auto-clear");
     }
 }
 
 #[cfg(not(unix))]
-fn spawn_clipboard_clear() {
-    crate::cli::output::warning(
-        "Clipboard auto-clear is not supported on this platform — clear manually",
-    );
+pub fn spawn_clipboard_clear(secs: u64) {
+    if secs > 0 {
+        output::warning("Clipboard auto-clear is not supported on this platform — clear manually");
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn values(pairs: &[(&'static str, &str)]) -> Vec<(&'static str, Zeroizing<String>)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (*k, Zeroizing::new(v.to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn clear_after_needs_copy() {
+        use crate::cli::{Cli, Commands};
+        use clap::Parser;
+
+        let cli = Cli::try_parse_from(["envvault", "get", "TOKEN", "--copy", "--clear-after", "5"])
+            .unwrap();
+        match cli.command {
+            Commands::Get { clear_after, .. } => assert_eq!(clear_after, Some(5)),
+            _ => panic!("expected get"),
+        }
+        assert!(Cli::try_parse_from(["envvault", "get", "TOKEN", "--clear-after", "5"]).is_err());
+    }
+
+    #[test]
+    fn clear_description_covers_disabled() {
+        assert_eq!(describe_clear(30), "clears in 30s");
+        assert_eq!(describe_clear(0), "not cleared automatically");
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    #[test]
+    fn copy_without_the_feature_explains_how_to_enable_it() {
+        let err = copy_to_clipboard("secret").unwrap_err();
+        assert!(
+            matches!(err, EnvVaultError::FeatureDisabled("clipboard")),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn render_lines_single_value_is_raw() {
+        let single = values(&[("A", "one\ntwo")]);
+        assert_eq!(
+            render_lines(&single, false, false).as_slice(),
+            b"one\ntwo\n"
+        );
+        assert_eq!(render_lines(&single, true, false).as_slice(), b"one\ntwo");
+    }
+
+    #[test]
+    fn render_lines_multiple_values_are_key_value() {
+        let many = values(&[("A", "1"), ("B", "2")]);
+        assert_eq!(render_lines(&many, false, false).as_slice(), b"A=1\nB=2\n");
+        assert_eq!(render_lines(&many, true, false).as_slice(), b"A=1\nB=2");
+        assert_eq!(render_lines(&many, false, true).as_slice(), b"A=1\0B=2\0");
+    }
+
+    #[test]
+    fn get_format_parses_known_values() {
+        assert_eq!(GetFormat::parse("line").unwrap(), GetFormat::Line);
+        assert_eq!(GetFormat::parse("TABLE").unwrap(), GetFormat::Table);
+        assert!(GetFormat::parse("yaml").is_err());
+    }
+
+    #[test]
+    fn decode_value_uses_the_stored_hint() {
+        assert_eq!(decode_value("A", Some("hex"), "ff00").unwrap(), [255, 0]);
+        assert_eq!(decode_value("A", Some("base64"), "aGk=").unwrap(), b"hi");
+
+        let err = decode_value("A", None, "aGk=").unwrap_err().to_string();
+        assert!(err.contains("no encoding hint"), "{err}");
+    }
+
+    #[test]
+    fn value_terminator_matches_flags() {
+        assert_eq!(value_terminator(false, false), b"\n");
+        assert_eq!(value_terminator(true, false), b"");
+        assert_eq!(value_terminator(false, true), b"\0");
+    }
+
     #[test]
     fn clipboard_copy_returns_error_on_invalid_clipboard() {
         // In a headless CI environment, clipboard access may fail.
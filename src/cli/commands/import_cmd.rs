@@ -3,25 +3,81 @@
 //! Supported formats:
 //! - `.env` files (auto-detected by extension or content)
 //! - JSON files (object with string values)
+//! - YAML files (`.yaml` / `.yml`): a flat mapping of keys to scalars;
+//!   numbers and booleans become their string form, `null` an empty value
+//! - 1Password and Bitwarden JSON exports (`--format 1password|bitwarden`,
+//!   see [`super::password_manager`]); `--filter` picks vaults or folders
+//!
+//! By default nested JSON values are stored as one JSON string under their
+//! top-level key.  `--flatten` instead turns `{"database": {"url": ..}}`
+//! into `DATABASE_URL` (segments joined by `--separator`, default `_`).
+//!
+//! Every name to be written must satisfy the project's `[key_policy]`;
+//! otherwise nothing is imported and all offending names are listed.
+//!
+//! `--merge-strategy` decides what happens to keys the vault already has:
+//! `overwrite` (default) replaces them, `skip` keeps the vault's value
+//! (`--skip-existing` is shorthand), and `fail` aborts before anything is
+//! written if any imported key already exists.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::Path;
 
+use super::password_manager;
 use crate::cli::env_parser;
-use crate::cli::output;
+use crate::cli::{key_policy, output};
 use crate::cli::{load_keyfile, prompt_password_for_vault, vault_path, Cli};
 use crate::errors::{EnvVaultError, Result};
 use crate::vault::VaultStore;
 
+/// What `import` does with keys the vault already holds
+/// (`--merge-strategy`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Replace the vault's value (default).
+    Overwrite,
+    /// Keep the vault's value.
+    Skip,
+    /// Import nothing if any key already exists.
+    Fail,
+}
+
+impl MergeStrategy {
+    /// Parse a `--merge-strategy` value.
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "overwrite" => Ok(Self::Overwrite),
+            "skip" => Ok(Self::Skip),
+            "fail" => Ok(Self::Fail),
+            other => Err(EnvVaultError::CommandFailed(format!(
+                "unknown merge strategy '{other}' — supported: overwrite, skip, fail"
+            ))),
+        }
+    }
+
+    /// Name used in output and the audit log.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Overwrite => "overwrite",
+            Self::Skip => "skip",
+            Self::Fail => "fail",
+        }
+    }
+}
+
 /// Execute the `import` command.
 pub fn execute(
     cli: &Cli,
     file_path: &str,
     format: Option<&str>,
     dry_run: bool,
-    skip_existing: bool,
+    merge_strategy: &str,
+    flatten: Option<&str>,
+    filter: Option<&str>,
 ) -> Result<()> {
+    let strategy = MergeStrategy::parse(merge_strategy)?;
+    let skip_existing = strategy == MergeStrategy::Skip;
     let vault = vault_path(cli)?;
     let source = Path::new(file_path);
 
@@ -36,119 +92,393 @@ pub fn execute(
     let vault_id = vault.to_string_lossy();
     let password = prompt_password_for_vault(Some(&vault_id))?;
     let mut store = VaultStore::open(&vault, password.as_bytes(), keyfile.as_deref())?;
+    crate::cli::warn_rotation_due(&store);
 
-    // Detect format from flag or file extension.
-    let detected_format = match format {
-        Some(f) => f.to_string(),
-        None => detect_format(source),
-    };
-
-    let secrets = match detected_format.as_str() {
-        "env" => env_parser::parse_env_file(source)?,
-        "json" => parse_json_file(source)?,
-        other => {
-            return Err(EnvVaultError::CommandFailed(format!(
-                "unknown import format '{other}' — use 'env' or 'json'"
-            )));
-        }
-    };
+    let secrets = parse_import_file(source, format, flatten, filter)?;
 
     if secrets.is_empty() {
         output::warning("No secrets found in the import file.");
         return Ok(());
     }
 
+    // Check every name that would be written, reporting all violations.
+    let mut keys: Vec<&str> = secrets
+        .keys()
+        .filter(|key| !(skip_existing && store.contains_key(key)))
+        .map(String::as_str)
+        .collect();
+    keys.sort_unstable();
+    key_policy::enforce(cli, keys)?;
+
+    // With `fail`, refuse before a single key is written.
+    if strategy == MergeStrategy::Fail {
+        let mut existing: Vec<&str> = secrets
+            .keys()
+            .filter(|key| store.contains_key(key))
+            .map(String::as_str)
+            .collect();
+        if !existing.is_empty() {
+            existing.sort_unstable();
+            return Err(EnvVaultError::CommandFailed(format!(
+                "--merge-strategy fail: {} already exist in the vault: {} — nothing was imported",
+                existing.len(),
+                existing.join(", ")
+            )));
+        }
+    }
+
     // Import each secret into the vault.
-    let mut count = 0;
-    let mut skipped = 0;
+    let mut counts = ImportCounts::default();
+    let mut progress = crate::progress::phase(crate::progress::IMPORT, secrets.len());
     for (key, value) in &secrets {
-        if skip_existing && store.contains_key(key) {
+        let exists = store.contains_key(key);
+        if skip_existing && exists {
             output::info(&format!("  ~ {key} (skipped, already exists)"));
-            skipped += 1;
+            counts.skipped += 1;
+            progress.inc();
             continue;
         }
 
         if dry_run {
-            let label = if store.contains_key(key) {
-                "update"
-            } else {
-                "add"
-            };
+            let label = if exists { "overwrite" } else { "add" };
             output::info(&format!("  + {key} (would {label})"));
         } else {
             store.set_secret(key, value)?;
-            output::info(&format!("  + {key}"));
+            if exists {
+                output::info(&format!("  + {key} (overwritten)"));
+            } else {
+                output::info(&format!("  + {key}"));
+            }
+        }
+        if exists {
+            counts.overwritten += 1;
+        } else {
+            counts.added += 1;
         }
-        count += 1;
+        progress.inc();
     }
+    drop(progress);
+    let count = counts.added + counts.overwritten;
 
     if dry_run {
         output::info(&format!(
-            "Dry run: {} secrets would be imported from {}{}",
+            "Dry run: {} secrets would be imported from {} ({})",
             count,
             source.display(),
-            if skipped > 0 {
-                format!(" ({skipped} skipped)")
-            } else {
-                String::new()
-            }
+            counts.summary()
         ));
         return Ok(());
     }
 
+    crate::cli::warn_clock_skew(&store);
     store.save()?;
 
     crate::audit::log_audit(
         cli,
         "import",
         None,
-        Some(&format!("{count} secrets from {}", source.display())),
+        Some(
+            &crate::audit::AuditDetails::import(count, &source.display().to_string())
+                .strategy(strategy.as_str())
+                .to_string(),
+        ),
     );
+    crate::cli::run_post_write_hook("import", &cli.env);
 
-    let skip_msg = if skipped > 0 {
-        format!(" ({skipped} skipped)")
-    } else {
-        String::new()
-    };
     output::success(&format!(
-        "Imported {} secrets from {} into '{}' vault{}",
+        "Imported {} secrets from {} into '{}' vault ({})",
         count,
         source.display(),
         store.environment(),
-        skip_msg
+        counts.summary()
     ));
 
     Ok(())
 }
 
+/// How many imported keys were new, replaced an existing value, or were
+/// left alone.
+#[derive(Debug, Default)]
+struct ImportCounts {
+    added: usize,
+    overwritten: usize,
+    skipped: usize,
+}
+
+impl ImportCounts {
+    fn summary(&self) -> String {
+        format!(
+            "{} added, {} overwritten, {} skipped",
+            self.added, self.overwritten, self.skipped
+        )
+    }
+}
+
+/// Parse `source` into key-value pairs the way `import` does: `format`
+/// (or the file extension) picks the parser, and `flatten` and `filter`
+/// are checked against it.  Also used by `env clone --from-file`.
+pub fn parse_import_file(
+    source: &Path,
+    format: Option<&str>,
+    flatten: Option<&str>,
+    filter: Option<&str>,
+) -> Result<HashMap<String, String>> {
+    // Detect format from flag or file extension.
+    let detected_format = match format {
+        Some(f) => f.to_string(),
+        None => detect_format(source),
+    };
+
+    if flatten.is_some() && detected_format != "json" {
+        return Err(EnvVaultError::CommandFailed(
+            "--flatten only applies to JSON imports".into(),
+        ));
+    }
+
+    let password_manager = matches!(detected_format.as_str(), "1password" | "bitwarden");
+    if filter.is_some() && !password_manager {
+        return Err(EnvVaultError::CommandFailed(
+            "--filter only applies to 1password and bitwarden imports".into(),
+        ));
+    }
+
+    match detected_format.as_str() {
+        "env" => {
+            let parsed = env_parser::read_env_file(source)?;
+            env_parser::warn_if_crlf(source, &parsed);
+            Ok(parsed.secrets)
+        }
+        "json" => parse_json_file(source, flatten),
+        "yaml" => parse_yaml(&read_file(source)?),
+        "1password" => password_manager::parse_1password(&read_file(source)?, filter),
+        "bitwarden" => password_manager::parse_bitwarden(&read_file(source)?, filter),
+        other => Err(EnvVaultError::CommandFailed(format!(
+            "unknown import format '{other}' — use 'env', 'json', 'yaml', '1password', or 'bitwarden'"
+        ))),
+    }
+}
+
 /// Detect the file format from its extension.
 fn detect_format(path: &Path) -> String {
     match path.extension().and_then(|e| e.to_str()) {
         Some("json") => "json".to_string(),
+        Some("yaml" | "yml") => "yaml".to_string(),
         _ => "env".to_string(), // Default to .env format.
     }
 }
 
 /// Parse a JSON file (object with string values) into a key-value map.
-fn parse_json_file(path: &Path) -> Result<HashMap<String, String>> {
-    let content = fs::read_to_string(path)
-        .map_err(|e| EnvVaultError::CommandFailed(format!("failed to read file: {e}")))?;
+///
+/// With `flatten` (the separator), nested values are expanded by
+/// [`flatten_json`]; otherwise they are kept as JSON strings.
+fn parse_json_file(path: &Path, flatten: Option<&str>) -> Result<HashMap<String, String>> {
+    parse_json(&read_file(path)?, flatten)
+}
+
+/// Parse a JSON object into a key-value map, as for [`parse_json_file`].
+fn parse_json(content: &str, flatten: Option<&str>) -> Result<HashMap<String, String>> {
+    let map = parse_json_object(content)?;
 
-    let map: HashMap<String, serde_json::Value> = serde_json::from_str(&content)
-        .map_err(|e| EnvVaultError::CommandFailed(format!("invalid JSON: {e}")))?;
+    if let Some(separator) = flatten {
+        return flatten_json(map, separator);
+    }
 
     let mut secrets = HashMap::new();
     for (key, value) in map {
-        let string_value = match value {
-            serde_json::Value::String(s) => s,
-            other => other.to_string(), // Convert non-strings to their JSON repr.
+        secrets.insert(key, leaf_to_string(value));
+    }
+
+    Ok(secrets)
+}
+
+/// Parse a JSON object whose values are all scalars, as `dump --json`
+/// writes it.  A nested object or array is an error naming its key rather
+/// than a value stored as JSON text.  Used by `set-many --json`.
+pub(crate) fn parse_flat_json(content: &str) -> Result<HashMap<String, String>> {
+    let map = parse_json_object(content)?;
+
+    let mut secrets = HashMap::with_capacity(map.len());
+    for (key, value) in map {
+        if value.is_object() || value.is_array() {
+            return Err(EnvVaultError::CommandFailed(format!(
+                "'{key}' holds a nested JSON value — only flat {{\"KEY\": \"value\"}} objects are accepted (the output of dump --json, without --with-metadata)"
+            )));
+        }
+        secrets.insert(key, leaf_to_string(value));
+    }
+
+    Ok(secrets)
+}
+
+fn parse_json_object(content: &str) -> Result<serde_json::Map<String, serde_json::Value>> {
+    serde_json::from_str(content)
+        .map_err(|e| EnvVaultError::CommandFailed(format!("invalid JSON: {e}")))
+}
+
+/// Parse a flat YAML mapping into a key-value map.
+///
+/// Strings are taken as-is (block scalars keep their newlines), numbers
+/// and booleans become their string form, and `null` an empty value.
+/// Nested mappings and sequences are rejected, naming the key.
+fn parse_yaml(content: &str) -> Result<HashMap<String, String>> {
+    use serde_yaml::Value;
+
+    let map: serde_yaml::Mapping = match serde_yaml::from_str(content) {
+        Ok(Value::Mapping(map)) => map,
+        Ok(Value::Null) => return Ok(HashMap::new()),
+        Ok(_) => {
+            return Err(EnvVaultError::CommandFailed(
+                "YAML import expects a mapping of KEY: value at the top level".into(),
+            ))
+        }
+        Err(e) => return Err(EnvVaultError::CommandFailed(format!("invalid YAML: {e}"))),
+    };
+
+    let mut secrets = HashMap::with_capacity(map.len());
+    for (key, value) in map {
+        let key = match key {
+            Value::String(key) => key,
+            other => {
+                return Err(EnvVaultError::CommandFailed(format!(
+                    "YAML key {} is not a string",
+                    yaml_preview(&other)
+                )))
+            }
+        };
+        let value = match value {
+            Value::String(s) => s,
+            Value::Number(n) => n.to_string(),
+            Value::Bool(b) => b.to_string(),
+            Value::Null => String::new(),
+            Value::Mapping(_) | Value::Sequence(_) => {
+                return Err(EnvVaultError::CommandFailed(format!(
+                    "'{key}' holds a nested YAML value — only flat KEY: value mappings can be imported"
+                )))
+            }
+            Value::Tagged(_) => {
+                return Err(EnvVaultError::CommandFailed(format!(
+                    "'{key}' holds a tagged YAML value — only plain scalars can be imported"
+                )))
+            }
         };
-        secrets.insert(key, string_value);
+        secrets.insert(key, value);
     }
 
     Ok(secrets)
 }
 
+/// Short rendering of a YAML value for error messages.
+fn yaml_preview(value: &serde_yaml::Value) -> String {
+    serde_yaml::to_string(value)
+        .map(|s| s.trim_end().to_string())
+        .unwrap_or_else(|_| "?".into())
+}
+
+fn read_file(path: &Path) -> Result<String> {
+    fs::read_to_string(path)
+        .map_err(|e| EnvVaultError::CommandFailed(format!("failed to read file: {e}")))
+}
+
+/// Flatten nested objects and arrays into `PARENT<sep>CHILD` keys.
+///
+/// Each path segment is uppercased and anything other than ASCII letters
+/// and digits becomes `_`; array elements use their index.  Two paths
+/// that end up with the same name are an error rather than a silent
+/// overwrite.
+pub fn flatten_json(
+    map: serde_json::Map<String, serde_json::Value>,
+    separator: &str,
+) -> Result<HashMap<String, String>> {
+    if separator.is_empty()
+        || !separator
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-' || b == b'.')
+    {
+        return Err(EnvVaultError::CommandFailed(format!(
+            "invalid --separator '{separator}' — use letters, digits, '_', '-', or '.'"
+        )));
+    }
+
+    // name -> (value, original JSON path) so collisions can name both paths.
+    let mut flat: BTreeMap<String, (String, String)> = BTreeMap::new();
+    for (key, value) in map {
+        flatten_into(&mut flat, &sanitize_segment(&key), key, value, separator)?;
+    }
+
+    Ok(flat
+        .into_iter()
+        .map(|(name, (value, _))| (name, value))
+        .collect())
+}
+
+fn flatten_into(
+    flat: &mut BTreeMap<String, (String, String)>,
+    name: &str,
+    path: String,
+    value: serde_json::Value,
+    separator: &str,
+) -> Result<()> {
+    use serde_json::Value;
+
+    let children: Vec<(String, Value)> = match value {
+        Value::Object(map) if !map.is_empty() => map.into_iter().collect(),
+        Value::Array(items) if !items.is_empty() => items
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| (i.to_string(), v))
+            .collect(),
+        leaf => {
+            if let Some((_, other_path)) = flat.get(name) {
+                return Err(EnvVaultError::CommandFailed(format!(
+                    "'{other_path}' and '{path}' both flatten to '{name}'"
+                )));
+            }
+            flat.insert(name.to_string(), (leaf_to_string(leaf), path));
+            return Ok(());
+        }
+    };
+
+    for (segment, child) in children {
+        let child_name = format!("{name}{separator}{}", sanitize_segment(&segment));
+        flatten_into(
+            flat,
+            &child_name,
+            format!("{path}.{segment}"),
+            child,
+            separator,
+        )?;
+    }
+    Ok(())
+}
+
+/// Uppercase a path segment and replace characters that aren't ASCII
+/// letters or digits with `_`.
+pub(crate) fn sanitize_segment(segment: &str) -> String {
+    if segment.is_empty() {
+        return "_".into();
+    }
+    segment
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Strings are stored as-is; everything else as its JSON text
+/// (`null` becomes an empty string).
+fn leaf_to_string(value: serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s,
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,16 +514,159 @@ mod tests {
         let mut file = NamedTempFile::with_suffix(".json").unwrap();
         write!(file, r#"{{"KEY": "value", "NUM": "42"}}"#).unwrap();
 
-        let secrets = parse_json_file(file.path()).unwrap();
+        let secrets = parse_json_file(file.path(), None).unwrap();
         assert_eq!(secrets["KEY"], "value");
         assert_eq!(secrets["NUM"], "42");
     }
 
+    #[test]
+    fn parse_yaml_converts_scalars_and_keeps_newlines() {
+        let secrets = parse_yaml(
+            "DATABASE_URL: postgres://db:5432/app\n\
+             PORT: 5432\n\
+             RATIO: 0.5\n\
+             DEBUG: true\n\
+             EMPTY:\n\
+             QUOTED: \"a # b\"\n\
+             PEM: |\n  -----BEGIN KEY-----\n  abc\n  -----END KEY-----\n",
+        )
+        .unwrap();
+        assert_eq!(secrets["DATABASE_URL"], "postgres://db:5432/app");
+        assert_eq!(secrets["PORT"], "5432");
+        assert_eq!(secrets["RATIO"], "0.5");
+        assert_eq!(secrets["DEBUG"], "true");
+        assert_eq!(secrets["EMPTY"], "");
+        assert_eq!(secrets["QUOTED"], "a # b");
+        assert_eq!(
+            secrets["PEM"],
+            "-----BEGIN KEY-----\nabc\n-----END KEY-----\n"
+        );
+        assert!(parse_yaml("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_yaml_rejects_nesting_and_non_mappings() {
+        let err = parse_yaml("OK: 1\nDATABASE:\n  url: x\n").unwrap_err();
+        assert!(
+            err.to_string().contains("'DATABASE' holds a nested"),
+            "{err}"
+        );
+
+        let err = parse_yaml("HOSTS:\n  - a\n  - b\n").unwrap_err();
+        assert!(err.to_string().contains("'HOSTS'"), "{err}");
+
+        assert!(parse_yaml("- a\n- b\n").is_err());
+        assert!(parse_yaml("1: one\n")
+            .unwrap_err()
+            .to_string()
+            .contains("not a string"));
+        assert!(parse_yaml("KEY: [unclosed\n").is_err());
+    }
+
+    #[test]
+    fn yaml_export_round_trips_byte_for_byte() {
+        let values = [
+            "plain",
+            "postgres://u:p@host:5432/db",
+            "#not-a-comment",
+            "line1\nline2\n",
+            "  leading and trailing  ",
+            "tab\tand \"quotes\" and \\ backslash",
+            "yes",
+            "0123",
+            "",
+            "ünïcødé ✓",
+            "- dash",
+            "crlf\r\n",
+        ];
+        let secrets: Vec<(String, String)> = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (format!("KEY_{i}"), v.to_string()))
+            .collect();
+
+        let yaml = crate::cli::commands::export::format_as_yaml(&secrets).unwrap();
+        let parsed = parse_yaml(&yaml).unwrap();
+        assert_eq!(parsed.len(), secrets.len());
+        for (key, value) in &secrets {
+            assert_eq!(&parsed[key], value, "{key}");
+        }
+    }
+
+    fn flatten(json: &str, separator: &str) -> Result<HashMap<String, String>> {
+        flatten_json(serde_json::from_str(json).unwrap(), separator)
+    }
+
+    #[test]
+    fn nested_json_is_kept_as_a_blob_without_flatten() {
+        let mut file = NamedTempFile::with_suffix(".json").unwrap();
+        write!(file, r#"{{"database": {{"url": "pg://", "pool": 5}}}}"#).unwrap();
+
+        let secrets = parse_json_file(file.path(), None).unwrap();
+        assert_eq!(secrets["database"], r#"{"pool":5,"url":"pg://"}"#);
+    }
+
+    #[test]
+    fn flatten_nested_objects_at_any_depth() {
+        let secrets = flatten(
+            r#"{"database": {"url": "pg://", "replica": {"primary": {"host": "db1"}}}}"#,
+            "_",
+        )
+        .unwrap();
+        assert_eq!(secrets["DATABASE_URL"], "pg://");
+        assert_eq!(secrets["DATABASE_REPLICA_PRIMARY_HOST"], "db1");
+        assert_eq!(secrets.len(), 2);
+    }
+
+    #[test]
+    fn flatten_arrays_use_indexes() {
+        let secrets = flatten(
+            r#"{"servers": [{"host": "a"}, {"host": "b"}], "tags": ["x", "y"]}"#,
+            "__",
+        )
+        .unwrap();
+        assert_eq!(secrets["SERVERS__0__HOST"], "a");
+        assert_eq!(secrets["SERVERS__1__HOST"], "b");
+        assert_eq!(secrets["TAGS__1"], "y");
+    }
+
+    #[test]
+    fn flatten_converts_non_string_leaves() {
+        let secrets = flatten(
+            r#"{"db": {"pool": 5, "ssl": true, "ratio": 0.5, "note": null, "opts": {}, "list": []}}"#,
+            "_",
+        )
+        .unwrap();
+        assert_eq!(secrets["DB_POOL"], "5");
+        assert_eq!(secrets["DB_SSL"], "true");
+        assert_eq!(secrets["DB_RATIO"], "0.5");
+        assert_eq!(secrets["DB_NOTE"], "");
+        assert_eq!(secrets["DB_OPTS"], "{}");
+        assert_eq!(secrets["DB_LIST"], "[]");
+    }
+
+    #[test]
+    fn flatten_sanitizes_segments_and_detects_collisions() {
+        let secrets = flatten(r#"{"api-keys": {"stripe.live": "sk"}}"#, "_").unwrap();
+        assert_eq!(secrets["API_KEYS_STRIPE_LIVE"], "sk");
+
+        let err = flatten(r#"{"a": {"b-c": "1"}, "A_B": {"C": "2"}}"#, "_").unwrap_err();
+        assert!(err.to_string().contains("both flatten to 'A_B_C'"), "{err}");
+    }
+
+    #[test]
+    fn flatten_rejects_invalid_separators() {
+        assert!(flatten(r#"{"a": 1}"#, "").is_err());
+        assert!(flatten(r#"{"a": 1}"#, "/").is_err());
+    }
+
     #[test]
     fn detect_format_from_extension() {
         assert_eq!(detect_format(Path::new("secrets.json")), "json");
         assert_eq!(detect_format(Path::new(".env")), "env");
         assert_eq!(detect_format(Path::new("secrets.env")), "env");
         assert_eq!(detect_format(Path::new("noext")), "env");
+        assert_eq!(detect_format(Path::new("config.yaml")), "yaml");
+        assert_eq!(detect_format(Path::new("config.yml")), "yaml");
     }
 }
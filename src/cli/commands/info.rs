@@ -0,0 +1,296 @@
+//! `envvault info` — show each vault's stored header details.
+//!
+//! Reads only the plaintext header, so no password is needed.  The KDF
+//! params shown are the ones baked into each vault at creation (or last
+//! `rotate-key`); editing `.envvault.toml` does not change them.
+//!
+//! `--raw-header` dumps what a single vault file's header claims, field by
+//! field, for debugging files of unknown origin.  Damaged files print as
+//! much as could be parsed and where parsing stopped.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use console::style;
+use serde_json::Value;
+
+use crate::cli::commands::env_list::list_environments;
+use crate::cli::output;
+use crate::cli::{vault_path, Cli};
+use crate::crypto::kdf::Argon2Params;
+use crate::errors::{EnvVaultError, Result};
+use crate::vault::format::{self, VaultInspection};
+
+/// Execute the `info` command.
+pub fn execute(cli: &Cli, raw_header: bool, vault_file: Option<&str>) -> Result<()> {
+    if raw_header {
+        let path = match vault_file {
+            Some(file) => PathBuf::from(file),
+            None => vault_path(cli)?,
+        };
+        return print_raw_header(&path);
+    }
+
+    let vault_dir = crate::cli::resolve_vault_dir(cli)?;
+
+    if !vault_dir.exists() {
+        output::info("No vault directory found.");
+        output::tip("Run `envvault init` to create a vault.");
+        return Ok(());
+    }
+
+    let mut envs = list_environments(&vault_dir)?;
+    envs.sort_by(|a, b| a.name.cmp(&b.name));
+
+    if envs.is_empty() {
+        output::info("No environments found.");
+        return Ok(());
+    }
+
+    let mut table = output::table();
+    table.set_header(vec![
+        "Environment",
+        "Secrets",
+        "Created",
+        "KDF",
+        "Keyfile",
+        "Status",
+    ]);
+
+    let mut weak = 0;
+    let mut legacy = 0;
+    for env in &envs {
+        let path = vault_dir.join(format!("{}.vault", env.name));
+        let raw = match format::read_vault(&path) {
+            Ok(raw) => raw,
+            Err(e) => {
+                table.add_row(vec![
+                    env.name.clone(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    style(format!("unreadable: {e}")).red().to_string(),
+                ]);
+                continue;
+            }
+        };
+
+        let params = Argon2Params::from(raw.header.argon2_params.unwrap_or_default());
+        let shortfalls = params.below_recommended();
+        let status = if raw.header.argon2_params.is_none() {
+            legacy += 1;
+            style("KDF params not stored").yellow().bold().to_string()
+        } else if shortfalls.is_empty() {
+            style("ok").green().to_string()
+        } else {
+            weak += 1;
+            style("below recommended").yellow().bold().to_string()
+        };
+
+        table.add_row(vec![
+            env.name.clone(),
+            raw.secrets.len().to_string(),
+            raw.header.created_at.format("%Y-%m-%d").to_string(),
+            params.to_string(),
+            if raw.header.keyfile_hash.is_some() {
+                "yes".into()
+            } else {
+                "no".into()
+            },
+            status,
+        ]);
+    }
+
+    println!("{table}");
+
+    #[cfg(feature = "audit-log")]
+    print_audit_schema(&vault_dir);
+
+    if weak > 0 {
+        output::warning(&format!(
+            "{weak} vault(s) use KDF params below the recommended defaults ({})",
+            Argon2Params::default()
+        ));
+        output::tip("Run `envvault --env <name> rotate-key` to re-key with the current config.");
+    }
+    if legacy > 0 {
+        output::warning(&format!(
+            "{legacy} vault(s) predate stored KDF params (created by v0.1.0) and rely on the built-in defaults"
+        ));
+        output::tip(
+            "Run `envvault --env <name> rotate-key` to record the params in the vault header.",
+        );
+    }
+
+    Ok(())
+}
+
+/// One line on the audit database's schema, if there is one.
+#[cfg(feature = "audit-log")]
+fn print_audit_schema(vault_dir: &Path) {
+    use crate::audit::{AuditLog, SCHEMA_VERSION};
+
+    let Some(version) = AuditLog::stored_schema_version(vault_dir) else {
+        return;
+    };
+    if version > SCHEMA_VERSION {
+        output::warning(&format!(
+            "Audit log: schema v{version} is newer than this build (v{SCHEMA_VERSION}) — it will not be written to."
+        ));
+    } else if version < SCHEMA_VERSION {
+        output::info(&format!(
+            "Audit log: schema v{version} (upgraded to v{SCHEMA_VERSION} on next use)"
+        ));
+    } else {
+        output::info(&format!("Audit log: schema v{version}"));
+    }
+}
+
+/// Print the raw header of one vault file.
+fn print_raw_header(path: &Path) -> Result<()> {
+    if !path.exists() {
+        return Err(EnvVaultError::VaultNotFound(path.to_path_buf()));
+    }
+    let data = std::fs::read(path)?;
+    let inspection = format::inspect_vault(&data);
+
+    print!(
+        "{}",
+        render_raw_header(&path.display().to_string(), &inspection)
+    );
+
+    if inspection.error.is_some() {
+        output::warning("The file is damaged; fields past the stopping point are not shown.");
+    }
+    Ok(())
+}
+
+/// Render a `VaultInspection` as aligned `label: value` lines.
+pub fn render_raw_header(path: &str, info: &VaultInspection) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "File:            {path} ({} bytes)", info.file_size);
+
+    if let Some(magic_ok) = info.magic_ok {
+        let magic = if magic_ok { "EVLT" } else { "not EVLT" };
+        let _ = writeln!(out, "Magic:           {magic}");
+    }
+    if let Some(version) = info.version {
+        let note = if version == format::CURRENT_VERSION {
+            "current".to_string()
+        } else if format::is_supported_version(version) {
+            "older, values not bound to names; `rotate-key` upgrades it".to_string()
+        } else {
+            format!(
+                "this build reads {}-{}",
+                format::OLDEST_VERSION,
+                format::CURRENT_VERSION
+            )
+        };
+        let _ = writeln!(out, "Format version:  {version} ({note})");
+    }
+    if let Some(len) = info.header_len {
+        let _ = writeln!(out, "Header length:   {len} bytes");
+    }
+
+    if let Some(ref fields) = info.header_fields {
+        let _ = writeln!(out, "Header:");
+        for (name, value) in fields {
+            let _ = writeln!(
+                out,
+                "  {:<15}{}",
+                format!("{name}:"),
+                header_field(name, value)
+            );
+        }
+        if !fields.contains_key("keyfile_hash") {
+            let _ = writeln!(out, "  {:<15}absent", "keyfile_hash:");
+        }
+        let check = info.header_problem.as_deref().unwrap_or("ok");
+        let _ = writeln!(out, "Header check:    {check}");
+    }
+
+    if let Some(count) = info.secrets_count {
+        let _ = writeln!(out, "Secrets:         {count}");
+    }
+    if let Some(len) = info.hmac_len {
+        let _ = writeln!(out, "HMAC:            {len} bytes (expected 32)");
+    }
+    if let Some(ref error) = info.error {
+        let _ = writeln!(out, "Parsing stopped: {error}");
+    }
+
+    out
+}
+
+/// Display form of one header field.  The salt is reduced to a short
+/// fingerprint and the keyfile hash to its presence.
+fn header_field(name: &str, value: &Value) -> String {
+    match (name, value) {
+        ("salt", Value::String(salt)) => match BASE64.decode(salt) {
+            Ok(bytes) => {
+                let fingerprint: String =
+                    bytes.iter().take(4).map(|b| format!("{b:02x}")).collect();
+                format!("{fingerprint}\u{2026} ({} bytes)", bytes.len())
+            }
+            Err(_) => "not valid base64".into(),
+        },
+        ("keyfile_hash", _) => "present".into(),
+        (_, Value::String(s)) => s.clone(),
+        (_, other) => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::devtools::{generate_vault, GenVaultOptions};
+
+    #[test]
+    fn raw_header_lists_fields_without_the_full_salt() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("dev.vault");
+        let opts = GenVaultOptions {
+            secrets: 3,
+            ..GenVaultOptions::default()
+        };
+        generate_vault(&path, &opts).unwrap();
+        let data = std::fs::read(&path).unwrap();
+        let raw = format::read_vault(&path).unwrap();
+
+        let text = render_raw_header("dev.vault", &format::inspect_vault(&data));
+        assert!(text.contains(&format!(
+            "File:            dev.vault ({} bytes)",
+            data.len()
+        )));
+        assert!(text.contains(&format!(
+            "Format version:  {} (current)",
+            format::CURRENT_VERSION
+        )));
+        assert!(text.contains("  environment:   test"));
+        assert!(text.contains("  keyfile_hash:  absent"));
+        assert!(text.contains("Header check:    ok"));
+        assert!(text.contains("Secrets:         3"));
+        assert!(text.contains("HMAC:            32 bytes (expected 32)"));
+
+        let full_salt = BASE64.encode(&raw.header.salt);
+        let fingerprint: String = raw.header.salt[..4]
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        assert!(!text.contains(&full_salt), "{text}");
+        assert!(
+            text.contains(&format!("  salt:          {fingerprint}")),
+            "{text}"
+        );
+    }
+
+    #[test]
+    fn raw_header_of_damaged_file_shows_where_parsing_stopped() {
+        let text = render_raw_header("bad.vault", &format::inspect_vault(b"NOPE and more"));
+        assert!(text.contains("Magic:           not EVLT"));
+        assert!(text.contains("Parsing stopped: at byte 0: missing EVLT magic bytes"));
+        assert!(!text.contains("Header:"));
+    }
+}
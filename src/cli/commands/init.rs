@@ -1,24 +1,48 @@
 //! `envvault init` — create a new vault, optionally importing .env secrets.
+//!
+//! `init` runs in phases: checks and the password prompt, creating the
+//! vault file, then importing `.env`, patching `.gitignore`, and
+//! installing the git hook.  If a phase after the vault was created fails,
+//! the new vault would block a re-run with `VaultAlreadyExists`, so `init`
+//! offers to delete it (`--clean-on-failure` deletes it without asking)
+//! and records an `init-failed` audit entry naming the phase.
 
 use std::fs;
-use std::io::{BufRead, BufReader};
+use std::io::{self, IsTerminal};
 use std::path::Path;
+use std::time::Duration;
 
 use dialoguer::Confirm;
 
-use crate::cli::env_parser::parse_env_line;
+use crate::cli::env_parser::{read_env_file, warn_if_crlf};
 use crate::cli::output;
-use crate::cli::{load_keyfile, prompt_new_password, Cli};
+use crate::cli::{load_keyfile, prompt_new_password, report_kdf_params, Cli};
 use crate::config::Settings;
+use crate::crypto::Cipher;
 use crate::errors::{EnvVaultError, Result};
 use crate::vault::VaultStore;
 
 /// Execute the `init` command.
-pub fn execute(cli: &Cli) -> Result<()> {
+///
+/// With `no_gitignore`, `.gitignore` is left untouched for repos that
+/// manage it centrally.  `cipher` picks the AEAD for secret values
+/// (default AES-256-GCM).  With `clean_on_failure`, a vault created by
+/// this run is deleted without asking if a later phase fails.  With
+/// `tune_ms`, the Argon2 params are calibrated to take about that many
+/// milliseconds here rather than read from `.envvault.toml`.
+pub fn execute(
+    cli: &Cli,
+    no_gitignore: bool,
+    cipher: Option<&str>,
+    clean_on_failure: bool,
+    tune_ms: Option<u64>,
+) -> Result<()> {
+    let cipher = cipher.map(Cipher::parse).transpose()?.unwrap_or_default();
     let cwd = std::env::current_dir()?;
     let vault_dir = cwd.join(&cli.vault_dir);
     let env = &cli.env;
     let vault_path = vault_dir.join(format!("{env}.vault"));
+    crate::cli::check_vault_symlinks(&vault_dir, &vault_path, cli.strict_permissions)?;
 
     // 1. Create the vault directory if it doesn't exist.
     if !vault_dir.exists() {
@@ -39,42 +63,51 @@ pub fn execute(cli: &Cli) -> Result<()> {
     // 4. Load optional keyfile and settings, then create the vault file.
     let keyfile = load_keyfile(cli)?;
     let settings = Settings::load(&cwd)?;
-    let mut store = VaultStore::create(
+    let params = match tune_ms {
+        Some(ms) => {
+            output::info(&format!("Calibrating Argon2id for ~{ms} ms per unlock..."));
+            crate::crypto::kdf::calibrate(Duration::from_millis(ms))
+        }
+        None => settings.argon2_params(),
+    };
+    crate::crypto::kdf::validate_params(&params)?;
+    report_kdf_params(&params);
+    let mut store = VaultStore::create_with_cipher(
         &vault_path,
         password.as_bytes(),
         &cli.env,
-        Some(&settings.argon2_params()),
+        Some(&params),
         keyfile.as_deref(),
+        cipher,
     )?;
     if keyfile.is_some() {
         output::info("Vault created with keyfile — you must pass --keyfile on every command.");
     }
+    if cipher != Cipher::default() {
+        output::info(&format!("Secrets are encrypted with {}.", cipher.as_str()));
+    }
     output::success(&format!(
         "Vault created for '{}' environment at {}",
         cli.env,
         vault_path.display()
     ));
 
-    // 5. Auto-detect .env file and offer to import it.
-    let env_file = cwd.join(".env");
-    if env_file.exists() {
-        let should_import = Confirm::new()
-            .with_prompt("Found .env file. Import secrets from it?")
-            .default(true)
-            .interact()
-            .map_err(|e| {
-                EnvVaultError::CommandFailed(format!("failed to read confirmation: {e}"))
-            })?;
-
-        if should_import {
-            let count = import_env_file(&env_file, &mut store)?;
-            store.save()?;
-            output::success(&format!("Imported {count} secrets from .env"));
-        }
+    // 5. Auto-detect .env file and offer to import it.  From here on a
+    //    failure would strand the new vault, so clean it up first.
+    if let Err(e) = import_dot_env(&cwd, &mut store) {
+        abort_init(cli, &vault_path, "import", clean_on_failure);
+        return Err(e);
     }
 
     // 6. Patch .gitignore to exclude the vault directory.
-    crate::cli::gitignore::patch_gitignore(&cwd, &format!("{}/", cli.vault_dir));
+    if no_gitignore {
+        output::tip(&format!(
+            "Make sure '{}/' is ignored by git.",
+            cli.vault_dir
+        ));
+    } else {
+        crate::cli::gitignore::patch_gitignore(&cwd, &format!("{}/", cli.vault_dir));
+    }
 
     // 7. Install pre-commit git hook to catch accidental secret leaks.
     match crate::git::install_hook(&cwd) {
@@ -92,6 +125,7 @@ pub fn execute(cli: &Cli) -> Result<()> {
 
     // 8. Audit log.
     crate::audit::log_audit(cli, "init", None, Some("vault created"));
+    crate::cli::run_post_write_hook("init", env);
 
     // 9. Show helpful tips.
     output::tip("Run `envvault set <KEY>` to add a secret.");
@@ -101,24 +135,95 @@ pub fn execute(cli: &Cli) -> Result<()> {
     Ok(())
 }
 
+/// Offer to import `<cwd>/.env` into the new vault and save it.  Without
+/// a terminal to ask on, the prompt's default (import) is taken.
+fn import_dot_env(cwd: &Path, store: &mut VaultStore) -> Result<()> {
+    let env_file = cwd.join(".env");
+    if !env_file.exists() {
+        return Ok(());
+    }
+
+    let should_import = if io::stdin().is_terminal() {
+        Confirm::new()
+            .with_prompt("Found .env file. Import secrets from it?")
+            .default(true)
+            .interact()
+            .map_err(|e| {
+                EnvVaultError::CommandFailed(format!("failed to read confirmation: {e}"))
+            })?
+    } else {
+        output::info("Found .env file — importing it (no terminal to ask).");
+        true
+    };
+
+    if should_import {
+        let count = import_env_file(&env_file, store)?;
+        store.save()?;
+        output::success(&format!("Imported {count} secrets from .env"));
+    }
+    Ok(())
+}
+
 /// Parse a .env file and import each KEY=VALUE pair into the vault.
 /// Returns the number of secrets imported.
 ///
 /// Handles the `export` prefix that some .env files use:
 ///   export DATABASE_URL=postgres://...
+///
+/// A key the vault rejects is reported with its file and line number.
 fn import_env_file(path: &Path, store: &mut VaultStore) -> Result<usize> {
-    let file = fs::File::open(path)?;
-    let reader = BufReader::new(file);
-    let mut count = 0;
+    let parsed = read_env_file(path)?;
+    warn_if_crlf(path, &parsed);
+
+    let mut keys: Vec<(&String, &String)> = parsed.secrets.iter().collect();
+    keys.sort_by_key(|(key, _)| parsed.lines.get(*key));
+    for (key, value) in keys {
+        store
+            .set_secret(key, value)
+            .map_err(|e| EnvVaultError::ImportLineError {
+                file: path.display().to_string(),
+                line: parsed.lines.get(key).copied().unwrap_or_default(),
+                key: key.clone(),
+                reason: e.to_string(),
+            })?;
+    }
 
-    for line in reader.lines() {
-        let line = line?;
+    Ok(parsed.secrets.len())
+}
 
-        if let Some((key, value)) = parse_env_line(&line) {
-            store.set_secret(key, value)?;
-            count += 1;
-        }
+/// Handle a phase failing after this run created `vault_path`: delete the
+/// vault (with `clean`, or if the user agrees) so `init` can be re-run,
+/// and audit the failure.
+fn abort_init(cli: &Cli, vault_path: &Path, phase: &str, clean: bool) {
+    let remove = clean
+        || (io::stdin().is_terminal()
+            && Confirm::new()
+                .with_prompt("init did not finish. Delete the new vault so it can be re-run?")
+                .default(true)
+                .interact()
+                .unwrap_or(false));
+
+    let removed = remove && fs::remove_file(vault_path).is_ok();
+    crate::audit::log_audit(
+        cli,
+        "init-failed",
+        None,
+        Some(&crate::audit::AuditDetails::init_failed(phase, removed).to_string()),
+    );
+
+    if removed {
+        output::warning(&format!(
+            "init failed during {phase} — removed {} so it can be re-run.",
+            vault_path.display()
+        ));
+    } else {
+        output::warning(&format!(
+            "init failed during {phase} — {} was created but not completed.",
+            vault_path.display()
+        ));
+        output::tip(&format!(
+            "Run `envvault env delete {}` and init again, or re-run init with --clean-on-failure.",
+            cli.env
+        ));
     }
-
-    Ok(count)
 }
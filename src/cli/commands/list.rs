@@ -1,18 +1,131 @@
 //! `envvault list` — display all secrets in a table.
+//!
+//! `--diff-env` decrypts every secret and compares it with the variable of
+//! the same name in envvault's own environment, flagging each as matching,
+//! different, or unset.  A stale `export` in the shell shadows the vault
+//! for anything that reads the environment first; this shows where.
+//!
+//! `--json` prints a JSON array of `{name, created_at, updated_at}`
+//! objects (RFC 3339 timestamps, plus whatever optional metadata is set:
+//! see [`SecretMetadata`]) and nothing else on stdout, for CI scripts.
+//! `--values` adds each decrypted `value`.
+//!
+//! `--tag` keeps only the secrets labelled with it (`set --tag`).
+//!
+//! `--policy-violations` lists only the names that break the project's
+//! `[key_policy]`, and fails if there are any; a vault written before the
+//! policy was added can be checked this way.
 
-use crate::cli::output;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use chrono::Utc;
+use serde::Serialize;
+use zeroize::Zeroize;
+
+use crate::cli::{key_policy, output};
 use crate::cli::{load_keyfile, prompt_password_for_vault, vault_path, Cli};
-use crate::errors::Result;
-use crate::vault::VaultStore;
+use crate::errors::{EnvVaultError, Result};
+use crate::vault::{SecretMetadata, VaultStore};
+
+/// Column to sort the secret list by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Name,
+    Created,
+    Updated,
+}
+
+impl SortKey {
+    /// Parse a `--sort` value.
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "name" => Ok(Self::Name),
+            "created" => Ok(Self::Created),
+            "updated" => Ok(Self::Updated),
+            other => Err(EnvVaultError::CommandFailed(format!(
+                "unknown sort key '{other}' — supported: name, created, updated"
+            ))),
+        }
+    }
+
+    /// Table header text of the column this key sorts by.
+    pub fn column(self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Created => "Created",
+            Self::Updated => "Updated",
+        }
+    }
+}
+
+/// One secret in the `--json` output: its metadata, plus the value with
+/// `--values`.
+#[derive(Serialize)]
+struct ListEntry<'a> {
+    #[serde(flatten)]
+    meta: &'a SecretMetadata,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<&'a str>,
+}
+
+/// How a secret compares with the current process environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvStatus {
+    /// The variable is set to the vault value.
+    Matches,
+    /// The variable is set to something else.
+    Differs,
+    /// The variable is not set (or is not valid Unicode).
+    Unset,
+}
+
+impl EnvStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Matches => "matches",
+            Self::Differs => "DIFFERS",
+            Self::Unset => "unset",
+        }
+    }
+}
+
+/// Compare `value` with whatever `lookup` returns for the variable `name`.
+pub fn env_status(name: &str, value: &str, lookup: impl Fn(&str) -> Option<String>) -> EnvStatus {
+    match lookup(name) {
+        Some(mut current) => {
+            let status = if current == value {
+                EnvStatus::Matches
+            } else {
+                EnvStatus::Differs
+            };
+            current.zeroize();
+            status
+        }
+        None => EnvStatus::Unset,
+    }
+}
 
 /// Execute the `list` command.
-pub fn execute(cli: &Cli) -> Result<()> {
+#[allow(clippy::too_many_arguments)]
+pub fn execute(
+    cli: &Cli,
+    sort: &str,
+    reverse: bool,
+    diff_env: bool,
+    json: bool,
+    values: bool,
+    tag: Option<&str>,
+    policy_violations: bool,
+) -> Result<()> {
+    let sort_key = SortKey::parse(sort)?;
+
     let path = vault_path(cli)?;
     let keyfile = load_keyfile(cli)?;
 
     let vault_id = path.to_string_lossy();
     let password = prompt_password_for_vault(Some(&vault_id))?;
-    let store = match VaultStore::open(&path, password.as_bytes(), keyfile.as_deref()) {
+    let mut store = match VaultStore::open(&path, password.as_bytes(), keyfile.as_deref()) {
         Ok(store) => store,
         Err(e) => {
             #[cfg(feature = "audit-log")]
@@ -20,24 +133,368 @@ pub fn execute(cli: &Cli) -> Result<()> {
             return Err(e);
         }
     };
+    crate::cli::warn_rotation_due(&store);
 
-    let secrets = store.list_secrets();
+    let mut secrets = store.list_secrets();
+    if let Some(tag) = tag {
+        secrets.retain(|s| s.has_tag(tag));
+    }
+    sort_secrets(&mut secrets, sort_key, reverse);
 
-    output::info(&format!(
-        "{} environment — {} secret(s)",
-        cli.env,
-        secrets.len()
-    ));
+    if policy_violations {
+        return print_policy_violations(cli, &secrets);
+    }
 
-    output::print_secrets_table(&secrets);
+    if json {
+        let expired = store.expired(Utc::now());
+        crate::cli::warn_expired(&store, expired.iter().map(String::as_str));
+        return print_json(cli, &mut store, &secrets, values);
+    }
+
+    output::info(&match tag {
+        Some(tag) => format!(
+            "{} environment — {} secret(s) tagged '{tag}'",
+            cli.env,
+            secrets.len()
+        ),
+        None => format!("{} environment — {} secret(s)", cli.env, secrets.len()),
+    });
+
+    if diff_env {
+        crate::cli::enable_access_tracking(&mut store);
+        let mut values = store.get_all_secrets()?;
+        crate::cli::persist_access(&mut store);
+        print_env_diff(&secrets, &values);
+        for v in values.values_mut() {
+            v.zeroize();
+        }
+    } else {
+        output::print_secrets_table_sorted(&secrets, Some((sort_key.column(), reverse)));
+    }
+
+    let expired = store.expired(Utc::now());
+    crate::cli::warn_expired(&store, expired.iter().map(String::as_str));
+
+    let future = store.future_dated(Utc::now(), crate::cli::clock_skew_tolerance());
+    if !future.is_empty() {
+        output::warning(&format!(
+            "{} secret(s) have timestamps in the future: {}",
+            future.len(),
+            future.join(", ")
+        ));
+        output::tip("Run `envvault repair --fix-timestamps` to clamp them to now.");
+    }
 
     #[cfg(feature = "audit-log")]
     crate::audit::log_read_audit(
         cli,
         "list",
         None,
-        Some(&format!("{} secrets", secrets.len())),
+        Some(&if diff_env {
+            format!("{} secrets compared with environment", secrets.len())
+        } else {
+            format!("{} secrets", secrets.len())
+        }),
     );
 
     Ok(())
 }
+
+/// `list --policy-violations`: a table of the names that break the
+/// `[key_policy]`, and an error if there are any.
+fn print_policy_violations(cli: &Cli, secrets: &[SecretMetadata]) -> Result<()> {
+    let Some(policy) = key_policy::configured()? else {
+        output::info("No [key_policy] is configured in .envvault.toml.");
+        return Ok(());
+    };
+    let violations = policy.violations(secrets.iter().map(|s| s.name.as_str()));
+    if violations.is_empty() {
+        output::success(&format!(
+            "All {} secret(s) in {} follow the key policy",
+            secrets.len(),
+            cli.env
+        ));
+        return Ok(());
+    }
+
+    let mut table = output::table();
+    table.set_header(vec!["Name", "Problem"]);
+    for v in &violations {
+        table.add_row(vec![v.key.clone(), v.reason.clone()]);
+    }
+    println!("{table}");
+    Err(EnvVaultError::CommandFailed(format!(
+        "{} of {} secret(s) in {} break the key policy",
+        violations.len(),
+        secrets.len(),
+        cli.env
+    )))
+}
+
+/// `list --json`: the array on stdout, anything else on stderr.
+fn print_json(
+    cli: &Cli,
+    store: &mut VaultStore,
+    secrets: &[SecretMetadata],
+    with_values: bool,
+) -> Result<()> {
+    let mut values = if with_values {
+        output::warning("--values prints decrypted secrets to stdout");
+        crate::cli::enable_access_tracking(store);
+        let values = store.get_all_secrets()?;
+        crate::cli::persist_access(store);
+        Some(values)
+    } else {
+        None
+    };
+
+    let result = format_as_json(secrets, values.as_ref());
+    if let Some(ref mut values) = values {
+        for v in values.values_mut() {
+            v.zeroize();
+        }
+    }
+    let mut rendered = result?;
+    println!("{rendered}");
+    rendered.zeroize();
+
+    crate::audit::log_read_audit(
+        cli,
+        "list",
+        None,
+        Some(&if with_values {
+            format!("{} secrets as JSON with values", secrets.len())
+        } else {
+            format!("{} secrets as JSON", secrets.len())
+        }),
+    );
+    Ok(())
+}
+
+/// Render `secrets` as a JSON array, in order, with each value from
+/// `values` if given.
+pub fn format_as_json(
+    secrets: &[SecretMetadata],
+    values: Option<&HashMap<String, String>>,
+) -> Result<String> {
+    let entries: Vec<ListEntry<'_>> = secrets
+        .iter()
+        .map(|s| ListEntry {
+            meta: s,
+            value: values.map(|v| v.get(&s.name).map_or("", String::as_str)),
+        })
+        .collect();
+    serde_json::to_string_pretty(&entries)
+        .map_err(|e| EnvVaultError::SerializationError(format!("list: {e}")))
+}
+
+/// Print each secret's [`EnvStatus`] and a one-line summary.
+fn print_env_diff(secrets: &[SecretMetadata], values: &HashMap<String, String>) {
+    if secrets.is_empty() {
+        output::info("No secrets in this vault yet.");
+        return;
+    }
+
+    let mut table = output::table();
+    table.set_header(["Name", "Environment"]);
+    let mut differs = 0;
+    for s in secrets {
+        let value = values.get(&s.name).map_or("", String::as_str);
+        let status = env_status(&s.name, value, |name| std::env::var(name).ok());
+        if status == EnvStatus::Differs {
+            differs += 1;
+        }
+        table.add_row([s.name.as_str(), status.label()]);
+    }
+    println!("{table}");
+
+    if differs > 0 {
+        output::warning(&format!(
+            "{differs} secret(s) are exported with a different value and shadow the vault"
+        ));
+    }
+}
+
+/// Sort secrets by `key`, descending if `reverse` is set.
+///
+/// Ties on a timestamp always fall back to ascending name order so the
+/// output is stable regardless of direction.
+pub fn sort_secrets(secrets: &mut [SecretMetadata], key: SortKey, reverse: bool) {
+    secrets.sort_by(|a, b| {
+        let primary = match key {
+            SortKey::Name => Ordering::Equal,
+            SortKey::Created => a.created_at.cmp(&b.created_at),
+            SortKey::Updated => a.updated_at.cmp(&b.updated_at),
+        };
+        let primary = if reverse { primary.reverse() } else { primary };
+
+        let by_name = a.name.cmp(&b.name);
+        let by_name = if reverse && key == SortKey::Name {
+            by_name.reverse()
+        } else {
+            by_name
+        };
+
+        primary.then(by_name)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Commands};
+    use chrono::{DateTime, TimeZone};
+    use clap::Parser;
+
+    fn meta(name: &str, created: i64, updated: i64) -> SecretMetadata {
+        SecretMetadata {
+            name: name.into(),
+            created_at: Utc.timestamp_opt(created, 0).unwrap(),
+            updated_at: Utc.timestamp_opt(updated, 0).unwrap(),
+            encoding: None,
+            access_count: None,
+            last_accessed: None,
+            expires_at: None,
+            description: None,
+            tags: Vec::new(),
+            order: None,
+        }
+    }
+
+    fn names(secrets: &[SecretMetadata]) -> Vec<&str> {
+        secrets.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    #[test]
+    fn sort_by_updated_ties_fall_back_to_name() {
+        let mut secrets = vec![meta("C", 1, 20), meta("B", 2, 10), meta("A", 3, 20)];
+
+        sort_secrets(&mut secrets, SortKey::Updated, false);
+        assert_eq!(names(&secrets), ["B", "A", "C"]);
+
+        sort_secrets(&mut secrets, SortKey::Updated, true);
+        assert_eq!(names(&secrets), ["A", "C", "B"]);
+    }
+
+    #[test]
+    fn sort_by_created_and_name() {
+        let mut secrets = vec![meta("A", 3, 0), meta("B", 1, 0), meta("C", 2, 0)];
+
+        sort_secrets(&mut secrets, SortKey::Created, false);
+        assert_eq!(names(&secrets), ["B", "C", "A"]);
+
+        sort_secrets(&mut secrets, SortKey::Name, true);
+        assert_eq!(names(&secrets), ["C", "B", "A"]);
+    }
+
+    #[test]
+    fn sort_key_parses_known_values() {
+        assert_eq!(SortKey::parse("name").unwrap(), SortKey::Name);
+        assert_eq!(SortKey::parse("Created").unwrap(), SortKey::Created);
+        assert_eq!(SortKey::parse("UPDATED").unwrap(), SortKey::Updated);
+        assert!(SortKey::parse("size").is_err());
+    }
+
+    #[test]
+    fn env_status_compares_with_lookup() {
+        let env = HashMap::from([
+            ("SAME".to_string(), "v1".to_string()),
+            ("STALE".to_string(), "old".to_string()),
+        ]);
+        let lookup = |name: &str| env.get(name).cloned();
+        assert_eq!(env_status("SAME", "v1", lookup), EnvStatus::Matches);
+        assert_eq!(env_status("STALE", "new", lookup), EnvStatus::Differs);
+        assert_eq!(env_status("MISSING", "x", lookup), EnvStatus::Unset);
+    }
+
+    #[test]
+    fn json_lists_metadata_and_optional_values() {
+        let secrets = vec![meta("B", 0, 60), meta("A", 0, 0)];
+
+        let out = format_as_json(&secrets, None).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(parsed[0]["name"], "B");
+        assert_eq!(parsed[0]["created_at"], "1970-01-01T00:00:00Z");
+        assert_eq!(parsed[0]["updated_at"], "1970-01-01T00:01:00Z");
+        assert!(parsed[0].get("value").is_none());
+        assert!(parsed[0].get("tags").is_none());
+        assert_eq!(parsed[1]["name"], "A");
+
+        let mut tagged = meta("T", 0, 0);
+        tagged.description = Some("Stripe live key".into());
+        tagged.tags = vec!["payments".into()];
+        let out = format_as_json(&[tagged], None).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(parsed[0]["description"], "Stripe live key");
+        assert_eq!(parsed[0]["tags"], serde_json::json!(["payments"]));
+
+        let values = HashMap::from([("A".to_string(), "s3cret".to_string())]);
+        let out = format_as_json(&secrets, Some(&values)).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(parsed[1]["value"], "s3cret");
+        assert_eq!(parsed[0]["value"], "");
+
+        assert_eq!(format_as_json(&[], None).unwrap(), "[]");
+    }
+
+    #[test]
+    fn json_from_a_vault_has_names_but_no_values() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("dev.vault");
+        let mut store = VaultStore::create(&path, b"json-password", "dev", None, None).unwrap();
+        store.set_secret("DB_URL", "postgres://hunter2").unwrap();
+        store.set_secret("API_KEY", "sk-plaintext").unwrap();
+
+        let out = format_as_json(&store.list_secrets(), None).unwrap();
+        assert!(!out.contains("hunter2"), "{out}");
+        assert!(!out.contains("sk-plaintext"), "{out}");
+
+        let parsed: Vec<serde_json::Value> = serde_json::from_str(&out).unwrap();
+        let names: Vec<&str> = parsed.iter().filter_map(|e| e["name"].as_str()).collect();
+        assert_eq!(names, ["API_KEY", "DB_URL"]);
+        for entry in &parsed {
+            let created = entry["created_at"].as_str().unwrap();
+            assert!(DateTime::parse_from_rfc3339(created).is_ok(), "{created}");
+            assert!(entry.get("value").is_none());
+        }
+    }
+
+    #[test]
+    fn list_flags_parse() {
+        let cli =
+            Cli::try_parse_from(["envvault", "list", "--sort", "updated", "--reverse"]).unwrap();
+        match cli.command {
+            Commands::List {
+                sort,
+                reverse,
+                diff_env,
+                json,
+                values,
+                tag,
+                policy_violations,
+            } => {
+                assert_eq!(sort, "updated");
+                assert!(reverse);
+                assert!(!diff_env);
+                assert!(!json);
+                assert!(!values);
+                assert_eq!(tag, None);
+                assert!(!policy_violations);
+            }
+            _ => panic!("expected list"),
+        }
+
+        let cli = Cli::try_parse_from(["envvault", "list"]).unwrap();
+        match cli.command {
+            Commands::List { sort, reverse, .. } => {
+                assert_eq!(sort, "name");
+                assert!(!reverse);
+            }
+            _ => panic!("expected list"),
+        }
+
+        assert!(Cli::try_parse_from(["envvault", "list", "--json", "--values"]).is_ok());
+        assert!(Cli::try_parse_from(["envvault", "list", "--values"]).is_err());
+        assert!(Cli::try_parse_from(["envvault", "list", "--json", "--diff-env"]).is_err());
+    }
+}
@@ -1,21 +1,39 @@
 pub mod audit_cmd;
 pub mod auth;
 pub mod completions;
+pub mod config_check;
+pub mod config_show;
 pub mod delete;
+#[cfg(feature = "dev-tools")]
+pub mod dev;
 pub mod diff;
+pub mod dump;
 pub mod edit;
 pub mod env_clone;
 pub mod env_delete;
 pub mod env_list;
 pub mod export;
+pub mod generate_assets;
 pub mod get;
 pub mod import_cmd;
+pub mod info;
 pub mod init;
 pub mod list;
+pub mod onboard;
+pub mod password_manager;
+pub mod prompt_info;
+pub mod raw_dump;
+pub mod recovery_doc;
+pub mod rename;
+pub mod repair;
 pub mod rotate;
+pub mod rotate_secret;
 pub mod run;
 pub mod scan;
 pub mod search;
+pub mod selftest;
 pub mod set;
+pub mod set_many;
 pub mod update;
+pub mod verify;
 pub mod version;
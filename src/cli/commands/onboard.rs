@@ -0,0 +1,54 @@
+//! `envvault onboard` — guided first-time setup for a project.
+//!
+//! Walks through the steps in [`crate::cli::onboard::STEPS`]: config file,
+//! keyfile, environments and passwords, OS keyring, `.env*` imports, the
+//! git hook, and shell completions.  Every step can be declined and is
+//! safe to re-run.  `--defaults` answers every question with its default
+//! and reads the password from `ENVVAULT_PASSWORD`, for scripted setup.
+
+use crate::cli::onboard::{self, Defaults, Interactive, Onboarding, Prompt};
+use crate::cli::output;
+use crate::cli::{load_keyfile, Cli};
+use crate::errors::Result;
+
+/// Execute the `onboard` command.
+pub fn execute(cli: &Cli, defaults: bool) -> Result<()> {
+    let mut ob = Onboarding {
+        project_dir: std::env::current_dir()?,
+        vault_dir_name: cli.vault_dir.clone(),
+        keyfile: load_keyfile(cli)?,
+        environments: Vec::new(),
+        created: Vec::new(),
+        password: None,
+        home_dir: std::env::var_os("HOME").map(Into::into),
+        shell: std::env::var("SHELL").ok(),
+    };
+
+    let mut prompt: Box<dyn Prompt> = if defaults {
+        Box::new(Defaults)
+    } else {
+        Box::new(Interactive)
+    };
+
+    let report = onboard::run_steps(&mut ob, prompt.as_mut())?;
+
+    let mut table = output::table();
+    table.set_header(vec!["Step", "Result", "Details"]);
+    for (name, outcome) in &report {
+        table.add_row(vec![
+            name.to_string(),
+            outcome.status().to_string(),
+            outcome.detail().to_string(),
+        ]);
+    }
+    println!("{table}");
+
+    output::success("Onboarding complete.");
+    if let Some(env) = ob.environments.first() {
+        output::tip(&format!(
+            "Run `envvault -e {env} set <KEY>` to add a secret, or `envvault -e {env} run -- <command>` to use them."
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,456 @@
+//! Password manager exports for `envvault import --format 1password|bitwarden`.
+//!
+//! Each text field of each item becomes one secret named
+//! `<ITEM>_<FIELD>`, both parts uppercased with anything other than ASCII
+//! letters and digits turned into `_` (so "Stripe" / "API key" becomes
+//! `STRIPE_API_KEY`).  Empty values and non-text fields — dates, menus,
+//! files, addresses, checkboxes, linked fields, URIs — are skipped.
+//!
+//! Supported shapes:
+//! - 1Password: the `export.data` file from a `.1pux` export
+//!   (`accounts[].vaults[].items[]`), or the JSON printed by
+//!   `op item get --format json` (one item or an array of them).
+//! - Bitwarden: an unencrypted JSON export (`folders` + `items`).
+//!
+//! `filter` keeps only items whose vault (1Password) or folder
+//! (Bitwarden) name contains it, ignoring case.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::Value;
+
+use super::import_cmd::sanitize_segment;
+use crate::errors::{EnvVaultError, Result};
+
+/// One text field pulled out of an export.
+#[derive(Debug, Clone, PartialEq)]
+struct Field {
+    item: String,
+    name: String,
+    value: String,
+}
+
+/// 1Password `.1pux` login field types that hold text (text, password,
+/// email).
+const OP_LOGIN_TEXT_TYPES: &[&str] = &["T", "P", "E"];
+
+/// 1Password `.1pux` section value kinds that hold text.
+const OP_SECTION_TEXT_KINDS: &[&str] = &["concealed", "string", "email", "url", "totp", "phone"];
+
+/// `op` CLI field types that hold text.
+const OP_CLI_TEXT_TYPES: &[&str] = &["STRING", "CONCEALED", "EMAIL", "URL", "OTP", "PHONE"];
+
+/// Bitwarden custom field types: 0 = text, 1 = hidden (2 = boolean and
+/// 3 = linked are skipped).
+const BITWARDEN_TEXT_FIELD_TYPES: &[u64] = &[0, 1];
+
+/// Bitwarden item types.
+const BITWARDEN_LOGIN: u64 = 1;
+const BITWARDEN_SECURE_NOTE: u64 = 2;
+
+/// Parse a 1Password export into secrets.
+pub fn parse_1password(content: &str, filter: Option<&str>) -> Result<HashMap<String, String>> {
+    let root = parse_json(content, "1Password")?;
+    let mut fields = Vec::new();
+
+    if let Some(accounts) = root.get("accounts").and_then(Value::as_array) {
+        for vault in accounts
+            .iter()
+            .filter_map(|a| a.get("vaults").and_then(Value::as_array))
+            .flatten()
+        {
+            let vault_name = str_at(vault, &["attrs", "name"]).unwrap_or_default();
+            if !matches_filter(vault_name, filter) {
+                continue;
+            }
+            for item in vault
+                .get("items")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                onepux_item_fields(item, &mut fields);
+            }
+        }
+    } else {
+        let items = match root {
+            Value::Array(items) => items,
+            item @ Value::Object(_) if item.get("fields").is_some() => vec![item],
+            _ => {
+                return Err(EnvVaultError::CommandFailed(
+                    "not a 1Password export — expected the export.data file from a .1pux export or `op item get --format json` output".into(),
+                ))
+            }
+        };
+        for item in &items {
+            let vault_name = str_at(item, &["vault", "name"]).unwrap_or_default();
+            if matches_filter(vault_name, filter) {
+                op_cli_item_fields(item, &mut fields);
+            }
+        }
+    }
+
+    into_secrets(fields)
+}
+
+/// Parse a Bitwarden JSON export into secrets.
+pub fn parse_bitwarden(content: &str, filter: Option<&str>) -> Result<HashMap<String, String>> {
+    let root = parse_json(content, "Bitwarden")?;
+
+    if root.get("encrypted").and_then(Value::as_bool) == Some(true) {
+        return Err(EnvVaultError::CommandFailed(
+            "this Bitwarden export is encrypted — export it as unencrypted JSON".into(),
+        ));
+    }
+    let Some(items) = root.get("items").and_then(Value::as_array) else {
+        return Err(EnvVaultError::CommandFailed(
+            "not a Bitwarden export — expected a JSON export with an `items` list".into(),
+        ));
+    };
+
+    let folders: HashMap<&str, &str> = root
+        .get("folders")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|f| Some((f.get("id")?.as_str()?, f.get("name")?.as_str()?)))
+        .collect();
+
+    let mut fields = Vec::new();
+    for item in items {
+        let folder = item
+            .get("folderId")
+            .and_then(Value::as_str)
+            .and_then(|id| folders.get(id).copied())
+            .unwrap_or_default();
+        if matches_filter(folder, filter) {
+            bitwarden_item_fields(item, &mut fields);
+        }
+    }
+
+    into_secrets(fields)
+}
+
+/// Fields of one `.1pux` item: login fields, the password of password
+/// items, and section fields.
+fn onepux_item_fields(item: &Value, fields: &mut Vec<Field>) {
+    let title = str_at(item, &["overview", "title"]).unwrap_or_default();
+    let Some(details) = item.get("details") else {
+        return;
+    };
+
+    for login in details
+        .get("loginFields")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let kind = login
+            .get("fieldType")
+            .and_then(Value::as_str)
+            .unwrap_or("T");
+        if !OP_LOGIN_TEXT_TYPES.contains(&kind) {
+            continue;
+        }
+        let name = ["designation", "name", "id"].iter().find_map(|k| {
+            login
+                .get(*k)
+                .and_then(Value::as_str)
+                .filter(|s| !s.is_empty())
+        });
+        if let (Some(name), Some(value)) = (name, login.get("value").and_then(Value::as_str)) {
+            push(fields, title, name, value);
+        }
+    }
+
+    if let Some(password) = details.get("password").and_then(Value::as_str) {
+        push(fields, title, "password", password);
+    }
+
+    for field in details
+        .get("sections")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|s| s.get("fields").and_then(Value::as_array))
+        .flatten()
+    {
+        // `value` is an object with a single kind → value entry.
+        let Some((kind, value)) = field
+            .get("value")
+            .and_then(Value::as_object)
+            .and_then(|v| v.iter().next())
+        else {
+            continue;
+        };
+        let Some(value) = value.as_str() else {
+            continue;
+        };
+        if !OP_SECTION_TEXT_KINDS.contains(&kind.as_str()) {
+            continue;
+        }
+        let name = ["title", "id"].iter().find_map(|k| {
+            field
+                .get(*k)
+                .and_then(Value::as_str)
+                .filter(|s| !s.is_empty())
+        });
+        if let Some(name) = name {
+            push(fields, title, name, value);
+        }
+    }
+}
+
+/// Fields of one `op item get --format json` item.
+fn op_cli_item_fields(item: &Value, fields: &mut Vec<Field>) {
+    let title = item
+        .get("title")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+
+    for field in item
+        .get("fields")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let kind = field
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if !OP_CLI_TEXT_TYPES.contains(&kind) {
+            continue;
+        }
+        let name = ["label", "id"].iter().find_map(|k| {
+            field
+                .get(*k)
+                .and_then(Value::as_str)
+                .filter(|s| !s.is_empty())
+        });
+        if let (Some(name), Some(value)) = (name, field.get("value").and_then(Value::as_str)) {
+            push(fields, title, name, value);
+        }
+    }
+}
+
+/// Fields of one Bitwarden item: login username/password/TOTP, the notes
+/// of secure notes, and text or hidden custom fields.
+fn bitwarden_item_fields(item: &Value, fields: &mut Vec<Field>) {
+    let name = item.get("name").and_then(Value::as_str).unwrap_or_default();
+    let kind = item.get("type").and_then(Value::as_u64);
+
+    if kind == Some(BITWARDEN_LOGIN) {
+        if let Some(login) = item.get("login") {
+            for key in ["username", "password", "totp"] {
+                if let Some(value) = login.get(key).and_then(Value::as_str) {
+                    push(fields, name, key, value);
+                }
+            }
+        }
+    }
+
+    if kind == Some(BITWARDEN_SECURE_NOTE) {
+        if let Some(notes) = item.get("notes").and_then(Value::as_str) {
+            push(fields, name, "notes", notes);
+        }
+    }
+
+    for field in item
+        .get("fields")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let text = field
+            .get("type")
+            .and_then(Value::as_u64)
+            .is_some_and(|t| BITWARDEN_TEXT_FIELD_TYPES.contains(&t));
+        if !text {
+            continue;
+        }
+        if let (Some(field_name), Some(value)) = (
+            field.get("name").and_then(Value::as_str),
+            field.get("value").and_then(Value::as_str),
+        ) {
+            push(fields, name, field_name, value);
+        }
+    }
+}
+
+fn push(fields: &mut Vec<Field>, item: &str, name: &str, value: &str) {
+    if !value.is_empty() {
+        fields.push(Field {
+            item: item.to_string(),
+            name: name.to_string(),
+            value: value.to_string(),
+        });
+    }
+}
+
+/// Name each field `<ITEM>_<FIELD>`.  Two fields that end up with the
+/// same name are an error rather than a silent overwrite.
+fn into_secrets(fields: Vec<Field>) -> Result<HashMap<String, String>> {
+    let mut secrets: BTreeMap<String, (String, String)> = BTreeMap::new();
+    for field in fields {
+        let key = format!(
+            "{}_{}",
+            sanitize_segment(&field.item),
+            sanitize_segment(&field.name)
+        );
+        let path = format!("{} / {}", field.item, field.name);
+        if let Some((_, other_path)) = secrets.get(&key) {
+            return Err(EnvVaultError::CommandFailed(format!(
+                "'{other_path}' and '{path}' both import as '{key}' — rename one or narrow the import with --filter"
+            )));
+        }
+        secrets.insert(key, (field.value, path));
+    }
+
+    Ok(secrets
+        .into_iter()
+        .map(|(key, (value, _))| (key, value))
+        .collect())
+}
+
+fn parse_json(content: &str, source: &str) -> Result<Value> {
+    serde_json::from_str(content)
+        .map_err(|e| EnvVaultError::CommandFailed(format!("invalid {source} export JSON: {e}")))
+}
+
+fn str_at<'a>(value: &'a Value, path: &[&str]) -> Option<&'a str> {
+    path.iter()
+        .try_fold(value, |v, key| v.get(*key))
+        .and_then(Value::as_str)
+}
+
+fn matches_filter(name: &str, filter: Option<&str>) -> bool {
+    filter.map_or(true, |f| name.to_lowercase().contains(&f.to_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONEPUX: &str = r#"{
+      "accounts": [{
+        "attrs": {"name": "Acme"},
+        "vaults": [
+          {"attrs": {"name": "Engineering"}, "items": [{
+            "overview": {"title": "Stripe"},
+            "details": {
+              "loginFields": [
+                {"designation": "username", "fieldType": "T", "value": "ops@acme.io"},
+                {"designation": "password", "fieldType": "P", "value": "hunter2"},
+                {"name": "remember", "fieldType": "C", "value": "✓"}
+              ],
+              "sections": [{"title": "API", "fields": [
+                {"title": "secret key", "value": {"concealed": "sk_live_1"}},
+                {"title": "expires", "value": {"date": 1700000000}},
+                {"title": "region", "value": {"menu": "eu"}},
+                {"title": "empty", "value": {"string": ""}}
+              ]}]
+            }
+          }, {
+            "overview": {"title": "db"},
+            "details": {"password": "pg-pass"}
+          }]},
+          {"attrs": {"name": "Personal"}, "items": [{
+            "overview": {"title": "Bank"},
+            "details": {"password": "nope"}
+          }]}
+        ]
+      }]
+    }"#;
+
+    const BITWARDEN: &str = r#"{
+      "encrypted": false,
+      "folders": [{"id": "f1", "name": "Production"}, {"id": "f2", "name": "Home"}],
+      "items": [
+        {"type": 1, "name": "AWS", "folderId": "f1",
+         "login": {"username": "AKIA1", "password": "aws-secret", "totp": null,
+                   "uris": [{"uri": "https://aws.amazon.com"}]},
+         "fields": [
+           {"name": "region", "value": "eu-west-1", "type": 0},
+           {"name": "api token", "value": "tok", "type": 1},
+           {"name": "mfa", "value": "true", "type": 2},
+           {"name": "user", "value": null, "type": 3}
+         ]},
+        {"type": 2, "name": "deploy notes", "folderId": "f1", "notes": "ssh in first"},
+        {"type": 1, "name": "Netflix", "folderId": "f2",
+         "login": {"username": "me", "password": "pw"}},
+        {"type": 3, "name": "Visa", "folderId": "f1", "card": {"number": "4111"}}
+      ]
+    }"#;
+
+    #[test]
+    fn onepux_export_keeps_text_fields_only() {
+        let secrets = parse_1password(ONEPUX, None).unwrap();
+        assert_eq!(secrets["STRIPE_USERNAME"], "ops@acme.io");
+        assert_eq!(secrets["STRIPE_PASSWORD"], "hunter2");
+        assert_eq!(secrets["STRIPE_SECRET_KEY"], "sk_live_1");
+        assert_eq!(secrets["DB_PASSWORD"], "pg-pass");
+        assert_eq!(secrets["BANK_PASSWORD"], "nope");
+        assert_eq!(secrets.len(), 5, "{secrets:?}");
+    }
+
+    #[test]
+    fn onepux_filter_matches_vault_names() {
+        let secrets = parse_1password(ONEPUX, Some("engineer")).unwrap();
+        assert!(secrets.contains_key("STRIPE_PASSWORD"));
+        assert!(!secrets.contains_key("BANK_PASSWORD"));
+    }
+
+    #[test]
+    fn op_cli_items_are_accepted() {
+        let json = r#"[{
+          "title": "GitHub", "vault": {"name": "Shared"},
+          "fields": [
+            {"id": "username", "type": "STRING", "label": "username", "value": "octo"},
+            {"id": "password", "type": "CONCEALED", "label": "password", "value": "ghp_x"},
+            {"id": "d", "type": "DATE", "label": "created", "value": "2024-01-01"},
+            {"id": "otp", "type": "OTP", "label": "one-time password", "value": "otpauth://x"}
+          ]
+        }]"#;
+        let secrets = parse_1password(json, None).unwrap();
+        assert_eq!(secrets["GITHUB_USERNAME"], "octo");
+        assert_eq!(secrets["GITHUB_PASSWORD"], "ghp_x");
+        assert_eq!(secrets["GITHUB_ONE_TIME_PASSWORD"], "otpauth://x");
+        assert_eq!(secrets.len(), 3);
+
+        assert!(parse_1password(json, Some("private")).unwrap().is_empty());
+        assert!(parse_1password(r#"{"nope": 1}"#, None).is_err());
+    }
+
+    #[test]
+    fn bitwarden_export_keeps_logins_notes_and_text_fields() {
+        let secrets = parse_bitwarden(BITWARDEN, Some("prod")).unwrap();
+        assert_eq!(secrets["AWS_USERNAME"], "AKIA1");
+        assert_eq!(secrets["AWS_PASSWORD"], "aws-secret");
+        assert_eq!(secrets["AWS_REGION"], "eu-west-1");
+        assert_eq!(secrets["AWS_API_TOKEN"], "tok");
+        assert_eq!(secrets["DEPLOY_NOTES_NOTES"], "ssh in first");
+        assert_eq!(secrets.len(), 5, "{secrets:?}");
+
+        let all = parse_bitwarden(BITWARDEN, None).unwrap();
+        assert_eq!(all["NETFLIX_PASSWORD"], "pw");
+    }
+
+    #[test]
+    fn bitwarden_rejects_encrypted_exports() {
+        let err = parse_bitwarden(r#"{"encrypted": true, "data": "x"}"#, None).unwrap_err();
+        assert!(err.to_string().contains("encrypted"), "{err}");
+    }
+
+    #[test]
+    fn colliding_names_are_an_error() {
+        let json = r#"{"items": [
+          {"type": 1, "name": "api", "login": {"password": "a"}},
+          {"type": 1, "name": "API", "login": {"password": "b"}}
+        ]}"#;
+        let err = parse_bitwarden(json, None).unwrap_err();
+        assert!(
+            err.to_string().contains("both import as 'API_PASSWORD'"),
+            "{err}"
+        );
+    }
+}
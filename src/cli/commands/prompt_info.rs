@@ -0,0 +1,209 @@
+//! `envvault prompt-info` — a short token for shell prompts.
+//!
+//! Prints the active environment, kube-ps1 style, or nothing at all:
+//! 1. `ENVVAULT_ACTIVE` when set (`envvault run` sets it for the child,
+//!    so `envvault run -- $SHELL` shows up in the nested prompt)
+//! 2. otherwise, when a vault directory is found from here, `--env` if
+//!    given, else `default_environment` from `.envvault.toml`
+//! 3. otherwise nothing
+//!
+//! `+key` is appended when that environment's vault needs a keyfile.
+//!
+//! This runs on every prompt, so it never asks for a password, never
+//! derives a key, and never reports errors.  The token is cached in
+//! `~/.config/envvault/prompt-info.cache`, keyed by the modification
+//! times of the vault directory and `.envvault.toml`; a cache hit costs
+//! a few `stat` calls and one small read.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::cli::{find_vault_dir, Cli};
+use crate::config::Settings;
+use crate::vault::format;
+
+/// Variable naming the environment of an envvault-launched shell.
+pub const ACTIVE_VAR: &str = "ENVVAULT_ACTIVE";
+
+/// Appended to the token when the vault needs a keyfile.
+pub const KEYFILE_MARKER: &str = "+key";
+
+/// Execute `envvault prompt-info`.  Always succeeds.
+pub fn execute(cli: &Cli) {
+    if let Some(token) = prompt_token(cli) {
+        println!("{token}");
+    }
+}
+
+/// The environment a prompt should show, in the order described above.
+///
+/// `default_env` is only called when it decides the result, so the
+/// config file is not read when `ENVVAULT_ACTIVE` or `--env` is set.
+pub fn resolve_env(
+    active: Option<&str>,
+    explicit_env: Option<&str>,
+    vault_dir_found: bool,
+    default_env: impl FnOnce() -> String,
+) -> Option<String> {
+    if let Some(active) = active.filter(|a| !a.is_empty()) {
+        return Some(active.to_string());
+    }
+    if !vault_dir_found {
+        return None;
+    }
+    Some(explicit_env.map_or_else(default_env, str::to_string))
+}
+
+/// `env`, plus [`KEYFILE_MARKER`] if `keyfile_required`.
+pub fn render_token(env: &str, keyfile_required: bool) -> String {
+    if keyfile_required {
+        format!("{env}{KEYFILE_MARKER}")
+    } else {
+        env.to_string()
+    }
+}
+
+fn prompt_token(cli: &Cli) -> Option<String> {
+    let cwd = std::env::current_dir().ok()?;
+    let active = std::env::var(ACTIVE_VAR).ok().filter(|a| !a.is_empty());
+    let explicit_env = cli.env_explicit.then_some(cli.env.as_str());
+
+    let vault_dir = find_vault_dir(&cwd, &cli.vault_dir);
+    let vault_dir_found = vault_dir.is_dir();
+    if active.is_none() && !vault_dir_found {
+        return None;
+    }
+    let project_dir = vault_dir.parent().unwrap_or(&cwd).to_path_buf();
+
+    let key = cache_key(
+        active.as_deref(),
+        explicit_env,
+        &vault_dir,
+        &project_dir.join(Settings::FILE_NAME),
+    );
+    let cache = cache_path();
+    if let Some(token) = cache.as_deref().and_then(|p| read_cache(p, &key)) {
+        return Some(token).filter(|t| !t.is_empty());
+    }
+
+    let env = resolve_env(active.as_deref(), explicit_env, vault_dir_found, || {
+        Settings::load(&project_dir)
+            .unwrap_or_default()
+            .default_environment
+    })?;
+    let keyfile_required = format::read_header(&vault_dir.join(format!("{env}.vault")))
+        .is_ok_and(|h| h.keyfile_hash.is_some());
+    let token = render_token(&env, keyfile_required);
+
+    if let Some(path) = cache {
+        write_cache(&path, &key, &token);
+    }
+    Some(token)
+}
+
+/// Everything the token depends on, including the modification times of
+/// the vault directory (vault saves rename into it) and the config file.
+fn cache_key(
+    active: Option<&str>,
+    explicit_env: Option<&str>,
+    vault_dir: &Path,
+    config: &Path,
+) -> String {
+    format!(
+        "{}|{}|{}|{}|{}",
+        active.unwrap_or(""),
+        explicit_env.unwrap_or(""),
+        vault_dir.display(),
+        mtime_nanos(vault_dir),
+        mtime_nanos(config)
+    )
+}
+
+/// Modification time as nanoseconds since the epoch, or `-` if unknown.
+fn mtime_nanos(path: &Path) -> String {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or_else(|| "-".to_string(), |d| d.as_nanos().to_string())
+}
+
+fn cache_path() -> Option<PathBuf> {
+    crate::version_check::dirs_cache_path().map(|dir| dir.join("prompt-info.cache"))
+}
+
+/// The cached token if the cache was written for `key`.
+fn read_cache(path: &Path, key: &str) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    let (cached_key, token) = content.trim_end_matches('\n').split_once('\t')?;
+    (cached_key == key).then(|| token.to_string())
+}
+
+fn write_cache(path: &Path, key: &str, token: &str) {
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _ = fs::write(path, format!("{key}\t{token}\n"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn active_variable_wins_over_everything() {
+        let env = resolve_env(Some("staging"), Some("prod"), true, || {
+            panic!("config must not be read")
+        });
+        assert_eq!(env.as_deref(), Some("staging"));
+
+        // Even outside a project.
+        let env = resolve_env(Some("staging"), None, false, || unreachable!());
+        assert_eq!(env.as_deref(), Some("staging"));
+    }
+
+    #[test]
+    fn explicit_env_then_config_default_inside_a_project() {
+        let env = resolve_env(None, Some("prod"), true, || unreachable!());
+        assert_eq!(env.as_deref(), Some("prod"));
+
+        let env = resolve_env(Some(""), None, true, || "qa".to_string());
+        assert_eq!(env.as_deref(), Some("qa"));
+    }
+
+    #[test]
+    fn nothing_outside_a_project() {
+        assert_eq!(
+            resolve_env(None, Some("prod"), false, || unreachable!()),
+            None
+        );
+    }
+
+    #[test]
+    fn keyfile_marker_is_appended() {
+        assert_eq!(render_token("prod", false), "prod");
+        assert_eq!(render_token("prod", true), "prod+key");
+    }
+
+    #[test]
+    fn cache_hits_only_for_the_same_key() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("nested").join("prompt-info.cache");
+        assert_eq!(read_cache(&path, "k1"), None);
+
+        write_cache(&path, "k1", "dev+key");
+        assert_eq!(read_cache(&path, "k1").as_deref(), Some("dev+key"));
+        assert_eq!(read_cache(&path, "k2"), None);
+
+        // A vault directory change shows up in the key.
+        let vault_dir = dir.path().join(".envvault");
+        fs::create_dir(&vault_dir).unwrap();
+        let config = dir.path().join(".envvault.toml");
+        let before = cache_key(None, None, &vault_dir, &config);
+        assert!(before.ends_with("|-"));
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(vault_dir.join("dev.vault"), b"x").unwrap();
+        assert_ne!(cache_key(None, None, &vault_dir, &config), before);
+    }
+}
@@ -0,0 +1,216 @@
+//! `envvault raw-dump` — print a vault's stored structure for forensics.
+//!
+//! Uses `read_vault` only: nothing is decrypted and no password is needed.
+//! Shows the header JSON exactly as stored, each secret's metadata and
+//! base64 ciphertext, and the stored HMAC tag.  The HMAC is not verified
+//! (that needs the password), so tampered files dump fine; for files too
+//! damaged to parse, `info --raw-header` shows where parsing stopped.
+//!
+//! The output holds the salt, ciphertexts, and HMAC: enough to attempt
+//! offline password guessing, just like the vault file itself.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::cli::output;
+use crate::cli::{vault_path, Cli};
+use crate::errors::{EnvVaultError, Result};
+use crate::vault::format;
+
+/// Everything `raw-dump` prints.
+#[derive(Debug, Serialize)]
+pub struct RawDump {
+    pub file: String,
+    pub file_size: u64,
+    pub format_version: u8,
+    /// Header JSON exactly as stored (unknown fields included).
+    pub header: Value,
+    pub secrets: Vec<RawSecret>,
+    /// Stored HMAC tag, base64.
+    pub hmac: String,
+}
+
+/// One stored secret: metadata plus ciphertext, never the value.
+#[derive(Debug, Serialize)]
+pub struct RawSecret {
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_accessed: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Plaintext bytes per chunk of a streamed value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chunk_size: Option<u32>,
+    /// Nonce + ciphertext + tag length in bytes.
+    pub ciphertext_len: usize,
+    /// Nonce + ciphertext + tag, base64.
+    pub ciphertext: String,
+}
+
+/// Execute the `raw-dump` command.
+pub fn execute(cli: &Cli, json: bool, vault_file: Option<&str>) -> Result<()> {
+    let path = match vault_file {
+        Some(file) => PathBuf::from(file),
+        None => vault_path(cli)?,
+    };
+
+    let dump = build_dump(&path).map_err(|e| match e {
+        EnvVaultError::InvalidVaultFormat(msg) => EnvVaultError::InvalidVaultFormat(format!(
+            "{msg} — `envvault info --raw-header` shows where parsing stopped"
+        )),
+        other => other,
+    })?;
+
+    if json {
+        let text = serde_json::to_string_pretty(&dump)
+            .map_err(|e| EnvVaultError::SerializationError(format!("JSON: {e}")))?;
+        println!("{text}");
+    } else {
+        print!("{}", render_text(&dump));
+    }
+
+    output::warning(
+        "This dump holds the salt, ciphertexts, and HMAC — share it only as you would the vault file.",
+    );
+    Ok(())
+}
+
+/// Read `path` with `read_vault` and collect its stored structure.
+pub fn build_dump(path: &Path) -> Result<RawDump> {
+    let raw = format::read_vault(path)?;
+    let file_size = std::fs::metadata(path)?.len();
+    let header = serde_json::from_slice(&raw.header_bytes)
+        .map_err(|e| EnvVaultError::InvalidVaultFormat(format!("header JSON: {e}")))?;
+
+    let secrets = raw
+        .secrets
+        .into_iter()
+        .map(|s| RawSecret {
+            ciphertext_len: s.encrypted_value.len(),
+            ciphertext: BASE64.encode(&s.encrypted_value),
+            name: s.name,
+            created_at: s.created_at,
+            updated_at: s.updated_at,
+            encoding: s.encoding,
+            access_count: s.access_count,
+            last_accessed: s.last_accessed,
+            expires_at: s.expires_at,
+            chunk_size: s.chunk_size,
+        })
+        .collect();
+
+    Ok(RawDump {
+        file: path.display().to_string(),
+        file_size,
+        format_version: raw.header.version,
+        header,
+        secrets,
+        hmac: BASE64.encode(&raw.stored_hmac),
+    })
+}
+
+/// Render a dump as labelled plain text.
+pub fn render_text(dump: &RawDump) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "File:            {} ({} bytes)",
+        dump.file, dump.file_size
+    );
+    let _ = writeln!(out, "Format version:  {}", dump.format_version);
+    let _ = writeln!(out, "Header:          {}", dump.header);
+    let _ = writeln!(out, "Secrets:         {}", dump.secrets.len());
+    for secret in &dump.secrets {
+        let _ = writeln!(out, "  {}", secret.name);
+        let _ = writeln!(
+            out,
+            "    created {}  updated {}",
+            secret.created_at.format("%Y-%m-%d %H:%M:%S"),
+            secret.updated_at.format("%Y-%m-%d %H:%M:%S")
+        );
+        if let Some(ref encoding) = secret.encoding {
+            let _ = writeln!(out, "    encoding {encoding}");
+        }
+        if let Some(expires_at) = secret.expires_at {
+            let _ = writeln!(
+                out,
+                "    expires {}",
+                expires_at.format("%Y-%m-%d %H:%M:%S")
+            );
+        }
+        if let Some(chunk_size) = secret.chunk_size {
+            let _ = writeln!(out, "    streamed in {chunk_size}-byte chunks");
+        }
+        let _ = writeln!(
+            out,
+            "    ciphertext ({} bytes): {}",
+            secret.ciphertext_len, secret.ciphertext
+        );
+    }
+    let _ = writeln!(out, "HMAC:            {}", dump.hmac);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::devtools::{generate_vault, GenVaultOptions};
+
+    #[test]
+    fn dump_matches_the_stored_bytes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("dev.vault");
+        let opts = GenVaultOptions {
+            secrets: 2,
+            ..GenVaultOptions::default()
+        };
+        generate_vault(&path, &opts).unwrap();
+        let raw = format::read_vault(&path).unwrap();
+
+        let dump = build_dump(&path).unwrap();
+        assert_eq!(dump.format_version, format::CURRENT_VERSION);
+        assert_eq!(
+            dump.header,
+            serde_json::from_slice::<Value>(&raw.header_bytes).unwrap()
+        );
+        assert_eq!(dump.hmac, BASE64.encode(&raw.stored_hmac));
+        assert_eq!(dump.secrets.len(), 2);
+        assert_eq!(
+            dump.secrets[0].ciphertext,
+            BASE64.encode(&raw.secrets[0].encrypted_value)
+        );
+
+        let text = render_text(&dump);
+        assert!(text.contains("Secrets:         2"));
+        assert!(text.contains(&dump.secrets[1].ciphertext));
+        let json = serde_json::to_value(&dump).unwrap();
+        assert!(json["secrets"][0]["ciphertext_len"].is_u64());
+    }
+
+    #[test]
+    fn dump_of_tampered_file_still_works() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("dev.vault");
+        generate_vault(&path, &GenVaultOptions::default()).unwrap();
+
+        // Flip a bit in the HMAC: opening would fail, dumping must not.
+        let mut data = std::fs::read(&path).unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 0x01;
+        std::fs::write(&path, &data).unwrap();
+
+        assert!(build_dump(&path).is_ok());
+        assert!(build_dump(&dir.path().join("missing.vault")).is_err());
+    }
+}
@@ -0,0 +1,343 @@
+//! Disaster-recovery document for `envvault export --format recovery-doc`.
+//!
+//! Builds a markdown runbook page from vault headers and the audit log:
+//! which environments exist, whether they need a keyfile, the stored KDF
+//! params, secret counts, and when each was created and last rotated.
+//! Only plaintext header metadata is read, so no password is needed and
+//! no secret values can end up in the document.  `--redact-names` also
+//! leaves out secret names.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+use crate::cli::commands::env_list::list_environments;
+use crate::crypto::kdf::Argon2Params;
+use crate::errors::Result;
+use crate::vault::format;
+
+/// Recovery-relevant metadata for one environment.
+#[derive(Debug, Clone)]
+pub struct EnvironmentRecord {
+    pub name: String,
+    /// Vault file path as shown in the document.
+    pub vault_file: String,
+    pub created_at: Option<DateTime<Utc>>,
+    /// User that ran `init`, from the audit log.
+    pub created_by: Option<String>,
+    /// Most recent `rotate-key`, from the audit log.
+    pub last_rotated: Option<DateTime<Utc>>,
+    pub keyfile_required: bool,
+    /// Whether the OS keyring holds a password (`None` when unknown).
+    pub in_keyring: Option<bool>,
+    pub kdf: Option<Argon2Params>,
+    pub key_len: usize,
+    pub secret_names: Vec<String>,
+    /// Set when the vault header could not be read.
+    pub error: Option<String>,
+}
+
+/// Collect a record for every `*.vault` file in `vault_dir`.
+///
+/// `display_dir` is the directory as it should appear in the document
+/// (usually the relative `--vault-dir`).
+pub fn collect(vault_dir: &Path, display_dir: &str) -> Result<Vec<EnvironmentRecord>> {
+    let mut envs = list_environments(vault_dir)?;
+    envs.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let history = AuditHistory::load(vault_dir);
+
+    let mut records = Vec::with_capacity(envs.len());
+    for env in envs {
+        let path = vault_dir.join(format!("{}.vault", env.name));
+        let mut record = EnvironmentRecord {
+            vault_file: format!("{display_dir}/{}.vault", env.name),
+            created_at: None,
+            created_by: history.created_by(&env.name),
+            last_rotated: history.last_rotated(&env.name),
+            keyfile_required: false,
+            in_keyring: keyring_has_password(&path),
+            kdf: None,
+            key_len: format::DEFAULT_KEY_LEN,
+            secret_names: Vec::new(),
+            error: None,
+            name: env.name,
+        };
+
+        match format::read_vault(&path) {
+            Ok(raw) => {
+                record.created_at = Some(raw.header.created_at);
+                record.keyfile_required = raw.header.keyfile_hash.is_some();
+                record.kdf = Some(Argon2Params::from(
+                    raw.header.argon2_params.unwrap_or_default(),
+                ));
+                record.key_len = raw.header.key_len;
+                record.secret_names = raw.secrets.into_iter().map(|s| s.name).collect();
+                record.secret_names.sort();
+            }
+            Err(e) => record.error = Some(e.to_string()),
+        }
+
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/// Render the recovery document as markdown.
+pub fn render(
+    records: &[EnvironmentRecord],
+    vault_dir: &str,
+    generated_at: DateTime<Utc>,
+    redact_names: bool,
+) -> String {
+    let mut doc = String::new();
+
+    let _ = writeln!(doc, "# EnvVault recovery document\n");
+    let _ = writeln!(
+        doc,
+        "Generated {} from `{vault_dir}`. Contains no secret values{}.\n",
+        format_time(Some(generated_at)),
+        if redact_names { " or secret names" } else { "" }
+    );
+
+    if records.is_empty() {
+        let _ = writeln!(doc, "No environments found.");
+        return doc;
+    }
+
+    let _ = writeln!(doc, "## Summary\n");
+    let _ = writeln!(
+        doc,
+        "| Environment | Secrets | Keyfile | Created | Last rotated |"
+    );
+    let _ = writeln!(doc, "|---|---|---|---|---|");
+    for r in records {
+        let _ = writeln!(
+            doc,
+            "| {} | {} | {} | {} | {} |",
+            r.name,
+            if r.error.is_some() {
+                "?".to_string()
+            } else {
+                r.secret_names.len().to_string()
+            },
+            yes_no(r.keyfile_required),
+            format_time(r.created_at),
+            format_time(r.last_rotated),
+        );
+    }
+
+    for r in records {
+        let _ = writeln!(doc, "\n## {}\n", r.name);
+        let _ = writeln!(doc, "- Vault file: `{}`", r.vault_file);
+
+        if let Some(ref error) = r.error {
+            let _ = writeln!(doc, "- **Unreadable:** {error}");
+            continue;
+        }
+
+        let _ = writeln!(doc, "- Created: {}", format_time(r.created_at));
+        if let Some(ref user) = r.created_by {
+            let _ = writeln!(doc, "- Created by: {user}");
+        }
+        let _ = writeln!(doc, "- Last key rotation: {}", format_time(r.last_rotated));
+        let _ = writeln!(doc, "- Keyfile required: {}", yes_no(r.keyfile_required));
+        if let Some(in_keyring) = r.in_keyring {
+            let _ = writeln!(doc, "- Password in OS keyring: {}", yes_no(in_keyring));
+        }
+        if let Some(ref kdf) = r.kdf {
+            let _ = writeln!(doc, "- Key derivation: {kdf}, {}-byte key", r.key_len);
+        }
+
+        if redact_names || r.secret_names.is_empty() {
+            let _ = writeln!(doc, "- Secrets: {}", r.secret_names.len());
+        } else {
+            let names: Vec<String> = r.secret_names.iter().map(|n| format!("`{n}`")).collect();
+            let _ = writeln!(
+                doc,
+                "- Secrets ({}): {}",
+                r.secret_names.len(),
+                names.join(", ")
+            );
+        }
+    }
+
+    doc
+}
+
+fn format_time(time: Option<DateTime<Utc>>) -> String {
+    time.map_or_else(
+        || "never recorded".to_string(),
+        |t| t.format("%Y-%m-%d %H:%M UTC").to_string(),
+    )
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+#[cfg(feature = "keyring-store")]
+fn keyring_has_password(vault_path: &Path) -> Option<bool> {
+    let id = vault_path.to_string_lossy();
+    crate::keyring::get_password(&id)
+        .ok()
+        .map(|pw| pw.is_some())
+}
+
+#[cfg(not(feature = "keyring-store"))]
+fn keyring_has_password(_vault_path: &Path) -> Option<bool> {
+    None
+}
+
+/// Audit log entries (newest first), used to look up `init` and
+/// `rotate-key` events per environment.
+struct AuditHistory {
+    #[cfg(feature = "audit-log")]
+    entries: Vec<crate::audit::AuditEntry>,
+}
+
+#[cfg(feature = "audit-log")]
+impl AuditHistory {
+    fn load(vault_dir: &Path) -> Self {
+        // Don't create an audit database just to read from it.
+        let entries = if crate::audit::AuditLog::db_path(vault_dir).exists() {
+            crate::audit::AuditLog::open(vault_dir)
+                .and_then(|audit| audit.query(usize::MAX, None).ok())
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        Self { entries }
+    }
+
+    fn created_by(&self, env: &str) -> Option<String> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| e.environment == env && e.operation == "init")
+            .and_then(|e| e.user.clone())
+    }
+
+    fn last_rotated(&self, env: &str) -> Option<DateTime<Utc>> {
+        self.entries
+            .iter()
+            .find(|e| e.environment == env && e.operation == "rotate-key")
+            .map(|e| e.timestamp)
+    }
+}
+
+#[cfg(not(feature = "audit-log"))]
+impl AuditHistory {
+    fn load(_vault_dir: &Path) -> Self {
+        Self {}
+    }
+
+    fn created_by(&self, _env: &str) -> Option<String> {
+        None
+    }
+
+    fn last_rotated(&self, _env: &str) -> Option<DateTime<Utc>> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::devtools::{generate_vault, GenVaultOptions};
+    use chrono::TimeZone;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(secs, 0).unwrap()
+    }
+
+    /// Collect from fixture vaults, then pin the time-dependent fields.
+    fn fixture_records() -> Vec<EnvironmentRecord> {
+        let dir = tempfile::TempDir::new().unwrap();
+        for (env, secrets) in [("dev", 2), ("prod", 3)] {
+            let opts = GenVaultOptions {
+                secrets,
+                environment: env.into(),
+                ..GenVaultOptions::default()
+            };
+            generate_vault(&dir.path().join(format!("{env}.vault")), &opts).unwrap();
+        }
+        std::fs::write(dir.path().join("broken.vault"), b"not a vault").unwrap();
+
+        let mut records = collect(dir.path(), ".envvault").unwrap();
+        for r in &mut records {
+            r.created_at = r.created_at.map(|_| at(1_700_000_000));
+            r.in_keyring = None;
+        }
+        records[2].created_by = Some("alice".into());
+        records[2].last_rotated = Some(at(1_710_000_000));
+        records
+    }
+
+    #[test]
+    fn recovery_doc_snapshot() {
+        let doc = render(&fixture_records(), ".envvault", at(1_720_000_000), false);
+        let fast = crate::devtools::fast_kdf_params();
+        let expected = format!(
+            "\
+# EnvVault recovery document
+
+Generated 2024-07-03 09:46 UTC from `.envvault`. Contains no secret values.
+
+## Summary
+
+| Environment | Secrets | Keyfile | Created | Last rotated |
+|---|---|---|---|---|
+| broken | ? | no | never recorded | never recorded |
+| dev | 2 | no | 2023-11-14 22:13 UTC | never recorded |
+| prod | 3 | no | 2023-11-14 22:13 UTC | 2024-03-09 16:00 UTC |
+
+## broken
+
+- Vault file: `.envvault/broken.vault`
+- **Unreadable:** {broken_error}
+
+## dev
+
+- Vault file: `.envvault/dev.vault`
+- Created: 2023-11-14 22:13 UTC
+- Last key rotation: never recorded
+- Keyfile required: no
+- Key derivation: {fast}, 32-byte key
+- Secrets (2): `SECRET_0000`, `SECRET_0001`
+
+## prod
+
+- Vault file: `.envvault/prod.vault`
+- Created: 2023-11-14 22:13 UTC
+- Created by: alice
+- Last key rotation: 2024-03-09 16:00 UTC
+- Keyfile required: no
+- Key derivation: {fast}, 32-byte key
+- Secrets (3): `SECRET_0000`, `SECRET_0001`, `SECRET_0002`
+",
+            broken_error = fixture_records()[0].error.clone().unwrap(),
+        );
+        assert_eq!(doc, expected);
+    }
+
+    #[test]
+    fn redacted_doc_has_no_secret_names() {
+        let doc = render(&fixture_records(), ".envvault", at(1_720_000_000), true);
+        assert!(doc.contains("Contains no secret values or secret names."));
+        assert!(doc.contains("- Secrets: 3"));
+        assert!(!doc.contains("SECRET_"), "{doc}");
+    }
+
+    #[test]
+    fn empty_vault_dir_renders_placeholder() {
+        let doc = render(&[], ".envvault", at(0), false);
+        assert!(doc.ends_with("No environments found.\n"));
+    }
+}
@@ -0,0 +1,53 @@
+//! `envvault rename` — change a secret's name without touching its value.
+//!
+//! Unlike `get` + `set` + `delete`, the value never reaches the shell and
+//! the secret keeps its original `created_at`.  The new name must be free
+//! unless `--force` is given, in which case the secret already using it is
+//! replaced.  The new name must satisfy the project's `[key_policy]`.
+
+use crate::cli::{key_policy, output};
+use crate::cli::{load_keyfile, prompt_password_for_vault, vault_path, Cli};
+use crate::errors::{EnvVaultError, Result};
+use crate::vault::VaultStore;
+
+/// Execute the `rename` command.
+pub fn execute(cli: &Cli, old: &str, new: &str, force: bool) -> Result<()> {
+    key_policy::enforce(cli, [new])?;
+    let path = vault_path(cli)?;
+    let keyfile = load_keyfile(cli)?;
+    let vault_id = path.to_string_lossy();
+    let password = prompt_password_for_vault(Some(&vault_id))?;
+    let mut store = VaultStore::open(&path, password.as_bytes(), keyfile.as_deref())?;
+    crate::cli::warn_rotation_due(&store);
+
+    let replaced = if force {
+        store.rename_secret_replacing(old, new)?
+    } else {
+        store.rename_secret(old, new).map_err(|e| match e {
+            EnvVaultError::SecretAlreadyExists(_) if old != new => EnvVaultError::CommandFailed(
+                format!("secret '{new}' already exists — pass --force to replace it"),
+            ),
+            other => other,
+        })?;
+        false
+    };
+    crate::cli::warn_clock_skew(&store);
+    store.save()?;
+
+    crate::audit::log_audit(
+        cli,
+        "rename",
+        Some(old),
+        Some(&crate::audit::AuditDetails::rename(old, new, replaced).to_string()),
+    );
+    crate::cli::run_post_write_hook("rename", &cli.env);
+
+    output::success(&format!(
+        "Renamed '{old}' to '{new}' in {} environment",
+        cli.env
+    ));
+    if replaced {
+        output::warning(&format!("The previous value of '{new}' was replaced."));
+    }
+    Ok(())
+}
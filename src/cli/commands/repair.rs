@@ -0,0 +1,64 @@
+//! `envvault repair` — fix known kinds of vault damage.
+//!
+//! `--fix-timestamps` clamps created/updated times that lie in the future
+//! (written by a machine with a broken clock) to now.  Without a fix flag
+//! the command only reports what it would change.
+
+use chrono::Utc;
+
+use crate::cli::output;
+use crate::cli::{clock_skew_tolerance, load_keyfile, prompt_password_for_vault, vault_path, Cli};
+use crate::errors::Result;
+use crate::vault::VaultStore;
+
+/// Execute the `repair` command.
+pub fn execute(cli: &Cli, fix_timestamps: bool) -> Result<()> {
+    let path = vault_path(cli)?;
+    let keyfile = load_keyfile(cli)?;
+    let vault_id = path.to_string_lossy();
+    let password = prompt_password_for_vault(Some(&vault_id))?;
+    let mut store = VaultStore::open(&path, password.as_bytes(), keyfile.as_deref())?;
+
+    let now = Utc::now();
+    let tolerance = clock_skew_tolerance();
+    let future = store.future_dated(now, tolerance);
+    let header_future = crate::vault::clock::is_future(store.created_at(), now, tolerance);
+
+    if future.is_empty() && !header_future {
+        output::success(&format!("No future-dated timestamps in {}.vault", cli.env));
+        return Ok(());
+    }
+
+    if !fix_timestamps {
+        if header_future {
+            output::warning("The vault's creation time is in the future.");
+        }
+        if !future.is_empty() {
+            output::warning(&format!(
+                "{} secret(s) have future-dated timestamps: {}",
+                future.len(),
+                future.join(", ")
+            ));
+        }
+        output::tip("Run `envvault repair --fix-timestamps` to clamp them to now.");
+        return Ok(());
+    }
+
+    let fixed = store.clamp_future_timestamps(now, tolerance)?;
+    store.save()?;
+
+    crate::audit::log_audit(
+        cli,
+        "repair",
+        None,
+        Some(&crate::audit::AuditDetails::repair(fixed).to_string()),
+    );
+    crate::cli::run_post_write_hook("repair", &cli.env);
+
+    output::success(&format!(
+        "Clamped {fixed} future-dated timestamp(s) in {}.vault to now",
+        cli.env
+    ));
+
+    Ok(())
+}
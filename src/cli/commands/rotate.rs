@@ -1,8 +1,9 @@
 //! `envvault rotate-key` — change the vault master password.
 //!
-//! Decrypts all secrets with the old password, generates a new salt,
-//! re-derives the master key from the new password, re-encrypts all
-//! secrets, and writes the vault atomically.
+//! Opens the vault with the old password, generates a new salt,
+//! re-derives the master key from the new password, re-encrypts every
+//! value as bytes (with the vault's existing cipher, keeping streamed
+//! values chunked), and writes the vault atomically.
 //!
 //! Optionally changes the keyfile with `--new-keyfile <path>` or removes
 //! the keyfile requirement with `--new-keyfile none`.
@@ -12,22 +13,33 @@ use std::path::Path;
 use zeroize::Zeroize;
 
 use crate::cli::output;
-use crate::cli::{load_keyfile, prompt_new_password, prompt_password_for_vault, vault_path, Cli};
+use crate::cli::{
+    load_keyfile, prompt_new_password, prompt_password_for_vault, report_kdf_params, vault_path,
+    Cli,
+};
 use crate::config::Settings;
 use crate::crypto::kdf::generate_salt;
 use crate::crypto::keyfile;
 use crate::crypto::keys::MasterKey;
 use crate::errors::Result;
-use crate::vault::format::{StoredArgon2Params, VaultHeader, CURRENT_VERSION};
+use crate::vault::format::{StoredArgon2Params, VaultHeader, CURRENT_VERSION, DEFAULT_KEY_LEN};
 use crate::vault::VaultStore;
 
 /// Execute the `rotate-key` command.
 ///
 /// `new_keyfile_arg`: `None` = keep existing keyfile, `Some("none")` = remove
 /// keyfile requirement, `Some(path)` = switch to a different keyfile.
-pub fn execute(cli: &Cli, new_keyfile_arg: Option<&str>) -> Result<()> {
+///
+/// Environments listed in `[safety] confirm_word_for` must be confirmed by
+/// typing their name first, unless `force` is set.
+pub fn execute(cli: &Cli, new_keyfile_arg: Option<&str>, force: bool) -> Result<()> {
     let path = vault_path(cli)?;
 
+    if !force && !crate::cli::confirm_env_word(&cli.env, "rotate the vault password")? {
+        output::info("Cancelled.");
+        return Ok(());
+    }
+
     // 1. Open the vault with the current password.
     output::info("Enter your current vault password.");
     let keyfile_data = load_keyfile(cli)?;
@@ -35,10 +47,7 @@ pub fn execute(cli: &Cli, new_keyfile_arg: Option<&str>) -> Result<()> {
     let old_password = prompt_password_for_vault(Some(&vault_id))?;
     let store = VaultStore::open(&path, old_password.as_bytes(), keyfile_data.as_deref())?;
 
-    // 2. Decrypt all secrets into memory.
-    let mut secrets = store.get_all_secrets()?;
-
-    // 3. Prompt for the new password.
+    // 2-3. Prompt for the new password.
     output::info("Choose your new vault password.");
     let new_password = prompt_new_password()?;
 
@@ -46,6 +55,8 @@ pub fn execute(cli: &Cli, new_keyfile_arg: Option<&str>) -> Result<()> {
     let cwd = std::env::current_dir()?;
     let settings = Settings::load(&cwd)?;
     let params = settings.argon2_params();
+    crate::crypto::kdf::validate_params(&params)?;
+    report_kdf_params(&params);
 
     // 5. Resolve keyfile for the new vault.
     let (new_keyfile_bytes, new_keyfile_hash) =
@@ -75,32 +86,35 @@ pub fn execute(cli: &Cli, new_keyfile_arg: Option<&str>) -> Result<()> {
             parallelism: params.parallelism,
         }),
         keyfile_hash: new_keyfile_hash,
+        key_len: DEFAULT_KEY_LEN,
+        salt_len: new_salt.len(),
+        cipher: store.header().cipher.clone(),
+        rotated_at: Some(chrono::Utc::now()),
     };
 
-    // 8. Create a new vault store with the new key and re-encrypt secrets.
-    let mut new_store = VaultStore::from_parts(path, new_header, new_master_key);
+    // 8. Create a new vault store with the new key and re-encrypt secrets
+    //    one at a time.  Values are unchanged, so every timestamp carries
+    //    over as-is.
+    let mut new_store = VaultStore::from_parts(path, new_header, new_master_key)?;
 
-    for (name, value) in &secrets {
-        new_store.set_secret(name, value)?;
+    let all_meta = store.list_secrets();
+    let mut progress = crate::progress::phase(crate::progress::REENCRYPT, all_meta.len());
+    for meta in all_meta {
+        new_store.copy_secret_from(&store, &meta)?;
+        progress.inc();
     }
+    drop(progress);
 
-    // 9. Zeroize plaintext secrets from memory.
-    for value in secrets.values_mut() {
-        value.zeroize();
-    }
-
-    // 10. Save atomically.
+    // 9. Save atomically.
     new_store.save()?;
 
     crate::audit::log_audit(
         cli,
         "rotate-key",
         None,
-        Some(&format!(
-            "{} secrets re-encrypted",
-            new_store.secret_count()
-        )),
+        Some(&crate::audit::AuditDetails::rotate(new_store.secret_count()).to_string()),
     );
+    crate::cli::run_post_write_hook("rotate-key", &cli.env);
 
     // Print a message indicating what changed.
     let keyfile_msg = match new_keyfile_arg {
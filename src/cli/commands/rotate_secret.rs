@@ -0,0 +1,315 @@
+//! `envvault rotate-secret` — replace one secret's value in a coordinated way.
+//!
+//! The new value is either generated (`--generate`) or read from the stdout
+//! of a command (`--value-from`).  An optional `--hook` command then gets
+//! the chance to apply it elsewhere (e.g. `ALTER USER` on the database)
+//! before the vault is touched: the vault is only updated if the hook
+//! succeeds, so a failure at any point leaves it unchanged.
+//!
+//! The hook never sees values in its arguments or environment.  It gets
+//! `ENVVAULT_SECRET_NAME` plus `ENVVAULT_OLD_VALUE_FILE` and
+//! `ENVVAULT_NEW_VALUE_FILE`, paths it can read the values from.  On Unix
+//! these are `/dev/fd/N` descriptors of already-deleted files; elsewhere
+//! they are owner-only temp files that are wiped afterwards.
+
+use std::fs;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use rand::TryRngCore;
+use zeroize::Zeroizing;
+
+use crate::cli::output;
+use crate::cli::{load_keyfile, prompt_password_for_vault, vault_path, Cli, AUTH_ENV_VARS};
+use crate::errors::{EnvVaultError, Result};
+use crate::vault::VaultStore;
+
+/// Length of `--generate` values unless `--length` says otherwise.
+pub const DEFAULT_GENERATED_LEN: usize = 32;
+
+const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Where the new value comes from.
+pub enum NewValue<'a> {
+    /// Random alphanumeric value of this length.
+    Generate(usize),
+    /// Stdout of this shell command.
+    Command(&'a str),
+}
+
+/// Execute the `rotate-secret` command.
+pub fn execute(cli: &Cli, key: &str, source: NewValue<'_>, hook: Option<&str>) -> Result<()> {
+    let path = vault_path(cli)?;
+    let keyfile = load_keyfile(cli)?;
+    let vault_id = path.to_string_lossy();
+    let password = prompt_password_for_vault(Some(&vault_id))?;
+    let mut store = VaultStore::open(&path, password.as_bytes(), keyfile.as_deref())?;
+    crate::cli::warn_rotation_due(&store);
+
+    // Fail on a missing key before generating or running anything.
+    let old_value = Zeroizing::new(store.get_secret(key)?);
+
+    let (new_value, source_name) = match source {
+        NewValue::Generate(len) => (generate_value(len)?, "generate"),
+        NewValue::Command(cmd) => (value_from_command(cmd)?, "command"),
+    };
+    if *new_value == *old_value {
+        return Err(EnvVaultError::CommandFailed(format!(
+            "the new value for '{key}' is the same as the current one — vault unchanged"
+        )));
+    }
+
+    if let Some(hook) = hook {
+        output::info(&format!("Running pre-update hook for '{key}'..."));
+        run_hook(hook, key, &old_value, &new_value)?;
+    }
+
+    crate::cli::warn_clock_skew(&store);
+    store.set_secret(key, &new_value)?;
+    store.save()?;
+
+    crate::audit::log_audit(
+        cli,
+        "rotate-secret",
+        Some(key),
+        Some(&crate::audit::AuditDetails::rotate_secret(source_name, hook.is_some()).to_string()),
+    );
+    crate::cli::run_post_write_hook("rotate-secret", &cli.env);
+
+    output::success(&format!("Rotated '{key}' in {} environment", cli.env));
+    Ok(())
+}
+
+/// Random value of `len` characters from `[A-Za-z0-9]`.
+pub fn generate_value(len: usize) -> Result<Zeroizing<String>> {
+    if len == 0 {
+        return Err(EnvVaultError::CommandFailed(
+            "--length must be at least 1".into(),
+        ));
+    }
+
+    let mut value = Zeroizing::new(String::with_capacity(len));
+    let mut buf = Zeroizing::new([0u8; 64]);
+    while value.len() < len {
+        rand::rngs::OsRng
+            .try_fill_bytes(buf.as_mut())
+            .map_err(|e| EnvVaultError::CommandFailed(format!("OS RNG failed: {e}")))?;
+        // Reject the top of the byte range so every character is equally
+        // likely.
+        let limit = 256 - 256 % ALPHABET.len();
+        for &b in buf.iter().filter(|&&b| usize::from(b) < limit) {
+            if value.len() == len {
+                break;
+            }
+            value.push(char::from(ALPHABET[usize::from(b) % ALPHABET.len()]));
+        }
+    }
+    Ok(value)
+}
+
+/// Build a shell command without the vault credentials in its environment.
+fn shell_command(cmd: &str) -> Command {
+    #[cfg(windows)]
+    let mut command = {
+        let mut c = Command::new("cmd");
+        c.args(["/C", cmd]);
+        c
+    };
+    #[cfg(not(windows))]
+    let mut command = {
+        let mut c = Command::new("sh");
+        c.args(["-c", cmd]);
+        c
+    };
+    for var in AUTH_ENV_VARS {
+        command.env_remove(var);
+    }
+    command
+}
+
+/// Run `cmd` and use its stdout (minus the trailing newline) as the value.
+pub fn value_from_command(cmd: &str) -> Result<Zeroizing<String>> {
+    let output = shell_command(cmd)
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| EnvVaultError::CommandFailed(format!("failed to run --value-from: {e}")))?;
+    let stdout = Zeroizing::new(output.stdout);
+
+    if !output.status.success() {
+        return Err(EnvVaultError::CommandFailed(format!(
+            "--value-from command failed ({}) — vault unchanged",
+            output.status
+        )));
+    }
+
+    let text = std::str::from_utf8(&stdout).map_err(|_| {
+        EnvVaultError::CommandFailed("--value-from output is not valid UTF-8".into())
+    })?;
+    let value = text.strip_suffix('\n').unwrap_or(text);
+    let value = value.strip_suffix('\r').unwrap_or(value);
+    if value.is_empty() {
+        return Err(EnvVaultError::CommandFailed(
+            "--value-from command printed nothing — vault unchanged".into(),
+        ));
+    }
+    Ok(Zeroizing::new(value.to_string()))
+}
+
+/// Run the pre-update hook; any failure aborts the rotation.
+pub fn run_hook(hook: &str, key: &str, old_value: &str, new_value: &str) -> Result<()> {
+    let old_file = ValueFile::new(old_value, "old")?;
+    let new_file = ValueFile::new(new_value, "new")?;
+
+    let status = shell_command(hook)
+        .env("ENVVAULT_SECRET_NAME", key)
+        .env("ENVVAULT_OLD_VALUE_FILE", old_file.child_path())
+        .env("ENVVAULT_NEW_VALUE_FILE", new_file.child_path())
+        .stdin(Stdio::null())
+        .status()
+        .map_err(|e| EnvVaultError::CommandFailed(format!("failed to run hook: {e}")))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(EnvVaultError::CommandFailed(format!(
+            "hook failed ({status}) — vault unchanged"
+        )))
+    }
+}
+
+/// A value handed to the hook through a file it can read but that no
+/// other process can find by name.
+struct ValueFile {
+    file: fs::File,
+    /// Set while the file still has a name on disk (non-Unix only).
+    path: Option<PathBuf>,
+}
+
+impl ValueFile {
+    fn new(value: &str, label: &str) -> Result<Self> {
+        let path = std::env::temp_dir().join(format!(
+            "envvault-rotate-{label}-{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        ));
+
+        let mut options = fs::OpenOptions::new();
+        options.read(true).write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&path).map_err(|e| {
+            EnvVaultError::CommandFailed(format!("failed to create hook value file: {e}"))
+        })?;
+        file.write_all(value.as_bytes())?;
+        file.flush()?;
+        file.seek(SeekFrom::Start(0))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::io::AsRawFd;
+            // Unlink right away and let the hook inherit the descriptor.
+            let _ = fs::remove_file(&path);
+            // SAFETY: fcntl on a descriptor we own; clearing FD_CLOEXEC has
+            // no memory effects.
+            unsafe {
+                let fd = file.as_raw_fd();
+                let flags = libc::fcntl(fd, libc::F_GETFD);
+                if flags < 0 || libc::fcntl(fd, libc::F_SETFD, flags & !libc::FD_CLOEXEC) < 0 {
+                    return Err(EnvVaultError::CommandFailed(
+                        "failed to share hook value file".into(),
+                    ));
+                }
+            }
+            Ok(Self { file, path: None })
+        }
+
+        #[cfg(not(unix))]
+        Ok(Self {
+            file,
+            path: Some(path),
+        })
+    }
+
+    /// Path the hook opens to read the value.
+    fn child_path(&self) -> String {
+        #[cfg(unix)]
+        {
+            use std::os::unix::io::AsRawFd;
+            format!("/dev/fd/{}", self.file.as_raw_fd())
+        }
+        #[cfg(not(unix))]
+        {
+            self.path
+                .as_ref()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default()
+        }
+    }
+}
+
+impl Drop for ValueFile {
+    fn drop(&mut self) {
+        // Wipe the contents before the file goes away.
+        if let Ok(len) = self.file.metadata().map(|m| m.len() as usize) {
+            let _ = self.file.seek(SeekFrom::Start(0));
+            let _ = self.file.write_all(&vec![0u8; len]);
+            let _ = self.file.flush();
+        }
+        if let Some(ref path) = self.path {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_values_are_alphanumeric_and_distinct() {
+        let a = generate_value(48).unwrap();
+        let b = generate_value(48).unwrap();
+        assert_eq!(a.len(), 48);
+        assert!(a.bytes().all(|c| ALPHABET.contains(&c)));
+        assert_ne!(*a, *b);
+        assert!(generate_value(0).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn value_from_command_trims_one_newline() {
+        assert_eq!(*value_from_command("printf 'abc\\n'").unwrap(), "abc");
+        assert_eq!(*value_from_command("printf 'a b  '").unwrap(), "a b  ");
+        assert!(value_from_command("printf ''").is_err());
+        assert!(value_from_command("echo nope; exit 3").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hook_reads_values_from_descriptors_not_argv() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let out = dir.path().join("seen");
+        let hook = format!(
+            "printf '%s|%s|%s|%s' \"$ENVVAULT_SECRET_NAME\" \"$(cat \"$ENVVAULT_OLD_VALUE_FILE\")\" \"$(cat \"$ENVVAULT_NEW_VALUE_FILE\")\" \"$*\" > '{}'",
+            out.display()
+        );
+
+        run_hook(&hook, "DB_PASSWORD", "old-pw", "new-pw").unwrap();
+        assert_eq!(
+            fs::read_to_string(&out).unwrap(),
+            "DB_PASSWORD|old-pw|new-pw|"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failing_hook_is_an_error() {
+        let err = run_hook("exit 1", "KEY", "a", "b").unwrap_err();
+        assert!(err.to_string().contains("vault unchanged"), "{err}");
+    }
+}
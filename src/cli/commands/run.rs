@@ -1,17 +1,54 @@
 //! `envvault run` — inject secrets into a child process.
-
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+//!
+//! The variables in [`AUTH_ENV_VARS`] (e.g. `ENVVAULT_PASSWORD`) are removed
+//! from the child environment unless `--keep-envvault-vars` is passed, so
+//! the vault password never reaches the child or anything it spawns.
+//!
+//! `--interactive` gives the child the controlling terminal as stdin even
+//! when envvault's own stdin is a pipe, so REPLs and prompts keep working.
+//! The vault password is read before the child starts, so the two never
+//! compete for input.
+//!
+//! `--only` and `--except` take names or `*`/`?` globs (case-sensitive)
+//! and may be repeated: `--only 'VITE_*'` injects just the frontend keys.
+//! The two are mutually exclusive.  A pattern that matches no secret is
+//! reported as a warning rather than silently ignored.
+//!
+//! `--with-env ENV:PREFIX` also injects another environment's secrets,
+//! each name prefixed with `PREFIX` (`--with-env shared:SHARED_` turns
+//! `DB_URL` into `SHARED_DB_URL`).  It can be repeated.  The other vault
+//! is tried with the same password first and prompted for if that fails.
+//! On a name collision the active environment wins, then the earlier
+//! `--with-env`.  `--only`/`--except` apply to the merged names.
+//!
+//! `--secrets-dir` hands secrets over as files instead, Docker/Kubernetes
+//! style: each one is written to `<dir>/<KEY>` (mode `0600`) and the child
+//! gets `<KEY>_FILE` pointing at it rather than the value itself.  The
+//! files are wiped and removed once the child exits; if envvault itself is
+//! killed first they are left behind.
+//!
+//! `--check-only KEY1,KEY2` is a preflight for init containers and
+//! orchestrators: it opens the vault, checks that every listed key exists
+//! in the active environment, and exits without decrypting anything or
+//! starting the command.  Missing keys make it fail with the full list.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
 use zeroize::Zeroize;
 
+use super::search::glob_match_case_sensitive;
 use crate::cli::output;
-use crate::cli::{load_keyfile, prompt_password_for_vault, vault_path, Cli};
+use crate::cli::{load_keyfile, prompt_password_for_vault, vault_path, Cli, AUTH_ENV_VARS};
 use crate::errors::{EnvVaultError, Result};
 use crate::vault::VaultStore;
 
 /// Execute the `run` command.
+#[allow(clippy::too_many_arguments)]
 pub fn execute(
     cli: &Cli,
     command: &[String],
@@ -20,10 +57,27 @@ pub fn execute(
     exclude: Option<&[String]>,
     redact_output: bool,
     allowed_commands: Option<&[String]>,
+    keep_envvault_vars: bool,
+    interactive: bool,
+    secrets_dir: Option<&str>,
+    with_env: &[String],
+    check_only: Option<&[String]>,
 ) -> Result<()> {
     if command.is_empty() {
         return Err(EnvVaultError::NoCommandSpecified);
     }
+    let overlays = with_env
+        .iter()
+        .map(|spec| EnvOverlay::parse(spec))
+        .collect::<Result<Vec<_>>>()?;
+
+    // Fail before prompting for a password if there is no terminal to hand
+    // over.
+    let tty_stdin = if interactive {
+        Some(terminal_stdin()?)
+    } else {
+        None
+    };
 
     // Validate the command against the allow list (if configured).
     if let Some(allowed) = allowed_commands {
@@ -35,7 +89,7 @@ pub fn execute(
     let keyfile = load_keyfile(cli)?;
     let vault_id = path.to_string_lossy();
     let password = prompt_password_for_vault(Some(&vault_id))?;
-    let store = match VaultStore::open(&path, password.as_bytes(), keyfile.as_deref()) {
+    let mut store = match VaultStore::open(&path, password.as_bytes(), keyfile.as_deref()) {
         Ok(store) => store,
         Err(e) => {
             #[cfg(feature = "audit-log")]
@@ -43,21 +97,56 @@ pub fn execute(
             return Err(e);
         }
     };
+    crate::cli::warn_rotation_due(&store);
 
-    // Decrypt all secrets into memory.
-    let mut secrets = store.get_all_secrets()?;
+    if let Some(keys) = check_only {
+        return check_required(cli, &store, keys);
+    }
+    crate::cli::enable_access_tracking(&mut store);
 
-    // Apply --only filter: keep only the specified keys.
-    if let Some(only_keys) = only {
-        secrets.retain(|k, _| only_keys.iter().any(|o| o == k));
+    // Decrypt all secrets into memory.
+    let mut all = store.get_all_secrets()?;
+    crate::cli::persist_access(&mut store);
+
+    // Add --with-env environments underneath the active one.
+    for overlay in &overlays {
+        let secrets = overlay.open(cli, &password, keyfile.as_deref())?;
+        let shadowed = merge_overlay(&mut all, secrets, &overlay.prefix);
+        if !shadowed.is_empty() {
+            output::warning(&format!(
+                "{} secret(s) from '{}' are shadowed by ones already set: {}",
+                shadowed.len(),
+                overlay.env,
+                shadowed.join(", ")
+            ));
+        }
     }
 
-    // Apply --exclude filter: remove the specified keys.
-    if let Some(exclude_keys) = exclude {
-        secrets.retain(|k, _| !exclude_keys.iter().any(|e| e == k));
+    // Apply --only or --except, warning about patterns that match nothing.
+    let filtered = only.is_some() || exclude.is_some();
+    for pattern in unmatched_patterns(&all, only)
+        .into_iter()
+        .chain(unmatched_patterns(&all, exclude))
+    {
+        output::warning(&format!("'{pattern}' matches no secrets in this vault"));
+    }
+    let mut secrets = select_secrets(all, only, exclude);
+    if filtered && secrets.is_empty() {
+        output::warning("No secrets left after --only/--except; the command runs without any");
     }
 
-    if clean_env {
+    let secret_files = match secrets_dir {
+        Some(dir) => Some(SecretFiles::write(Path::new(dir), &secrets)?),
+        None => None,
+    };
+
+    if let Some(ref files) = secret_files {
+        output::success(&format!(
+            "Wrote {} secrets to {}",
+            secrets.len(),
+            files.dir.display()
+        ));
+    } else if clean_env {
         output::success(&format!(
             "Injected {} secrets into clean environment",
             secrets.len()
@@ -78,10 +167,24 @@ pub fn execute(
 
     if clean_env {
         cmd.env_clear();
+    } else if !keep_envvault_vars {
+        for var in AUTH_ENV_VARS {
+            cmd.env_remove(var);
+        }
     }
 
     // Always inject the marker so child processes know they're running under envvault.
     cmd.env("ENVVAULT_INJECTED", "true");
+    cmd.env(super::prompt_info::ACTIVE_VAR, &cli.env);
+
+    match secret_files {
+        Some(ref files) => cmd.envs(files.env_vars()),
+        None => cmd.envs(&secrets),
+    };
+
+    if let Some(stdin) = tty_stdin {
+        cmd.stdin(stdin);
+    }
 
     // Apply process isolation on Unix (prevent /proc/pid/environ leaks).
     #[cfg(unix)]
@@ -106,7 +209,7 @@ pub fn execute(
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
 
-        let mut child = cmd.envs(&secrets).spawn()?;
+        let mut child = cmd.spawn()?;
 
         let secret_values: Vec<String> = secrets
             .values()
@@ -138,9 +241,12 @@ pub fn execute(
 
         child.wait()?
     } else {
-        cmd.envs(&secrets).status()?
+        cmd.status()?
     };
 
+    // The child is gone; wipe the secret files before anything else.
+    drop(secret_files);
+
     // Zeroize plaintext secrets — the child process has its own copies.
     for v in secrets.values_mut() {
         v.zeroize();
@@ -164,6 +270,186 @@ pub fn execute(
     }
 }
 
+/// One `--with-env ENV:PREFIX` argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvOverlay {
+    pub env: String,
+    pub prefix: String,
+}
+
+impl EnvOverlay {
+    /// Parse `ENV:PREFIX`; `ENV` alone means no prefix.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (env, prefix) = spec.split_once(':').unwrap_or((spec, ""));
+        crate::cli::validate_env_name(env).map_err(|e| {
+            EnvVaultError::CommandFailed(format!("invalid --with-env '{spec}': {e}"))
+        })?;
+        Ok(Self {
+            env: env.to_string(),
+            prefix: prefix.to_string(),
+        })
+    }
+
+    /// Decrypt this environment's secrets, trying `password` first.
+    fn open(
+        &self,
+        cli: &Cli,
+        password: &str,
+        keyfile: Option<&[u8]>,
+    ) -> Result<HashMap<String, String>> {
+        let vault_dir = crate::cli::resolve_vault_dir(cli)?;
+        let path = vault_dir.join(format!("{}.vault", self.env));
+        if !path.exists() {
+            return Err(EnvVaultError::EnvironmentNotFound(self.env.clone()));
+        }
+        crate::cli::check_vault_symlinks(&vault_dir, &path, cli.strict_permissions)?;
+
+        let store = match VaultStore::open(&path, password.as_bytes(), keyfile) {
+            Ok(store) => store,
+            Err(EnvVaultError::HmacMismatch | EnvVaultError::DecryptionFailed) => {
+                output::info(&format!(
+                    "Environment '{}' uses a different password.",
+                    self.env
+                ));
+                let vault_id = path.to_string_lossy();
+                let password = prompt_password_for_vault(Some(&vault_id))?;
+                VaultStore::open(&path, password.as_bytes(), keyfile)?
+            }
+            Err(e) => return Err(e),
+        };
+        store.get_all_secrets()
+    }
+}
+
+/// Add `overlay` to `secrets` with every name prefixed by `prefix`.
+///
+/// Names already in `secrets` keep their value; the overlay's value is
+/// zeroized and the prefixed name returned, sorted.
+pub fn merge_overlay(
+    secrets: &mut HashMap<String, String>,
+    overlay: HashMap<String, String>,
+    prefix: &str,
+) -> Vec<String> {
+    let mut shadowed = Vec::new();
+    for (key, mut value) in overlay {
+        match secrets.entry(format!("{prefix}{key}")) {
+            Entry::Occupied(existing) => {
+                value.zeroize();
+                shadowed.push(existing.key().clone());
+            }
+            Entry::Vacant(slot) => {
+                slot.insert(value);
+            }
+        }
+    }
+    shadowed.sort();
+    shadowed
+}
+
+/// Secrets written out for `--secrets-dir`; wiped and removed on drop.
+struct SecretFiles {
+    dir: PathBuf,
+    /// Whether the directory was created here (and so is removed too).
+    created_dir: bool,
+    files: Vec<(String, PathBuf)>,
+}
+
+impl SecretFiles {
+    /// Write each secret to `<dir>/<KEY>`, owner-only.
+    ///
+    /// Refuses to overwrite existing files, so a leftover from an earlier
+    /// run (or a planted symlink) is an error rather than a target.
+    fn write(dir: &Path, secrets: &HashMap<String, String>) -> Result<Self> {
+        let created_dir = !dir.exists();
+        if created_dir {
+            let mut builder = fs::DirBuilder::new();
+            builder.recursive(true);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::DirBuilderExt;
+                builder.mode(0o700);
+            }
+            builder.create(dir).map_err(|e| {
+                EnvVaultError::CommandFailed(format!(
+                    "failed to create secrets dir {}: {e}",
+                    dir.display()
+                ))
+            })?;
+        }
+
+        // Absolute paths, so the child can change directory freely.
+        let mut files = SecretFiles {
+            dir: fs::canonicalize(dir)?,
+            created_dir,
+            files: Vec::with_capacity(secrets.len()),
+        };
+
+        let mut keys: Vec<&String> = secrets.keys().collect();
+        keys.sort();
+        for key in keys {
+            let path = files.dir.join(key);
+            let mut options = fs::OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                options.mode(0o600);
+            }
+            let mut file = options.open(&path).map_err(|e| {
+                EnvVaultError::CommandFailed(format!(
+                    "failed to write secret file {}: {e}",
+                    path.display()
+                ))
+            })?;
+            // Track the file before writing so a failed write is cleaned up.
+            files.files.push((key.clone(), path));
+            file.write_all(secrets[key].as_bytes())?;
+        }
+
+        Ok(files)
+    }
+
+    /// `<KEY>_FILE` variables for the child.
+    fn env_vars(&self) -> impl Iterator<Item = (String, &Path)> {
+        self.files
+            .iter()
+            .map(|(key, path)| (format!("{key}_FILE"), path.as_path()))
+    }
+}
+
+impl Drop for SecretFiles {
+    fn drop(&mut self) {
+        for (_, path) in &self.files {
+            super::edit::secure_delete(path);
+        }
+        if self.created_dir {
+            // Only succeeds if the child left nothing else behind.
+            let _ = fs::remove_dir(&self.dir);
+        }
+    }
+}
+
+/// Stdin for an `--interactive` child: inherited if it is already a
+/// terminal, otherwise the controlling terminal opened directly.
+fn terminal_stdin() -> Result<Stdio> {
+    use std::io::IsTerminal;
+
+    if std::io::stdin().is_terminal() {
+        return Ok(Stdio::inherit());
+    }
+
+    #[cfg(windows)]
+    let console = "CONIN$";
+    #[cfg(not(windows))]
+    let console = "/dev/tty";
+
+    std::fs::File::open(console).map(Stdio::from).map_err(|e| {
+        EnvVaultError::CommandFailed(format!(
+            "--interactive needs a terminal, but {console} could not be opened: {e}"
+        ))
+    })
+}
+
 /// Validate that a command is in the allowed list.
 ///
 /// Extracts the basename from the command path (e.g. `/usr/bin/node` → `node`)
@@ -218,18 +504,95 @@ pub fn redact_line(line: &str, secret_values: &[String]) -> String {
     result
 }
 
-/// Filter secrets by only/exclude lists. Used for testing.
-pub fn filter_secrets(
-    secrets: &mut std::collections::HashMap<String, String>,
+/// The secrets to inject: those matching `only`, or all but those
+/// matching `except`, or everything if neither is given.
+///
+/// The CLI never passes both; if it did, `only` would be applied first.
+/// Values that are left out are zeroized.
+pub fn select_secrets(
+    all: HashMap<String, String>,
     only: Option<&[String]>,
-    exclude: Option<&[String]>,
-) {
-    if let Some(only_keys) = only {
-        secrets.retain(|k, _| only_keys.iter().any(|o| o == k));
+    except: Option<&[String]>,
+) -> HashMap<String, String> {
+    let keep = |key: &str| {
+        only.map_or(true, |patterns| matches_any(patterns, key))
+            && except.map_or(true, |patterns| !matches_any(patterns, key))
+    };
+    all.into_iter()
+        .filter_map(|(key, mut value)| {
+            if keep(&key) {
+                Some((key, value))
+            } else {
+                value.zeroize();
+                None
+            }
+        })
+        .collect()
+}
+
+/// Patterns in `patterns` that match none of the keys in `secrets`.
+pub fn unmatched_patterns<'a>(
+    secrets: &HashMap<String, String>,
+    patterns: Option<&'a [String]>,
+) -> Vec<&'a str> {
+    patterns
+        .unwrap_or_default()
+        .iter()
+        .filter(|p| !secrets.keys().any(|k| glob_match_case_sensitive(p, k)))
+        .map(String::as_str)
+        .collect()
+}
+
+fn matches_any(patterns: &[String], key: &str) -> bool {
+    patterns.iter().any(|p| glob_match_case_sensitive(p, key))
+}
+
+/// `--check-only`: succeed if every key in `keys` exists in `store`.
+fn check_required(cli: &Cli, store: &VaultStore, keys: &[String]) -> Result<()> {
+    let required: Vec<&str> = keys
+        .iter()
+        .map(|k| k.trim())
+        .filter(|k| !k.is_empty())
+        .collect();
+    let missing = missing_keys(&required, |key| store.contains_key(key));
+
+    crate::audit::log_read_audit(
+        cli,
+        "run",
+        None,
+        Some(&format!(
+            "check-only: {} of {} required secrets present",
+            required.len() - missing.len(),
+            required.len()
+        )),
+    );
+
+    if !missing.is_empty() {
+        return Err(EnvVaultError::CommandFailed(format!(
+            "{} required secret(s) missing from {}.vault: {}",
+            missing.len(),
+            store.environment(),
+            missing.join(", ")
+        )));
     }
-    if let Some(exclude_keys) = exclude {
-        secrets.retain(|k, _| !exclude_keys.iter().any(|e| e == k));
+    output::success(&format!(
+        "All {} required secrets present in {}.vault",
+        required.len(),
+        store.environment()
+    ));
+    Ok(())
+}
+
+/// The keys in `required` for which `exists` is false, in order and
+/// without duplicates.
+pub fn missing_keys<'a>(required: &[&'a str], exists: impl Fn(&str) -> bool) -> Vec<&'a str> {
+    let mut missing: Vec<&str> = Vec::new();
+    for &key in required {
+        if !exists(key) && !missing.contains(&key) {
+            missing.push(key);
+        }
     }
+    missing
 }
 
 #[cfg(test)]
@@ -237,54 +600,110 @@ mod tests {
     use super::*;
     use std::collections::HashMap;
 
+    fn sample() -> HashMap<String, String> {
+        HashMap::from([
+            ("VITE_API_URL".into(), "1".into()),
+            ("VITE_DEBUG".into(), "2".into()),
+            ("vite_lower".into(), "3".into()),
+            ("DATABASE_URL".into(), "4".into()),
+        ])
+    }
+
+    fn sorted_keys(secrets: &HashMap<String, String>) -> Vec<&str> {
+        let mut keys: Vec<&str> = secrets.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        keys
+    }
+
     #[test]
-    fn filter_only_keeps_specified_keys() {
-        let mut secrets = HashMap::from([
-            ("A".into(), "1".into()),
-            ("B".into(), "2".into()),
-            ("C".into(), "3".into()),
-        ]);
-        let only = vec!["A".to_string(), "C".to_string()];
-        filter_secrets(&mut secrets, Some(&only), None);
-        assert_eq!(secrets.len(), 2);
-        assert!(secrets.contains_key("A"));
-        assert!(secrets.contains_key("C"));
-        assert!(!secrets.contains_key("B"));
+    fn select_only_keeps_matching_keys() {
+        let only = vec!["DATABASE_URL".to_string(), "VITE_DEBUG".to_string()];
+        let selected = select_secrets(sample(), Some(&only), None);
+        assert_eq!(sorted_keys(&selected), ["DATABASE_URL", "VITE_DEBUG"]);
+        assert_eq!(selected["DATABASE_URL"], "4");
     }
 
     #[test]
-    fn filter_exclude_removes_specified_keys() {
-        let mut secrets = HashMap::from([
-            ("A".into(), "1".into()),
-            ("B".into(), "2".into()),
-            ("C".into(), "3".into()),
-        ]);
-        let exclude = vec!["B".to_string()];
-        filter_secrets(&mut secrets, None, Some(&exclude));
-        assert_eq!(secrets.len(), 2);
-        assert!(secrets.contains_key("A"));
-        assert!(secrets.contains_key("C"));
+    fn select_except_drops_matching_keys() {
+        let except = vec!["VITE_DEBUG".to_string()];
+        let selected = select_secrets(sample(), None, Some(&except));
+        assert_eq!(
+            sorted_keys(&selected),
+            ["DATABASE_URL", "VITE_API_URL", "vite_lower"]
+        );
     }
 
     #[test]
-    fn filter_only_and_exclude_combined() {
-        let mut secrets = HashMap::from([
-            ("A".into(), "1".into()),
-            ("B".into(), "2".into()),
-            ("C".into(), "3".into()),
-        ]);
-        let only = vec!["A".to_string(), "B".to_string()];
-        let exclude = vec!["B".to_string()];
-        filter_secrets(&mut secrets, Some(&only), Some(&exclude));
-        assert_eq!(secrets.len(), 1);
-        assert!(secrets.contains_key("A"));
+    fn select_accepts_case_sensitive_globs() {
+        let only = vec!["VITE_*".to_string()];
+        let selected = select_secrets(sample(), Some(&only), None);
+        assert_eq!(sorted_keys(&selected), ["VITE_API_URL", "VITE_DEBUG"]);
+
+        let except = vec!["*_URL".to_string()];
+        let selected = select_secrets(sample(), None, Some(&except));
+        assert_eq!(sorted_keys(&selected), ["VITE_DEBUG", "vite_lower"]);
     }
 
     #[test]
-    fn filter_no_flags_keeps_all() {
-        let mut secrets = HashMap::from([("A".into(), "1".into()), ("B".into(), "2".into())]);
-        filter_secrets(&mut secrets, None, None);
-        assert_eq!(secrets.len(), 2);
+    fn select_unknown_names_leave_the_rest() {
+        let only = vec!["MISSING".to_string(), "VITE_DEBUG".to_string()];
+        let selected = select_secrets(sample(), Some(&only), None);
+        assert_eq!(sorted_keys(&selected), ["VITE_DEBUG"]);
+
+        let except = vec!["MISSING".to_string()];
+        assert_eq!(select_secrets(sample(), None, Some(&except)).len(), 4);
+    }
+
+    #[test]
+    fn select_no_flags_keeps_all() {
+        assert_eq!(select_secrets(sample(), None, None), sample());
+    }
+
+    #[test]
+    fn unmatched_patterns_are_reported() {
+        let secrets = HashMap::from([("VITE_A".into(), "1".into()), ("DB".into(), "2".into())]);
+        let patterns = vec!["VITE_*".to_string(), "NEXT_*".to_string(), "DB".to_string()];
+        assert_eq!(unmatched_patterns(&secrets, Some(&patterns)), ["NEXT_*"]);
+        assert!(unmatched_patterns(&secrets, None).is_empty());
+    }
+
+    #[test]
+    fn with_env_spec_parses_env_and_prefix() {
+        assert_eq!(
+            EnvOverlay::parse("shared:SHARED_").unwrap(),
+            EnvOverlay {
+                env: "shared".into(),
+                prefix: "SHARED_".into()
+            }
+        );
+        assert_eq!(EnvOverlay::parse("shared").unwrap().prefix, "");
+        assert_eq!(EnvOverlay::parse("shared:").unwrap().prefix, "");
+        assert!(EnvOverlay::parse("Shared:X_").is_err());
+        assert!(EnvOverlay::parse(":X_").is_err());
+    }
+
+    #[test]
+    fn overlay_is_prefixed_and_never_overrides() {
+        let mut secrets = HashMap::from([
+            ("DB_URL".to_string(), "app-db".to_string()),
+            ("SHARED_TOKEN".to_string(), "mine".to_string()),
+        ]);
+        let overlay = HashMap::from([
+            ("DB_URL".to_string(), "shared-db".to_string()),
+            ("TOKEN".to_string(), "theirs".to_string()),
+        ]);
+
+        let shadowed = merge_overlay(&mut secrets, overlay, "SHARED_");
+        assert_eq!(shadowed, ["SHARED_TOKEN"]);
+        assert_eq!(secrets.len(), 3);
+        assert_eq!(secrets["DB_URL"], "app-db");
+        assert_eq!(secrets["SHARED_DB_URL"], "shared-db");
+        assert_eq!(secrets["SHARED_TOKEN"], "mine");
+
+        // Without a prefix the active environment still wins.
+        let overlay = HashMap::from([("DB_URL".to_string(), "other".to_string())]);
+        assert_eq!(merge_overlay(&mut secrets, overlay, ""), ["DB_URL"]);
+        assert_eq!(secrets["DB_URL"], "app-db");
     }
 
     #[test]
@@ -318,6 +737,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn secret_files_are_owner_only_and_removed_on_drop() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let dir = tmp.path().join("secrets");
+        let secrets = HashMap::from([
+            ("DB_PASS".to_string(), "hunter2".to_string()),
+            ("API_KEY".to_string(), "sk-1".to_string()),
+        ]);
+
+        let files = SecretFiles::write(&dir, &secrets).unwrap();
+        let vars: Vec<(String, PathBuf)> = files
+            .env_vars()
+            .map(|(name, path)| (name, path.to_path_buf()))
+            .collect();
+        assert_eq!(vars.len(), 2);
+        assert_eq!(vars[0].0, "API_KEY_FILE");
+        assert!(vars[0].1.is_absolute());
+        assert_eq!(fs::read_to_string(&vars[1].1).unwrap(), "hunter2");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&vars[0].1).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+            let mode = fs::metadata(&dir).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+
+        drop(files);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn secret_files_never_overwrite_existing_files() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        fs::write(tmp.path().join("TOKEN"), "keep me").unwrap();
+        let secrets = HashMap::from([("TOKEN".to_string(), "secret".to_string())]);
+
+        assert!(SecretFiles::write(tmp.path(), &secrets).is_err());
+        assert_eq!(
+            fs::read_to_string(tmp.path().join("TOKEN")).unwrap(),
+            "keep me"
+        );
+        // A directory that already existed is left in place.
+        assert!(tmp.path().exists());
+    }
+
     // --- allowed_commands tests ---
 
     #[test]
@@ -347,4 +813,21 @@ mod tests {
         let err = validate_allowed_command("/usr/bin/python", &allowed).unwrap_err();
         assert!(err.to_string().contains("python"));
     }
+
+    #[test]
+    fn missing_keys_are_listed_once_in_order() {
+        let present = sample();
+        let exists = |key: &str| present.contains_key(key);
+
+        assert!(missing_keys(&["VITE_DEBUG", "DATABASE_URL"], exists).is_empty());
+        assert_eq!(
+            missing_keys(
+                &["STRIPE_KEY", "VITE_DEBUG", "REDIS_URL", "STRIPE_KEY"],
+                exists
+            ),
+            ["STRIPE_KEY", "REDIS_URL"]
+        );
+        // Names are case-sensitive, like the variables they become.
+        assert_eq!(missing_keys(&["vite_debug"], exists), ["vite_debug"]);
+    }
 }
@@ -1,38 +1,61 @@
 //! `envvault search` — search secrets by name pattern.
 //!
-//! Supports simple glob matching: `*` matches any sequence, `?` matches one char.
-//! Matching is case-insensitive.
+//! A pattern without wildcards matches any name containing it, so `db`
+//! finds `DB_URL` and `READ_DB_HOST`.  With `*` (any sequence) or `?` (one
+//! char) it is a glob over the whole name: `DB_*` only finds names starting
+//! with `DB_`.  `--regex` switches to regular expressions (unanchored).
+//! Every mode is case-insensitive.  Only metadata is read unless
+//! `--values` is given.
+//!
+//! `--values` decrypts and prints only the matching values instead of the
+//! metadata table.  Exits non-zero when nothing matches.  A plain name
+//! also matches longer names containing it, so scripts checking that one
+//! secret exists should anchor it: `search --regex '^NAME$'` (still
+//! case-insensitive).
+
+use regex::RegexBuilder;
+use zeroize::Zeroizing;
 
 use crate::cli::output;
 use crate::cli::{load_keyfile, prompt_password_for_vault, vault_path, Cli};
-use crate::errors::Result;
-use crate::vault::VaultStore;
+use crate::errors::{EnvVaultError, Result};
+use crate::vault::{SecretMetadata, VaultStore};
 
 /// Execute the `search` command.
-pub fn execute(cli: &Cli, pattern: &str) -> Result<()> {
+pub fn execute(cli: &Cli, pattern: &str, values: bool, regex: bool) -> Result<()> {
+    // Reject a bad regex before asking for the password.
+    let matcher = NameMatcher::new(pattern, regex)?;
+
     let path = vault_path(cli)?;
     let keyfile = load_keyfile(cli)?;
 
     let vault_id = path.to_string_lossy();
     let password = prompt_password_for_vault(Some(&vault_id))?;
-    let store = VaultStore::open(&path, password.as_bytes(), keyfile.as_deref())?;
-
-    let secrets = store.list_secrets();
-    let matches: Vec<_> = secrets
-        .iter()
-        .filter(|s| glob_match(pattern, &s.name))
-        .collect();
+    let mut store = VaultStore::open(&path, password.as_bytes(), keyfile.as_deref())?;
+    crate::cli::warn_rotation_due(&store);
 
+    let matches = filter_secrets(store.list_secrets(), &matcher);
     if matches.is_empty() {
-        output::info(&format!("No secrets matching '{pattern}'"));
-        return Ok(());
+        return Err(EnvVaultError::CommandFailed(format!(
+            "no secrets matching '{pattern}'"
+        )));
     }
 
     output::info(&format!(
         "{} secret(s) matching '{pattern}':",
         matches.len()
     ));
-    output::print_secrets_table(&matches.into_iter().cloned().collect::<Vec<_>>());
+    if values {
+        crate::cli::enable_access_tracking(&mut store);
+        let decrypted = matches
+            .iter()
+            .map(|s| Ok((s.name.as_str(), Zeroizing::new(store.get_secret(&s.name)?))))
+            .collect::<Result<Vec<_>>>()?;
+        super::get::print_values_table(&decrypted, true);
+        crate::cli::persist_access(&mut store);
+    } else {
+        output::print_secrets_table(&matches);
+    }
 
     #[cfg(feature = "audit-log")]
     crate::audit::log_read_audit(cli, "search", None, Some(&format!("pattern: {pattern}")));
@@ -40,6 +63,48 @@ pub fn execute(cli: &Cli, pattern: &str) -> Result<()> {
     Ok(())
 }
 
+/// The secrets whose names `matcher` accepts.
+pub fn filter_secrets(secrets: Vec<SecretMetadata>, matcher: &NameMatcher) -> Vec<SecretMetadata> {
+    secrets
+        .into_iter()
+        .filter(|s| matcher.is_match(&s.name))
+        .collect()
+}
+
+/// How `search` matches secret names.
+pub enum NameMatcher {
+    /// A pattern without wildcards, stored lowercased.
+    Substring(String),
+    Glob(String),
+    Regex(regex::Regex),
+}
+
+impl NameMatcher {
+    /// Substring matcher for a plain pattern, glob matcher when it has
+    /// `*` or `?`, or a regex one with `regex`; all case-insensitive.
+    pub fn new(pattern: &str, regex: bool) -> Result<Self> {
+        if !regex {
+            if pattern.contains(['*', '?']) {
+                return Ok(Self::Glob(pattern.to_string()));
+            }
+            return Ok(Self::Substring(pattern.to_ascii_lowercase()));
+        }
+        RegexBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+            .map(Self::Regex)
+            .map_err(|e| EnvVaultError::CommandFailed(format!("invalid --regex pattern: {e}")))
+    }
+
+    pub fn is_match(&self, name: &str) -> bool {
+        match self {
+            Self::Substring(needle) => name.to_ascii_lowercase().contains(needle.as_str()),
+            Self::Glob(pattern) => glob_match(pattern, name),
+            Self::Regex(re) => re.is_match(name),
+        }
+    }
+}
+
 /// Simple glob matcher supporting `*` (any sequence) and `?` (single char).
 /// Case-insensitive.
 pub fn glob_match(pattern: &str, text: &str) -> bool {
@@ -48,6 +113,11 @@ pub fn glob_match(pattern: &str, text: &str) -> bool {
     glob_match_inner(pattern.as_bytes(), text.as_bytes())
 }
 
+/// Like [`glob_match`], but case-sensitive, as environment variable names are.
+pub fn glob_match_case_sensitive(pattern: &str, text: &str) -> bool {
+    glob_match_inner(pattern.as_bytes(), text.as_bytes())
+}
+
 fn glob_match_inner(pattern: &[u8], text: &[u8]) -> bool {
     let mut p = 0;
     let mut t = 0;
@@ -118,6 +188,67 @@ mod tests {
         assert!(glob_match("DB_*", "DB_"));
     }
 
+    #[test]
+    fn regex_matcher_is_unanchored_and_case_insensitive() {
+        let m = NameMatcher::new("^aws_.*(key|token)$", true).unwrap();
+        assert!(m.is_match("AWS_ACCESS_KEY"));
+        assert!(m.is_match("aws_session_token"));
+        assert!(!m.is_match("MY_AWS_KEY"));
+
+        let m = NameMatcher::new("TOKEN", true).unwrap();
+        assert!(m.is_match("GITHUB_TOKEN_RO"));
+
+        assert!(NameMatcher::new("(", true).is_err());
+        // Without --regex the same text is a literal substring.
+        assert!(NameMatcher::new("(", false).unwrap().is_match("A(B"));
+    }
+
+    fn names(secrets: &[SecretMetadata]) -> Vec<&str> {
+        secrets.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    fn metadata(names: &[&str]) -> Vec<SecretMetadata> {
+        let now = chrono::Utc::now();
+        names
+            .iter()
+            .map(|name| SecretMetadata {
+                name: (*name).into(),
+                created_at: now,
+                updated_at: now,
+                encoding: None,
+                access_count: None,
+                last_accessed: None,
+                expires_at: None,
+                description: None,
+                tags: Vec::new(),
+                order: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn filter_secrets_by_substring_glob_regex_and_no_match() {
+        let all = metadata(&["DB_URL", "READ_DB_HOST", "API_KEY", "GITHUB_TOKEN"]);
+        let filter = |pattern: &str, regex: bool| {
+            filter_secrets(all.clone(), &NameMatcher::new(pattern, regex).unwrap())
+        };
+
+        assert_eq!(names(&filter("db", false)), ["DB_URL", "READ_DB_HOST"]);
+        assert_eq!(names(&filter("Token", false)), ["GITHUB_TOKEN"]);
+        assert_eq!(names(&filter("DB_*", false)), ["DB_URL"]);
+        assert_eq!(
+            names(&filter("(key|token)$", true)),
+            ["API_KEY", "GITHUB_TOKEN"]
+        );
+
+        assert!(filter("STRIPE", false).is_empty());
+        assert!(filter("STRIPE_*", false).is_empty());
+        assert!(matches!(
+            NameMatcher::new("[", true),
+            Err(EnvVaultError::CommandFailed(_))
+        ));
+    }
+
     #[test]
     fn glob_multiple_stars() {
         assert!(glob_match("*DB*", "MY_DB_URL"));
@@ -0,0 +1,205 @@
+//! `envvault selftest` — known-answer tests for the crypto stack.
+//!
+//! Runs fixed inputs through the same functions vaults use and compares
+//! the results against baked-in outputs:
+//! - AES-256-GCM: a published test vector through `decrypt`, tamper
+//!   rejection, and an `encrypt` round trip
+//! - ChaCha20-Poly1305: a fixed vector through `decrypt_with`
+//! - HKDF-SHA256: RFC 5869 test case 3, plus the per-secret and HMAC
+//!   sub-key derivations
+//! - Argon2id: `derive_master_key_with_params` with fixed password, salt,
+//!   and (minimum) params
+//!
+//! A dependency upgrade that changes any of these outputs would make
+//! existing vaults unreadable, so every mismatch is reported and the
+//! command exits non-zero.
+
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+use crate::cli::output;
+use crate::crypto::{
+    decrypt, decrypt_with, derive_hmac_key, derive_master_key_with_params, derive_secret_key,
+    encrypt, Argon2Params, Cipher,
+};
+use crate::errors::{EnvVaultError, Result};
+
+/// AES-256-GCM test case 14 from the GCM specification (McGrew & Viega):
+/// all-zero key and nonce, 16 zero bytes of plaintext.
+const GCM_CIPHERTEXT_AND_TAG: &str =
+    "cea7403d4d606b6e074ec5d3baf39d18d0d1c8a799996bf0265b98b5d48ab919";
+
+/// ChaCha20-Poly1305 (RFC 8439) with an all-zero key and nonce over 16
+/// zero bytes of plaintext, no associated data.
+const CHACHA_CIPHERTEXT_AND_TAG: &str =
+    "9f07e7be5551387a98ba977c732d080dc34a88047320f52aa2c6683ef8084d2f";
+
+/// RFC 5869 test case 3: SHA-256, 22 bytes of `0x0b`, empty salt and info.
+const HKDF_RFC5869_OKM: &str =
+    "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8";
+
+/// `derive_hmac_key` of the master key `00 01 .. 1f`.
+const HMAC_KEY: &str = "c4a20445c1c38cb8cd161e8bc5ec99468e815f9ed444c09649a33d57733f3ad8";
+
+/// `derive_secret_key` of the master key `00 01 .. 1f` for `DATABASE_URL`.
+const SECRET_KEY: &str = "0adc86c95853c9a075ef8648c2a3906dca372936db052f1885d3cbd827552873";
+
+const ARGON2_PASSWORD: &[u8] = b"correct horse battery staple";
+const ARGON2_SALT: &[u8] = b"envvault-selftest-salt-000000000";
+
+/// Argon2id (v0x13, 8192 KiB, 1 iteration, 1 lane) of the password and
+/// salt above.
+const ARGON2_KEY: &str = "feb8927141835830eced6a3b53289a925ad0ecec5186878238ac2aa213750e48";
+
+/// One known-answer test: a name and the check itself.
+type Check = (&'static str, fn() -> std::result::Result<(), String>);
+
+const CHECKS: &[Check] = &[
+    ("AES-256-GCM known answer (decrypt)", gcm_known_answer),
+    ("AES-256-GCM tamper rejection", gcm_rejects_tampering),
+    ("AES-256-GCM encrypt round trip", gcm_round_trip),
+    (
+        "ChaCha20-Poly1305 known answer (decrypt)",
+        chacha_known_answer,
+    ),
+    ("HKDF-SHA256 RFC 5869 test case 3", hkdf_rfc5869),
+    ("HKDF sub-key derivation", hkdf_sub_keys),
+    ("Argon2id master key derivation", argon2_known_answer),
+];
+
+/// Execute `envvault selftest`.
+pub fn execute() -> Result<()> {
+    let results = run_checks();
+    let failed = results.iter().filter(|(_, r)| r.is_err()).count();
+
+    for (name, result) in &results {
+        match result {
+            Ok(()) => output::success(name),
+            Err(e) => output::error(&format!("{name}: {e}")),
+        }
+    }
+
+    if failed == 0 {
+        output::success(&format!("All {} self-tests passed", results.len()));
+        Ok(())
+    } else {
+        Err(EnvVaultError::CommandFailed(format!(
+            "{failed} of {} self-tests FAILED — do not trust this build with your vaults",
+            results.len()
+        )))
+    }
+}
+
+/// Run every check, in order, collecting each outcome.
+pub fn run_checks() -> Vec<(&'static str, std::result::Result<(), String>)> {
+    CHECKS
+        .iter()
+        .map(|(name, check)| (*name, check()))
+        .collect()
+}
+
+fn gcm_known_answer() -> std::result::Result<(), String> {
+    let mut input = vec![0u8; 12];
+    input.extend(unhex(GCM_CIPHERTEXT_AND_TAG));
+    let plaintext = decrypt(&[0u8; 32], &input).map_err(|e| e.to_string())?;
+    expect_eq(&plaintext, &[0u8; 16])
+}
+
+fn gcm_rejects_tampering() -> std::result::Result<(), String> {
+    let mut input = vec![0u8; 12];
+    input.extend(unhex(GCM_CIPHERTEXT_AND_TAG));
+    let last = input.len() - 1;
+    input[last] ^= 0x01;
+    match decrypt(&[0u8; 32], &input) {
+        Ok(_) => Err("a modified tag was accepted".into()),
+        Err(_) => Ok(()),
+    }
+}
+
+fn gcm_round_trip() -> std::result::Result<(), String> {
+    let key = unhex(SECRET_KEY);
+    let plaintext = b"postgres://selftest@localhost/db";
+    let first = encrypt(&key, plaintext).map_err(|e| e.to_string())?;
+    let second = encrypt(&key, plaintext).map_err(|e| e.to_string())?;
+    if first == second {
+        return Err("two encryptions produced the same nonce and ciphertext".into());
+    }
+    let decrypted = decrypt(&key, &first).map_err(|e| e.to_string())?;
+    expect_eq(&decrypted, plaintext)
+}
+
+fn chacha_known_answer() -> std::result::Result<(), String> {
+    let mut input = vec![0u8; 12];
+    input.extend(unhex(CHACHA_CIPHERTEXT_AND_TAG));
+    let plaintext =
+        decrypt_with(Cipher::ChaCha20Poly1305, &[0u8; 32], &input).map_err(|e| e.to_string())?;
+    expect_eq(&plaintext, &[0u8; 16])
+}
+
+fn hkdf_rfc5869() -> std::result::Result<(), String> {
+    let hk = Hkdf::<Sha256>::new(Some(&[]), &[0x0b; 22]);
+    let mut okm = [0u8; 42];
+    hk.expand(&[], &mut okm).map_err(|e| e.to_string())?;
+    expect_eq(&okm, &unhex(HKDF_RFC5869_OKM))
+}
+
+fn hkdf_sub_keys() -> std::result::Result<(), String> {
+    let master: Vec<u8> = (0u8..32).collect();
+    let hmac_key = derive_hmac_key(&master).map_err(|e| e.to_string())?;
+    expect_eq(&hmac_key, &unhex(HMAC_KEY))?;
+    let secret_key = derive_secret_key(&master, "DATABASE_URL").map_err(|e| e.to_string())?;
+    expect_eq(&secret_key, &unhex(SECRET_KEY))
+}
+
+fn argon2_known_answer() -> std::result::Result<(), String> {
+    let params = Argon2Params {
+        memory_kib: crate::crypto::kdf::MIN_MEMORY_KIB,
+        iterations: 1,
+        parallelism: 1,
+    };
+    let key = derive_master_key_with_params(ARGON2_PASSWORD, ARGON2_SALT, &params)
+        .map_err(|e| e.to_string())?;
+    expect_eq(&key, &unhex(ARGON2_KEY))
+}
+
+fn expect_eq(actual: &[u8], expected: &[u8]) -> std::result::Result<(), String> {
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "expected {}, got {}",
+            to_hex(expected),
+            to_hex(actual)
+        ))
+    }
+}
+
+/// Decode one of the hex constants above.
+fn unhex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("test vector is valid hex"))
+        .collect()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_check_passes_on_this_build() {
+        for (name, result) in run_checks() {
+            assert_eq!(result, Ok(()), "{name}");
+        }
+    }
+
+    #[test]
+    fn mismatches_show_both_values() {
+        let err = expect_eq(&[0xab], &[0xcd]).unwrap_err();
+        assert_eq!(err, "expected cd, got ab");
+    }
+}
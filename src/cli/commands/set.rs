@@ -1,14 +1,61 @@
 //! `envvault set` — add or update a secret in the vault.
+//!
+//! `--encoding base64|hex` records how the value is encoded so that
+//! `get --decode` can return the original bytes; `--encoding raw` clears
+//! the hint.  Updating a value without `--encoding` keeps the existing hint.
+//!
+//! `--expires 30d` (or `24h`, `90m`) records when the value should be
+//! rotated; `get` and `list` warn once that time has passed.
+//! `--expires never` clears it, and updating a value without `--expires`
+//! keeps the existing expiry.
+//!
+//! `--description` and `--tag` annotate the secret for `list`.  Both are
+//! stored unencrypted.  `--tag` replaces all existing tags, and
+//! `--description ""` or `--tag ""` clears them; without the options, an
+//! update keeps what was there.
+//!
+//! The key must satisfy the project's `[key_policy]`, if any (see
+//! [`key_policy`](crate::cli::key_policy)).
+//!
+//! `--order N` places the secret at position `N` for `export --sort
+//! declared`; an update without it keeps the existing position.
 
 use std::io::{self, IsTerminal, Read};
 
-use crate::cli::output;
+use chrono::{DateTime, Utc};
+
+use crate::cli::{key_policy, output};
 use crate::cli::{load_keyfile, prompt_password_for_vault, vault_path, Cli};
-use crate::errors::Result;
-use crate::vault::VaultStore;
+use crate::errors::{EnvVaultError, Result};
+use crate::vault::{secret, SecretEncoding, VaultStore};
 
 /// Execute the `set` command.
-pub fn execute(cli: &Cli, key: &str, value: Option<&str>, force: bool) -> Result<()> {
+#[allow(clippy::too_many_arguments)]
+pub fn execute(
+    cli: &Cli,
+    key: &str,
+    value: Option<&str>,
+    force: bool,
+    encoding: Option<&str>,
+    expires: Option<&str>,
+    description: Option<&str>,
+    tags: &[String],
+    order: Option<u32>,
+) -> Result<()> {
+    key_policy::enforce(cli, [key])?;
+    let encoding = encoding.map(SecretEncoding::parse).transpose()?;
+    let expires_at = expires.map(|e| parse_expires(e, Utc::now())).transpose()?;
+    // `--tag ""` on its own clears the tags.
+    let tags = if tags.is_empty() {
+        None
+    } else {
+        let given: Vec<&str> = tags
+            .iter()
+            .map(String::as_str)
+            .filter(|t| !t.is_empty())
+            .collect();
+        Some(secret::normalize_tags(&given)?)
+    };
     let path = vault_path(cli)?;
 
     // Determine the secret value from one of three sources.
@@ -19,32 +66,68 @@ pub fn execute(cli: &Cli, key: &str, value: Option<&str>, force: bool) -> Result
         }
         v.to_string()
     } else if !io::stdin().is_terminal() {
-        // Source 2: Piped input (stdin is not a terminal).
+        // Source 2: Piped input (stdin is not a terminal).  With
+        // --stdin-password the first line was the password; the value is
+        // whatever follows it.
         let mut buf = String::new();
         io::stdin().read_to_string(&mut buf)?;
+        if buf.is_empty() && crate::cli::stdin_password_in_use() {
+            return Err(EnvVaultError::CommandFailed(format!(
+                "no value for {key} on stdin after the password line — pipe the password, then the value, or pass the value as an argument"
+            )));
+        }
         buf.trim_end().to_string()
     } else {
         // Source 3: Interactive secure prompt (default).
         dialoguer::Password::new()
             .with_prompt(format!("Enter value for {key}"))
             .interact()
-            .map_err(|e| {
-                crate::errors::EnvVaultError::CommandFailed(format!("input prompt: {e}"))
-            })?
+            .map_err(|e| EnvVaultError::CommandFailed(format!("input prompt: {e}")))?
     };
 
+    // Refuse to tag a value with an encoding it isn't in.
+    if let Some(Some(enc)) = encoding {
+        enc.decode(&secret_value)?;
+    }
+
     // Open the vault, set the secret, and save.
     let keyfile = load_keyfile(cli)?;
     let vault_id = path.to_string_lossy();
     let password = prompt_password_for_vault(Some(&vault_id))?;
     let mut store = VaultStore::open(&path, password.as_bytes(), keyfile.as_deref())?;
+    crate::cli::warn_rotation_due(&store);
 
-    let existed = store.get_secret(key).is_ok();
-    store.set_secret(key, &secret_value)?;
+    let existed = store.contains_key(key);
+    match expires_at {
+        Some(expires_at) => store.set_secret_with_expiry(key, &secret_value, expires_at)?,
+        None => store.set_secret(key, &secret_value)?,
+    }
+    if let Some(enc) = encoding {
+        store.set_encoding(key, enc)?;
+    }
+    if let Some(description) = description {
+        let description = description.trim();
+        store.set_description(
+            key,
+            (!description.is_empty()).then(|| description.to_string()),
+        )?;
+    }
+    if let Some(tags) = tags {
+        store.set_tags(key, &tags)?;
+    }
+    if let Some(order) = order {
+        store.set_order(key, Some(order))?;
+    }
+    crate::cli::warn_clock_skew(&store);
     store.save()?;
 
-    let op_detail = if existed { "updated" } else { "added" };
-    crate::audit::log_audit(cli, "set", Some(key), Some(op_detail));
+    crate::audit::log_audit(
+        cli,
+        "set",
+        Some(key),
+        Some(&crate::audit::AuditDetails::set(existed).to_string()),
+    );
+    crate::cli::run_post_write_hook("set", &cli.env);
 
     if existed {
         output::success(&format!(
@@ -62,7 +145,51 @@ pub fn execute(cli: &Cli, key: &str, value: Option<&str>, force: bool) -> Result
         ));
     }
 
+    if let Some(Some(at)) = expires_at {
+        output::info(&format!("Expires {} UTC", at.format("%Y-%m-%d %H:%M")));
+    }
+
     output::tip("Run your app: envvault run -- <command>");
 
     Ok(())
 }
+
+/// Parse an `--expires` value: a duration from `now` such as `30d`, or
+/// `never` (yields `None`, clearing the expiry).
+pub fn parse_expires(value: &str, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+    if value.trim().eq_ignore_ascii_case("never") {
+        return Ok(None);
+    }
+    let span = super::audit_cmd::parse_span(value)?;
+    if span <= chrono::Duration::zero() {
+        return Err(EnvVaultError::CommandFailed(format!(
+            "--expires '{value}' must be in the future"
+        )));
+    }
+    now.checked_add_signed(span).map(Some).ok_or_else(|| {
+        EnvVaultError::CommandFailed(format!("--expires '{value}' is too far in the future"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expires_is_a_duration_from_now_or_never() {
+        let now: DateTime<Utc> = "2024-01-01T00:00:00Z".parse().unwrap();
+        assert_eq!(
+            parse_expires("30d", now).unwrap(),
+            Some("2024-01-31T00:00:00Z".parse().unwrap())
+        );
+        assert_eq!(
+            parse_expires("12h", now).unwrap(),
+            Some("2024-01-01T12:00:00Z".parse().unwrap())
+        );
+        assert_eq!(parse_expires("never", now).unwrap(), None);
+        assert_eq!(parse_expires("Never", now).unwrap(), None);
+        assert!(parse_expires("0d", now).is_err());
+        assert!(parse_expires("-1d", now).is_err());
+        assert!(parse_expires("soon", now).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! `envvault set-many` — set many secrets at once from stdin.
+//!
+//! `--json` reads one JSON object (`{"KEY":"value",...}`) from stdin and
+//! writes every key, opening and saving the vault once.  It is the inverse
+//! of `dump --json`, so `envvault dump --json | jq ... | envvault
+//! set-many --json` round-trips.  Strings are stored as-is, numbers and
+//! booleans as their JSON text, and `null` as empty.  A nested object or
+//! array (such as `dump --json --with-metadata` output) is an error naming
+//! the key, so metadata is never stored as a value.
+//!
+//! Every name must satisfy the project's `[key_policy]`; otherwise nothing
+//! is written and all offending names are listed.
+
+use std::collections::BTreeMap;
+use std::io::{self, IsTerminal, Read};
+
+use zeroize::Zeroize;
+
+use super::import_cmd;
+use crate::cli::{key_policy, output};
+use crate::cli::{load_keyfile, prompt_password_for_vault, vault_path, Cli};
+use crate::errors::{EnvVaultError, Result};
+use crate::vault::VaultStore;
+
+/// Execute the `set-many` command.
+pub fn execute(cli: &Cli, json: bool) -> Result<()> {
+    if !json {
+        return Err(EnvVaultError::CommandFailed(
+            "set-many needs an input format — pass --json and pipe a JSON object".into(),
+        ));
+    }
+    if io::stdin().is_terminal() {
+        return Err(EnvVaultError::CommandFailed(
+            "set-many --json reads a JSON object from stdin — pipe one in, e.g. envvault dump --json | envvault set-many --json".into(),
+        ));
+    }
+
+    // With --stdin-password the first line was the password; the object is
+    // whatever follows it.
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    let parsed = import_cmd::parse_flat_json(&input);
+    input.zeroize();
+    let mut secrets: BTreeMap<String, String> = parsed?.into_iter().collect();
+
+    if secrets.is_empty() {
+        output::warning("No secrets in the JSON object on stdin.");
+        return Ok(());
+    }
+
+    key_policy::enforce(cli, secrets.keys().map(String::as_str))?;
+
+    let path = vault_path(cli)?;
+    let keyfile = load_keyfile(cli)?;
+    let vault_id = path.to_string_lossy();
+    let password = prompt_password_for_vault(Some(&vault_id))?;
+    let mut store = VaultStore::open(&path, password.as_bytes(), keyfile.as_deref())?;
+    crate::cli::warn_rotation_due(&store);
+
+    let mut added = 0;
+    let mut updated = 0;
+    for (key, value) in &secrets {
+        if store.contains_key(key) {
+            updated += 1;
+        } else {
+            added += 1;
+        }
+        store.set_secret(key, value)?;
+    }
+    for value in secrets.values_mut() {
+        value.zeroize();
+    }
+
+    crate::cli::warn_clock_skew(&store);
+    store.save()?;
+
+    crate::audit::log_audit(
+        cli,
+        "set-many",
+        None,
+        Some(&crate::audit::AuditDetails::set_many(added, updated).to_string()),
+    );
+    crate::cli::run_post_write_hook("set-many", &cli.env);
+
+    output::success(&format!(
+        "Set {} secrets in {}.vault ({added} added, {updated} updated, {} total)",
+        added + updated,
+        cli.env,
+        store.secret_count()
+    ));
+
+    Ok(())
+}
@@ -40,13 +40,21 @@ pub fn detect_install_method() -> InstallMethod {
 pub fn execute() -> Result<()> {
     let current = env!("CARGO_PKG_VERSION");
 
+    // Without the registry check every build would look up to date.
+    if !crate::version_check::ENABLED {
+        return Err(EnvVaultError::FeatureDisabled(
+            crate::features::VERSION_CHECK,
+        ));
+    }
+
     // Check for updates first.
     let latest = crate::version_check::check_latest_version(current);
     match &latest {
         Some(ver) => {
             output::info(&format!(
-                "Update available: {} → {}",
+                "Update available: {} {} {}",
                 style(current).red(),
+                output::Symbol::Arrow.glyph(),
                 style(ver).green().bold()
             ));
         }
@@ -0,0 +1,235 @@
+//! `envvault verify` — check vault files for corruption without decrypting.
+//!
+//! Every `*.vault` file in the vault directory (or only `--env`'s) gets
+//! the structural checks of `format::read_vault`: magic bytes, version,
+//! length fields, and header and secrets JSON.  None of that needs a
+//! password.
+//!
+//! When a password is available without prompting (`--stdin-password`,
+//! `--password-file`, `ENVVAULT_PASSWORD`, or the keyring), the HMAC is
+//! verified too.  No secret is decrypted either way.  Any failure makes
+//! the command exit non-zero, which is what a CI step wants.
+
+use std::path::Path;
+
+use console::style;
+use zeroize::Zeroize;
+
+use crate::cli::commands::env_list::list_environments;
+use crate::cli::output;
+use crate::cli::{available_password, load_keyfile, Cli};
+use crate::errors::{EnvVaultError, Result};
+use crate::vault::{format, VaultStore};
+
+/// Outcome of the HMAC check for one vault.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HmacCheck {
+    Verified,
+    /// No password was available.
+    NoPassword,
+    /// The vault needs a keyfile and none was given.
+    NoKeyfile,
+    /// The structural checks failed, so there was nothing to verify.
+    NotReached,
+    Failed(String),
+}
+
+/// Result of verifying one vault file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// `Err` with the reason if the file is structurally broken.
+    pub structure: std::result::Result<(), String>,
+    pub hmac: HmacCheck,
+}
+
+impl VerifyReport {
+    /// Whether the file passed every check that could be run.
+    pub fn passed(&self) -> bool {
+        self.structure.is_ok() && !matches!(self.hmac, HmacCheck::Failed(_))
+    }
+}
+
+/// Execute the `verify` command.
+pub fn execute(cli: &Cli) -> Result<()> {
+    let vault_dir = crate::cli::resolve_vault_dir(cli)?;
+
+    let names = if cli.env_explicit {
+        vec![cli.env.clone()]
+    } else if vault_dir.is_dir() {
+        let mut envs: Vec<String> = list_environments(&vault_dir)?
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        envs.sort();
+        envs
+    } else {
+        Vec::new()
+    };
+    if names.is_empty() {
+        return Err(EnvVaultError::CommandFailed(format!(
+            "no vault files found in {}",
+            vault_dir.display()
+        )));
+    }
+
+    let keyfile = load_keyfile(cli)?;
+
+    let mut table = output::table();
+    table.set_header(vec!["Environment", "Structure", "HMAC", "Result"]);
+
+    let mut failed = 0;
+    let mut unchecked = 0;
+    for name in &names {
+        let path = vault_dir.join(format!("{name}.vault"));
+        let vault_id = path.to_string_lossy();
+        let password = available_password(Some(&vault_id));
+        let report = verify_file(
+            &path,
+            password.as_deref().map(String::as_bytes),
+            keyfile.as_deref(),
+        );
+
+        if !report.passed() {
+            failed += 1;
+        }
+        if matches!(report.hmac, HmacCheck::NoPassword | HmacCheck::NoKeyfile) {
+            unchecked += 1;
+        }
+
+        let structure = match &report.structure {
+            Ok(()) => style("ok".to_string()).green(),
+            Err(e) => style(e.clone()).red(),
+        };
+        let hmac = match &report.hmac {
+            HmacCheck::Verified => style("ok".to_string()).green(),
+            HmacCheck::NoPassword => style("skipped (no password)".to_string()).dim(),
+            HmacCheck::NoKeyfile => style("skipped (needs --keyfile)".to_string()).dim(),
+            HmacCheck::NotReached => style("-".to_string()).dim(),
+            HmacCheck::Failed(e) => style(e.clone()).red(),
+        };
+        let result = if report.passed() {
+            style("PASS").green().bold()
+        } else {
+            style("FAIL").red().bold()
+        };
+        table.add_row(vec![
+            name.clone(),
+            structure.to_string(),
+            hmac.to_string(),
+            result.to_string(),
+        ]);
+    }
+
+    println!("{table}");
+
+    if unchecked > 0 {
+        output::tip("Set ENVVAULT_PASSWORD (and --keyfile if needed) to verify HMACs as well.");
+    }
+
+    if failed > 0 {
+        return Err(EnvVaultError::CommandFailed(format!(
+            "{failed} of {} vault file(s) failed verification",
+            names.len()
+        )));
+    }
+
+    output::success(&format!("{} vault file(s) verified", names.len()));
+    Ok(())
+}
+
+/// Run the structural checks on the vault at `path`, then the HMAC check
+/// if `password` is given.
+pub fn verify_file(path: &Path, password: Option<&[u8]>, keyfile: Option<&[u8]>) -> VerifyReport {
+    let raw = match format::read_vault(path) {
+        Ok(raw) => raw,
+        Err(e) => {
+            return VerifyReport {
+                structure: Err(e.to_string()),
+                hmac: HmacCheck::NotReached,
+            };
+        }
+    };
+
+    let hmac = match password {
+        None => HmacCheck::NoPassword,
+        Some(_) if raw.header.keyfile_hash.is_some() && keyfile.is_none() => HmacCheck::NoKeyfile,
+        Some(password) => match check_hmac(&raw, password, keyfile) {
+            Ok(()) => HmacCheck::Verified,
+            Err(EnvVaultError::HmacMismatch) => {
+                HmacCheck::Failed("mismatch (wrong password or modified file)".into())
+            }
+            Err(e) => HmacCheck::Failed(e.to_string()),
+        },
+    };
+
+    VerifyReport {
+        structure: Ok(()),
+        hmac,
+    }
+}
+
+fn check_hmac(raw: &format::RawVault, password: &[u8], keyfile: Option<&[u8]>) -> Result<()> {
+    let master_key = VaultStore::derive_master_key(&raw.header, password, keyfile)?;
+    let mut hmac_key = master_key.derive_hmac_key()?;
+    let result = format::verify_hmac(
+        &hmac_key,
+        &raw.header_bytes,
+        &raw.secrets_bytes,
+        &raw.stored_hmac,
+    );
+    hmac_key.zeroize();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_vault(dir: &Path) -> std::path::PathBuf {
+        let path = dir.join("dev.vault");
+        let mut store = VaultStore::create(&path, b"verify-password", "dev", None, None).unwrap();
+        store.set_secret("API_KEY", "sk-verify").unwrap();
+        store.save().unwrap();
+        path
+    }
+
+    #[test]
+    fn structure_is_checked_without_a_password() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = create_vault(dir.path());
+
+        let report = verify_file(&path, None, None);
+        assert_eq!(report.structure, Ok(()));
+        assert_eq!(report.hmac, HmacCheck::NoPassword);
+        assert!(report.passed());
+
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[0] = b'X';
+        std::fs::write(&path, &bytes).unwrap();
+        let report = verify_file(&path, None, None);
+        assert!(report.structure.unwrap_err().contains("magic"));
+        assert_eq!(report.hmac, HmacCheck::NotReached);
+    }
+
+    #[test]
+    fn hmac_is_checked_with_a_password() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = create_vault(dir.path());
+
+        let report = verify_file(&path, Some(b"verify-password"), None);
+        assert_eq!(report.hmac, HmacCheck::Verified);
+
+        let report = verify_file(&path, Some(b"wrong-password"), None);
+        assert!(matches!(report.hmac, HmacCheck::Failed(_)));
+        assert!(!report.passed());
+
+        // Flip one byte of the HMAC tag: still parses, no longer verifies.
+        let mut bytes = std::fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0x01;
+        std::fs::write(&path, &bytes).unwrap();
+        let report = verify_file(&path, Some(b"verify-password"), None);
+        assert_eq!(report.structure, Ok(()));
+        assert!(!report.passed());
+    }
+}
@@ -1,21 +1,118 @@
 //! `envvault version` — display version and check for updates.
+//!
+//! `--json` prints the same information as one JSON object for update
+//! tooling.  The update check behaves as in text mode: it is skipped
+//! without the `version-check` feature and `latest` is `null` offline.
+//!
+//! `--refresh` ignores the 24-hour cache, and `--verbose` prints why the
+//! update check failed (unreachable registry, proxy, unexpected response)
+//! instead of staying quiet.
+
+use std::sync::OnceLock;
 
 use console::style;
+use serde::Serialize;
+
+use crate::errors::{EnvVaultError, Result};
+use crate::vault::format::CURRENT_VERSION;
+
+/// Short git commit hash of the build (`-dirty` suffix, or `unknown`).
+pub const GIT_COMMIT: &str = env!("ENVVAULT_GIT_COMMIT");
+
+/// UTC date the binary was built (`YYYY-MM-DD`).
+pub const BUILD_DATE: &str = env!("ENVVAULT_BUILD_DATE");
+
+/// Target triple the binary was built for.
+pub const TARGET: &str = env!("ENVVAULT_TARGET");
 
-use crate::errors::Result;
+/// Comma-separated cargo features enabled at build time, or `none`.
+pub const FEATURES: &str = env!("ENVVAULT_FEATURES");
+
+/// Multi-line version text used by `envvault version` and `--version`.
+///
+/// Includes the vault file format version this binary reads and writes,
+/// so bug reports say which vaults it can open.
+pub fn long_version() -> &'static str {
+    static LONG_VERSION: OnceLock<String> = OnceLock::new();
+    LONG_VERSION.get_or_init(|| {
+        format!(
+            "{}\ncommit:   {GIT_COMMIT}\nbuilt:    {BUILD_DATE}\ntarget:   {TARGET}\nformat:   v{CURRENT_VERSION}\nfeatures: {FEATURES}",
+            env!("CARGO_PKG_VERSION"),
+        )
+    })
+}
+
+/// Machine-readable output of `version --json`.
+#[derive(Debug, Serialize)]
+pub struct VersionInfo {
+    pub version: &'static str,
+    pub format_version: u8,
+    /// Latest published version, if it could be determined.
+    pub latest: Option<String>,
+    pub update_available: bool,
+    pub features: Vec<&'static str>,
+    pub commit: &'static str,
+    pub build_date: &'static str,
+    pub target: &'static str,
+}
+
+impl VersionInfo {
+    /// Build info for this binary, given the latest published version.
+    pub fn new(latest: Option<String>) -> Self {
+        let version = env!("CARGO_PKG_VERSION");
+        Self {
+            version,
+            format_version: CURRENT_VERSION,
+            update_available: latest.as_deref().is_some_and(|l| l != version),
+            latest,
+            features: FEATURES.split(',').filter(|f| *f != "none").collect(),
+            commit: GIT_COMMIT,
+            build_date: BUILD_DATE,
+            target: TARGET,
+        }
+    }
+}
 
 /// Execute the `version` command.
-pub fn execute() -> Result<()> {
+pub fn execute(json: bool, refresh: bool, verbose: bool) -> Result<()> {
+    let lookup = || match crate::version_check::lookup_latest(refresh) {
+        Ok(latest) => Some(latest),
+        Err(e) => {
+            if verbose && crate::version_check::ENABLED {
+                crate::cli::output::warning(&format!("Update check failed: {e}"));
+            }
+            None
+        }
+    };
+
+    if json {
+        let info = VersionInfo::new(lookup());
+        let rendered = serde_json::to_string_pretty(&info)
+            .map_err(|e| EnvVaultError::SerializationError(format!("version: {e}")))?;
+        println!("{rendered}");
+        return Ok(());
+    }
+
+    // Only the semver part is compared against crates.io.
     let current = env!("CARGO_PKG_VERSION");
-    println!("envvault {current}");
+    println!("envvault {}", long_version());
+
+    if !crate::version_check::ENABLED {
+        println!(
+            "\nUpdate check skipped: {}",
+            EnvVaultError::FeatureDisabled(crate::features::VERSION_CHECK)
+        );
+        return Ok(());
+    }
 
     // Check for updates (behind feature flag, never fails).
-    match crate::version_check::check_latest_version(current) {
+    match lookup().filter(|latest| latest != current) {
         Some(latest) => {
             println!(
-                "\n{} A newer version is available: {} → {}",
+                "\n{} A newer version is available: {} {} {}",
                 style("Update available!").yellow().bold(),
                 style(current).red(),
+                crate::cli::output::Symbol::Arrow.glyph(),
                 style(&latest).green().bold()
             );
             println!("  Run {} to update", style("envvault update").cyan());
@@ -27,3 +124,73 @@ pub fn execute() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_version_starts_with_semver() {
+        let first = long_version().lines().next().unwrap();
+        assert_eq!(first, env!("CARGO_PKG_VERSION"));
+
+        let parts: Vec<u64> = first
+            .split('-')
+            .next()
+            .unwrap()
+            .split('.')
+            .map(|p| p.parse().unwrap())
+            .collect();
+        assert_eq!(parts.len(), 3);
+    }
+
+    #[test]
+    fn long_version_has_build_metadata_fields() {
+        let fields: Vec<(&str, &str)> = long_version()
+            .lines()
+            .skip(1)
+            .map(|line| {
+                let (key, value) = line.split_once(':').unwrap();
+                (key, value.trim())
+            })
+            .collect();
+
+        let keys: Vec<&str> = fields.iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, ["commit", "built", "target", "format", "features"]);
+        assert!(fields.contains(&("format", &*format!("v{CURRENT_VERSION}"))));
+        assert!(fields.iter().all(|(_, v)| !v.is_empty()));
+    }
+
+    #[test]
+    fn version_info_reports_updates_and_features() {
+        let current = VersionInfo::new(Some(env!("CARGO_PKG_VERSION").into()));
+        assert!(!current.update_available);
+        assert_eq!(current.format_version, CURRENT_VERSION);
+        assert!(!current.features.contains(&"none"));
+
+        let newer = VersionInfo::new(Some("999.0.0".into()));
+        assert!(newer.update_available);
+
+        let offline = serde_json::to_value(VersionInfo::new(None)).unwrap();
+        assert_eq!(offline["latest"], serde_json::Value::Null);
+        assert_eq!(offline["update_available"], false);
+        assert!(offline["features"].is_array());
+    }
+
+    #[test]
+    fn build_metadata_is_well_formed() {
+        assert!(
+            GIT_COMMIT == "unknown"
+                || GIT_COMMIT
+                    .trim_end_matches("-dirty")
+                    .chars()
+                    .all(|c| c.is_ascii_hexdigit()),
+            "unexpected commit: {GIT_COMMIT}"
+        );
+        assert!(chrono::NaiveDate::parse_from_str(BUILD_DATE, "%Y-%m-%d").is_ok());
+        assert!(!TARGET.is_empty());
+
+        #[cfg(feature = "audit-log")]
+        assert!(FEATURES.split(',').any(|f| f == "audit-log"));
+    }
+}
@@ -1,18 +1,26 @@
 //! Shared `.env` file parsing logic.
 //!
 //! Used by both `init` (for auto-import) and `import` commands.
+//!
+//! Windows line endings are accepted: CRLF is normalized to LF before
+//! splitting, and a stray trailing `\r` never ends up in a value.
 
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
+use zeroize::Zeroizing;
+
+use crate::cli::output;
 use crate::errors::{EnvVaultError, Result};
 
 /// Parse a single `.env` line into a (key, value) pair.
 ///
 /// Returns `None` for blank lines, comments, and lines without `=`.
-/// Handles: `export` prefix, double/single quotes, values with `=`.
+/// Handles: `export` prefix, double/single quotes, values with `=`, and
+/// a trailing `\r` from CRLF files.
 pub fn parse_env_line(line: &str) -> Option<(&str, &str)> {
+    let line = line.strip_suffix('\r').unwrap_or(line);
     let trimmed = line.trim();
 
     // Skip empty lines and comments.
@@ -42,20 +50,64 @@ pub fn parse_env_line(line: &str) -> Option<(&str, &str)> {
     Some((key, value))
 }
 
-/// Parse a `.env` file into a key-value map.
-pub fn parse_env_file(path: &Path) -> Result<HashMap<String, String>> {
-    let content = fs::read_to_string(path)
-        .map_err(|e| EnvVaultError::CommandFailed(format!("failed to read file: {e}")))?;
+/// `.env` content parsed by [`parse_env_content`].
+#[derive(Debug, Default)]
+pub struct ParsedEnv {
+    pub secrets: HashMap<String, String>,
+    /// 1-based line on which each key in `secrets` was (last) set.
+    pub lines: HashMap<String, usize>,
+    /// Number of CRLF line endings that were normalized.
+    pub crlf_lines: usize,
+}
 
-    let mut secrets = HashMap::new();
+/// Parse `.env` content, normalizing CRLF line endings first.
+pub fn parse_env_content(content: &str) -> ParsedEnv {
+    let crlf_lines = content.matches("\r\n").count();
+    let normalized = if crlf_lines > 0 {
+        Zeroizing::new(content.replace("\r\n", "\n"))
+    } else {
+        Zeroizing::new(content.to_string())
+    };
 
-    for line in content.lines() {
+    let mut secrets = HashMap::new();
+    let mut lines = HashMap::new();
+    for (number, line) in normalized.split('\n').enumerate() {
         if let Some((key, value)) = parse_env_line(line) {
             secrets.insert(key.to_string(), value.to_string());
+            lines.insert(key.to_string(), number + 1);
         }
     }
 
-    Ok(secrets)
+    ParsedEnv {
+        secrets,
+        lines,
+        crlf_lines,
+    }
+}
+
+/// Read and parse a `.env` file.
+pub fn read_env_file(path: &Path) -> Result<ParsedEnv> {
+    let content = Zeroizing::new(
+        fs::read_to_string(path)
+            .map_err(|e| EnvVaultError::CommandFailed(format!("failed to read file: {e}")))?,
+    );
+    Ok(parse_env_content(&content))
+}
+
+/// Parse a `.env` file into a key-value map.
+pub fn parse_env_file(path: &Path) -> Result<HashMap<String, String>> {
+    read_env_file(path).map(|parsed| parsed.secrets)
+}
+
+/// Tell the user their file has Windows line endings, which were stripped.
+pub fn warn_if_crlf(path: &Path, parsed: &ParsedEnv) {
+    if parsed.crlf_lines > 0 {
+        output::warning(&format!(
+            "{} uses Windows (CRLF) line endings — stripped '\\r' from {} line(s)",
+            path.display(),
+            parsed.crlf_lines
+        ));
+    }
 }
 
 #[cfg(test)]
@@ -122,6 +174,26 @@ mod tests {
         assert_eq!(parse_env_line("NOEQUALS"), None);
     }
 
+    #[test]
+    fn parse_strips_trailing_carriage_return() {
+        assert_eq!(parse_env_line("KEY=value\r"), Some(("KEY", "value")));
+        assert_eq!(
+            parse_env_line("KEY=\"quoted value\"\r"),
+            Some(("KEY", "quoted value"))
+        );
+    }
+
+    #[test]
+    fn parse_content_normalizes_crlf() {
+        let parsed = parse_env_content("# comment\r\nURL=https://example.com\r\nEMPTY=\r\n");
+        assert_eq!(parsed.secrets["URL"], "https://example.com");
+        assert_eq!(parsed.secrets["EMPTY"], "");
+        assert_eq!(parsed.crlf_lines, 3);
+
+        let parsed = parse_env_content("URL=https://example.com\n");
+        assert_eq!(parsed.crlf_lines, 0);
+    }
+
     #[test]
     fn parse_trims_whitespace() {
         assert_eq!(parse_env_line("  KEY  =  value  "), Some(("KEY", "value")));
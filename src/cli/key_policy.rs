@@ -0,0 +1,193 @@
+//! Key naming policy from `[key_policy]` in `.envvault.toml`.
+//!
+//! ```toml
+//! [key_policy]
+//! pattern = "^[A-Z][A-Z0-9_]*$"
+//! required_prefixes = ["DB_", "STRIPE_", "AWS_"]
+//! ```
+//!
+//! Every name written by `set`, `import`, `rename`, and `edit` must match
+//! `pattern` and start with one of `required_prefixes`; either may be left
+//! out, and with neither there is no policy.  Bulk operations report every
+//! offending name at once rather than stopping at the first.
+//!
+//! The policy lives in the CLI: `VaultStore` accepts any valid name, so
+//! vaults written before the policy existed still open.  `--no-policy`
+//! skips the check for one invocation, and `list --policy-violations`
+//! finds existing names that do not conform.
+
+use regex::Regex;
+
+use crate::cli::Cli;
+use crate::config::{KeyPolicySettings, Settings};
+use crate::errors::{EnvVaultError, Result};
+
+/// A compiled `[key_policy]`.
+#[derive(Debug)]
+pub struct KeyPolicy {
+    pattern: Option<Regex>,
+    required_prefixes: Vec<String>,
+}
+
+/// A name that breaks the policy, and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub key: String,
+    pub reason: String,
+}
+
+impl KeyPolicy {
+    /// Compile the policy in `settings`; `None` if it sets no rule.
+    pub fn from_settings(settings: &KeyPolicySettings) -> Result<Option<Self>> {
+        let pattern = settings
+            .pattern
+            .as_deref()
+            .map(|p| {
+                Regex::new(p).map_err(|e| {
+                    EnvVaultError::ConfigError(format!("key_policy.pattern: invalid regex: {e}"))
+                })
+            })
+            .transpose()?;
+        if pattern.is_none() && settings.required_prefixes.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            pattern,
+            required_prefixes: settings.required_prefixes.clone(),
+        }))
+    }
+
+    /// Why `key` breaks the policy, if it does.
+    pub fn check(&self, key: &str) -> Option<String> {
+        let mut reasons = Vec::new();
+        if let Some(ref pattern) = self.pattern {
+            if !pattern.is_match(key) {
+                reasons.push(format!("does not match {}", pattern.as_str()));
+            }
+        }
+        if !self.required_prefixes.is_empty()
+            && !self.required_prefixes.iter().any(|p| key.starts_with(p))
+        {
+            reasons.push(format!(
+                "does not start with {}",
+                self.required_prefixes.join(", ")
+            ));
+        }
+        (!reasons.is_empty()).then(|| reasons.join("; "))
+    }
+
+    /// Every key in `keys` that breaks the policy, in order.
+    pub fn violations<'a>(&self, keys: impl IntoIterator<Item = &'a str>) -> Vec<Violation> {
+        keys.into_iter()
+            .filter_map(|key| {
+                self.check(key).map(|reason| Violation {
+                    key: key.to_string(),
+                    reason,
+                })
+            })
+            .collect()
+    }
+
+    /// Fail with every violation if any of `keys` breaks the policy.
+    pub fn enforce<'a>(&self, keys: impl IntoIterator<Item = &'a str>) -> Result<()> {
+        let violations = self.violations(keys);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violation_error(&violations))
+        }
+    }
+}
+
+/// The policy configured for the current project, if any.
+pub fn configured() -> Result<Option<KeyPolicy>> {
+    let settings = Settings::load(&std::env::current_dir()?)?;
+    KeyPolicy::from_settings(&settings.key_policy)
+}
+
+/// The policy to enforce: [`configured`], or `None` with `--no-policy`.
+pub fn load(cli: &Cli) -> Result<Option<KeyPolicy>> {
+    if cli.no_policy {
+        return Ok(None);
+    }
+    configured()
+}
+
+/// Fail with every violation if any of `keys` breaks the project's policy.
+pub fn enforce<'a>(cli: &Cli, keys: impl IntoIterator<Item = &'a str>) -> Result<()> {
+    match load(cli)? {
+        Some(policy) => policy.enforce(keys),
+        None => Ok(()),
+    }
+}
+
+/// One error listing all of `violations`.
+pub fn violation_error(violations: &[Violation]) -> EnvVaultError {
+    let mut message = format!(
+        "{} key(s) break the key policy in {}:",
+        violations.len(),
+        Settings::FILE_NAME
+    );
+    for v in violations {
+        message.push_str(&format!("\n  {}: {}", v.key, v.reason));
+    }
+    message.push_str("\nRename them, or pass --no-policy to skip the check once.");
+    EnvVaultError::CommandFailed(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(pattern: Option<&str>, prefixes: &[&str]) -> Option<KeyPolicy> {
+        KeyPolicy::from_settings(&KeyPolicySettings {
+            pattern: pattern.map(str::to_string),
+            required_prefixes: prefixes.iter().map(|p| (*p).to_string()).collect(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn no_rules_means_no_policy() {
+        assert!(policy(None, &[]).is_none());
+        assert!(KeyPolicy::from_settings(&KeyPolicySettings {
+            pattern: Some("([".into()),
+            required_prefixes: Vec::new(),
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn pattern_and_prefixes_are_both_checked() {
+        let policy = policy(Some("^[A-Z][A-Z0-9_]*$"), &["DB_", "STRIPE_"]).unwrap();
+
+        assert_eq!(policy.check("DB_URL"), None);
+        assert_eq!(policy.check("STRIPE_KEY_2"), None);
+        assert_eq!(
+            policy.check("REDIS_URL").unwrap(),
+            "does not start with DB_, STRIPE_"
+        );
+        assert_eq!(
+            policy.check("db_url").unwrap(),
+            "does not match ^[A-Z][A-Z0-9_]*$; does not start with DB_, STRIPE_"
+        );
+        assert!(policy
+            .check("DB_url")
+            .unwrap()
+            .starts_with("does not match"));
+    }
+
+    #[test]
+    fn bulk_checks_report_every_violation() {
+        let policy = policy(Some("^[A-Z][A-Z0-9_]*$"), &[]).unwrap();
+        let violations = policy.violations(["OK", "bad-one", "ALSO_OK", "9LIVES"]);
+        let keys: Vec<&str> = violations.iter().map(|v| v.key.as_str()).collect();
+        assert_eq!(keys, ["bad-one", "9LIVES"]);
+
+        let message = violation_error(&violations).to_string();
+        assert!(message.contains("2 key(s)"), "{message}");
+        assert!(message.contains("bad-one: does not match"), "{message}");
+        assert!(message.contains("9LIVES: does not match"), "{message}");
+        assert!(message.contains("--no-policy"), "{message}");
+    }
+}
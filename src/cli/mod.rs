@@ -3,14 +3,31 @@
 pub mod commands;
 pub mod env_parser;
 pub mod gitignore;
+pub mod key_policy;
+pub mod onboard;
 pub mod output;
 
+use std::io::{self, IsTerminal};
+
 use clap::Parser;
 
 use zeroize::Zeroizing;
 
 use crate::errors::{EnvVaultError, Result};
 
+/// Shell snippets shown in `envvault prompt-info --help`.
+const PROMPT_INFO_EXAMPLES: &str = "\
+Examples:
+  # bash / zsh
+  PS1='$(envvault prompt-info 2>/dev/null | sed \"s/.*/[&] /\")'\"$PS1\"
+
+  # starship.toml
+  [custom.envvault]
+  command = \"envvault prompt-info\"
+  when = true
+  format = \"[$output]($style) \"
+  style = \"bold yellow\"";
+
 /// Minimum password length to prevent trivially weak passwords.
 const MIN_PASSWORD_LEN: usize = 8;
 
@@ -19,7 +36,8 @@ const MIN_PASSWORD_LEN: usize = 8;
 #[command(
     name = "envvault",
     about = "Encrypted environment variable manager",
-    version
+    version,
+    long_version = commands::version::long_version()
 )]
 pub struct Cli {
     #[command(subcommand)]
@@ -36,13 +54,111 @@ pub struct Cli {
     /// Path to a keyfile for two-factor vault access
     #[arg(long, global = true)]
     pub keyfile: Option<String>,
+
+    /// Use ASCII markers and table borders instead of Unicode
+    #[arg(long, global = true)]
+    pub ascii: bool,
+
+    /// Refuse to use a vault directory or vault file that is a symlink
+    #[arg(long, global = true)]
+    pub strict_permissions: bool,
+
+    /// Print how long each vault phase took (read, KDF, HMAC, ...) to stderr
+    #[arg(long, global = true, hide = true)]
+    pub timings: bool,
+
+    /// Read the vault password from the first line of stdin
+    #[arg(long, global = true)]
+    pub stdin_password: bool,
+
+    /// Read the vault password from a file only its owner can read
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        env = "ENVVAULT_PASSWORD_FILE"
+    )]
+    pub password_file: Option<String>,
+
+    /// Write newline-delimited JSON progress events to stderr (for GUIs)
+    #[arg(long, global = true)]
+    pub progress_json: bool,
+
+    /// How to show times in tables: local, utc, or relative
+    #[arg(
+        long,
+        global = true,
+        value_name = "MODE",
+        default_value = "local",
+        value_parser = ["local", "utc", "relative"]
+    )]
+    pub time: String,
+
+    /// Skip the [key_policy] naming check for this command
+    #[arg(long, global = true)]
+    pub no_policy: bool,
+
+    /// Whether `--env` was given on the command line rather than
+    /// defaulted (set by [`Cli::parse_args`]).
+    #[arg(skip)]
+    pub env_explicit: bool,
+
+    /// Subcommand path such as `import` or `audit export` (set by
+    /// [`Cli::parse_args`]), used in `--progress-json` events.
+    #[arg(skip)]
+    pub command_name: String,
+}
+
+impl Cli {
+    /// Parse `std::env::args`, also recording whether `--env` was passed.
+    ///
+    /// Commands that work across environments (such as `audit`) narrow
+    /// to one only when it was asked for explicitly.
+    pub fn parse_args() -> Self {
+        use clap::{parser::ValueSource, CommandFactory, FromArgMatches};
+
+        let matches = Self::command().get_matches();
+        let mut cli = Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        cli.env_explicit = matches.value_source("env") == Some(ValueSource::CommandLine);
+
+        let mut names = Vec::new();
+        let mut current = &matches;
+        while let Some((name, sub)) = current.subcommand() {
+            names.push(name);
+            current = sub;
+        }
+        cli.command_name = names.join(" ");
+        cli
+    }
 }
 
 /// All available subcommands.
 #[derive(clap::Subcommand)]
 pub enum Commands {
     /// Initialize a new vault (auto-imports .env)
-    Init,
+    Init {
+        /// Do not add the vault directory to .gitignore
+        #[arg(long)]
+        no_gitignore: bool,
+        /// Cipher for secret values: aes-256-gcm (default) or chacha20-poly1305
+        #[arg(long)]
+        cipher: Option<String>,
+        /// If a step after creating the vault fails (e.g. the .env import),
+        /// delete the new vault without asking so init can be re-run
+        #[arg(long)]
+        clean_on_failure: bool,
+        /// Benchmark Argon2 on this machine and pick params that take about
+        /// MS milliseconds to derive (default 500), instead of .envvault.toml
+        #[arg(long, value_name = "MS", num_args = 0..=1, default_missing_value = "500")]
+        tune: Option<u64>,
+    },
+
+    /// Guided project setup: config, vaults, keyfile, imports, git hook, completions
+    Onboard {
+        /// Accept every default without prompting (password from --password-file, --stdin-password, or ENVVAULT_PASSWORD)
+        #[arg(long)]
+        defaults: bool,
+    },
 
     /// Set a secret (add or update)
     Set {
@@ -53,29 +169,113 @@ pub enum Commands {
         /// Skip the shell-history warning for inline values
         #[arg(short, long)]
         force: bool,
+        /// Record how the value is encoded: base64, hex, or raw (clears the hint)
+        #[arg(long)]
+        encoding: Option<String>,
+        /// Warn on reads after this long, e.g. 30d, 24h, 90m (or "never" to clear)
+        #[arg(long, value_name = "DURATION")]
+        expires: Option<String>,
+        /// Note what the secret is for (stored unencrypted; "" clears it)
+        #[arg(long, value_name = "TEXT")]
+        description: Option<String>,
+        /// Label the secret (repeatable; replaces existing tags, "" clears them)
+        #[arg(long = "tag", value_name = "NAME")]
+        tags: Vec<String>,
+        /// Position of the secret for `export --sort declared`
+        #[arg(long, value_name = "N")]
+        order: Option<u32>,
+    },
+
+    /// Set many secrets at once from a JSON object on stdin
+    SetMany {
+        /// Read `{"KEY":"value",...}` from stdin (the output of `dump --json`)
+        #[arg(long)]
+        json: bool,
     },
 
     /// Get a secret's value
     Get {
-        /// Secret name
-        key: String,
-        /// Copy to clipboard (auto-clears after 30 seconds)
-        #[arg(short = 'c', long)]
+        /// Secret name(s)
+        #[arg(required = true)]
+        keys: Vec<String>,
+        /// Copy to the clipboard instead of printing (cleared after 30s by default)
+        #[arg(short = 'c', long, visible_alias = "copy")]
         clipboard: bool,
+        /// Print the value without a trailing newline
+        #[arg(short = 'n', long, conflicts_with = "print0")]
+        no_newline: bool,
+        /// Terminate the value with a NUL byte instead of a newline
+        #[arg(long)]
+        print0: bool,
+        /// Output format: line (default) or table
+        #[arg(long, default_value = "line")]
+        format: String,
+        /// Show values in table output instead of masking them
+        #[arg(long)]
+        reveal: bool,
+        /// Write the decoded bytes of a secret stored with an encoding hint
+        #[arg(long, conflicts_with_all = ["clipboard", "no_newline", "print0"])]
+        decode: bool,
+        /// Clear the clipboard after this many seconds instead of clipboard_clear_secs (0 = never)
+        #[arg(long, value_name = "SECONDS", requires = "clipboard")]
+        clear_after: Option<u64>,
     },
 
     /// List all secrets
-    List,
+    List {
+        /// Sort by: name (default), created, or updated
+        #[arg(long, default_value = "name")]
+        sort: String,
+        /// Reverse the sort order (e.g. most recently updated first)
+        #[arg(long)]
+        reverse: bool,
+        /// Compare each value with the variable of the same name in the current environment
+        #[arg(long)]
+        diff_env: bool,
+        /// Print a JSON array of {name, created_at, updated_at} instead of a table
+        #[arg(long, conflicts_with = "diff_env")]
+        json: bool,
+        /// Include decrypted values in the JSON output (requires --json)
+        #[arg(long, requires = "json")]
+        values: bool,
+        /// Only list secrets with this tag
+        #[arg(long, value_name = "NAME")]
+        tag: Option<String>,
+        /// List the names that break the [key_policy] in .envvault.toml (fails if any do)
+        #[arg(long, conflicts_with_all = ["diff_env", "json"])]
+        policy_violations: bool,
+    },
+
+    /// Print every decrypted secret at once (use --json for jq pipelines)
+    Dump {
+        /// Emit a single compact JSON object instead of KEY=value lines
+        #[arg(long)]
+        json: bool,
+        /// Nest each value with its created/updated timestamps (requires --json)
+        #[arg(long, requires = "json")]
+        with_metadata: bool,
+    },
 
     /// Delete a secret
     Delete {
-        /// Secret name
+        /// Secret name, or a glob such as 'LEGACY_*' to delete every match
         key: String,
         /// Skip confirmation prompt
         #[arg(short, long)]
         force: bool,
     },
 
+    /// Rename a secret, keeping its value and creation time
+    Rename {
+        /// Current secret name
+        old: String,
+        /// New secret name
+        new: String,
+        /// Replace the secret already named <NEW>, if there is one
+        #[arg(short, long)]
+        force: bool,
+    },
+
     /// Run a command with secrets injected
     Run {
         /// Command and arguments (after --)
@@ -86,12 +286,18 @@ pub enum Commands {
         #[arg(long)]
         clean_env: bool,
 
-        /// Only inject these secrets (comma-separated)
-        #[arg(long, value_delimiter = ',')]
+        /// Only inject secrets matching these names or globs (comma-separated, repeatable)
+        #[arg(long, value_delimiter = ',', value_name = "GLOB")]
         only: Option<Vec<String>>,
 
-        /// Exclude these secrets (comma-separated)
-        #[arg(long, value_delimiter = ',')]
+        /// Inject all secrets except those matching these names or globs
+        #[arg(
+            long = "except",
+            visible_alias = "exclude",
+            conflicts_with = "only",
+            value_delimiter = ',',
+            value_name = "GLOB"
+        )]
         exclude: Option<Vec<String>>,
 
         /// Replace secret values in child process output with [REDACTED]
@@ -101,6 +307,52 @@ pub enum Commands {
         /// Only allow these commands to run (comma-separated basenames)
         #[arg(long, value_delimiter = ',')]
         allowed_commands: Option<Vec<String>>,
+
+        /// Pass ENVVAULT_PASSWORD and other auth variables through to the child
+        #[arg(long)]
+        keep_envvault_vars: bool,
+
+        /// Give the child the terminal as stdin, even if envvault's stdin is piped
+        #[arg(short, long)]
+        interactive: bool,
+
+        /// Write each secret to <DIR>/<KEY> and set <KEY>_FILE instead of <KEY>
+        #[arg(long, value_name = "DIR")]
+        secrets_dir: Option<String>,
+
+        /// Also inject another environment's secrets with PREFIX added to each name (repeatable)
+        #[arg(long, value_name = "ENV:PREFIX")]
+        with_env: Vec<String>,
+
+        /// Only check that these secrets exist (comma-separated); do not run the command
+        #[arg(long, value_delimiter = ',', value_name = "KEY")]
+        check_only: Option<Vec<String>>,
+    },
+
+    /// Replace one secret's value, optionally running a hook first
+    RotateSecret {
+        /// Secret to rotate
+        key: String,
+
+        /// Generate a random alphanumeric value
+        #[arg(
+            long,
+            conflicts_with = "value_from",
+            required_unless_present = "value_from"
+        )]
+        generate: bool,
+
+        /// Length of the generated value
+        #[arg(long, default_value_t = crate::cli::commands::rotate_secret::DEFAULT_GENERATED_LEN, requires = "generate")]
+        length: usize,
+
+        /// Use the stdout of this shell command as the new value
+        #[arg(long, value_name = "CMD")]
+        value_from: Option<String>,
+
+        /// Shell command to run before the vault is updated; a non-zero exit aborts the rotation
+        #[arg(long, value_name = "CMD")]
+        hook: Option<String>,
     },
 
     /// Change the vault's master password
@@ -108,17 +360,86 @@ pub enum Commands {
         /// Path to a new keyfile (or "none" to remove keyfile requirement)
         #[arg(long)]
         new_keyfile: Option<String>,
+        /// Skip the [safety] confirm_word_for prompt
+        #[arg(short, long)]
+        force: bool,
     },
 
     /// Export secrets to a file or stdout
     Export {
-        /// Output format: env (default) or json
+        /// Output format: env (default), dotenv-docker, json, yaml, k8s (a v1/Secret
+        /// manifest), or recovery-doc.
+        ///
+        /// env quotes values with spaces or special characters, which
+        /// `docker run --env-file` would keep as literal quote characters;
+        /// dotenv-docker writes every value unquoted instead and refuses
+        /// values containing newlines, which docker cannot represent.
         #[arg(short, long, default_value = "env")]
         format: String,
 
         /// Output file path (prints to stdout if omitted)
         #[arg(short, long)]
         output: Option<String>,
+
+        /// Copy the output to the clipboard (cleared after 30s by default)
+        #[arg(long, conflicts_with = "output")]
+        clip: bool,
+
+        /// Merge into the existing --output file instead of overwriting it
+        #[arg(long, requires = "output")]
+        append: bool,
+
+        /// With --append, keep the file's value for keys it already has
+        #[arg(long, requires = "append")]
+        no_clobber: bool,
+
+        /// Only export keys added or changed since this backup vault file
+        #[arg(long, value_name = "BACKUP")]
+        changed_since: Option<String>,
+
+        /// Leave secret names out of the recovery-doc format
+        #[arg(long)]
+        redact_names: bool,
+
+        /// Env format: add a header comment and a `# ── PREFIX ──` section per key prefix
+        #[arg(long, conflicts_with = "append")]
+        group_by_prefix: bool,
+
+        /// With --group-by-prefix, the prefix ends at the first occurrence of this
+        #[arg(long, default_value = "_", requires = "group_by_prefix")]
+        separator: String,
+
+        /// Export every environment in the vault directory (needs --output-dir)
+        #[arg(
+            long,
+            requires = "output_dir",
+            conflicts_with_all = ["output", "clip", "append", "changed_since", "group_by_prefix"]
+        )]
+        all_envs: bool,
+
+        /// Directory for --all-envs files, named <env>.<format>
+        #[arg(long, value_name = "DIR", requires = "all_envs")]
+        output_dir: Option<String>,
+
+        /// With --all-envs, replace files that already exist
+        #[arg(long, requires = "all_envs")]
+        force: bool,
+
+        /// Key order: name (default) or declared (`set --order`, then the order added)
+        #[arg(long, default_value = "name")]
+        sort: String,
+
+        /// Only export keys that start with this (e.g. BACKEND_)
+        #[arg(long, value_name = "STR")]
+        prefix: Option<String>,
+
+        /// k8s format: metadata.name of the Secret (default envvault-<env>)
+        #[arg(long, conflicts_with = "all_envs")]
+        name: Option<String>,
+
+        /// k8s format: metadata.namespace of the Secret
+        #[arg(long, conflicts_with = "all_envs")]
+        namespace: Option<String>,
     },
 
     /// Import secrets from a file
@@ -126,7 +447,8 @@ pub enum Commands {
         /// Path to the file to import
         file: String,
 
-        /// Import format: env (default) or json (auto-detected from extension)
+        /// Import format: env (default), json or yaml (auto-detected from
+        /// extension), 1password, or bitwarden
         #[arg(short, long)]
         format: Option<String>,
 
@@ -134,9 +456,27 @@ pub enum Commands {
         #[arg(long)]
         dry_run: bool,
 
-        /// Skip secrets that already exist in the vault
-        #[arg(long)]
+        /// Keys already in the vault: overwrite (default), skip, or fail
+        /// (import nothing if any exists)
+        #[arg(long, value_name = "STRATEGY", default_value = "overwrite")]
+        merge_strategy: String,
+
+        /// Skip secrets that already exist in the vault (same as --merge-strategy skip)
+        #[arg(long, conflicts_with = "merge_strategy")]
         skip_existing: bool,
+
+        /// Flatten nested JSON into PARENT_CHILD keys instead of storing JSON blobs
+        #[arg(long)]
+        flatten: bool,
+
+        /// Separator for --flatten key segments
+        #[arg(long, default_value = "_", requires = "flatten")]
+        separator: String,
+
+        /// Only import items from 1Password vaults / Bitwarden folders whose
+        /// name contains this (case-insensitive)
+        #[arg(long, requires = "format")]
+        filter: Option<String>,
     },
 
     /// Manage authentication methods (keyring, keyfile)
@@ -151,30 +491,101 @@ pub enum Commands {
         action: EnvAction,
     },
 
+    /// Repair vault damage such as future-dated timestamps
+    Repair {
+        /// Clamp created/updated times that lie in the future to now
+        #[arg(long)]
+        fix_timestamps: bool,
+    },
+
+    /// Check vault files for corruption; verifies HMACs when a password is available
+    Verify,
+
+    /// Show each vault's stored KDF params and header details (no password needed)
+    Info {
+        /// Print every parsed header field of one vault (the current --env by default)
+        #[arg(long)]
+        raw_header: bool,
+        /// Vault file to inspect with --raw-header instead of the current environment
+        #[arg(long, value_name = "PATH", requires = "raw_header")]
+        vault_file: Option<String>,
+    },
+
+    /// Dump a vault's stored structure (header, ciphertexts, HMAC) without decrypting
+    RawDump {
+        /// Print one JSON object instead of text
+        #[arg(long)]
+        json: bool,
+        /// Vault file to dump instead of the current environment's
+        #[arg(long, value_name = "PATH")]
+        vault_file: Option<String>,
+    },
+
+    /// Inspect and validate project configuration (.envvault.toml)
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
     /// Compare secrets between two environments
     Diff {
         /// Target environment to compare against
-        target_env: String,
+        #[arg(
+            required_unless_present = "against_file",
+            conflicts_with = "against_file"
+        )]
+        target_env: Option<String>,
+        /// Compare against a plaintext .env file instead of another environment
+        #[arg(long, value_name = "PATH")]
+        against_file: Option<String>,
         /// Show secret values in diff output
         #[arg(long)]
         show_values: bool,
     },
 
     /// Open secrets in an editor (decrypts to temp file, re-encrypts on save)
-    Edit,
+    Edit {
+        /// Key order: name (default) or declared (`set --order`, then the order added)
+        #[arg(long, default_value = "name")]
+        sort: String,
+    },
 
     /// Show version and check for updates
-    Version,
+    Version {
+        /// Print version, format, update, and feature info as JSON
+        #[arg(long)]
+        json: bool,
+        /// Ignore the cached result and ask the registry again
+        #[arg(long)]
+        refresh: bool,
+        /// Say why the update check failed, if it did
+        #[arg(short, long)]
+        verbose: bool,
+    },
 
     /// Update envvault to the latest version
     Update,
 
+    /// Run known-answer tests against the AES-GCM, HKDF, and Argon2id code
+    Selftest,
+
+    /// Print the active environment for a shell prompt (no password, silent on errors)
+    #[command(after_help = PROMPT_INFO_EXAMPLES)]
+    PromptInfo,
+
     /// Generate shell completion scripts
     Completions {
         /// Shell to generate completions for (bash, zsh, fish, powershell)
         shell: String,
     },
 
+    /// Write all shell completions and man pages into a directory (for packagers)
+    GenerateAssets {
+        /// Output directory (creates completions/ and man/ inside it)
+        #[arg(short, long)]
+        output: String,
+    },
+
     /// Scan files for leaked secrets (API keys, tokens, passwords)
     Scan {
         /// Exit with code 1 if secrets are found (for CI/CD)
@@ -190,13 +601,23 @@ pub enum Commands {
         gitleaks_config: Option<String>,
     },
 
-    /// Search secrets by name pattern (supports * and ? wildcards)
+    /// Search secrets by name (substring, * and ? wildcards, or --regex)
     Search {
-        /// Glob pattern to match (e.g. DB_*, *_KEY, API_?)
+        /// Part of a name (e.g. db), or a glob (e.g. DB_*, *_KEY, API_?)
         pattern: String,
+
+        /// Decrypt and show the matching values instead of metadata
+        #[arg(long)]
+        values: bool,
+
+        /// Treat the pattern as a regular expression instead of a glob
+        #[arg(long)]
+        regex: bool,
     },
 
     /// View, export, or purge the audit log
+    ///
+    /// Entries from every environment are shown unless `--env` is given.
     Audit {
         /// Subcommand: export, purge (omit to view entries)
         #[command(subcommand)]
@@ -208,6 +629,60 @@ pub enum Commands {
         #[arg(long)]
         since: Option<String>,
     },
+
+    /// Show the audit trail for one secret (set, delete, rename, ...)
+    History {
+        /// Secret name
+        key: String,
+        /// Number of entries to show (default: 50)
+        #[arg(long, default_value = "50")]
+        last: usize,
+    },
+
+    /// Generate test fixtures (requires the dev-tools feature)
+    #[cfg(feature = "dev-tools")]
+    #[command(hide = true)]
+    Dev {
+        #[command(subcommand)]
+        action: DevAction,
+    },
+}
+
+/// Dev subcommands for generating test fixtures.
+#[cfg(feature = "dev-tools")]
+#[derive(clap::Subcommand)]
+pub enum DevAction {
+    /// Create a vault with seeded pseudo-random secrets (uses --env for its name)
+    GenVault {
+        /// Number of secrets to generate
+        #[arg(long, default_value = "10")]
+        secrets: usize,
+        /// Length of each generated value
+        #[arg(long, default_value = "32")]
+        value_size: usize,
+        /// Vault password
+        #[arg(long, default_value = "testpass")]
+        password: String,
+        /// Seed for the generated values
+        #[arg(long, default_value = "0")]
+        seed: u64,
+        /// Output vault path
+        #[arg(long)]
+        out: String,
+    },
+
+    /// Create a small vault and corrupt it: hmac, truncate, header, or magic
+    GenCorrupt {
+        /// Corruption to apply
+        #[arg(long)]
+        mode: String,
+        /// Vault password
+        #[arg(long, default_value = "testpass")]
+        password: String,
+        /// Output vault path
+        #[arg(long)]
+        out: String,
+    },
 }
 
 /// Audit subcommands for export and purge.
@@ -244,6 +719,23 @@ pub enum AuthAction {
     KeyfileGenerate {
         /// Path for the keyfile (default: <vault_dir>/keyfile)
         path: Option<String>,
+        /// Do not add the keyfile to .gitignore
+        #[arg(long)]
+        no_gitignore: bool,
+    },
+}
+
+/// Config subcommands for inspecting `.envvault.toml`.
+#[derive(clap::Subcommand)]
+pub enum ConfigAction {
+    /// Validate the config and print the effective settings (no vault needed)
+    Check,
+
+    /// Print the effective settings (defaults + global + project + flags)
+    Show {
+        /// Print as JSON instead of TOML
+        #[arg(long)]
+        json: bool,
     },
 }
 
@@ -260,6 +752,10 @@ pub enum EnvAction {
         /// Prompt for a different password for the new vault
         #[arg(long)]
         new_password: bool,
+        /// Create the vault from this .env, JSON, or YAML file instead of
+        /// copying the current environment (always asks for a new password)
+        #[arg(long, value_name = "PATH", conflicts_with = "new_password")]
+        from_file: Option<String>,
     },
 
     /// Delete a vault environment
@@ -276,10 +772,186 @@ pub enum EnvAction {
 // Shared helpers used by multiple commands
 // ---------------------------------------------------------------------------
 
+/// Announce the KDF params about to be baked into a vault.
+///
+/// Warns loudly when they are weaker than the built-in defaults, since
+/// the params are fixed per vault and later config changes do not apply.
+pub fn report_kdf_params(params: &crate::crypto::kdf::Argon2Params) {
+    output::info(&format!(
+        "Key derivation: {params} (stored in the vault header)"
+    ));
+
+    let shortfalls = params.below_recommended();
+    if !shortfalls.is_empty() {
+        output::warning(&format!(
+            "KDF params are weaker than the recommended defaults: {}",
+            shortfalls.join(", ")
+        ));
+        output::tip(
+            "Vaults keep the params they were created with — after raising argon2_* in .envvault.toml, run `envvault rotate-key` to apply them.",
+        );
+    }
+}
+
+/// Environment variables that carry vault credentials.
+///
+/// `run` removes these from the child environment so the password is not
+/// handed to the child and everything it spawns.  Any new auth-related
+/// variable belongs in this list.
+pub const AUTH_ENV_VARS: &[&str] = &[
+    "ENVVAULT_PASSWORD",
+    "ENVVAULT_PASSWORD_FILE",
+    "ENVVAULT_KEYFILE",
+];
+
+/// Password read by `--stdin-password`, shared by every prompt in this
+/// process.
+static STDIN_PASSWORD: std::sync::Mutex<Option<Zeroizing<String>>> = std::sync::Mutex::new(None);
+
+/// Read the vault password from the first line of stdin (`--stdin-password`).
+///
+/// Called once from `main` before any command runs.  Only the first line
+/// is consumed: `set` reads its value from what follows, and a `run`
+/// child inherits the rest of stdin untouched.
+pub fn init_stdin_password() -> Result<()> {
+    if io::stdin().is_terminal() {
+        return Err(EnvVaultError::CommandFailed(
+            "--stdin-password needs the password piped in, but stdin is a terminal".into(),
+        ));
+    }
+
+    let line = read_stdin_line()?;
+    let line = line.strip_suffix(b"\r").unwrap_or(&line);
+    let password = std::str::from_utf8(line).map_err(|_| {
+        EnvVaultError::CommandFailed("--stdin-password: password is not valid UTF-8".into())
+    })?;
+    if password.is_empty() {
+        return Err(EnvVaultError::CommandFailed(
+            "--stdin-password: no password on the first line of stdin".into(),
+        ));
+    }
+
+    if let Ok(mut slot) = STDIN_PASSWORD.lock() {
+        *slot = Some(Zeroizing::new(password.to_string()));
+    }
+    Ok(())
+}
+
+/// Whether `--stdin-password` supplied the password for this process.
+pub fn stdin_password_in_use() -> bool {
+    STDIN_PASSWORD.lock().is_ok_and(|slot| slot.is_some())
+}
+
+fn stdin_password() -> Option<Zeroizing<String>> {
+    STDIN_PASSWORD.lock().ok().and_then(|slot| slot.clone())
+}
+
+/// Password read by `--password-file`, shared by every prompt in this
+/// process.
+static FILE_PASSWORD: std::sync::Mutex<Option<Zeroizing<String>>> = std::sync::Mutex::new(None);
+
+/// Read the vault password from `path` (`--password-file` or
+/// `ENVVAULT_PASSWORD_FILE`).
+///
+/// Called once from `main` before any command runs.
+pub fn init_password_file(path: &std::path::Path) -> Result<()> {
+    let password = read_password_file(path)?;
+    if let Ok(mut slot) = FILE_PASSWORD.lock() {
+        *slot = Some(password);
+    }
+    Ok(())
+}
+
+/// The password stored in `path`, without trailing line breaks.
+///
+/// On Unix the file must not be accessible to group or others, like an
+/// SSH private key; an empty file is an error rather than an empty
+/// password.
+pub fn read_password_file(path: &std::path::Path) -> Result<Zeroizing<String>> {
+    let shown = path.display();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(path)
+            .map_err(|e| EnvVaultError::CommandFailed(format!("password file {shown}: {e}")))?
+            .permissions()
+            .mode();
+        if mode & 0o077 != 0 {
+            return Err(EnvVaultError::CommandFailed(format!(
+                "password file {shown} has mode {:04o}, so group or others can access it — run `chmod 600 {shown}`",
+                mode & 0o7777
+            )));
+        }
+    }
+
+    let contents = Zeroizing::new(
+        std::fs::read(path)
+            .map_err(|e| EnvVaultError::CommandFailed(format!("password file {shown}: {e}")))?,
+    );
+    let password = std::str::from_utf8(&contents).map_err(|_| {
+        EnvVaultError::CommandFailed(format!("password file {shown} is not valid UTF-8"))
+    })?;
+    let password = password.trim_end_matches(['\r', '\n']);
+    if password.is_empty() {
+        return Err(EnvVaultError::CommandFailed(format!(
+            "password file {shown} is empty"
+        )));
+    }
+    Ok(Zeroizing::new(password.to_string()))
+}
+
+fn file_password() -> Option<Zeroizing<String>> {
+    FILE_PASSWORD.lock().ok().and_then(|slot| slot.clone())
+}
+
+/// The password given by `--stdin-password` or `--password-file`, in that
+/// order.
+fn scripted_password() -> Option<Zeroizing<String>> {
+    stdin_password().or_else(file_password)
+}
+
+/// Read one line from stdin, without the newline.
+///
+/// On Unix this reads the descriptor a byte at a time, bypassing the
+/// buffered `Stdin` handle, so nothing past the newline is taken from a
+/// pipe that a child process inherits later.
+#[cfg(unix)]
+fn read_stdin_line() -> Result<Zeroizing<Vec<u8>>> {
+    use std::io::Read;
+    use std::os::unix::io::FromRawFd;
+
+    // SAFETY: fd 0 stays open for the life of the process, and the
+    // ManuallyDrop wrapper keeps this handle from closing it.
+    let mut stdin = std::mem::ManuallyDrop::new(unsafe { std::fs::File::from_raw_fd(0) });
+    let mut line = Zeroizing::new(Vec::new());
+    let mut byte = [0u8; 1];
+    loop {
+        match stdin.read(&mut byte) {
+            Ok(0) => break,
+            Ok(_) if byte[0] == b'\n' => break,
+            Ok(_) => line.push(byte[0]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(line)
+}
+
+#[cfg(not(unix))]
+fn read_stdin_line() -> Result<Zeroizing<Vec<u8>>> {
+    let mut line = Zeroizing::new(String::new());
+    io::stdin().read_line(&mut line)?;
+    let trimmed = line.strip_suffix('\n').unwrap_or(&line);
+    Ok(Zeroizing::new(trimmed.as_bytes().to_vec()))
+}
+
 /// Get the vault password, trying in order:
-/// 1. `ENVVAULT_PASSWORD` env var (CI/CD)
-/// 2. OS keyring (if compiled with `keyring-store` feature)
-/// 3. Interactive prompt
+/// 1. First line of stdin (`--stdin-password`)
+/// 2. `--password-file` / `ENVVAULT_PASSWORD_FILE`
+/// 3. `ENVVAULT_PASSWORD` env var (CI/CD)
+/// 4. OS keyring (if compiled with `keyring-store` feature)
+/// 5. Interactive prompt
 ///
 /// Returns `Zeroizing<String>` so the password is wiped from memory on drop.
 pub fn prompt_password() -> Result<Zeroizing<String>> {
@@ -288,22 +960,46 @@ pub fn prompt_password() -> Result<Zeroizing<String>> {
 
 /// Get the vault password with an optional vault path for keyring lookup.
 ///
+/// Sources are tried in the order listed on [`prompt_password`]: explicit
+/// flags first, then the environment, the keyring, and finally a prompt.
+///
 /// Returns `Zeroizing<String>` so the password is wiped from memory on drop.
 pub fn prompt_password_for_vault(vault_id: Option<&str>) -> Result<Zeroizing<String>> {
-    // 1. Check the environment variable first (CI/CD friendly).
+    // 1-4. Flags, the environment, and the keyring.
+    if let Some(pw) = available_password(vault_id) {
+        return Ok(pw);
+    }
+
+    // 5. Fall back to interactive prompt.
+    let pw = dialoguer::Password::new()
+        .with_prompt("Enter vault password")
+        .interact()
+        .map_err(|e| EnvVaultError::CommandFailed(format!("password prompt: {e}")))?;
+    Ok(Zeroizing::new(pw))
+}
+
+/// The vault password if one can be had without prompting: sources 1-4
+/// of [`prompt_password`].  `verify` uses this to check HMACs only when
+/// a password is at hand.
+pub fn available_password(vault_id: Option<&str>) -> Option<Zeroizing<String>> {
+    // 1-2. An explicit --stdin-password or --password-file wins over
+    //      everything else.
+    if let Some(pw) = scripted_password() {
+        return Some(pw);
+    }
+
+    // 3. Check the environment variable (CI/CD friendly).
     if let Ok(pw) = std::env::var("ENVVAULT_PASSWORD") {
         if !pw.is_empty() {
-            return Ok(Zeroizing::new(pw));
+            return Some(Zeroizing::new(pw));
         }
     }
 
-    // 2. Try the OS keyring (if feature enabled and vault_id provided).
+    // 4. Try the OS keyring (if feature enabled and vault_id provided).
     #[cfg(feature = "keyring-store")]
     if let Some(id) = vault_id {
-        match crate::keyring::get_password(id) {
-            Ok(Some(pw)) => return Ok(Zeroizing::new(pw)),
-            Ok(None) => {} // No stored password, continue to prompt.
-            Err(_) => {}   // Keyring unavailable, continue to prompt.
+        if let Ok(Some(pw)) = crate::keyring::get_password(id) {
+            return Some(Zeroizing::new(pw));
         }
     }
 
@@ -311,31 +1007,31 @@ pub fn prompt_password_for_vault(vault_id: Option<&str>) -> Result<Zeroizing<Str
     #[cfg(not(feature = "keyring-store"))]
     let _ = vault_id;
 
-    // 3. Fall back to interactive prompt.
-    let pw = dialoguer::Password::new()
-        .with_prompt("Enter vault password")
-        .interact()
-        .map_err(|e| EnvVaultError::CommandFailed(format!("password prompt: {e}")))?;
-    Ok(Zeroizing::new(pw))
+    None
 }
 
 /// Prompt for a new password with confirmation (used during `init`).
 ///
-/// Also respects `ENVVAULT_PASSWORD` for scripted/CI usage.
+/// Also respects `--stdin-password`, `--password-file`, and
+/// `ENVVAULT_PASSWORD` for scripted/CI usage (no confirmation in any case).
 /// Enforces a minimum password length.
 ///
 /// Returns `Zeroizing<String>` so the password is wiped from memory on drop.
 pub fn prompt_new_password() -> Result<Zeroizing<String>> {
-    // Check the environment variable first (CI/CD friendly).
-    if let Ok(pw) = std::env::var("ENVVAULT_PASSWORD") {
-        if !pw.is_empty() {
-            if pw.len() < MIN_PASSWORD_LEN {
-                return Err(EnvVaultError::CommandFailed(format!(
-                    "password must be at least {MIN_PASSWORD_LEN} characters"
-                )));
-            }
-            return Ok(Zeroizing::new(pw));
+    // Check the flags and the environment variable first (CI/CD friendly).
+    let scripted = scripted_password().or_else(|| {
+        std::env::var("ENVVAULT_PASSWORD")
+            .ok()
+            .filter(|pw| !pw.is_empty())
+            .map(Zeroizing::new)
+    });
+    if let Some(pw) = scripted {
+        if pw.len() < MIN_PASSWORD_LEN {
+            return Err(EnvVaultError::CommandFailed(format!(
+                "password must be at least {MIN_PASSWORD_LEN} characters"
+            )));
         }
+        return Ok(pw);
     }
 
     loop {
@@ -361,11 +1057,269 @@ pub fn prompt_new_password() -> Result<Zeroizing<String>> {
 
 /// Build the full path to a vault file from the CLI arguments.
 ///
-/// Example: `<cwd>/.envvault/dev.vault`
+/// Example: `<project>/.envvault/dev.vault`
+///
+/// Warns (or fails under `--strict-permissions`) when the vault directory
+/// or file is a symlink; see [`check_vault_symlinks`].
 pub fn vault_path(cli: &Cli) -> Result<std::path::PathBuf> {
-    let cwd = std::env::current_dir()?;
     let env = &cli.env;
-    Ok(cwd.join(&cli.vault_dir).join(format!("{env}.vault")))
+    let vault_dir = resolve_vault_dir(cli)?;
+    let path = vault_dir.join(format!("{env}.vault"));
+    check_vault_symlinks(&vault_dir, &path, cli.strict_permissions)?;
+    Ok(path)
+}
+
+/// Allowed clock skew from `max_clock_skew_secs` in `.envvault.toml`.
+pub fn clock_skew_tolerance() -> chrono::Duration {
+    let secs = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| crate::config::Settings::load(&cwd).ok())
+        .map_or(crate::vault::clock::DEFAULT_MAX_CLOCK_SKEW_SECS, |s| {
+            s.max_clock_skew_secs
+        });
+    crate::vault::clock::tolerance(secs)
+}
+
+/// Warn before writing if the system clock is behind the vault's newest
+/// timestamp, so a broken clock doesn't go unnoticed.
+pub fn warn_clock_skew(store: &crate::vault::VaultStore) {
+    if let Some(behind) = store.clock_skew(chrono::Utc::now(), clock_skew_tolerance()) {
+        output::warning(&format!(
+            "The system clock is {} behind this vault's newest timestamp — check it before trusting new timestamps.",
+            crate::vault::clock::describe(behind)
+        ));
+    }
+}
+
+/// Warn if the vault's key is older than `[security] rotate_after_days`.
+///
+/// Called by the commands right after they open a vault.
+pub fn warn_rotation_due(store: &crate::vault::VaultStore) {
+    let Some(days) = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| crate::config::Settings::load(&cwd).ok())
+        .and_then(|s| s.security.rotate_after_days)
+    else {
+        return;
+    };
+    if let Some(age) = store.rotation_due(chrono::Utc::now(), days) {
+        output::warning(&format!(
+            "The {} vault's key is {age} days old (rotate_after_days = {days}) — run `envvault rotate-key`.",
+            store.environment()
+        ));
+    }
+}
+
+/// Warn about each of `names` whose `set --expires` time has passed.
+pub fn warn_expired<'a>(
+    store: &crate::vault::VaultStore,
+    names: impl IntoIterator<Item = &'a str>,
+) {
+    let now = chrono::Utc::now();
+    for name in names {
+        if let Some(at) = store.secret_expires_at(name).filter(|at| *at <= now) {
+            output::warning(&format!(
+                "'{name}' expired on {} UTC — rotate it, then `envvault set {name} --expires <DURATION>`",
+                at.format("%Y-%m-%d %H:%M")
+            ));
+        }
+    }
+}
+
+/// Ask the user to type `word` to confirm; true only on an exact match.
+pub fn confirm_by_typing(word: &str, prompt: &str) -> Result<bool> {
+    let typed: String = dialoguer::Input::new()
+        .with_prompt(prompt)
+        .allow_empty(true)
+        .interact_text()
+        .map_err(|e| EnvVaultError::CommandFailed(format!("confirm prompt: {e}")))?;
+    Ok(typed == word)
+}
+
+/// Whether `env` is listed in `[safety] confirm_word_for` in `.envvault.toml`.
+pub fn needs_confirm_word(env: &str) -> bool {
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| crate::config::Settings::load(&cwd).ok())
+        .is_some_and(|s| s.safety.needs_confirm_word(env))
+}
+
+/// Make the user type the environment name before `action` on an
+/// environment listed in `[safety] confirm_word_for`.  Returns false if
+/// they typed anything else; environments not listed always pass.
+pub fn confirm_env_word(env: &str, action: &str) -> Result<bool> {
+    if !needs_confirm_word(env) {
+        return Ok(true);
+    }
+    output::warning(&format!("About to {action} in the '{env}' environment."));
+    confirm_by_typing(env, &format!("Type '{env}' to confirm"))
+}
+
+/// Start counting reads on `store` if `[telemetry] track_access = true`.
+pub fn enable_access_tracking(store: &mut crate::vault::VaultStore) {
+    let enabled = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| crate::config::Settings::load(&cwd).ok())
+        .is_some_and(|s| s.telemetry.track_access);
+    store.set_track_access(enabled);
+}
+
+/// Write counted reads back to the vault.  Failure only warns: the read
+/// itself already succeeded.
+pub fn persist_access(store: &mut crate::vault::VaultStore) {
+    if !store.has_pending_access() {
+        return;
+    }
+    if let Err(e) = store.save() {
+        output::warning(&format!("Could not record secret access counts: {e}"));
+    }
+}
+
+/// Print the `--timings` breakdown to stderr.
+pub fn print_timings() {
+    let phases = crate::timings::summary();
+    if phases.is_empty() {
+        eprintln!("No vault phases were timed.");
+        return;
+    }
+    eprintln!("{}", timings_table(&phases));
+}
+
+/// Render phase totals as a table with a final total row.
+pub fn timings_table(phases: &[crate::timings::PhaseTotal]) -> comfy_table::Table {
+    let ms = |d: std::time::Duration| format!("{:.1} ms", d.as_secs_f64() * 1000.0);
+
+    let mut table = output::table();
+    table.set_header(vec!["Phase", "Calls", "Time"]);
+    for phase in phases {
+        table.add_row(vec![
+            phase.label.to_string(),
+            phase.calls.to_string(),
+            ms(phase.total),
+        ]);
+    }
+    let total = phases.iter().map(|p| p.total).sum();
+    table.add_row(vec!["total".to_string(), String::new(), ms(total)]);
+    table
+}
+
+/// Run `[hooks] post_write` after a command has saved the vault.
+///
+/// Gets `ENVVAULT_OP` (the audit operation name, e.g. `set`) and
+/// `ENVVAULT_ENV`, never secret values or the credentials in
+/// [`AUTH_ENV_VARS`].  A failing hook only warns: the write is not
+/// rolled back.
+pub fn run_post_write_hook(op: &str, env: &str) {
+    let hook = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| crate::config::Settings::load(&cwd).ok())
+        .and_then(|s| s.hooks.post_write)
+        .filter(|h| !h.trim().is_empty());
+    let Some(hook) = hook else {
+        return;
+    };
+
+    let result = post_write_command(&hook, op, env).and_then(|mut cmd| {
+        cmd.status()
+            .map_err(|e| EnvVaultError::CommandFailed(format!("failed to run: {e}")))
+    });
+    match result {
+        Ok(status) if status.success() => {}
+        Ok(status) => output::warning(&format!(
+            "post_write hook failed ({status}) — the vault was still saved"
+        )),
+        Err(e) => output::warning(&format!("post_write hook: {e} — the vault was still saved")),
+    }
+}
+
+/// Build the `post_write` hook command: `hook` split into words, without
+/// a shell.
+pub fn post_write_command(hook: &str, op: &str, env: &str) -> Result<std::process::Command> {
+    let words = shlex::split(hook)
+        .filter(|w| !w.is_empty())
+        .ok_or_else(|| {
+            EnvVaultError::CommandFailed(format!(
+                "cannot parse command '{hook}' (unbalanced quotes?)"
+            ))
+        })?;
+
+    let mut cmd = std::process::Command::new(&words[0]);
+    cmd.args(&words[1..])
+        .env("ENVVAULT_OP", op)
+        .env("ENVVAULT_ENV", env)
+        .stdin(std::process::Stdio::null());
+    for var in AUTH_ENV_VARS {
+        cmd.env_remove(var);
+    }
+    Ok(cmd)
+}
+
+/// Guard against a swapped-in symlink redirecting vault reads or writes.
+///
+/// If `vault_dir` or `vault_file` is itself a symlink, warn with its
+/// target — or return an error when `strict` (`--strict-permissions`).
+pub fn check_vault_symlinks(
+    vault_dir: &std::path::Path,
+    vault_file: &std::path::Path,
+    strict: bool,
+) -> Result<()> {
+    for path in [vault_dir, vault_file] {
+        let Some(target) = symlink_target(path) else {
+            continue;
+        };
+        let msg = format!("{} is a symlink to {}", path.display(), target.display());
+        if strict {
+            return Err(EnvVaultError::CommandFailed(format!(
+                "{msg} — refusing to use it with --strict-permissions"
+            )));
+        }
+        output::warning(&format!("{msg} — make sure you trust the target"));
+    }
+    Ok(())
+}
+
+/// The target of `path` if `path` itself is a symlink (not following it).
+pub fn symlink_target(path: &std::path::Path) -> Option<std::path::PathBuf> {
+    let metadata = std::fs::symlink_metadata(path).ok()?;
+    if !metadata.file_type().is_symlink() {
+        return None;
+    }
+    Some(std::fs::read_link(path).unwrap_or_else(|_| std::path::PathBuf::from("?")))
+}
+
+/// Resolve the vault directory for this invocation.
+///
+/// Every command that reads an existing vault (and the audit log) goes
+/// through here, so running from a subdirectory finds the same vault dir
+/// as running from the project root.  See [`find_vault_dir`].
+pub fn resolve_vault_dir(cli: &Cli) -> Result<std::path::PathBuf> {
+    let cwd = std::env::current_dir()?;
+    Ok(find_vault_dir(&cwd, &cli.vault_dir))
+}
+
+/// Locate `vault_dir` starting from `start`.
+///
+/// An absolute path is returned as-is.  A relative one is looked up in
+/// `start` and then each parent, stopping at the first directory that
+/// contains it or at a git repository root.  If none is found the result
+/// is `start/vault_dir`, where `init` would create it.
+pub fn find_vault_dir(start: &std::path::Path, vault_dir: &str) -> std::path::PathBuf {
+    let relative = std::path::Path::new(vault_dir);
+    if relative.is_absolute() {
+        return relative.to_path_buf();
+    }
+
+    for dir in start.ancestors() {
+        let candidate = dir.join(relative);
+        if candidate.is_dir() {
+            return candidate;
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+
+    start.join(relative)
 }
 
 /// Load the keyfile bytes, checking in order:
@@ -440,6 +1394,59 @@ pub fn validate_env_name(name: &str) -> Result<()> {
 mod tests {
     use super::*;
 
+    fn password_file(contents: &[u8], mode: u32) -> (tempfile::TempDir, std::path::PathBuf) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("password");
+        std::fs::write(&path, contents).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        }
+        #[cfg(not(unix))]
+        let _ = mode;
+        (dir, path)
+    }
+
+    #[test]
+    fn password_file_is_read_without_trailing_newlines() {
+        for contents in [
+            &b"hunter2-hunter2"[..],
+            b"hunter2-hunter2\n",
+            b"hunter2-hunter2\r\n",
+        ] {
+            let (_dir, path) = password_file(contents, 0o600);
+            assert_eq!(
+                read_password_file(&path).unwrap().as_str(),
+                "hunter2-hunter2"
+            );
+        }
+        // Inner and leading whitespace is part of the password.
+        let (_dir, path) = password_file(b" two words \n", 0o600);
+        assert_eq!(read_password_file(&path).unwrap().as_str(), " two words ");
+
+        let (_dir, path) = password_file(b"\n", 0o600);
+        let err = read_password_file(&path).unwrap_err();
+        assert!(err.to_string().contains("is empty"), "{err}");
+        assert!(read_password_file(std::path::Path::new("/nonexistent/password")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn password_file_readable_by_others_is_rejected() {
+        for mode in [0o644, 0o640, 0o604, 0o660] {
+            let (_dir, path) = password_file(b"hunter2-hunter2\n", mode);
+            let err = read_password_file(&path).unwrap_err();
+            assert!(matches!(err, EnvVaultError::CommandFailed(_)), "{err}");
+            assert!(
+                err.to_string().contains(&format!("mode {mode:04o}")),
+                "{err}"
+            );
+        }
+        let (_dir, path) = password_file(b"hunter2-hunter2\n", 0o400);
+        assert!(read_password_file(&path).is_ok());
+    }
+
     #[test]
     fn valid_env_names() {
         assert!(validate_env_name("dev").is_ok());
@@ -479,4 +1486,206 @@ mod tests {
         let long_name = "a".repeat(65);
         assert!(validate_env_name(&long_name).is_err());
     }
+
+    #[test]
+    fn find_vault_dir_walks_up_to_project() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let project = tmp.path().join("project");
+        let nested = project.join("src/deep");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir_all(project.join(".envvault")).unwrap();
+
+        assert_eq!(
+            find_vault_dir(&nested, ".envvault"),
+            project.join(".envvault")
+        );
+        assert_eq!(
+            find_vault_dir(&project, ".envvault"),
+            project.join(".envvault")
+        );
+    }
+
+    #[test]
+    fn find_vault_dir_stops_at_git_root() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".envvault")).unwrap();
+        let repo = tmp.path().join("repo");
+        let nested = repo.join("sub");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::create_dir_all(&nested).unwrap();
+
+        // The outer .envvault belongs to a different project.
+        assert_eq!(
+            find_vault_dir(&nested, ".envvault"),
+            nested.join(".envvault")
+        );
+    }
+
+    #[test]
+    fn find_vault_dir_keeps_absolute_paths() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let absolute = tmp.path().join("vaults");
+        assert_eq!(
+            find_vault_dir(std::path::Path::new("/"), absolute.to_str().unwrap()),
+            absolute
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_vault_dir_warns_or_refuses() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let elsewhere = tmp.path().join("elsewhere");
+        std::fs::create_dir_all(&elsewhere).unwrap();
+        let link = tmp.path().join(".envvault");
+        std::os::unix::fs::symlink(&elsewhere, &link).unwrap();
+        let file = link.join("dev.vault");
+
+        assert_eq!(symlink_target(&link), Some(elsewhere));
+        assert_eq!(symlink_target(&file), None);
+        assert!(check_vault_symlinks(&link, &file, false).is_ok());
+        let err = check_vault_symlinks(&link, &file, true).unwrap_err();
+        assert!(err.to_string().contains("--strict-permissions"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_vault_file_is_detected() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let planted = tmp.path().join("planted.vault");
+        std::fs::write(&planted, b"x").unwrap();
+        let file = tmp.path().join("dev.vault");
+        std::os::unix::fs::symlink(&planted, &file).unwrap();
+
+        assert!(check_vault_symlinks(tmp.path(), &file, true).is_err());
+        // Plain files and missing paths are fine.
+        assert!(check_vault_symlinks(tmp.path(), &planted, true).is_ok());
+        assert!(check_vault_symlinks(tmp.path(), &tmp.path().join("none"), true).is_ok());
+    }
+
+    #[test]
+    fn timings_table_lists_each_phase_and_a_total() {
+        use crate::timings::{PhaseTotal, HMAC_VERIFY, KDF};
+        use std::time::Duration;
+
+        let table = timings_table(&[
+            PhaseTotal {
+                label: KDF,
+                calls: 1,
+                total: Duration::from_micros(812_340),
+            },
+            PhaseTotal {
+                label: HMAC_VERIFY,
+                calls: 1,
+                total: Duration::from_micros(150),
+            },
+        ])
+        .to_string();
+        assert!(table.contains("key derivation"), "{table}");
+        assert!(table.contains("812.3 ms"), "{table}");
+        assert!(table.contains("0.1 ms"), "{table}");
+        assert!(table.contains("812.5 ms"), "{table}");
+    }
+
+    #[test]
+    fn post_write_command_passes_op_and_env_only() {
+        let cmd = post_write_command("./deploy.sh 'my app' --now", "set", "prod").unwrap();
+        assert_eq!(cmd.get_program(), "./deploy.sh");
+        let args: Vec<_> = cmd.get_args().collect();
+        assert_eq!(args, ["my app", "--now"]);
+
+        let envs: std::collections::HashMap<_, _> = cmd
+            .get_envs()
+            .map(|(k, v)| (k.to_string_lossy(), v.map(|v| v.to_string_lossy())))
+            .collect();
+        assert_eq!(envs["ENVVAULT_OP"].as_deref(), Some("set"));
+        assert_eq!(envs["ENVVAULT_ENV"].as_deref(), Some("prod"));
+        for var in AUTH_ENV_VARS {
+            assert_eq!(envs[*var], None, "{var} should be removed");
+        }
+
+        assert!(post_write_command("deploy 'unterminated", "set", "dev").is_err());
+        assert!(post_write_command("   ", "set", "dev").is_err());
+    }
+
+    #[test]
+    fn run_interactive_flag_parses_before_command() {
+        use clap::Parser;
+
+        let cli = Cli::try_parse_from(["envvault", "run", "-i", "--", "python", "-i"]).unwrap();
+        match cli.command {
+            Commands::Run {
+                interactive,
+                command,
+                ..
+            } => {
+                assert!(interactive);
+                assert_eq!(command, ["python", "-i"]);
+            }
+            _ => panic!("expected run"),
+        }
+    }
+
+    #[test]
+    fn run_only_and_except_are_repeatable() {
+        use clap::Parser;
+
+        let cli = Cli::try_parse_from([
+            "envvault",
+            "run",
+            "--only",
+            "VITE_*",
+            "--only",
+            "PUBLIC_*,APP_NAME",
+            "--",
+            "npm",
+            "run",
+            "build",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Run { only, .. } => {
+                assert_eq!(only.unwrap(), ["VITE_*", "PUBLIC_*", "APP_NAME"]);
+            }
+            _ => panic!("expected run"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "envvault",
+            "run",
+            "--except",
+            "VITE_DEBUG",
+            "--exclude",
+            "X",
+            "--",
+            "npm",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Run { exclude, .. } => {
+                assert_eq!(exclude.unwrap(), ["VITE_DEBUG", "X"]);
+            }
+            _ => panic!("expected run"),
+        }
+    }
+
+    #[test]
+    fn run_only_and_except_are_mutually_exclusive() {
+        use clap::Parser;
+
+        let Err(err) = Cli::try_parse_from([
+            "envvault", "run", "--only", "A", "--except", "B", "--", "true",
+        ]) else {
+            panic!("--only and --except together must be rejected");
+        };
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn export_clip_conflicts_with_output() {
+        use clap::Parser;
+
+        assert!(Cli::try_parse_from(["envvault", "export", "--clip"]).is_ok());
+        assert!(Cli::try_parse_from(["envvault", "export", "--clip", "-o", ".env"]).is_err());
+    }
 }
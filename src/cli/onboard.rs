@@ -0,0 +1,791 @@
+//! Guided project setup used by `envvault onboard`.
+//!
+//! Setup is a fixed list of [`STEPS`].  Each step is a plain function over
+//! an [`Onboarding`] context and a [`Prompt`], returns what it did as a
+//! [`StepOutcome`], and is safe to re-run: work that is already in place is
+//! reported as such instead of being redone.  Answers come from a `Prompt`
+//! so tests (and `--defaults`) can drive every step without a terminal.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use zeroize::Zeroizing;
+
+use crate::cli::env_parser::{read_env_file, warn_if_crlf};
+use crate::cli::output;
+use crate::config::Settings;
+use crate::errors::{EnvVaultError, Result};
+use crate::vault::VaultStore;
+
+/// Source of answers for the onboarding steps.
+pub trait Prompt {
+    /// Ask a yes/no question.
+    fn confirm(&mut self, question: &str, default: bool) -> Result<bool>;
+
+    /// Ask for a line of text.
+    fn input(&mut self, question: &str, default: &str) -> Result<String>;
+
+    /// Ask for a new vault password (with confirmation).
+    fn new_password(&mut self) -> Result<Zeroizing<String>>;
+
+    /// Ask for the password of an existing vault.
+    fn existing_password(&mut self, vault_id: &str) -> Result<Zeroizing<String>>;
+}
+
+/// Answers every question with its default, for `onboard --defaults`.
+///
+/// Passwords come from `--stdin-password`, `--password-file`, or
+/// `ENVVAULT_PASSWORD`, in that order; there is no one to ask.
+pub struct Defaults;
+
+impl Defaults {
+    fn password() -> Result<Zeroizing<String>> {
+        crate::cli::scripted_password()
+            .or_else(|| {
+                std::env::var("ENVVAULT_PASSWORD")
+                    .ok()
+                    .filter(|pw| !pw.is_empty())
+                    .map(Zeroizing::new)
+            })
+            .ok_or_else(Self::missing_password)
+    }
+
+    fn missing_password() -> EnvVaultError {
+        EnvVaultError::CommandFailed(
+            "onboard --defaults needs the vault password from --stdin-password, --password-file, or ENVVAULT_PASSWORD, and none was given"
+                .into(),
+        )
+    }
+}
+
+impl Prompt for Defaults {
+    fn confirm(&mut self, _question: &str, default: bool) -> Result<bool> {
+        Ok(default)
+    }
+
+    fn input(&mut self, _question: &str, default: &str) -> Result<String> {
+        Ok(default.to_string())
+    }
+
+    fn new_password(&mut self) -> Result<Zeroizing<String>> {
+        // With a password available this never prompts; it rejects
+        // passwords shorter than `init` allows.
+        Self::password()?;
+        crate::cli::prompt_new_password()
+    }
+
+    fn existing_password(&mut self, vault_id: &str) -> Result<Zeroizing<String>> {
+        crate::cli::available_password(Some(vault_id)).ok_or_else(Self::missing_password)
+    }
+}
+
+/// Asks on the terminal.
+pub struct Interactive;
+
+impl Prompt for Interactive {
+    fn confirm(&mut self, question: &str, default: bool) -> Result<bool> {
+        dialoguer::Confirm::new()
+            .with_prompt(question)
+            .default(default)
+            .interact()
+            .map_err(|e| EnvVaultError::CommandFailed(format!("failed to read confirmation: {e}")))
+    }
+
+    fn input(&mut self, question: &str, default: &str) -> Result<String> {
+        dialoguer::Input::new()
+            .with_prompt(question)
+            .default(default.to_string())
+            .interact_text()
+            .map_err(|e| EnvVaultError::CommandFailed(format!("failed to read input: {e}")))
+    }
+
+    fn new_password(&mut self) -> Result<Zeroizing<String>> {
+        crate::cli::prompt_new_password()
+    }
+
+    fn existing_password(&mut self, vault_id: &str) -> Result<Zeroizing<String>> {
+        crate::cli::prompt_password_for_vault(Some(vault_id))
+    }
+}
+
+/// What a step did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepOutcome {
+    /// The step made changes.
+    Done(String),
+    /// Nothing to do: an earlier run (or the user) already set this up.
+    AlreadyDone(String),
+    /// The step was declined or does not apply here.
+    Skipped(String),
+}
+
+impl StepOutcome {
+    /// Short status word for the summary table.
+    pub fn status(&self) -> &'static str {
+        match self {
+            Self::Done(_) => "done",
+            Self::AlreadyDone(_) => "already set up",
+            Self::Skipped(_) => "skipped",
+        }
+    }
+
+    /// Details shown next to the status.
+    pub fn detail(&self) -> &str {
+        match self {
+            Self::Done(d) | Self::AlreadyDone(d) | Self::Skipped(d) => d,
+        }
+    }
+}
+
+/// Password strength as reported during onboarding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PasswordStrength {
+    Weak,
+    Fair,
+    Strong,
+}
+
+impl fmt::Display for PasswordStrength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Weak => "weak",
+            Self::Fair => "fair",
+            Self::Strong => "strong",
+        })
+    }
+}
+
+/// Rough strength estimate from length and character variety.
+///
+/// Not an entropy calculation — just enough to steer people away from
+/// short, single-class passwords.
+pub fn password_strength(password: &str) -> PasswordStrength {
+    let len = password.chars().count();
+    let classes = [
+        password.chars().any(|c| c.is_ascii_lowercase()),
+        password.chars().any(|c| c.is_ascii_uppercase()),
+        password.chars().any(|c| c.is_ascii_digit()),
+        password.chars().any(|c| !c.is_ascii_alphanumeric()),
+    ]
+    .iter()
+    .filter(|&&present| present)
+    .count();
+
+    if len >= 16 && classes >= 2 || len >= 12 && classes >= 3 {
+        PasswordStrength::Strong
+    } else if len >= 10 && classes >= 2 {
+        PasswordStrength::Fair
+    } else {
+        PasswordStrength::Weak
+    }
+}
+
+/// State shared between steps.
+pub struct Onboarding {
+    /// Project root (where `.envvault.toml` lives).
+    pub project_dir: PathBuf,
+    /// Vault directory as given by `--vault-dir` (relative to the project).
+    pub vault_dir_name: String,
+    /// Keyfile new vaults are created with, if any.
+    pub keyfile: Option<Vec<u8>>,
+    /// Environments that have a vault after the environments step.
+    pub environments: Vec<String>,
+    /// Environments whose vault this run created.
+    pub created: Vec<String>,
+    /// Password chosen for the vaults created in this run.
+    pub password: Option<Zeroizing<String>>,
+    /// Home directory for shell completions (`None` skips that step).
+    pub home_dir: Option<PathBuf>,
+    /// Login shell, e.g. from `$SHELL`.
+    pub shell: Option<String>,
+}
+
+impl Onboarding {
+    fn vault_dir(&self) -> PathBuf {
+        self.project_dir.join(&self.vault_dir_name)
+    }
+
+    fn vault_path(&self, env: &str) -> PathBuf {
+        self.vault_dir().join(format!("{env}.vault"))
+    }
+
+    fn settings(&self) -> Result<Settings> {
+        Settings::load(&self.project_dir)
+    }
+}
+
+/// A setup step.
+pub type StepFn = fn(&mut Onboarding, &mut dyn Prompt) -> Result<StepOutcome>;
+
+/// Every step, in the order `onboard` runs them.
+pub const STEPS: &[(&str, StepFn)] = &[
+    ("Config file", config_file),
+    ("Keyfile", keyfile),
+    ("Environments", environments),
+    ("OS keyring", keyring),
+    ("Import .env files", import_env_files),
+    ("Git hook", git_hook),
+    ("Shell completions", completions),
+];
+
+/// Run all [`STEPS`], returning each step's name and outcome.
+///
+/// Stops at the first step that fails.
+pub fn run_steps(
+    ob: &mut Onboarding,
+    prompt: &mut dyn Prompt,
+) -> Result<Vec<(&'static str, StepOutcome)>> {
+    let mut report = Vec::with_capacity(STEPS.len());
+    for (name, step) in STEPS {
+        report.push((*name, step(ob, prompt)?));
+    }
+    Ok(report)
+}
+
+/// Starter `.envvault.toml`; everything else keeps its default.
+fn config_template(vault_dir: &str) -> String {
+    format!(
+        "# EnvVault project settings — see `envvault config show` for all options.\n\
+         default_environment = \"dev\"\n\
+         vault_dir = \"{vault_dir}\"\n"
+    )
+}
+
+/// Create `.envvault.toml` if the project has none.
+pub fn config_file(ob: &mut Onboarding, prompt: &mut dyn Prompt) -> Result<StepOutcome> {
+    let path = Settings::config_path(&ob.project_dir);
+    if path.exists() {
+        // Surface parse errors now rather than half-way through setup.
+        ob.settings()?;
+        return Ok(StepOutcome::AlreadyDone("found .envvault.toml".into()));
+    }
+
+    if !prompt.confirm("Create .envvault.toml with default settings?", true)? {
+        return Ok(StepOutcome::Skipped("using built-in defaults".into()));
+    }
+
+    fs::write(&path, config_template(&ob.vault_dir_name))?;
+    Ok(StepOutcome::Done("created .envvault.toml".into()))
+}
+
+/// Use the configured keyfile, or offer to generate one for new vaults.
+pub fn keyfile(ob: &mut Onboarding, prompt: &mut dyn Prompt) -> Result<StepOutcome> {
+    if ob.keyfile.is_some() {
+        return Ok(StepOutcome::AlreadyDone("keyfile configured".into()));
+    }
+
+    if !prompt.confirm(
+        "Generate a keyfile? New vaults will need both it and the password",
+        false,
+    )? {
+        return Ok(StepOutcome::Skipped("password only".into()));
+    }
+
+    let relative = format!("{}/keyfile", ob.vault_dir_name.trim_end_matches('/'));
+    let path = ob.project_dir.join(&relative);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    ob.keyfile = Some(crate::crypto::keyfile::generate_keyfile(&path)?);
+    crate::cli::gitignore::patch_gitignore(&ob.project_dir, &relative);
+
+    // Point later commands at it via the project config.
+    let config_path = Settings::config_path(&ob.project_dir);
+    let existing = fs::read_to_string(&config_path).unwrap_or_default();
+    fs::write(
+        &config_path,
+        format!("keyfile_path = \"{relative}\"\n{existing}"),
+    )?;
+
+    Ok(StepOutcome::Done(format!(
+        "generated {relative} (keep it out of git)"
+    )))
+}
+
+/// Create a vault for each chosen environment that doesn't have one.
+pub fn environments(ob: &mut Onboarding, prompt: &mut dyn Prompt) -> Result<StepOutcome> {
+    let settings = ob.settings()?;
+    let answer = prompt.input(
+        "Environments to set up (comma-separated)",
+        &settings.default_environment,
+    )?;
+
+    let mut wanted: Vec<String> = Vec::new();
+    for name in answer.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        crate::cli::validate_env_name(name)?;
+        crate::config::validate_env_against_config(name, &settings)?;
+        if !wanted.iter().any(|w| w == name) {
+            wanted.push(name.to_string());
+        }
+    }
+    if wanted.is_empty() {
+        return Ok(StepOutcome::Skipped("no environments chosen".into()));
+    }
+
+    let vault_dir = ob.vault_dir();
+    fs::create_dir_all(&vault_dir)?;
+    crate::cli::gitignore::patch_gitignore(
+        &ob.project_dir,
+        &format!("{}/", ob.vault_dir_name.trim_end_matches('/')),
+    );
+
+    let params = settings.argon2_params();
+    crate::crypto::kdf::validate_params(&params)?;
+
+    let mut existing = Vec::new();
+    for env in &wanted {
+        let path = ob.vault_path(env);
+        if path.exists() {
+            existing.push(env.clone());
+            continue;
+        }
+
+        if ob.password.is_none() {
+            let password = prompt.new_password()?;
+            let strength = password_strength(&password);
+            match strength {
+                PasswordStrength::Weak => output::warning(&format!(
+                    "Password strength: {strength} — prefer 12+ characters mixing letters, digits, and symbols."
+                )),
+                _ => output::info(&format!("Password strength: {strength}")),
+            }
+            ob.password = Some(password);
+        }
+        let password = ob.password.as_ref().map_or(&[][..], |pw| pw.as_bytes());
+
+        VaultStore::create(&path, password, env, Some(&params), ob.keyfile.as_deref())?;
+        #[cfg(feature = "audit-log")]
+        if let Some(audit) = crate::audit::AuditLog::open(&vault_dir) {
+            audit.log("init", env, None, Some("vault created by onboard"));
+        }
+        ob.created.push(env.clone());
+    }
+
+    ob.environments = wanted;
+
+    if ob.created.is_empty() {
+        Ok(StepOutcome::AlreadyDone(format!(
+            "vaults exist: {}",
+            existing.join(", ")
+        )))
+    } else {
+        let mut detail = format!("created {}", ob.created.join(", "));
+        if !existing.is_empty() {
+            detail.push_str(&format!("; already had {}", existing.join(", ")));
+        }
+        Ok(StepOutcome::Done(detail))
+    }
+}
+
+/// Offer to store the new vaults' password in the OS keyring.
+#[cfg(feature = "keyring-store")]
+pub fn keyring(ob: &mut Onboarding, prompt: &mut dyn Prompt) -> Result<StepOutcome> {
+    let Some(ref password) = ob.password else {
+        return Ok(StepOutcome::Skipped("no new vaults".into()));
+    };
+    if !prompt.confirm("Store the password in the OS keyring?", false)? {
+        return Ok(StepOutcome::Skipped("declined".into()));
+    }
+
+    for env in &ob.created {
+        let vault_id = ob.vault_path(env).to_string_lossy().to_string();
+        crate::keyring::store_password(&vault_id, password)?;
+    }
+    Ok(StepOutcome::Done(format!(
+        "stored for {}",
+        ob.created.join(", ")
+    )))
+}
+
+/// Keyring support is not compiled in.
+#[cfg(not(feature = "keyring-store"))]
+pub fn keyring(_ob: &mut Onboarding, _prompt: &mut dyn Prompt) -> Result<StepOutcome> {
+    Ok(StepOutcome::Skipped(
+        crate::errors::EnvVaultError::FeatureDisabled(crate::features::KEYRING_STORE).to_string(),
+    ))
+}
+
+/// Environment a `.env*` file belongs to, if it is one we should import.
+///
+/// `.env` maps to `default_env` and `.env.<name>` to `<name>`; templates
+/// (`.env.example`, `.env.sample`, `.env.template`) are ignored.
+pub fn env_file_target(file_name: &str, default_env: &str) -> Option<String> {
+    if file_name == ".env" {
+        return Some(default_env.to_string());
+    }
+    let suffix = file_name.strip_prefix(".env.")?;
+    match suffix {
+        "example" | "sample" | "template" | "dist" => None,
+        "local" => Some(default_env.to_string()),
+        name => crate::cli::validate_env_name(name)
+            .is_ok()
+            .then(|| name.to_string()),
+    }
+}
+
+/// Import `.env*` files into the matching vaults, skipping keys that are
+/// already there.
+pub fn import_env_files(ob: &mut Onboarding, prompt: &mut dyn Prompt) -> Result<StepOutcome> {
+    let settings = ob.settings()?;
+    let mut files: Vec<(String, String)> = fs::read_dir(&ob.project_dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            env_file_target(&name, &settings.default_environment).map(|env| (name, env))
+        })
+        .collect();
+    files.sort();
+
+    if files.is_empty() {
+        return Ok(StepOutcome::Skipped("no .env files found".into()));
+    }
+
+    let mut imported = Vec::new();
+    let mut unchanged = Vec::new();
+    let mut no_vault = Vec::new();
+
+    for (file, env) in files {
+        let path = ob.vault_path(&env);
+        if !path.exists() {
+            no_vault.push(file);
+            continue;
+        }
+        if !prompt.confirm(&format!("Import {file} into '{env}'?"), true)? {
+            continue;
+        }
+
+        let mut store = open_vault(ob, prompt, &path)?;
+        let count = import_new_keys(&ob.project_dir.join(&file), &mut store)?;
+        if count == 0 {
+            unchanged.push(file);
+            continue;
+        }
+        store.save()?;
+        #[cfg(feature = "audit-log")]
+        if let Some(audit) = crate::audit::AuditLog::open(&ob.vault_dir()) {
+            audit.log(
+                "import",
+                &env,
+                None,
+                Some(&crate::audit::AuditDetails::import(count, &file).to_string()),
+            );
+        }
+        crate::cli::run_post_write_hook("import", &env);
+        imported.push(format!("{count} from {file} into {env}"));
+    }
+
+    let mut notes = Vec::new();
+    if !unchanged.is_empty() {
+        notes.push(format!("nothing new in {}", unchanged.join(", ")));
+    }
+    if !no_vault.is_empty() {
+        notes.push(format!("no vault for {}", no_vault.join(", ")));
+    }
+
+    if imported.is_empty() {
+        let detail = notes.join("; ");
+        if unchanged.is_empty() {
+            return Ok(StepOutcome::Skipped(detail));
+        }
+        return Ok(StepOutcome::AlreadyDone(detail));
+    }
+    let mut detail = format!("imported {}", imported.join(", "));
+    if !notes.is_empty() {
+        detail.push_str("; ");
+        detail.push_str(&notes.join("; "));
+    }
+    Ok(StepOutcome::Done(detail))
+}
+
+/// Open a vault with the password chosen earlier, or ask for its own.
+fn open_vault(ob: &Onboarding, prompt: &mut dyn Prompt, path: &Path) -> Result<VaultStore> {
+    if let Some(ref password) = ob.password {
+        match VaultStore::open(path, password.as_bytes(), ob.keyfile.as_deref()) {
+            Ok(store) => return Ok(store),
+            Err(EnvVaultError::HmacMismatch | EnvVaultError::DecryptionFailed) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    let vault_id = path.to_string_lossy();
+    let password = prompt.existing_password(&vault_id)?;
+    VaultStore::open(path, password.as_bytes(), ob.keyfile.as_deref())
+}
+
+/// Add the keys from `file` that the vault doesn't have yet.
+fn import_new_keys(file: &Path, store: &mut VaultStore) -> Result<usize> {
+    let parsed = read_env_file(file)?;
+    warn_if_crlf(file, &parsed);
+
+    let mut count = 0;
+    for (key, value) in &parsed.secrets {
+        if !store.contains_key(key) {
+            store.set_secret(key, value)?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// Install the pre-commit hook that blocks committed secrets.
+pub fn git_hook(ob: &mut Onboarding, prompt: &mut dyn Prompt) -> Result<StepOutcome> {
+    use crate::git::InstallResult;
+
+    if !ob.project_dir.join(".git").is_dir() {
+        return Ok(StepOutcome::Skipped("not a git repository".into()));
+    }
+    if !prompt.confirm(
+        "Install the pre-commit hook that blocks committed secrets?",
+        true,
+    )? {
+        return Ok(StepOutcome::Skipped("declined".into()));
+    }
+
+    Ok(match crate::git::install_hook(&ob.project_dir)? {
+        InstallResult::Installed => StepOutcome::Done("installed .git/hooks/pre-commit".into()),
+        InstallResult::AlreadyInstalled => {
+            StepOutcome::AlreadyDone("pre-commit hook installed".into())
+        }
+        InstallResult::ExistingHookFound => {
+            StepOutcome::Skipped("another pre-commit hook is already there".into())
+        }
+        InstallResult::NotAGitRepo => StepOutcome::Skipped("not a git repository".into()),
+    })
+}
+
+/// Where completions for `shell` are picked up automatically, relative to
+/// the home directory.
+pub fn completion_file(shell: &str) -> Option<&'static str> {
+    match shell {
+        "bash" => Some(".local/share/bash-completion/completions/envvault"),
+        "zsh" => Some(".zfunc/_envvault"),
+        "fish" => Some(".config/fish/completions/envvault.fish"),
+        _ => None,
+    }
+}
+
+/// Install completions for the login shell.
+pub fn completions(ob: &mut Onboarding, prompt: &mut dyn Prompt) -> Result<StepOutcome> {
+    let shell = ob
+        .shell
+        .as_deref()
+        .and_then(|s| Path::new(s).file_name())
+        .map(|s| s.to_string_lossy().to_string());
+    let (Some(home), Some(shell)) = (ob.home_dir.clone(), shell) else {
+        return Ok(StepOutcome::Skipped("could not detect the shell".into()));
+    };
+    let Some(relative) = completion_file(&shell) else {
+        return Ok(StepOutcome::Skipped(format!(
+            "no standard location for {shell} — see `envvault completions`"
+        )));
+    };
+
+    let script = crate::cli::commands::completions::render(
+        crate::cli::commands::completions::parse_shell(&shell)?,
+    );
+    let path = home.join(relative);
+    if fs::read(&path).is_ok_and(|existing| existing == script) {
+        return Ok(StepOutcome::AlreadyDone(format!("~/{relative}")));
+    }
+    if !prompt.confirm(&format!("Install {shell} completions?"), true)? {
+        return Ok(StepOutcome::Skipped("declined".into()));
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, script)?;
+
+    let mut detail = format!("wrote ~/{relative}");
+    if shell == "zsh" {
+        detail.push_str(" (add ~/.zfunc to fpath)");
+    }
+    Ok(StepOutcome::Done(detail))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use tempfile::TempDir;
+
+    /// Replays canned answers; panics if a step asks more than expected.
+    struct Scripted {
+        confirms: VecDeque<bool>,
+        inputs: VecDeque<&'static str>,
+    }
+
+    impl Scripted {
+        fn new(confirms: &[bool], inputs: &[&'static str]) -> Self {
+            Self {
+                confirms: confirms.iter().copied().collect(),
+                inputs: inputs.iter().copied().collect(),
+            }
+        }
+    }
+
+    impl Prompt for Scripted {
+        fn confirm(&mut self, question: &str, _default: bool) -> Result<bool> {
+            Ok(self.confirms.pop_front().expect(question))
+        }
+
+        fn input(&mut self, question: &str, _default: &str) -> Result<String> {
+            Ok(self.inputs.pop_front().expect(question).to_string())
+        }
+
+        fn new_password(&mut self) -> Result<Zeroizing<String>> {
+            Ok(Zeroizing::new("Onboard-Pass-123".into()))
+        }
+
+        fn existing_password(&mut self, _vault_id: &str) -> Result<Zeroizing<String>> {
+            Ok(Zeroizing::new("Onboard-Pass-123".into()))
+        }
+    }
+
+    fn onboarding(dir: &Path) -> Onboarding {
+        Onboarding {
+            project_dir: dir.to_path_buf(),
+            vault_dir_name: ".envvault".into(),
+            keyfile: None,
+            environments: Vec::new(),
+            created: Vec::new(),
+            password: None,
+            home_dir: Some(dir.join("home")),
+            shell: Some("/bin/bash".into()),
+        }
+    }
+
+    /// Keep fixture vaults fast.
+    fn write_fast_config(dir: &Path) {
+        let fast = crate::devtools::fast_kdf_params();
+        fs::write(
+            dir.join(".envvault.toml"),
+            format!(
+                "argon2_memory_kib = {}\nargon2_iterations = {}\nargon2_parallelism = {}\n",
+                fast.memory_kib, fast.iterations, fast.parallelism
+            ),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn password_strength_grades() {
+        assert_eq!(password_strength("password"), PasswordStrength::Weak);
+        assert_eq!(password_strength("password12"), PasswordStrength::Fair);
+        assert_eq!(password_strength("Password-123"), PasswordStrength::Strong);
+        assert_eq!(
+            password_strength("correct horse battery"),
+            PasswordStrength::Strong
+        );
+    }
+
+    #[test]
+    fn env_files_map_to_environments() {
+        assert_eq!(env_file_target(".env", "dev").as_deref(), Some("dev"));
+        assert_eq!(env_file_target(".env.local", "dev").as_deref(), Some("dev"));
+        assert_eq!(
+            env_file_target(".env.staging", "dev").as_deref(),
+            Some("staging")
+        );
+        assert_eq!(env_file_target(".env.example", "dev"), None);
+        assert_eq!(env_file_target(".env.Bad_Name", "dev"), None);
+        assert_eq!(env_file_target(".envrc", "dev"), None);
+    }
+
+    #[test]
+    fn config_step_creates_once() {
+        let dir = TempDir::new().unwrap();
+        let mut ob = onboarding(dir.path());
+
+        let outcome = config_file(&mut ob, &mut Scripted::new(&[true], &[])).unwrap();
+        assert_eq!(outcome, StepOutcome::Done("created .envvault.toml".into()));
+        assert_eq!(ob.settings().unwrap().vault_dir, ".envvault");
+
+        // Second run asks nothing.
+        let outcome = config_file(&mut ob, &mut Scripted::new(&[], &[])).unwrap();
+        assert!(matches!(outcome, StepOutcome::AlreadyDone(_)));
+    }
+
+    #[test]
+    fn declined_steps_are_skipped() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        let mut ob = onboarding(dir.path());
+        let mut prompt = Scripted::new(&[false, false, false, false], &[]);
+
+        assert!(matches!(
+            config_file(&mut ob, &mut prompt).unwrap(),
+            StepOutcome::Skipped(_)
+        ));
+        assert!(matches!(
+            keyfile(&mut ob, &mut prompt).unwrap(),
+            StepOutcome::Skipped(_)
+        ));
+        assert!(matches!(
+            git_hook(&mut ob, &mut prompt).unwrap(),
+            StepOutcome::Skipped(_)
+        ));
+        assert!(matches!(
+            completions(&mut ob, &mut prompt).unwrap(),
+            StepOutcome::Skipped(_)
+        ));
+        assert!(!dir.path().join(".envvault.toml").exists());
+        assert!(!dir.path().join(".git/hooks/pre-commit").exists());
+    }
+
+    #[test]
+    fn environments_and_import_are_idempotent() {
+        let dir = TempDir::new().unwrap();
+        write_fast_config(dir.path());
+        fs::write(dir.path().join(".env"), "A=1\nB=2\n").unwrap();
+        fs::write(dir.path().join(".env.staging"), "S=3\n").unwrap();
+        fs::write(dir.path().join(".env.example"), "A=changeme\n").unwrap();
+        let mut ob = onboarding(dir.path());
+
+        let outcome = environments(&mut ob, &mut Scripted::new(&[], &["dev, staging"])).unwrap();
+        assert_eq!(outcome, StepOutcome::Done("created dev, staging".into()));
+        assert!(ob.vault_path("staging").exists());
+
+        let outcome = import_env_files(&mut ob, &mut Scripted::new(&[true, true], &[])).unwrap();
+        assert_eq!(
+            outcome,
+            StepOutcome::Done(
+                "imported 2 from .env into dev, 1 from .env.staging into staging".into()
+            )
+        );
+
+        // A second run finds everything in place.
+        let mut again = onboarding(dir.path());
+        let outcome = environments(&mut again, &mut Scripted::new(&[], &["dev,staging"])).unwrap();
+        assert!(matches!(outcome, StepOutcome::AlreadyDone(_)));
+        let outcome = import_env_files(&mut again, &mut Scripted::new(&[true, true], &[])).unwrap();
+        assert!(
+            matches!(outcome, StepOutcome::AlreadyDone(_)),
+            "{outcome:?}"
+        );
+
+        let store = VaultStore::open(&ob.vault_path("dev"), b"Onboard-Pass-123", None).unwrap();
+        assert_eq!(store.get_secret("B").unwrap(), "2");
+    }
+
+    #[test]
+    fn completions_step_writes_and_detects_script() {
+        let dir = TempDir::new().unwrap();
+        let mut ob = onboarding(dir.path());
+
+        let outcome = completions(&mut ob, &mut Scripted::new(&[true], &[])).unwrap();
+        assert!(matches!(outcome, StepOutcome::Done(_)));
+        assert!(dir
+            .path()
+            .join("home/.local/share/bash-completion/completions/envvault")
+            .exists());
+
+        let outcome = completions(&mut ob, &mut Scripted::new(&[], &[])).unwrap();
+        assert!(matches!(outcome, StepOutcome::AlreadyDone(_)));
+
+        ob.shell = Some("/usr/bin/tcsh".into());
+        let outcome = completions(&mut ob, &mut Scripted::new(&[], &[])).unwrap();
+        assert!(matches!(outcome, StepOutcome::Skipped(_)));
+    }
+}
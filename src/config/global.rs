@@ -4,7 +4,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::settings::AuditSettings;
+use super::settings::{AuditSettings, UpdateSettings};
 
 /// Global configuration loaded from `~/.config/envvault/config.toml`.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -20,6 +20,10 @@ pub struct GlobalConfig {
     /// Audit settings.
     #[serde(default)]
     pub audit: AuditSettings,
+
+    /// Update check settings.
+    #[serde(default)]
+    pub update: UpdateSettings,
 }
 
 impl GlobalConfig {
@@ -39,7 +43,7 @@ impl GlobalConfig {
     }
 
     /// Path to the global config file.
-    fn config_path() -> Option<std::path::PathBuf> {
+    pub fn config_path() -> Option<std::path::PathBuf> {
         let home = std::env::var("HOME")
             .or_else(|_| std::env::var("USERPROFILE"))
             .ok()?;
@@ -3,5 +3,6 @@ mod settings;
 
 pub use global::GlobalConfig;
 pub use settings::{
-    validate_env_against_config, AuditSettings, CustomPattern, SecretScanningSettings, Settings,
+    validate_env_against_config, AuditSettings, CustomPattern, HookSettings, KeyPolicySettings,
+    SafetySettings, SecretScanningSettings, Settings, TelemetrySettings, UpdateSettings,
 };
@@ -43,6 +43,33 @@ pub struct Settings {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub editor: Option<String>,
 
+    /// Launch vim/nvim with swap, backup, and undo files disabled so
+    /// `envvault edit` leaves no plaintext copies behind (default: true).
+    #[serde(default = "default_true")]
+    pub editor_no_swapfile: bool,
+
+    /// How far (in seconds) the system clock may lag behind the newest
+    /// vault timestamp, or a timestamp may sit in the future, before
+    /// writes warn and `list` flags it (default: 300).
+    #[serde(default = "default_max_clock_skew_secs")]
+    pub max_clock_skew_secs: u64,
+
+    /// Secrets updated within this many hours need their name typed to
+    /// confirm `delete`, instead of y/N (default: 24; 0 turns this off).
+    #[serde(default = "default_delete_confirm_recent_hours")]
+    pub delete_confirm_recent_hours: u64,
+
+    /// Largest secrets section (in MiB) a vault may have.  Bigger files
+    /// are refused before they are read, and writes that would exceed it
+    /// fail (default: 64).
+    #[serde(default = "default_max_secrets_mb")]
+    pub max_secrets_mb: u64,
+
+    /// Seconds after which `get --copy` and `export --clip` clear the
+    /// clipboard (default: 30; 0 leaves it alone).
+    #[serde(default = "default_clipboard_clear_secs")]
+    pub clipboard_clear_secs: u64,
+
     /// Audit log settings.
     #[serde(default)]
     pub audit: AuditSettings,
@@ -50,6 +77,30 @@ pub struct Settings {
     /// Secret scanning settings (for future use).
     #[serde(default)]
     pub secret_scanning: SecretScanningSettings,
+
+    /// Usage tracking settings.
+    #[serde(default)]
+    pub telemetry: TelemetrySettings,
+
+    /// Update check settings.
+    #[serde(default)]
+    pub update: UpdateSettings,
+
+    /// Commands run around vault operations.
+    #[serde(default)]
+    pub hooks: HookSettings,
+
+    /// Extra confirmation for destructive operations.
+    #[serde(default)]
+    pub safety: SafetySettings,
+
+    /// Key rotation reminders.
+    #[serde(default)]
+    pub security: SecuritySettings,
+
+    /// Naming rules for secret keys.
+    #[serde(default)]
+    pub key_policy: KeyPolicySettings,
 }
 
 /// Audit log configuration.
@@ -60,6 +111,80 @@ pub struct AuditSettings {
     pub log_reads: bool,
 }
 
+/// Usage tracking configuration.  Stored in the vault, never sent anywhere.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TelemetrySettings {
+    /// Count reads per secret (`access_count` / `last_accessed`).  Off by
+    /// default because it makes `get`, `run`, `export`, and `dump` write
+    /// the vault.
+    #[serde(default)]
+    pub track_access: bool,
+}
+
+/// Hook configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HookSettings {
+    /// Command run after a command writes the vault (e.g. to trigger a
+    /// redeploy).  Split like a shell would, but not run through one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_write: Option<String>,
+}
+
+/// Safety configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SafetySettings {
+    /// Environments where `env delete` and `rotate-key` make you type the
+    /// environment name to confirm, instead of y/N or nothing (e.g.
+    /// `["prod"]`).  `--force` skips the prompt.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub confirm_word_for: Vec<String>,
+}
+
+impl SafetySettings {
+    /// Whether destructive operations on `env` need the typed name.
+    pub fn needs_confirm_word(&self, env: &str) -> bool {
+        self.confirm_word_for.iter().any(|e| e == env)
+    }
+}
+
+/// Key rotation configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SecuritySettings {
+    /// Warn when a vault's master key is older than this many days:
+    /// measured from the last `rotate-key`, or from creation if it was
+    /// never rotated.  Unset (the default) or 0 turns the warning off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotate_after_days: Option<u64>,
+}
+
+/// Key naming policy, enforced by the CLI (see `cli::key_policy`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KeyPolicySettings {
+    /// Regex every key must match, e.g. `^[A-Z][A-Z0-9_]*$`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+
+    /// Every key must start with one of these, e.g. `["DB_", "STRIPE_"]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_prefixes: Vec<String>,
+}
+
+/// Update check configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateSettings {
+    /// Base URL of a crates.io-compatible registry API to query for the
+    /// latest version, for mirrors and air-gapped networks (default:
+    /// `https://crates.io`).  `$ENVVAULT_REGISTRY_URL` takes precedence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry_url: Option<String>,
+
+    /// Full URL to query instead of the registry's crates API, for an
+    /// internal endpoint serving the same JSON
+    /// (`{"crate": {"max_version": "..."}}`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check_url: Option<String>,
+}
+
 /// Secret scanning configuration.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SecretScanningSettings {
@@ -101,6 +226,26 @@ fn default_argon2_parallelism() -> u32 {
     4
 }
 
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_clock_skew_secs() -> u64 {
+    crate::vault::clock::DEFAULT_MAX_CLOCK_SKEW_SECS
+}
+
+fn default_delete_confirm_recent_hours() -> u64 {
+    crate::cli::commands::delete::DEFAULT_RECENT_HOURS
+}
+
+fn default_max_secrets_mb() -> u64 {
+    crate::vault::format::DEFAULT_MAX_SECRETS_MB
+}
+
+fn default_clipboard_clear_secs() -> u64 {
+    crate::cli::commands::get::DEFAULT_CLIPBOARD_CLEAR_SECS
+}
+
 // ── Implementation ───────────────────────────────────────────────────
 
 impl Default for Settings {
@@ -114,15 +259,26 @@ impl Default for Settings {
             keyfile_path: None,
             allowed_environments: None,
             editor: None,
+            editor_no_swapfile: true,
+            max_clock_skew_secs: default_max_clock_skew_secs(),
+            delete_confirm_recent_hours: default_delete_confirm_recent_hours(),
+            max_secrets_mb: default_max_secrets_mb(),
+            clipboard_clear_secs: default_clipboard_clear_secs(),
             audit: AuditSettings::default(),
             secret_scanning: SecretScanningSettings::default(),
+            telemetry: TelemetrySettings::default(),
+            update: UpdateSettings::default(),
+            hooks: HookSettings::default(),
+            safety: SafetySettings::default(),
+            security: SecuritySettings::default(),
+            key_policy: KeyPolicySettings::default(),
         }
     }
 }
 
 impl Settings {
     /// Name of the config file we look for in the project root.
-    const FILE_NAME: &'static str = ".envvault.toml";
+    pub const FILE_NAME: &'static str = ".envvault.toml";
 
     /// Load settings from `<project_dir>/.envvault.toml`.
     ///
@@ -144,6 +300,37 @@ impl Settings {
         Ok(settings)
     }
 
+    /// Load settings like [`Settings::load`], also returning the dotted
+    /// paths of keys that were ignored (typos or unsupported tables).
+    pub fn load_with_unknown_keys(project_dir: &Path) -> Result<(Self, Vec<String>)> {
+        let config_path = project_dir.join(Self::FILE_NAME);
+
+        if !config_path.exists() {
+            return Ok((Self::default(), Vec::new()));
+        }
+
+        let contents = std::fs::read_to_string(&config_path)?;
+
+        let mut unknown = Vec::new();
+        let settings: Settings =
+            serde_ignored::deserialize(toml::Deserializer::new(&contents), |path| {
+                unknown.push(path.to_string())
+            })
+            .map_err(|e| {
+                EnvVaultError::ConfigError(format!(
+                    "Failed to parse {}: {e}",
+                    config_path.display()
+                ))
+            })?;
+
+        Ok((settings, unknown))
+    }
+
+    /// Path of the project config file inside `project_dir`.
+    pub fn config_path(project_dir: &Path) -> PathBuf {
+        project_dir.join(Self::FILE_NAME)
+    }
+
     /// Build the full path to a vault file for a given environment.
     ///
     /// Example: `project_dir/.envvault/dev.vault`
@@ -197,6 +384,7 @@ mod tests {
         assert!(s.keyfile_path.is_none());
         assert!(s.allowed_environments.is_none());
         assert!(s.editor.is_none());
+        assert!(s.editor_no_swapfile);
         assert!(!s.audit.log_reads);
         assert!(s.secret_scanning.custom_patterns.is_empty());
     }
@@ -241,6 +429,17 @@ argon2_parallelism = 8
         assert_eq!(settings.argon2_iterations, 3);
     }
 
+    #[test]
+    fn load_with_unknown_keys_reports_typos() {
+        let tmp = TempDir::new().unwrap();
+        let config = "vault_dri = \"x\"\n[audit]\nlog_reads = true\nlog_writes = true\n";
+        fs::write(tmp.path().join(".envvault.toml"), config).unwrap();
+
+        let (settings, unknown) = Settings::load_with_unknown_keys(tmp.path()).unwrap();
+        assert!(settings.audit.log_reads);
+        assert_eq!(unknown, ["vault_dri", "audit.log_writes"]);
+    }
+
     #[test]
     fn load_errors_on_invalid_toml() {
         let tmp = TempDir::new().unwrap();
@@ -313,6 +512,20 @@ argon2_parallelism = 8
 
         let settings = Settings::load(tmp.path()).unwrap();
         assert_eq!(settings.editor.as_deref(), Some("nano"));
+        assert!(settings.editor_no_swapfile);
+    }
+
+    #[test]
+    fn load_parses_editor_no_swapfile_opt_out() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join(".envvault.toml"),
+            "editor_no_swapfile = false\n",
+        )
+        .unwrap();
+
+        let settings = Settings::load(tmp.path()).unwrap();
+        assert!(!settings.editor_no_swapfile);
     }
 
     #[test]
@@ -323,6 +536,83 @@ argon2_parallelism = 8
 
         let settings = Settings::load(tmp.path()).unwrap();
         assert!(settings.audit.log_reads);
+        assert!(!settings.telemetry.track_access);
+    }
+
+    #[test]
+    fn load_parses_telemetry_section() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join(".envvault.toml"),
+            "[telemetry]\ntrack_access = true\n",
+        )
+        .unwrap();
+
+        let settings = Settings::load(tmp.path()).unwrap();
+        assert!(settings.telemetry.track_access);
+    }
+
+    #[test]
+    fn load_parses_hooks_section() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join(".envvault.toml"),
+            "[hooks]\npost_write = \"./deploy.sh --env staging\"\n",
+        )
+        .unwrap();
+
+        let settings = Settings::load(tmp.path()).unwrap();
+        assert_eq!(
+            settings.hooks.post_write.as_deref(),
+            Some("./deploy.sh --env staging")
+        );
+        assert!(Settings::default().hooks.post_write.is_none());
+    }
+
+    #[test]
+    fn load_parses_safety_section() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join(".envvault.toml"),
+            "[safety]\nconfirm_word_for = [\"prod\", \"staging\"]\n",
+        )
+        .unwrap();
+
+        let settings = Settings::load(tmp.path()).unwrap();
+        assert!(settings.safety.needs_confirm_word("prod"));
+        assert!(settings.safety.needs_confirm_word("staging"));
+        assert!(!settings.safety.needs_confirm_word("dev"));
+        assert!(!Settings::default().safety.needs_confirm_word("prod"));
+    }
+
+    #[test]
+    fn load_parses_security_section() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join(".envvault.toml"),
+            "[security]\nrotate_after_days = 90\n",
+        )
+        .unwrap();
+
+        let settings = Settings::load(tmp.path()).unwrap();
+        assert_eq!(settings.security.rotate_after_days, Some(90));
+        assert_eq!(Settings::default().security.rotate_after_days, None);
+    }
+
+    #[test]
+    fn load_parses_key_policy_section() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join(".envvault.toml"),
+            "[key_policy]\npattern = \"^[A-Z_]+$\"\nrequired_prefixes = [\"DB_\", \"AWS_\"]\n",
+        )
+        .unwrap();
+
+        let (settings, unknown) = Settings::load_with_unknown_keys(tmp.path()).unwrap();
+        assert!(unknown.is_empty(), "{unknown:?}");
+        assert_eq!(settings.key_policy.pattern.as_deref(), Some("^[A-Z_]+$"));
+        assert_eq!(settings.key_policy.required_prefixes, ["DB_", "AWS_"]);
+        assert!(Settings::default().key_policy.pattern.is_none());
     }
 
     #[test]
@@ -1,34 +1,160 @@
-//! AES-256-GCM authenticated encryption.
+//! Authenticated encryption: AES-256-GCM (the default) or
+//! ChaCha20-Poly1305.
 //!
 //! Each call to `encrypt` generates a fresh random 12-byte nonce and
 //! prepends it to the ciphertext.  `decrypt` splits the nonce back out
-//! before decrypting.
-//!
-//! Layout of the returned byte buffer:
+//! before decrypting.  Both ciphers use the same layout:
 //!   [ 12-byte nonce | ciphertext + 16-byte auth tag ]
+//!
+//! ChaCha20-Poly1305 is faster than AES-GCM on CPUs without AES
+//! instructions (older servers, some ARM boards).  The cipher is chosen
+//! per vault and recorded in its header.
+//!
+//! The `_aad` variants also authenticate associated data that is not
+//! stored in the output: vaults pass the secret's name, so a value only
+//! decrypts under the name it was written for.
+//!
+//! `encrypt_stream` / `decrypt_stream` handle values too large to buffer,
+//! using the STREAM construction (`aead::stream`, big-endian 32-bit
+//! counter) over fixed-size chunks:
+//!   [ 7-byte nonce prefix | chunk + tag | chunk + tag | ... | last + tag ]
+//! Each chunk's nonce is the prefix, its index, and a last-chunk flag, so
+//! reordered, dropped, or truncated chunks fail to decrypt.  Every chunk
+//! authenticates the same associated data.
+
+use std::io::{ErrorKind, Read, Write};
+use std::ops::Sub;
 
-use aes_gcm::aead::{Aead, KeyInit, OsRng};
-use aes_gcm::{AeadCore, Aes256Gcm, Nonce};
+use aes_gcm::aead::consts::U5;
+use aes_gcm::aead::generic_array::{ArrayLength, GenericArray};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::stream::{DecryptorBE32, EncryptorBE32};
+use aes_gcm::aead::{Aead, AeadCore, AeadInPlace, KeyInit, Nonce, OsRng, Payload};
+use aes_gcm::Aes256Gcm;
+use chacha20poly1305::ChaCha20Poly1305;
+use zeroize::Zeroizing;
 
 use crate::errors::{EnvVaultError, Result};
 
-/// Size of the AES-256-GCM nonce in bytes.
+/// Size of the nonce in bytes (the same for both ciphers).
 const NONCE_LEN: usize = 12;
 
-/// Encrypt `plaintext` with a 32-byte `key`.
+/// Size of the authentication tag in bytes (the same for both ciphers).
+const TAG_LEN: usize = 16;
+
+/// Random part of a streamed value's nonces; STREAM fills the other five
+/// bytes with the chunk counter and last-chunk flag.
+pub const STREAM_NONCE_PREFIX_LEN: usize = 7;
+
+/// Plaintext bytes per chunk written by `encrypt_stream` by default.
+pub const STREAM_CHUNK_LEN: usize = 64 * 1024;
+
+/// Largest chunk `decrypt_stream` accepts, so a bogus length read from a
+/// vault cannot make it allocate without bound.
+pub const MAX_STREAM_CHUNK_LEN: usize = 16 * 1024 * 1024;
+
+/// AEAD cipher used for secret values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Cipher {
+    /// AES-256-GCM, used by every vault written before the cipher was
+    /// recorded in the header.
+    #[default]
+    Aes256Gcm,
+    ChaCha20Poly1305,
+}
+
+impl Cipher {
+    /// Parse a cipher name as given to `--cipher` or stored in a header.
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "aes-256-gcm" => Ok(Self::Aes256Gcm),
+            "chacha20-poly1305" => Ok(Self::ChaCha20Poly1305),
+            other => Err(EnvVaultError::CommandFailed(format!(
+                "unknown cipher '{other}' — supported: aes-256-gcm, chacha20-poly1305"
+            ))),
+        }
+    }
+
+    /// Name stored in the vault header.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Aes256Gcm => "aes-256-gcm",
+            Self::ChaCha20Poly1305 => "chacha20-poly1305",
+        }
+    }
+}
+
+/// Encrypt `plaintext` with a 32-byte `key` using AES-256-GCM.
 ///
 /// Returns the nonce prepended to the ciphertext (nonce || ciphertext).
 pub fn encrypt(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    encrypt_with(Cipher::Aes256Gcm, key, plaintext)
+}
+
+/// Decrypt data that was produced by `encrypt`.
+///
+/// Expects the first 12 bytes to be the nonce, followed by the ciphertext.
+pub fn decrypt(key: &[u8], ciphertext_with_nonce: &[u8]) -> Result<Vec<u8>> {
+    decrypt_with(Cipher::Aes256Gcm, key, ciphertext_with_nonce)
+}
+
+/// Encrypt `plaintext` with a 32-byte `key` using `cipher`.
+pub fn encrypt_with(cipher: Cipher, key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    encrypt_with_aad(cipher, key, plaintext, &[])
+}
+
+/// Decrypt data that was produced by `encrypt_with` and the same `cipher`.
+pub fn decrypt_with(cipher: Cipher, key: &[u8], ciphertext_with_nonce: &[u8]) -> Result<Vec<u8>> {
+    decrypt_with_aad(cipher, key, ciphertext_with_nonce, &[])
+}
+
+/// Encrypt like [`encrypt_with`], also authenticating `aad`.
+///
+/// `aad` is not part of the output; decryption must be given the same
+/// bytes.  Empty `aad` is the same as none.
+pub fn encrypt_with_aad(
+    cipher: Cipher,
+    key: &[u8],
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>> {
+    match cipher {
+        Cipher::Aes256Gcm => seal::<Aes256Gcm>(key, plaintext, aad),
+        Cipher::ChaCha20Poly1305 => seal::<ChaCha20Poly1305>(key, plaintext, aad),
+    }
+}
+
+/// Decrypt data that was produced by `encrypt_with_aad` with the same
+/// `cipher` and `aad`.
+pub fn decrypt_with_aad(
+    cipher: Cipher,
+    key: &[u8],
+    ciphertext_with_nonce: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>> {
+    match cipher {
+        Cipher::Aes256Gcm => open::<Aes256Gcm>(key, ciphertext_with_nonce, aad),
+        Cipher::ChaCha20Poly1305 => open::<ChaCha20Poly1305>(key, ciphertext_with_nonce, aad),
+    }
+}
+
+fn seal<C: Aead + AeadCore + KeyInit>(key: &[u8], plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
     // Build the cipher from the raw key bytes.
-    let cipher = Aes256Gcm::new_from_slice(key)
+    let cipher = C::new_from_slice(key)
         .map_err(|e| EnvVaultError::EncryptionFailed(format!("invalid key length: {e}")))?;
 
     // Generate a random 12-byte nonce.
-    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let nonce = C::generate_nonce(&mut OsRng);
 
     // Encrypt and authenticate the plaintext.
     let ciphertext = cipher
-        .encrypt(&nonce, plaintext)
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
         .map_err(|e| EnvVaultError::EncryptionFailed(format!("encryption error: {e}")))?;
 
     // Prepend the nonce so the caller only needs to store one blob.
@@ -38,26 +164,214 @@ pub fn encrypt(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
     Ok(output)
 }
 
-/// Decrypt data that was produced by `encrypt`.
-///
-/// Expects the first 12 bytes to be the nonce, followed by the ciphertext.
-pub fn decrypt(key: &[u8], ciphertext_with_nonce: &[u8]) -> Result<Vec<u8>> {
+fn open<C: Aead + AeadCore + KeyInit>(
+    key: &[u8],
+    ciphertext_with_nonce: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>> {
     // Make sure we have at least a nonce worth of bytes.
     if ciphertext_with_nonce.len() < NONCE_LEN {
         return Err(EnvVaultError::DecryptionFailed);
     }
 
-    // Split nonce from ciphertext.
+    // Split nonce from ciphertext.  Both ciphers take a 12-byte nonce.
     let (nonce_bytes, ciphertext) = ciphertext_with_nonce.split_at(NONCE_LEN);
-    let nonce = Nonce::from_slice(nonce_bytes);
+    let nonce = Nonce::<C>::from_slice(nonce_bytes);
 
     // Build the cipher from the raw key bytes.
-    let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| EnvVaultError::DecryptionFailed)?;
+    let cipher = C::new_from_slice(key).map_err(|_| EnvVaultError::DecryptionFailed)?;
 
     // Decrypt and verify the auth tag.
     let plaintext = cipher
-        .decrypt(nonce, ciphertext)
+        .decrypt(
+            nonce,
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
         .map_err(|_| EnvVaultError::DecryptionFailed)?;
 
     Ok(plaintext)
 }
+
+/// Encrypt everything `reader` yields into `writer` in `chunk_len`-byte
+/// chunks, holding at most two chunks in memory.  Each chunk also
+/// authenticates `aad` (empty for none).
+///
+/// Returns the number of plaintext bytes read.
+pub fn encrypt_stream(
+    cipher: Cipher,
+    key: &[u8],
+    chunk_len: usize,
+    aad: &[u8],
+    reader: impl Read,
+    writer: impl Write,
+) -> Result<u64> {
+    check_chunk_len(chunk_len)?;
+    match cipher {
+        Cipher::Aes256Gcm => seal_stream::<Aes256Gcm>(key, chunk_len, aad, reader, writer),
+        Cipher::ChaCha20Poly1305 => {
+            seal_stream::<ChaCha20Poly1305>(key, chunk_len, aad, reader, writer)
+        }
+    }
+}
+
+/// Decrypt a stream produced by `encrypt_stream` with the same `cipher`,
+/// `chunk_len`, and `aad` into `writer`.
+///
+/// Chunks are written as they are verified, so on error `writer` may
+/// already hold a prefix of the plaintext; the caller must discard it.
+/// Returns the number of plaintext bytes written.
+pub fn decrypt_stream(
+    cipher: Cipher,
+    key: &[u8],
+    chunk_len: usize,
+    aad: &[u8],
+    reader: impl Read,
+    writer: impl Write,
+) -> Result<u64> {
+    check_chunk_len(chunk_len)?;
+    match cipher {
+        Cipher::Aes256Gcm => open_stream::<Aes256Gcm>(key, chunk_len, aad, reader, writer),
+        Cipher::ChaCha20Poly1305 => {
+            open_stream::<ChaCha20Poly1305>(key, chunk_len, aad, reader, writer)
+        }
+    }
+}
+
+fn check_chunk_len(chunk_len: usize) -> Result<()> {
+    if chunk_len == 0 || chunk_len > MAX_STREAM_CHUNK_LEN {
+        return Err(EnvVaultError::EncryptionFailed(format!(
+            "stream chunk length {chunk_len} is outside 1-{MAX_STREAM_CHUNK_LEN}"
+        )));
+    }
+    Ok(())
+}
+
+fn seal_stream<C>(
+    key: &[u8],
+    chunk_len: usize,
+    aad: &[u8],
+    mut reader: impl Read,
+    mut writer: impl Write,
+) -> Result<u64>
+where
+    C: AeadInPlace + KeyInit,
+    C::NonceSize: Sub<U5>,
+    <C::NonceSize as Sub<U5>>::Output: ArrayLength<u8>,
+{
+    let cipher = C::new_from_slice(key)
+        .map_err(|e| EnvVaultError::EncryptionFailed(format!("invalid key length: {e}")))?;
+
+    let mut prefix = GenericArray::default();
+    OsRng.fill_bytes(&mut prefix);
+    writer.write_all(&prefix)?;
+    let mut stream = EncryptorBE32::from_aead(cipher, &prefix);
+
+    // Read one chunk ahead: only the last chunk may be short (or empty),
+    // and STREAM has to be told which chunk is the last.
+    let mut current = Zeroizing::new(vec![0u8; chunk_len]);
+    let mut next = Zeroizing::new(vec![0u8; chunk_len]);
+    let mut filled = read_full(&mut reader, &mut current)?;
+    let mut total = 0u64;
+    loop {
+        let next_filled = if filled == chunk_len {
+            read_full(&mut reader, &mut next)?
+        } else {
+            0
+        };
+        total += filled as u64;
+        if next_filled == 0 {
+            let sealed = stream
+                .encrypt_last(Payload {
+                    msg: &current[..filled],
+                    aad,
+                })
+                .map_err(|e| EnvVaultError::EncryptionFailed(format!("encryption error: {e}")))?;
+            writer.write_all(&sealed)?;
+            return Ok(total);
+        }
+        let sealed = stream
+            .encrypt_next(Payload {
+                msg: &current[..filled],
+                aad,
+            })
+            .map_err(|e| EnvVaultError::EncryptionFailed(format!("encryption error: {e}")))?;
+        writer.write_all(&sealed)?;
+        std::mem::swap(&mut current, &mut next);
+        filled = next_filled;
+    }
+}
+
+fn open_stream<C>(
+    key: &[u8],
+    chunk_len: usize,
+    aad: &[u8],
+    mut reader: impl Read,
+    mut writer: impl Write,
+) -> Result<u64>
+where
+    C: AeadInPlace + KeyInit,
+    C::NonceSize: Sub<U5>,
+    <C::NonceSize as Sub<U5>>::Output: ArrayLength<u8>,
+{
+    let cipher = C::new_from_slice(key).map_err(|_| EnvVaultError::DecryptionFailed)?;
+
+    let mut prefix = GenericArray::default();
+    if read_full(&mut reader, &mut prefix)? != prefix.len() {
+        return Err(EnvVaultError::DecryptionFailed);
+    }
+    let mut stream = DecryptorBE32::from_aead(cipher, &prefix);
+
+    let sealed_len = chunk_len + TAG_LEN;
+    let mut current = vec![0u8; sealed_len];
+    let mut next = vec![0u8; sealed_len];
+    let mut filled = read_full(&mut reader, &mut current)?;
+    let mut total = 0u64;
+    loop {
+        let next_filled = if filled == sealed_len {
+            read_full(&mut reader, &mut next)?
+        } else {
+            0
+        };
+        if next_filled == 0 {
+            let plaintext = Zeroizing::new(
+                stream
+                    .decrypt_last(Payload {
+                        msg: &current[..filled],
+                        aad,
+                    })
+                    .map_err(|_| EnvVaultError::DecryptionFailed)?,
+            );
+            writer.write_all(&plaintext)?;
+            return Ok(total + plaintext.len() as u64);
+        }
+        let plaintext = Zeroizing::new(
+            stream
+                .decrypt_next(Payload {
+                    msg: &current[..filled],
+                    aad,
+                })
+                .map_err(|_| EnvVaultError::DecryptionFailed)?,
+        );
+        writer.write_all(&plaintext)?;
+        total += plaintext.len() as u64;
+        std::mem::swap(&mut current, &mut next);
+        filled = next_filled;
+    }
+}
+
+/// Fill `buf` from `reader`, stopping early only at end of input.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
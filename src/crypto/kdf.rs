@@ -4,8 +4,11 @@
 //! GPU-based attacks.  Parameters are configurable via `Argon2Params`
 //! (loaded from `.envvault.toml` or sensible defaults).
 
+use std::time::{Duration, Instant};
+
 use argon2::{Algorithm, Argon2, Params, Version};
 use rand::TryRngCore;
+use zeroize::Zeroizing;
 
 use crate::errors::{EnvVaultError, Result};
 
@@ -39,6 +42,41 @@ impl Default for Argon2Params {
     }
 }
 
+impl Argon2Params {
+    /// Describe how these params fall short of the built-in defaults.
+    ///
+    /// Only memory and iterations are compared: fewer lanes changes speed,
+    /// not the cost an attacker pays per guess.  Returns an empty list
+    /// when the params are at least as strong as the defaults.
+    pub fn below_recommended(&self) -> Vec<String> {
+        let recommended = Self::default();
+        let mut shortfalls = Vec::new();
+        if self.memory_kib < recommended.memory_kib {
+            shortfalls.push(format!(
+                "memory {} KiB < recommended {} KiB",
+                self.memory_kib, recommended.memory_kib
+            ));
+        }
+        if self.iterations < recommended.iterations {
+            shortfalls.push(format!(
+                "iterations {} < recommended {}",
+                self.iterations, recommended.iterations
+            ));
+        }
+        shortfalls
+    }
+}
+
+impl std::fmt::Display for Argon2Params {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Argon2id m={} KiB, t={}, p={}",
+            self.memory_kib, self.iterations, self.parallelism
+        )
+    }
+}
+
 /// Derive a 32-byte master key from a password and salt using Argon2id.
 ///
 /// Uses the default Argon2id parameters (64 MB, 3 iterations, 4 lanes).
@@ -48,17 +86,60 @@ pub fn derive_master_key(password: &[u8], salt: &[u8]) -> Result<[u8; KEY_LEN]>
 }
 
 /// Minimum safe memory cost in KiB (8 MB).
-const MIN_MEMORY_KIB: u32 = 8_192;
+pub const MIN_MEMORY_KIB: u32 = 8_192;
+
+/// Maximum accepted memory cost in KiB (4 GB).  Anything larger would make
+/// every open stall or fail, and a crafted header could use it as a DoS.
+pub const MAX_MEMORY_KIB: u32 = 4 * 1024 * 1024;
+
+/// Maximum accepted iteration count.
+pub const MAX_ITERATIONS: u32 = 64;
+
+/// Maximum accepted parallelism lanes.
+pub const MAX_PARALLELISM: u32 = 64;
 
 /// Derive a 32-byte master key with explicit Argon2id parameters.
 ///
 /// The same password + salt + params will always produce the same key.
 /// Enforces minimum Argon2 parameters to prevent dangerously weak KDF settings.
+/// Use `derive_master_key_with_len` for vaults recording another `key_len`.
 pub fn derive_master_key_with_params(
     password: &[u8],
     salt: &[u8],
     argon2_params: &Argon2Params,
 ) -> Result<[u8; KEY_LEN]> {
+    let argon2 = build_argon2(argon2_params, KEY_LEN)?;
+
+    let mut key = [0u8; KEY_LEN];
+    argon2
+        .hash_password_into(password, salt, &mut key)
+        .map_err(|e| EnvVaultError::KeyDerivationFailed(format!("Argon2id hashing failed: {e}")))?;
+
+    Ok(key)
+}
+
+/// Derive a master key of `key_len` bytes with explicit Argon2id parameters.
+///
+/// Used when opening vaults whose header records a non-default `key_len`.
+/// The result is zeroized on drop.
+pub fn derive_master_key_with_len(
+    password: &[u8],
+    salt: &[u8],
+    argon2_params: &Argon2Params,
+    key_len: usize,
+) -> Result<Zeroizing<Vec<u8>>> {
+    let argon2 = build_argon2(argon2_params, key_len)?;
+
+    let mut key = Zeroizing::new(vec![0u8; key_len]);
+    argon2
+        .hash_password_into(password, salt, &mut key)
+        .map_err(|e| EnvVaultError::KeyDerivationFailed(format!("Argon2id hashing failed: {e}")))?;
+
+    Ok(key)
+}
+
+/// Reject Argon2 parameters below the safe minimums or above the ceilings.
+pub fn validate_params(argon2_params: &Argon2Params) -> Result<()> {
     if argon2_params.memory_kib < MIN_MEMORY_KIB {
         return Err(EnvVaultError::KeyDerivationFailed(format!(
             "Argon2 memory_kib must be at least {MIN_MEMORY_KIB} (got {})",
@@ -75,23 +156,99 @@ pub fn derive_master_key_with_params(
             "Argon2 parallelism must be at least 1".into(),
         ));
     }
+    if argon2_params.memory_kib > MAX_MEMORY_KIB {
+        return Err(EnvVaultError::KeyDerivationFailed(format!(
+            "Argon2 memory_kib must be at most {MAX_MEMORY_KIB} (got {})",
+            argon2_params.memory_kib
+        )));
+    }
+    if argon2_params.iterations > MAX_ITERATIONS {
+        return Err(EnvVaultError::KeyDerivationFailed(format!(
+            "Argon2 iterations must be at most {MAX_ITERATIONS} (got {})",
+            argon2_params.iterations
+        )));
+    }
+    if argon2_params.parallelism > MAX_PARALLELISM {
+        return Err(EnvVaultError::KeyDerivationFailed(format!(
+            "Argon2 parallelism must be at most {MAX_PARALLELISM} (got {})",
+            argon2_params.parallelism
+        )));
+    }
+    Ok(())
+}
+
+/// Pick Argon2id params that take roughly `target` to derive on this machine.
+///
+/// Starts at `MIN_MEMORY_KIB` with the default iterations and lanes, times
+/// one derivation per step and doubles the memory until a step overshoots
+/// the target (then adds iterations once memory hits `MAX_MEMORY_KIB`).
+/// The result never goes below the minimums, even when `target` is shorter
+/// than a single minimum-cost derivation.
+pub fn calibrate(target: Duration) -> Argon2Params {
+    let salt = [0u8; SALT_LEN];
+    calibrate_with(target, |params| {
+        let start = Instant::now();
+        let _ = derive_master_key_with_params(b"envvault-calibrate", &salt, params);
+        start.elapsed()
+    })
+}
+
+/// The search behind `calibrate`, with the timing supplied by `measure`.
+///
+/// Once memory doubling overshoots, the memory is interpolated linearly
+/// between the last step that fit and the one that did not (rounded down
+/// to whole MiB), so a longer target never yields weaker params.
+pub fn calibrate_with<F>(target: Duration, mut measure: F) -> Argon2Params
+where
+    F: FnMut(&Argon2Params) -> Duration,
+{
+    let defaults = Argon2Params::default();
+    let mut best = Argon2Params {
+        memory_kib: MIN_MEMORY_KIB,
+        ..defaults
+    };
+    let mut candidate = best;
+    let mut best_time = Duration::ZERO;
+
+    loop {
+        let elapsed = measure(&candidate);
+        if elapsed > target {
+            if candidate.memory_kib > best.memory_kib && !best_time.is_zero() {
+                let scaled = u128::from(best.memory_kib) * target.as_nanos() / best_time.as_nanos();
+                let capped = scaled.min(u128::from(candidate.memory_kib) - 1) as u32;
+                best.memory_kib = (capped / 1024 * 1024).max(best.memory_kib);
+            }
+            return best;
+        }
+        best = candidate;
+        best_time = elapsed;
+
+        if candidate.memory_kib < MAX_MEMORY_KIB {
+            candidate.memory_kib = candidate.memory_kib.saturating_mul(2).min(MAX_MEMORY_KIB);
+        } else if candidate.iterations < MAX_ITERATIONS {
+            candidate.iterations += 1;
+        } else {
+            return best;
+        }
+    }
+}
+
+/// Validate `argon2_params` and build an Argon2id instance producing
+/// `key_len` bytes.
+///
+/// Enforces minimum Argon2 parameters to prevent dangerously weak KDF settings.
+fn build_argon2(argon2_params: &Argon2Params, key_len: usize) -> Result<Argon2<'static>> {
+    validate_params(argon2_params)?;
 
     let params = Params::new(
         argon2_params.memory_kib,
         argon2_params.iterations,
         argon2_params.parallelism,
-        Some(KEY_LEN),
+        Some(key_len),
     )
     .map_err(|e| EnvVaultError::KeyDerivationFailed(format!("invalid Argon2 params: {e}")))?;
 
-    let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
-
-    let mut key = [0u8; KEY_LEN];
-    argon2
-        .hash_password_into(password, salt, &mut key)
-        .map_err(|e| EnvVaultError::KeyDerivationFailed(format!("Argon2id hashing failed: {e}")))?;
-
-    Ok(key)
+    Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
 }
 
 /// Generate a cryptographically random 32-byte salt.
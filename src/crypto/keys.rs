@@ -52,25 +52,36 @@ fn hkdf_derive(ikm: &[u8], info: &[u8]) -> Result<[u8; KEY_LEN]> {
     Ok(okm)
 }
 
-/// A wrapper around a 32-byte master key that automatically zeroes
-/// its memory when dropped.
+/// A wrapper around the master key that automatically zeroes its
+/// memory when dropped.
+///
+/// The master key is 32 bytes unless the vault header records another
+/// `key_len`; it is only ever used as HKDF input, so sub-keys are always
+/// 32 bytes regardless.
 ///
 /// Use this to hold the master key in memory so it cannot linger
 /// after it is no longer needed.
 #[derive(Zeroize)]
 #[zeroize(drop)]
 pub struct MasterKey {
-    bytes: [u8; KEY_LEN],
+    bytes: Vec<u8>,
 }
 
 impl MasterKey {
     /// Create a new `MasterKey` from raw bytes.
     pub fn new(bytes: [u8; KEY_LEN]) -> Self {
-        Self { bytes }
+        Self::from_slice(&bytes)
+    }
+
+    /// Create a new `MasterKey` from a key of any length.
+    pub fn from_slice(bytes: &[u8]) -> Self {
+        Self {
+            bytes: bytes.to_vec(),
+        }
     }
 
     /// Access the raw key bytes (e.g. to pass to HKDF or encryption).
-    pub fn as_bytes(&self) -> &[u8; KEY_LEN] {
+    pub fn as_bytes(&self) -> &[u8] {
         &self.bytes
     }
 
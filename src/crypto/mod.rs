@@ -1,7 +1,7 @@
 //! Cryptographic primitives for EnvVault.
 //!
 //! This module provides:
-//! - AES-256-GCM encryption and decryption (`encryption`)
+//! - AES-256-GCM / ChaCha20-Poly1305 encryption and decryption (`encryption`)
 //! - Argon2id password-based key derivation (`kdf`)
 //! - HKDF-based per-secret key and HMAC key derivation (`keys`)
 
@@ -12,7 +12,12 @@ pub mod keys;
 
 // Re-export the most commonly used items so callers can write:
 //   use crate::crypto::{encrypt, decrypt, derive_master_key, ...};
-pub use encryption::{decrypt, encrypt};
-pub use kdf::{derive_master_key, derive_master_key_with_params, generate_salt, Argon2Params};
+pub use encryption::{
+    decrypt, decrypt_with, decrypt_with_aad, encrypt, encrypt_with, encrypt_with_aad, Cipher,
+};
+pub use kdf::{
+    derive_master_key, derive_master_key_with_len, derive_master_key_with_params, generate_salt,
+    Argon2Params,
+};
 pub use keyfile::{combine_password_keyfile, generate_keyfile, hash_keyfile, load_keyfile};
 pub use keys::{derive_hmac_key, derive_secret_key};
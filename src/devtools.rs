@@ -0,0 +1,213 @@
+//! Test fixture helpers for envvault and downstream tools.
+//!
+//! Manufacturing vault files by hand is tedious and easy to get subtly
+//! wrong, so this module exposes the same generator used by envvault's own
+//! integration tests:
+//!
+//! - [`generate_vault`] writes a vault filled with seeded pseudo-random
+//!   secrets using the fast KDF profile ([`fast_kdf_params`]).
+//! - [`corrupt_vault`] damages an existing vault file in one of the
+//!   standard ways ([`CorruptMode`]) so readers can be tested against it.
+//!
+//! Secret names and values depend only on the seed, so two runs with the
+//! same [`GenVaultOptions`] decrypt to identical contents. The file bytes
+//! still differ between runs because salts and nonces are always random.
+//!
+//! The same functionality is available from the command line when the
+//! binary is built with `--features dev-tools`:
+//!
+//! ```text
+//! envvault --env test dev gen-vault --secrets 100 --value-size 64 \
+//!     --password testpass --out fixtures/test.vault
+//! envvault dev gen-corrupt --mode hmac --password testpass --out fixtures/bad.vault
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::crypto::kdf::{Argon2Params, MIN_MEMORY_KIB};
+use crate::errors::{EnvVaultError, Result};
+use crate::vault::VaultStore;
+
+/// Argon2id parameters for fixtures: the minimum the KDF accepts.
+///
+/// Never use these for real vaults — they exist so tests run quickly.
+pub fn fast_kdf_params() -> Argon2Params {
+    Argon2Params {
+        memory_kib: MIN_MEMORY_KIB,
+        iterations: 1,
+        parallelism: 1,
+    }
+}
+
+/// Options for [`generate_vault`].
+#[derive(Debug, Clone)]
+pub struct GenVaultOptions {
+    /// Number of secrets to generate.
+    pub secrets: usize,
+    /// Length of each generated value in characters.
+    pub value_size: usize,
+    /// Environment name recorded in the vault header.
+    pub environment: String,
+    /// Vault password.
+    pub password: String,
+    /// Seed for the pseudo-random values.
+    pub seed: u64,
+}
+
+impl Default for GenVaultOptions {
+    fn default() -> Self {
+        Self {
+            secrets: 10,
+            value_size: 32,
+            environment: "test".into(),
+            password: "testpass".into(),
+            seed: 0,
+        }
+    }
+}
+
+/// Create a vault at `path` filled with deterministic secrets.
+///
+/// Secrets are named `SECRET_0000`, `SECRET_0001`, ... and hold
+/// alphanumeric values of `value_size` characters drawn from a PRNG
+/// seeded with `options.seed`. Fails if a file already exists at `path`.
+pub fn generate_vault(path: &Path, options: &GenVaultOptions) -> Result<VaultStore> {
+    let params = fast_kdf_params();
+    let mut store = VaultStore::create(
+        path,
+        options.password.as_bytes(),
+        &options.environment,
+        Some(&params),
+        None,
+    )?;
+
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    for i in 0..options.secrets {
+        let value: String = (0..options.value_size)
+            .map(|_| rng.sample(rand::distr::Alphanumeric) as char)
+            .collect();
+        store.set_secret(&format!("SECRET_{i:04}"), &value)?;
+    }
+
+    store.save()?;
+    Ok(store)
+}
+
+/// The standard ways to damage a vault file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorruptMode {
+    /// Flip the last byte, which belongs to the HMAC tag.
+    Hmac,
+    /// Cut the file down to its first 8 bytes.
+    Truncate,
+    /// Flip a byte inside the header JSON.
+    Header,
+    /// Overwrite the `EVLT` magic bytes.
+    Magic,
+}
+
+impl CorruptMode {
+    /// All modes, in the order they are documented.
+    pub const ALL: [CorruptMode; 4] = [
+        CorruptMode::Hmac,
+        CorruptMode::Truncate,
+        CorruptMode::Header,
+        CorruptMode::Magic,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            CorruptMode::Hmac => "hmac",
+            CorruptMode::Truncate => "truncate",
+            CorruptMode::Header => "header",
+            CorruptMode::Magic => "magic",
+        }
+    }
+}
+
+impl fmt::Display for CorruptMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CorruptMode {
+    type Err = EnvVaultError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        CorruptMode::ALL
+            .into_iter()
+            .find(|mode| mode.as_str() == s)
+            .ok_or_else(|| {
+                EnvVaultError::CommandFailed(format!(
+                    "unknown corruption mode '{s}' (expected hmac, truncate, header, or magic)"
+                ))
+            })
+    }
+}
+
+/// Damage the vault file at `path` in place.
+pub fn corrupt_vault(path: &Path, mode: CorruptMode) -> Result<()> {
+    let mut data = fs::read(path)?;
+
+    // Byte 12 is past the 9-byte preamble (magic + version + header length),
+    // so it always lands inside the header JSON of a well-formed vault.
+    if data.len() <= 12 {
+        return Err(EnvVaultError::InvalidVaultFormat(
+            "file is too short to corrupt".into(),
+        ));
+    }
+
+    match mode {
+        CorruptMode::Hmac => {
+            let last = data.len() - 1;
+            data[last] ^= 0xFF;
+        }
+        CorruptMode::Truncate => data.truncate(8),
+        CorruptMode::Header => data[12] ^= 0xFF,
+        CorruptMode::Magic => {
+            data[0] = b'X';
+            data[1] = b'Y';
+        }
+    }
+
+    fs::write(path, &data)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn generated_contents_depend_only_on_seed() {
+        let dir = TempDir::new().unwrap();
+        let options = GenVaultOptions {
+            secrets: 3,
+            value_size: 16,
+            ..GenVaultOptions::default()
+        };
+
+        let a = generate_vault(&dir.path().join("a.vault"), &options).unwrap();
+        let b = generate_vault(&dir.path().join("b.vault"), &options).unwrap();
+
+        assert_eq!(a.secret_count(), 3);
+        assert_eq!(a.get_all_secrets().unwrap(), b.get_all_secrets().unwrap());
+        assert_eq!(a.get_secret("SECRET_0002").unwrap().len(), 16);
+    }
+
+    #[test]
+    fn corrupt_mode_parses_its_own_display() {
+        for mode in CorruptMode::ALL {
+            assert_eq!(mode.to_string().parse::<CorruptMode>().unwrap(), mode);
+        }
+        assert!("bogus".parse::<CorruptMode>().is_err());
+    }
+}
@@ -24,6 +24,9 @@ pub enum EnvVaultError {
     #[error("Invalid vault format: {0}")]
     InvalidVaultFormat(String),
 
+    #[error("Vault file too large: {0}")]
+    VaultTooLarge(String),
+
     #[error("HMAC verification failed — vault file may be tampered")]
     HmacMismatch,
 
@@ -88,6 +91,23 @@ pub enum EnvVaultError {
 
     #[error("Command not allowed: {0}")]
     CommandNotAllowed(String),
+
+    /// A `.env` entry the vault refused while importing it.
+    #[error("{file}:{line}: cannot import '{key}': {reason}")]
+    ImportLineError {
+        file: String,
+        line: usize,
+        key: String,
+        reason: String,
+    },
+
+    /// A code path behind a cargo feature this binary was built without;
+    /// holds the feature name (see [`crate::features`]).
+    #[error(
+        "built without `{0}` — rebuild with `{rebuild}`",
+        rebuild = crate::features::REBUILD_FULL
+    )]
+    FeatureDisabled(&'static str),
 }
 
 /// Convenience type alias for EnvVault results.
@@ -0,0 +1,37 @@
+//! Cargo features that gate optional integrations.
+//!
+//! `cargo install envvault-cli` builds the `cli` default set (the tool and
+//! its audit log); `--features full` adds the OS keyring, the update check,
+//! and the clipboard.  A command that needs a feature this binary lacks
+//! fails with [`EnvVaultError::FeatureDisabled`](crate::errors::EnvVaultError::FeatureDisabled),
+//! so every such error names the feature and the same rebuild command.
+//! `envvault version` lists the features a binary was built with.
+
+/// The SQLite audit log (`audit`, `audit export`, `audit verify`).
+pub const AUDIT_LOG: &str = "audit-log";
+
+/// OS keyring auto-unlock (`auth keyring`).
+pub const KEYRING_STORE: &str = "keyring-store";
+
+/// The crates.io update check (`version`, `update`).
+pub const VERSION_CHECK: &str = "version-check";
+
+/// System clipboard access (`get --copy`, `export --clip`).
+pub const CLIPBOARD: &str = "clipboard";
+
+/// Rebuild command suggested when a feature is missing.
+pub const REBUILD_FULL: &str = "cargo install envvault-cli --features full";
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::EnvVaultError;
+
+    #[test]
+    fn disabled_feature_error_names_the_feature_and_the_fix() {
+        let msg = EnvVaultError::FeatureDisabled(super::CLIPBOARD).to_string();
+        assert_eq!(
+            msg,
+            "built without `clipboard` — rebuild with `cargo install envvault-cli --features full`"
+        );
+    }
+}
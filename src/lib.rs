@@ -4,6 +4,9 @@ pub mod audit;
 #[cfg(not(feature = "audit-log"))]
 pub mod audit {
     //! No-op audit stub when the `audit-log` feature is disabled.
+    mod details;
+    pub use details::{details_value, render_details, AuditDetails};
+
     pub fn log_audit(
         _cli: &crate::cli::Cli,
         _op: &str,
@@ -26,8 +29,12 @@ pub mod audit {
 pub mod cli;
 pub mod config;
 pub mod crypto;
+pub mod devtools;
 pub mod errors;
+pub mod features;
 pub mod git;
+pub mod progress;
+pub mod timings;
 pub mod vault;
 pub mod version_check;
 
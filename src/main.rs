@@ -1,39 +1,143 @@
-use clap::Parser;
-use envvault::cli::{validate_env_name, AuditAction, AuthAction, Cli, Commands, EnvAction};
+#[cfg(feature = "dev-tools")]
+use envvault::cli::DevAction;
+use envvault::cli::{
+    validate_env_name, AuditAction, AuthAction, Cli, Commands, ConfigAction, EnvAction,
+};
 
 fn main() {
-    let cli = Cli::parse();
+    let cli = Cli::parse_args();
+
+    // Runs on every shell prompt: skip the config checks below, which read
+    // `.envvault.toml` and may print errors.
+    if let Commands::PromptInfo = cli.command {
+        envvault::cli::commands::prompt_info::execute(&cli);
+        return;
+    }
+
+    envvault::cli::output::init_ascii(cli.ascii);
+    match envvault::cli::output::TimeFormat::parse(&cli.time) {
+        Ok(format) => envvault::cli::output::init_time(format),
+        Err(e) => fail(&cli, &e),
+    }
+    if cli.timings {
+        envvault::timings::enable();
+    }
+    if cli.progress_json {
+        envvault::progress::enable_json();
+    }
+    envvault::progress::start(&cli.command_name);
+
+    if cli.stdin_password {
+        if let Err(e) = envvault::cli::init_stdin_password() {
+            fail(&cli, &e);
+        }
+    }
+    if let Some(ref path) = cli.password_file {
+        if let Err(e) = envvault::cli::init_password_file(std::path::Path::new(path)) {
+            fail(&cli, &e);
+        }
+    }
 
     // Validate the environment name early to catch typos.
     if let Err(e) = validate_env_name(&cli.env) {
-        envvault::cli::output::error(&e.to_string());
-        std::process::exit(1);
+        fail(&cli, &e);
     }
 
     // If allowed_environments is configured, reject names not in the list.
     if let Ok(cwd) = std::env::current_dir() {
         if let Ok(settings) = envvault::config::Settings::load(&cwd) {
             if let Err(e) = envvault::config::validate_env_against_config(&cli.env, &settings) {
-                envvault::cli::output::error(&e.to_string());
-                std::process::exit(1);
+                fail(&cli, &e);
             }
+            envvault::vault::format::set_max_secrets_mb(settings.max_secrets_mb);
         }
     }
 
     let result = match cli.command {
-        Commands::Init => envvault::cli::commands::init::execute(&cli),
+        Commands::Init {
+            no_gitignore,
+            ref cipher,
+            clean_on_failure,
+            tune,
+        } => envvault::cli::commands::init::execute(
+            &cli,
+            no_gitignore,
+            cipher.as_deref(),
+            clean_on_failure,
+            tune,
+        ),
+        Commands::Onboard { defaults } => envvault::cli::commands::onboard::execute(&cli, defaults),
         Commands::Set {
             ref key,
             ref value,
             force,
-        } => envvault::cli::commands::set::execute(&cli, key, value.as_deref(), force),
-        Commands::Get { ref key, clipboard } => {
-            envvault::cli::commands::get::execute(&cli, key, clipboard)
-        }
-        Commands::List => envvault::cli::commands::list::execute(&cli),
+            ref encoding,
+            ref expires,
+            ref description,
+            ref tags,
+            order,
+        } => envvault::cli::commands::set::execute(
+            &cli,
+            key,
+            value.as_deref(),
+            force,
+            encoding.as_deref(),
+            expires.as_deref(),
+            description.as_deref(),
+            tags,
+            order,
+        ),
+        Commands::SetMany { json } => envvault::cli::commands::set_many::execute(&cli, json),
+        Commands::Get {
+            ref keys,
+            clipboard,
+            no_newline,
+            print0,
+            ref format,
+            reveal,
+            decode,
+            clear_after,
+        } => envvault::cli::commands::get::execute(
+            &cli,
+            keys,
+            clipboard,
+            no_newline,
+            print0,
+            format,
+            reveal,
+            decode,
+            clear_after,
+        ),
+        Commands::List {
+            ref sort,
+            reverse,
+            diff_env,
+            json,
+            values,
+            ref tag,
+            policy_violations,
+        } => envvault::cli::commands::list::execute(
+            &cli,
+            sort,
+            reverse,
+            diff_env,
+            json,
+            values,
+            tag.as_deref(),
+            policy_violations,
+        ),
+        Commands::Dump {
+            json,
+            with_metadata,
+        } => envvault::cli::commands::dump::execute(&cli, json, with_metadata),
         Commands::Delete { ref key, force } => {
             envvault::cli::commands::delete::execute(&cli, key, force)
         }
+        Commands::Rename {
+            ref old,
+            ref new,
+            force,
+        } => envvault::cli::commands::rename::execute(&cli, old, new, force),
         Commands::Run {
             ref command,
             clean_env,
@@ -41,6 +145,11 @@ fn main() {
             ref exclude,
             redact_output,
             ref allowed_commands,
+            keep_envvault_vars,
+            interactive,
+            ref secrets_dir,
+            ref with_env,
+            ref check_only,
         } => envvault::cli::commands::run::execute(
             &cli,
             command,
@@ -49,50 +158,177 @@ fn main() {
             exclude.as_deref(),
             redact_output,
             allowed_commands.as_deref(),
+            keep_envvault_vars,
+            interactive,
+            secrets_dir.as_deref(),
+            with_env,
+            check_only.as_deref(),
         ),
-        Commands::RotateKey { ref new_keyfile } => {
-            envvault::cli::commands::rotate::execute(&cli, new_keyfile.as_deref())
+        Commands::RotateSecret {
+            ref key,
+            generate,
+            length,
+            ref value_from,
+            ref hook,
+        } => {
+            use envvault::cli::commands::rotate_secret::{self, NewValue};
+            let source = match value_from {
+                Some(cmd) if !generate => NewValue::Command(cmd),
+                _ => NewValue::Generate(length),
+            };
+            rotate_secret::execute(&cli, key, source, hook.as_deref())
         }
+        Commands::RotateKey {
+            ref new_keyfile,
+            force,
+        } => envvault::cli::commands::rotate::execute(&cli, new_keyfile.as_deref(), force),
         Commands::Export {
             ref format,
             ref output,
-        } => envvault::cli::commands::export::execute(&cli, format, output.as_deref()),
+            clip,
+            append,
+            no_clobber,
+            ref changed_since,
+            redact_names,
+            group_by_prefix,
+            ref separator,
+            all_envs,
+            ref output_dir,
+            force,
+            ref sort,
+            ref prefix,
+            ref name,
+            ref namespace,
+        } => {
+            use envvault::cli::commands::export::{AppendMode, K8sOptions};
+            if all_envs {
+                envvault::cli::commands::export::execute_all_envs(
+                    &cli,
+                    format,
+                    output_dir.as_deref().unwrap_or_default(),
+                    force,
+                    sort,
+                    prefix.as_deref(),
+                )
+            } else {
+                let append = append.then_some(if no_clobber {
+                    AppendMode::FileWins
+                } else {
+                    AppendMode::VaultWins
+                });
+                envvault::cli::commands::export::execute(
+                    &cli,
+                    format,
+                    output.as_deref(),
+                    clip,
+                    append,
+                    changed_since.as_deref(),
+                    redact_names,
+                    group_by_prefix.then_some(separator.as_str()),
+                    sort,
+                    prefix.as_deref(),
+                    K8sOptions {
+                        name: name.as_deref(),
+                        namespace: namespace.as_deref(),
+                    },
+                )
+            }
+        }
         Commands::Import {
             ref file,
             ref format,
             dry_run,
+            ref merge_strategy,
             skip_existing,
+            flatten,
+            ref separator,
+            ref filter,
         } => envvault::cli::commands::import_cmd::execute(
             &cli,
             file,
             format.as_deref(),
             dry_run,
-            skip_existing,
+            if skip_existing {
+                "skip"
+            } else {
+                merge_strategy
+            },
+            flatten.then_some(separator.as_str()),
+            filter.as_deref(),
         ),
         Commands::Env { ref action } => match action {
             EnvAction::List => envvault::cli::commands::env_list::execute(&cli),
             EnvAction::Clone {
                 ref target,
                 new_password,
-            } => envvault::cli::commands::env_clone::execute(&cli, target, *new_password),
+                ref from_file,
+            } => envvault::cli::commands::env_clone::execute(
+                &cli,
+                target,
+                *new_password,
+                from_file.as_deref(),
+            ),
             EnvAction::Delete { ref name, force } => {
                 envvault::cli::commands::env_delete::execute(&cli, name, *force)
             }
         },
+        Commands::Repair { fix_timestamps } => {
+            envvault::cli::commands::repair::execute(&cli, fix_timestamps)
+        }
+        Commands::Verify => envvault::cli::commands::verify::execute(&cli),
+        Commands::Info {
+            raw_header,
+            ref vault_file,
+        } => envvault::cli::commands::info::execute(&cli, raw_header, vault_file.as_deref()),
+        Commands::RawDump {
+            json,
+            ref vault_file,
+        } => envvault::cli::commands::raw_dump::execute(&cli, json, vault_file.as_deref()),
+        Commands::Config { ref action } => match action {
+            ConfigAction::Check => envvault::cli::commands::config_check::execute(),
+            ConfigAction::Show { json } => {
+                envvault::cli::commands::config_show::execute(&cli, *json)
+            }
+        },
         Commands::Diff {
             ref target_env,
+            ref against_file,
             show_values,
-        } => envvault::cli::commands::diff::execute(&cli, target_env, show_values),
-        Commands::Edit => envvault::cli::commands::edit::execute(&cli),
-        Commands::Version => envvault::cli::commands::version::execute(),
+        } => match (target_env, against_file) {
+            (_, Some(file)) => {
+                envvault::cli::commands::diff::execute_against_file(&cli, file, show_values)
+            }
+            (Some(target_env), None) => {
+                envvault::cli::commands::diff::execute(&cli, target_env, show_values)
+            }
+            (None, None) => unreachable!("clap requires a target environment or --against-file"),
+        },
+        Commands::Edit { ref sort } => envvault::cli::commands::edit::execute(&cli, sort),
+        Commands::Version {
+            json,
+            refresh,
+            verbose,
+        } => envvault::cli::commands::version::execute(json, refresh, verbose),
         Commands::Update => envvault::cli::commands::update::execute(),
+        Commands::Selftest => envvault::cli::commands::selftest::execute(),
+        Commands::PromptInfo => unreachable!("handled before the config checks"),
         Commands::Completions { ref shell } => envvault::cli::commands::completions::execute(shell),
+        Commands::GenerateAssets { ref output } => {
+            envvault::cli::commands::generate_assets::execute(output)
+        }
         Commands::Scan {
             ci,
             ref dir,
             ref gitleaks_config,
         } => envvault::cli::commands::scan::execute(ci, dir.as_deref(), gitleaks_config.as_deref()),
-        Commands::Search { ref pattern } => envvault::cli::commands::search::execute(&cli, pattern),
+        Commands::Search {
+            ref pattern,
+            values,
+            regex,
+        } => envvault::cli::commands::search::execute(&cli, pattern, values, regex),
+        Commands::History { ref key, last } => {
+            envvault::cli::commands::audit_cmd::execute_history(&cli, key, last)
+        }
         Commands::Audit {
             ref action,
             last,
@@ -113,14 +349,53 @@ fn main() {
             AuthAction::Keyring { delete } => {
                 envvault::cli::commands::auth::execute_keyring(&cli, *delete)
             }
-            AuthAction::KeyfileGenerate { ref path } => {
-                envvault::cli::commands::auth::execute_keyfile_generate(&cli, path.as_deref())
-            }
+            AuthAction::KeyfileGenerate {
+                ref path,
+                no_gitignore,
+            } => envvault::cli::commands::auth::execute_keyfile_generate(
+                &cli,
+                path.as_deref(),
+                *no_gitignore,
+            ),
+        },
+        #[cfg(feature = "dev-tools")]
+        Commands::Dev { ref action } => match action {
+            DevAction::GenVault {
+                secrets,
+                value_size,
+                ref password,
+                seed,
+                ref out,
+            } => envvault::cli::commands::dev::execute_gen_vault(
+                &cli,
+                *secrets,
+                *value_size,
+                password,
+                *seed,
+                out,
+            ),
+            DevAction::GenCorrupt {
+                ref mode,
+                ref password,
+                ref out,
+            } => envvault::cli::commands::dev::execute_gen_corrupt(mode, password, out),
         },
     };
 
-    if let Err(e) = result {
-        envvault::cli::output::error(&e.to_string());
-        std::process::exit(1);
+    if cli.timings {
+        envvault::cli::print_timings();
+    }
+
+    match result {
+        Ok(()) => envvault::progress::done(&cli.command_name),
+        Err(e) => fail(&cli, &e),
     }
 }
+
+/// Report `e` (also as a `--progress-json` error event) and exit 1.
+fn fail(cli: &Cli, e: &envvault::errors::EnvVaultError) -> ! {
+    let message = e.to_string();
+    envvault::progress::error(&cli.command_name, &message);
+    envvault::cli::output::error(&message);
+    std::process::exit(1);
+}
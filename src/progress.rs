@@ -0,0 +1,201 @@
+//! Progress reporting for long-running commands, for `--progress-json`.
+//!
+//! Commands report each phase of their work through a [`phase`] guard and
+//! advance it as they go.  Until a backend is enabled (the default, since
+//! envvault has no terminal progress bars) a guard is just a flag check.
+//!
+//! The JSON backend writes newline-delimited events to stderr for
+//! frontends that shell out to envvault:
+//!
+//! ```text
+//! {"event":"start","command":"import"}
+//! {"event":"progress","phase":"import","current":0,"total":310}
+//! {"event":"progress","phase":"import","current":42,"total":310}
+//! {"event":"progress","phase":"import","current":310,"total":310}
+//! {"event":"done","command":"import"}
+//! ```
+//!
+//! A phase reports when it starts and ends, and in between at most once
+//! every [`MIN_INTERVAL`].  A failed command ends with an `error` event
+//! carrying the message instead of `done`.  Warnings are still printed to
+//! stderr as plain text, so consumers should skip lines that are not JSON.
+
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Key derivation while opening a vault.
+pub const DERIVE_KEY: &str = "derive-key";
+/// Decrypting every secret in `get_all_secrets`.
+pub const DECRYPT: &str = "decrypt";
+/// Writing imported secrets into the vault.
+pub const IMPORT: &str = "import";
+/// Re-encrypting secrets under a new key.
+pub const REENCRYPT: &str = "re-encrypt";
+/// Exporting one environment after another.
+pub const EXPORT: &str = "export";
+/// Writing the vault file.
+pub const SAVE: &str = "save";
+
+/// Shortest gap between two progress events of the same phase.
+pub const MIN_INTERVAL: Duration = Duration::from_millis(100);
+
+static JSON: AtomicBool = AtomicBool::new(false);
+
+/// One line of `--progress-json` output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum Event<'a> {
+    Start {
+        command: &'a str,
+    },
+    Progress {
+        phase: &'a str,
+        current: usize,
+        total: usize,
+    },
+    Done {
+        command: &'a str,
+    },
+    Error {
+        command: &'a str,
+        message: &'a str,
+    },
+}
+
+/// Emit JSON events on stderr for the rest of the process.
+pub fn enable_json() {
+    JSON.store(true, Ordering::Relaxed);
+}
+
+/// Whether `--progress-json` is on.
+pub fn json_enabled() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// `command` has started.
+pub fn start(command: &str) {
+    emit(&Event::Start { command });
+}
+
+/// `command` finished successfully.
+pub fn done(command: &str) {
+    emit(&Event::Done { command });
+}
+
+/// `command` failed with `message`.
+pub fn error(command: &str, message: &str) {
+    emit(&Event::Error { command, message });
+}
+
+fn emit(event: &Event) {
+    if !json_enabled() {
+        return;
+    }
+    if let Ok(line) = serde_json::to_string(event) {
+        // One write per event so lines from threads never interleave.
+        let _ = writeln!(std::io::stderr().lock(), "{line}");
+    }
+}
+
+/// Report the phase `name` of `total` steps until the guard is dropped.
+pub fn phase(name: &'static str, total: usize) -> Phase {
+    let mut phase = Phase {
+        name,
+        current: 0,
+        total,
+        last_emit: None,
+        reported: None,
+        active: json_enabled(),
+    };
+    phase.report(Instant::now(), true);
+    phase
+}
+
+/// Guard returned by [`phase`]; reports where the phase ended when
+/// dropped, so a failed phase does not claim to be complete.
+#[must_use = "the phase ends when the guard is dropped"]
+pub struct Phase {
+    name: &'static str,
+    current: usize,
+    total: usize,
+    last_emit: Option<Instant>,
+    /// `current` as of the last event.
+    reported: Option<usize>,
+    active: bool,
+}
+
+impl Phase {
+    /// One more step is done.
+    pub fn inc(&mut self) {
+        self.set(self.current.saturating_add(1));
+    }
+
+    /// `current` steps are done.
+    pub fn set(&mut self, current: usize) {
+        self.current = current.min(self.total);
+        self.report(Instant::now(), self.current == self.total);
+    }
+
+    fn report(&mut self, now: Instant, force: bool) {
+        if !self.active || !(force || due(self.last_emit, now)) {
+            return;
+        }
+        self.last_emit = Some(now);
+        self.reported = Some(self.current);
+        emit(&Event::Progress {
+            phase: self.name,
+            current: self.current,
+            total: self.total,
+        });
+    }
+}
+
+impl Drop for Phase {
+    fn drop(&mut self) {
+        if self.reported != Some(self.current) {
+            self.report(Instant::now(), true);
+        }
+    }
+}
+
+/// Whether enough time has passed since the last event of a phase.
+fn due(last_emit: Option<Instant>, now: Instant) -> bool {
+    last_emit.map_or(true, |last| now.duration_since(last) >= MIN_INTERVAL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_serialize_to_one_flat_object() {
+        let progress = Event::Progress {
+            phase: REENCRYPT,
+            current: 42,
+            total: 310,
+        };
+        assert_eq!(
+            serde_json::to_string(&progress).unwrap(),
+            r#"{"event":"progress","phase":"re-encrypt","current":42,"total":310}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&Event::Error {
+                command: "import",
+                message: "bad file"
+            })
+            .unwrap(),
+            r#"{"event":"error","command":"import","message":"bad file"}"#
+        );
+    }
+
+    #[test]
+    fn progress_is_throttled_between_boundaries() {
+        let start = Instant::now();
+        assert!(due(None, start));
+        assert!(!due(Some(start), start + Duration::from_millis(99)));
+        assert!(due(Some(start), start + MIN_INTERVAL));
+    }
+}
@@ -0,0 +1,118 @@
+//! Wall-clock timings of vault phases, for `--timings`.
+//!
+//! Instrumented code wraps each phase in a [`phase`] guard.  While
+//! timings are disabled (the default) a guard is just a flag check: no
+//! clock is read and nothing is recorded.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Reading the vault file from disk.
+pub const READ_FILE: &str = "read file";
+/// Deserializing the header and secrets JSON.
+pub const JSON_PARSE: &str = "JSON parse";
+/// Argon2id master key derivation.
+pub const KDF: &str = "key derivation";
+/// Checking the stored HMAC.
+pub const HMAC_VERIFY: &str = "HMAC verify";
+/// Decrypting every secret in `get_all_secrets` (or one by one in
+/// `copy_secret_from`).
+pub const DECRYPT_ALL: &str = "decrypt secrets";
+/// Serializing the header and secrets JSON on save.
+pub const SERIALIZE: &str = "serialize";
+/// Computing the HMAC on save.
+pub const HMAC_SIGN: &str = "HMAC sign";
+/// Writing and renaming the vault file.
+pub const WRITE_FILE: &str = "write file";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static RECORDED: Mutex<Vec<(&str, Duration)>> = Mutex::new(Vec::new());
+
+/// Start recording phase timings for the rest of the process.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Time the enclosing scope as `label` (one of the constants above).
+pub fn phase(label: &'static str) -> Phase {
+    Phase {
+        label,
+        start: ENABLED.load(Ordering::Relaxed).then(Instant::now),
+    }
+}
+
+/// Guard returned by [`phase`]; records its duration when dropped.
+#[must_use = "the phase ends when the guard is dropped"]
+pub struct Phase {
+    label: &'static str,
+    start: Option<Instant>,
+}
+
+impl Drop for Phase {
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            if let Ok(mut recorded) = RECORDED.lock() {
+                recorded.push((self.label, start.elapsed()));
+            }
+        }
+    }
+}
+
+/// Total time and number of runs of one phase.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseTotal {
+    pub label: &'static str,
+    pub calls: usize,
+    pub total: Duration,
+}
+
+/// Everything recorded so far, summed per phase in first-seen order.
+pub fn summary() -> Vec<PhaseTotal> {
+    let recorded = RECORDED.lock().map(|r| r.clone()).unwrap_or_default();
+    summarize(&recorded)
+}
+
+fn summarize(recorded: &[(&'static str, Duration)]) -> Vec<PhaseTotal> {
+    let mut totals: Vec<PhaseTotal> = Vec::new();
+    for &(label, elapsed) in recorded {
+        match totals.iter_mut().find(|t| t.label == label) {
+            Some(total) => {
+                total.calls += 1;
+                total.total += elapsed;
+            }
+            None => totals.push(PhaseTotal {
+                label,
+                calls: 1,
+                total: elapsed,
+            }),
+        }
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phases_are_summed_in_first_seen_order() {
+        let ms = Duration::from_millis;
+        let totals = summarize(&[(KDF, ms(300)), (HMAC_VERIFY, ms(1)), (KDF, ms(200))]);
+        assert_eq!(
+            totals,
+            [
+                PhaseTotal {
+                    label: KDF,
+                    calls: 2,
+                    total: ms(500)
+                },
+                PhaseTotal {
+                    label: HMAC_VERIFY,
+                    calls: 1,
+                    total: ms(1)
+                },
+            ]
+        );
+    }
+}
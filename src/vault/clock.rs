@@ -0,0 +1,99 @@
+//! Clock-skew checks for vault timestamps.
+//!
+//! A machine with a broken clock (we've seen CI runners years ahead) writes
+//! timestamps that later confuse sorting and staleness checks.  These
+//! helpers take `now` as a parameter so they can be tested deterministically.
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Default allowed clock skew, in seconds (`max_clock_skew_secs`).
+pub const DEFAULT_MAX_CLOCK_SKEW_SECS: u64 = 300;
+
+/// `max_clock_skew_secs` as a `Duration`, saturating on absurd values.
+pub fn tolerance(max_clock_skew_secs: u64) -> Duration {
+    i64::try_from(max_clock_skew_secs)
+        .ok()
+        .and_then(Duration::try_seconds)
+        .unwrap_or(Duration::MAX)
+}
+
+/// Whether `timestamp` is later than `now` by more than `tolerance`.
+pub fn is_future(timestamp: DateTime<Utc>, now: DateTime<Utc>, tolerance: Duration) -> bool {
+    timestamp.signed_duration_since(now).gt(&tolerance)
+}
+
+/// How far `now` lags behind `latest`, if by more than `tolerance`.
+pub fn clock_behind(
+    now: DateTime<Utc>,
+    latest: DateTime<Utc>,
+    tolerance: Duration,
+) -> Option<Duration> {
+    let gap = latest.signed_duration_since(now);
+    (gap > tolerance).then_some(gap)
+}
+
+/// Short human form of a duration: `3d 4h`, `2h 5m`, `7m`, `45s`.
+pub fn describe(duration: Duration) -> String {
+    let secs = duration.num_seconds().max(0);
+    let (days, hours, mins) = (secs / 86_400, secs % 86_400 / 3_600, secs % 3_600 / 60);
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {mins}m")
+    } else if mins > 0 {
+        format!("{mins}m")
+    } else {
+        format!("{secs}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(secs, 0).unwrap()
+    }
+
+    const NOW: i64 = 1_700_000_000;
+
+    #[test]
+    fn future_timestamps_beyond_tolerance_are_flagged() {
+        let tol = tolerance(300);
+        assert!(!is_future(at(NOW), at(NOW), tol));
+        assert!(!is_future(at(NOW + 300), at(NOW), tol));
+        assert!(is_future(at(NOW + 301), at(NOW), tol));
+        assert!(!is_future(at(NOW - 86_400), at(NOW), tol));
+    }
+
+    #[test]
+    fn clock_behind_reports_the_gap() {
+        let tol = tolerance(60);
+        assert_eq!(clock_behind(at(NOW), at(NOW + 30), tol), None);
+        assert_eq!(
+            clock_behind(at(NOW), at(NOW + 7_200), tol),
+            Some(Duration::seconds(7_200))
+        );
+        assert_eq!(clock_behind(at(NOW + 10), at(NOW), tol), None);
+    }
+
+    #[test]
+    fn tolerance_saturates() {
+        assert_eq!(tolerance(0), Duration::zero());
+        assert_eq!(tolerance(u64::MAX), Duration::MAX);
+        assert!(!is_future(
+            at(NOW + 1_000_000),
+            at(NOW),
+            tolerance(u64::MAX)
+        ));
+    }
+
+    #[test]
+    fn describe_picks_two_largest_units() {
+        assert_eq!(describe(Duration::seconds(45)), "45s");
+        assert_eq!(describe(Duration::seconds(7 * 60 + 5)), "7m");
+        assert_eq!(describe(Duration::seconds(2 * 3_600 + 5 * 60)), "2h 5m");
+        assert_eq!(describe(Duration::days(3) + Duration::hours(4)), "3d 4h");
+    }
+}
@@ -7,7 +7,9 @@
 //! ```
 //!
 //! - **Magic** (`EVLT`): identifies the file as an EnvVault vault.
-//! - **Version**: format version (currently `1`).
+//! - **Version**: format version (currently `2`).  Version 1 files are
+//!   still read and written; version 2 binds each secret's value to its
+//!   name as AEAD associated data (see [`AAD_VERSION`]).
 //! - **Header length**: little-endian u32 telling us where the header
 //!   JSON ends and the secrets JSON begins.
 //! - **Header JSON**: serialized `VaultHeader`.
@@ -15,7 +17,9 @@
 //! - **HMAC-SHA256**: 32-byte tag computed over header + secrets bytes.
 
 use std::fs;
+use std::io::Read;
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use chrono::{DateTime, Utc};
 use hmac::{Hmac, Mac};
@@ -23,7 +27,9 @@ use serde::{Deserialize, Serialize};
 use sha2::Sha256;
 
 use super::secret::Secret;
+use crate::crypto::Cipher;
 use crate::errors::{EnvVaultError, Result};
+use crate::timings;
 
 // ---------------------------------------------------------------------------
 // Constants
@@ -32,8 +38,21 @@ use crate::errors::{EnvVaultError, Result};
 /// Magic bytes at the start of every vault file.
 const MAGIC: &[u8; 4] = b"EVLT";
 
-/// Current binary format version.
-pub const CURRENT_VERSION: u8 = 1;
+/// Current binary format version, written by `create` and `rotate-key`.
+pub const CURRENT_VERSION: u8 = 2;
+
+/// Oldest format version this build reads.
+pub const OLDEST_VERSION: u8 = 1;
+
+/// First version whose secret values authenticate the secret name as
+/// associated data.  Version 1 vaults keep encrypting without it until
+/// `rotate-key` re-encrypts them as the current version.
+pub const AAD_VERSION: u8 = 2;
+
+/// Whether this build reads format `version`.
+pub fn is_supported_version(version: u8) -> bool {
+    (OLDEST_VERSION..=CURRENT_VERSION).contains(&version)
+}
 
 /// Size of the HMAC tag appended to the file (SHA-256 = 32 bytes).
 const HMAC_LEN: usize = 32;
@@ -41,6 +60,49 @@ const HMAC_LEN: usize = 32;
 /// Fixed-size prefix: 4 (magic) + 1 (version) + 4 (header_len).
 const PREFIX_LEN: usize = 9;
 
+/// Upper bound on the header JSON size.  A real header is a few hundred
+/// bytes; anything near this limit is a malformed or malicious file.
+/// The header is parsed before the HMAC can be checked, so this is kept
+/// tight.
+pub const MAX_HEADER_LEN: usize = 16 * 1024;
+
+/// Default upper bound on the secrets JSON section (`max_secrets_mb`).
+pub const DEFAULT_MAX_SECRETS_MB: u64 = 64;
+
+static MAX_SECRETS_LEN: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_SECRETS_MB as usize * MIB);
+
+const MIB: usize = 1024 * 1024;
+
+/// Accepted range for the decoded salt length in bytes.
+const MIN_SALT_LEN: usize = 16;
+const MAX_SALT_LEN: usize = 64;
+
+/// Decoded length of a keyfile hash (SHA-256 = 32 bytes).
+const KEYFILE_HASH_LEN: usize = 32;
+
+/// Salt and master key length assumed for headers written before
+/// `salt_len` / `key_len` were recorded.
+pub const DEFAULT_KEY_LEN: usize = 32;
+
+/// Accepted range for the stored master key length in bytes.
+const MIN_KEY_LEN: usize = 16;
+const MAX_KEY_LEN: usize = 64;
+
+/// Set the largest secrets section this process will read or write, in
+/// MiB (`max_secrets_mb` in `.envvault.toml`).
+pub fn set_max_secrets_mb(mb: u64) {
+    let bytes = usize::try_from(mb)
+        .ok()
+        .and_then(|mb| mb.checked_mul(MIB))
+        .unwrap_or(usize::MAX);
+    MAX_SECRETS_LEN.store(bytes, Ordering::Relaxed);
+}
+
+/// The current secrets section limit in bytes.
+pub fn max_secrets_len() -> usize {
+    MAX_SECRETS_LEN.load(Ordering::Relaxed)
+}
+
 // ---------------------------------------------------------------------------
 // VaultHeader
 // ---------------------------------------------------------------------------
@@ -65,6 +127,16 @@ impl Default for StoredArgon2Params {
     }
 }
 
+impl From<StoredArgon2Params> for crate::crypto::kdf::Argon2Params {
+    fn from(stored: StoredArgon2Params) -> Self {
+        Self {
+            memory_kib: stored.memory_kib,
+            iterations: stored.iterations,
+            parallelism: stored.parallelism,
+        }
+    }
+}
+
 /// Metadata stored at the beginning of a vault file.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VaultHeader {
@@ -90,6 +162,42 @@ pub struct VaultHeader {
     /// Presence of this field means a keyfile is required to open the vault.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub keyfile_hash: Option<String>,
+
+    /// Length of the Argon2id-derived master key in bytes.
+    /// Missing in older vaults, which always used 32.
+    #[serde(default = "default_key_len")]
+    pub key_len: usize,
+
+    /// Length of the KDF salt in bytes; must match the decoded `salt`.
+    /// Missing in older vaults, which always used 32.
+    #[serde(default = "default_key_len")]
+    pub salt_len: usize,
+
+    /// AEAD cipher for secret values (`aes-256-gcm` or
+    /// `chacha20-poly1305`).  Missing in older vaults, which always used
+    /// AES-256-GCM.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cipher: Option<String>,
+
+    /// When `rotate-key` last replaced the master key.  Missing for vaults
+    /// whose key has never been rotated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotated_at: Option<DateTime<Utc>>,
+}
+
+impl VaultHeader {
+    /// The cipher recorded in the header, AES-256-GCM if none is.
+    pub fn cipher(&self) -> Result<Cipher> {
+        match self.cipher {
+            Some(ref name) => Cipher::parse(name)
+                .map_err(|_| EnvVaultError::InvalidVaultFormat(format!("unknown cipher '{name}'"))),
+            None => Ok(Cipher::default()),
+        }
+    }
+}
+
+fn default_key_len() -> usize {
+    DEFAULT_KEY_LEN
 }
 
 // ---------------------------------------------------------------------------
@@ -110,12 +218,26 @@ pub fn write_vault(
     secrets: &[Secret],
     hmac_key: &[u8],
 ) -> Result<()> {
+    let serialize = timings::phase(timings::SERIALIZE);
     let header_bytes = serde_json::to_vec(header)
         .map_err(|e| EnvVaultError::SerializationError(format!("header: {e}")))?;
     let secrets_bytes = serde_json::to_vec(secrets)
         .map_err(|e| EnvVaultError::SerializationError(format!("secrets: {e}")))?;
+    drop(serialize);
+
+    // Never write a vault that `read_vault` would refuse to open.
+    let max_secrets_len = max_secrets_len();
+    if secrets_bytes.len() > max_secrets_len {
+        return Err(EnvVaultError::VaultTooLarge(format!(
+            "secrets would take {} bytes, over the {max_secrets_len}-byte limit (max_secrets_mb in .envvault.toml)",
+            secrets_bytes.len()
+        )));
+    }
 
-    let hmac_tag = compute_hmac(hmac_key, &header_bytes, &secrets_bytes)?;
+    let hmac_tag = {
+        let _sign = timings::phase(timings::HMAC_SIGN);
+        compute_hmac(hmac_key, &header_bytes, &secrets_bytes)?
+    };
 
     // Build the binary blob.
     let header_len = u32::try_from(header_bytes.len()).map_err(|_| {
@@ -128,24 +250,39 @@ pub fn write_vault(
     let mut buf = Vec::with_capacity(total);
 
     buf.extend_from_slice(MAGIC); // 4 bytes
-    buf.push(CURRENT_VERSION); // 1 byte
+    buf.push(header.version); // 1 byte
     buf.extend_from_slice(&header_len.to_le_bytes()); // 4 bytes LE
     buf.extend_from_slice(&header_bytes); // header JSON
     buf.extend_from_slice(&secrets_bytes); // secrets JSON
     buf.extend_from_slice(&hmac_tag); // 32 bytes
 
-    // Atomic write: write to a temp file, then rename.
-    // The temp file is in the same directory so rename is guaranteed
-    // to be atomic on the same filesystem.
+    let _write = timings::phase(timings::WRITE_FILE);
+    write_atomic(path, &buf, |tmp, bytes| fs::write(tmp, bytes))
+}
+
+/// Put `bytes` at `path` by handing a temp file path to `write`, then
+/// renaming it over `path`.
+///
+/// The temp file is in the same directory so rename is guaranteed to be
+/// atomic on the same filesystem.  If `write` or the rename fails, the
+/// temp file is removed and `path` keeps its previous contents (or stays
+/// absent).  Tests pass a failing `write` to check exactly that.
+pub fn write_atomic(
+    path: &Path,
+    bytes: &[u8],
+    write: impl FnOnce(&Path, &[u8]) -> std::io::Result<()>,
+) -> Result<()> {
     let parent = path.parent().unwrap_or(Path::new("."));
     let tmp_path = parent.join(format!(
         ".{}.tmp",
         path.file_name().unwrap_or_default().to_string_lossy()
     ));
 
-    fs::write(&tmp_path, &buf)?;
-    fs::rename(&tmp_path, path)?;
-
+    let written = write(&tmp_path, bytes).and_then(|()| fs::rename(&tmp_path, path));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e.into());
+    }
     Ok(())
 }
 
@@ -164,17 +301,120 @@ pub struct RawVault {
     pub stored_hmac: Vec<u8>,
 }
 
+/// A vault file split into its sections, with only the header parsed.
+///
+/// The header has to be parsed before the HMAC can be checked (it holds
+/// the salt and KDF params), but the secrets JSON does not: `open`
+/// verifies the HMAC over `secrets_bytes` first and only then calls
+/// [`UnverifiedVault::into_verified`], so unauthenticated secrets are
+/// never deserialized.
+pub struct UnverifiedVault {
+    pub header: VaultHeader,
+    /// The raw header JSON bytes exactly as stored on disk.
+    pub header_bytes: Vec<u8>,
+    /// The raw secrets JSON bytes exactly as stored on disk.
+    pub secrets_bytes: Vec<u8>,
+    /// The HMAC tag stored at the end of the file.
+    pub stored_hmac: Vec<u8>,
+}
+
+impl UnverifiedVault {
+    /// Parse the secrets JSON of a vault whose HMAC has been verified.
+    ///
+    /// A parse failure here means the file was written with valid keys
+    /// but bad JSON (a bug, not tampering), and says so.
+    pub fn into_verified(self) -> Result<RawVault> {
+        let secrets = parse_secrets(&self.secrets_bytes).map_err(|e| {
+            EnvVaultError::InvalidVaultFormat(format!(
+                "secrets JSON is invalid even though the HMAC verified: {e}"
+            ))
+        })?;
+        Ok(self.with_secrets(secrets))
+    }
+
+    fn with_secrets(self, secrets: Vec<Secret>) -> RawVault {
+        RawVault {
+            header: self.header,
+            secrets,
+            header_bytes: self.header_bytes,
+            secrets_bytes: self.secrets_bytes,
+            stored_hmac: self.stored_hmac,
+        }
+    }
+}
+
+/// Read and validate only the header of the vault at `path`.
+///
+/// Stops after the header bytes, so the cost does not grow with the
+/// number of secrets.  Nothing is authenticated: treat the result as a
+/// hint (as `prompt-info` does), never as a basis for decryption.
+pub fn read_header(path: &Path) -> Result<VaultHeader> {
+    let mut file = fs::File::open(path)?;
+    let mut prefix = [0u8; PREFIX_LEN];
+    file.read_exact(&mut prefix)?;
+
+    if &prefix[0..4] != MAGIC {
+        return Err(EnvVaultError::InvalidVaultFormat(
+            "missing EVLT magic bytes".into(),
+        ));
+    }
+    if !is_supported_version(prefix[4]) {
+        return Err(unsupported_version(prefix[4]));
+    }
+    let header_len = u32::from_le_bytes([prefix[5], prefix[6], prefix[7], prefix[8]]) as usize;
+    if header_len > MAX_HEADER_LEN {
+        return Err(EnvVaultError::InvalidVaultFormat(format!(
+            "header length {header_len} exceeds maximum of {MAX_HEADER_LEN} bytes"
+        )));
+    }
+
+    let mut header_bytes = vec![0u8; header_len];
+    file.read_exact(&mut header_bytes)?;
+    let mut header: VaultHeader = serde_json::from_slice(&header_bytes)
+        .map_err(|e| EnvVaultError::InvalidVaultFormat(format!("header JSON: {e}")))?;
+    validate_header(&mut header)?;
+    Ok(header)
+}
+
 /// Read a vault file from disk and return its parts **with raw bytes**.
 ///
-/// The caller should verify the HMAC over `header_bytes` and
-/// `secrets_bytes` (the original bytes from disk) before trusting
-/// the deserialized data.
+/// Parses the secrets JSON without authenticating it, which is what the
+/// password-free tools (`info`, `raw-dump`, recovery docs) want; the
+/// size limit still applies.  Anything that decrypts should go through
+/// [`read_vault_unverified`] and check the HMAC before parsing.
 pub fn read_vault(path: &Path) -> Result<RawVault> {
+    let unverified = read_vault_unverified(path, max_secrets_len())?;
+    let secrets = parse_secrets(&unverified.secrets_bytes)
+        .map_err(|e| EnvVaultError::InvalidVaultFormat(format!("secrets JSON: {e}")))?;
+    Ok(unverified.with_secrets(secrets))
+}
+
+/// Read a vault file and parse only its header.
+///
+/// Files whose secrets section would exceed `max_secrets_len` bytes are
+/// rejected with [`EnvVaultError::VaultTooLarge`] from their size on
+/// disk, before being read into memory.
+pub fn read_vault_unverified(path: &Path, max_secrets_len: usize) -> Result<UnverifiedVault> {
     if !path.exists() {
         return Err(EnvVaultError::VaultNotFound(path.to_path_buf()));
     }
 
-    let data = fs::read(path)?;
+    let max_file_len = PREFIX_LEN
+        .saturating_add(MAX_HEADER_LEN)
+        .saturating_add(max_secrets_len)
+        .saturating_add(HMAC_LEN);
+    let file_len = fs::metadata(path)?.len();
+    if usize::try_from(file_len).map_or(true, |len| len > max_file_len) {
+        return Err(EnvVaultError::VaultTooLarge(format!(
+            "{} is {file_len} bytes, over the {max_file_len}-byte limit (raise max_secrets_mb in .envvault.toml if this vault is genuine)",
+            path.display()
+        )));
+    }
+
+    let data = {
+        let _read = timings::phase(timings::READ_FILE);
+        fs::read(path)?
+    };
 
     // Minimum size: prefix + HMAC.
     let min_size = PREFIX_LEN + HMAC_LEN;
@@ -193,10 +433,8 @@ pub fn read_vault(path: &Path) -> Result<RawVault> {
     }
 
     let version = data[4];
-    if version != CURRENT_VERSION {
-        return Err(EnvVaultError::InvalidVaultFormat(format!(
-            "unsupported version {version}, expected {CURRENT_VERSION}"
-        )));
+    if !is_supported_version(version) {
+        return Err(unsupported_version(version));
     }
 
     let header_len_u32 = u32::from_le_bytes(
@@ -210,6 +448,12 @@ pub fn read_vault(path: &Path) -> Result<RawVault> {
         ))
     })?;
 
+    if header_len > MAX_HEADER_LEN {
+        return Err(EnvVaultError::InvalidVaultFormat(format!(
+            "header length {header_len} exceeds maximum of {MAX_HEADER_LEN} bytes"
+        )));
+    }
+
     let header_end = PREFIX_LEN + header_len;
     if header_end + HMAC_LEN > data.len() {
         return Err(EnvVaultError::InvalidVaultFormat(
@@ -224,23 +468,204 @@ pub fn read_vault(path: &Path) -> Result<RawVault> {
     let secrets_bytes = data[header_end..secrets_end].to_vec();
     let stored_hmac = data[secrets_end..].to_vec();
 
-    // --- Deserialize from the raw bytes ---
+    if secrets_bytes.len() > max_secrets_len {
+        return Err(EnvVaultError::VaultTooLarge(format!(
+            "secrets section is {} bytes, over the {max_secrets_len}-byte limit (raise max_secrets_mb in .envvault.toml if this vault is genuine)",
+            secrets_bytes.len()
+        )));
+    }
 
-    let header: VaultHeader = serde_json::from_slice(&header_bytes)
-        .map_err(|e| EnvVaultError::InvalidVaultFormat(format!("header JSON: {e}")))?;
+    // --- Deserialize the header only ---
 
-    let secrets: Vec<Secret> = serde_json::from_slice(&secrets_bytes)
-        .map_err(|e| EnvVaultError::InvalidVaultFormat(format!("secrets JSON: {e}")))?;
+    let _parse = timings::phase(timings::JSON_PARSE);
+    let mut header: VaultHeader = serde_json::from_slice(&header_bytes)
+        .map_err(|e| EnvVaultError::InvalidVaultFormat(format!("header JSON: {e}")))?;
+    validate_header(&mut header)?;
 
-    Ok(RawVault {
+    Ok(UnverifiedVault {
         header,
-        secrets,
         header_bytes,
         secrets_bytes,
         stored_hmac,
     })
 }
 
+fn parse_secrets(secrets_bytes: &[u8]) -> serde_json::Result<Vec<Secret>> {
+    let _parse = timings::phase(timings::JSON_PARSE);
+    serde_json::from_slice(secrets_bytes)
+}
+
+fn unsupported_version(version: u8) -> EnvVaultError {
+    EnvVaultError::InvalidVaultFormat(format!(
+        "unsupported version {version}, expected {OLDEST_VERSION}-{CURRENT_VERSION}"
+    ))
+}
+
+/// Bound the header fields an attacker could inflate in a crafted vault.
+///
+/// The header is parsed before any authentication is possible, so its
+/// fields are checked against the sizes we actually write.  A length
+/// mismatch is reported here, by field, rather than later as a KDF error
+/// or HMAC mismatch.  `keyfile_hash` is rewritten in canonical form so
+/// it compares equal to [`crate::crypto::keyfile::hash_keyfile`].
+fn validate_header(header: &mut VaultHeader) -> Result<()> {
+    if !is_supported_version(header.version) {
+        return Err(unsupported_version(header.version));
+    }
+
+    let salt_len = header.salt.len();
+    if !(MIN_SALT_LEN..=MAX_SALT_LEN).contains(&salt_len) {
+        return Err(EnvVaultError::InvalidVaultFormat(format!(
+            "salt decoded to {salt_len} bytes, expected {MIN_SALT_LEN}-{MAX_SALT_LEN}"
+        )));
+    }
+    if header.salt_len != salt_len {
+        return Err(EnvVaultError::InvalidVaultFormat(format!(
+            "salt decoded to {salt_len} bytes, expected {} (salt_len)",
+            header.salt_len
+        )));
+    }
+
+    header.cipher()?;
+
+    if !(MIN_KEY_LEN..=MAX_KEY_LEN).contains(&header.key_len) {
+        return Err(EnvVaultError::InvalidVaultFormat(format!(
+            "key_len is {}, expected {MIN_KEY_LEN}-{MAX_KEY_LEN}",
+            header.key_len
+        )));
+    }
+
+    if let Some(ref mut hash) = header.keyfile_hash {
+        // Reject oversized strings before spending time decoding them.
+        if hash.len() > KEYFILE_HASH_LEN * 2 {
+            return Err(EnvVaultError::InvalidVaultFormat(format!(
+                "keyfile_hash is {} characters, expected a base64 SHA-256 digest",
+                hash.len()
+            )));
+        }
+        let decoded = decode_base64_lenient(hash).map_err(|e| {
+            EnvVaultError::InvalidVaultFormat(format!("keyfile_hash is not valid base64: {e}"))
+        })?;
+        if decoded.len() != KEYFILE_HASH_LEN {
+            return Err(EnvVaultError::InvalidVaultFormat(format!(
+                "keyfile_hash decodes to {} bytes, expected {KEYFILE_HASH_LEN}",
+                decoded.len()
+            )));
+        }
+        *hash = BASE64.encode(decoded);
+    }
+
+    Ok(())
+}
+
+/// Everything `inspect_vault` could parse from a vault file.
+///
+/// Fields are filled in file order and stay `None` past the point where
+/// parsing stopped.
+#[derive(Debug, Default)]
+pub struct VaultInspection {
+    pub file_size: usize,
+    /// Whether the first four bytes are `EVLT`.
+    pub magic_ok: Option<bool>,
+    /// The binary format version byte.
+    pub version: Option<u8>,
+    pub header_len: Option<usize>,
+    /// Header JSON fields as stored, including ones this build doesn't know.
+    pub header_fields: Option<serde_json::Map<String, serde_json::Value>>,
+    /// Why the header would be rejected by `read_vault`, if it would.
+    pub header_problem: Option<String>,
+    /// Length of the secrets JSON array.
+    pub secrets_count: Option<usize>,
+    /// Bytes left for the HMAC tag after the header (`HMAC_LEN` when intact).
+    pub hmac_len: Option<usize>,
+    /// Where and why parsing stopped, for damaged files.
+    pub error: Option<String>,
+}
+
+impl VaultInspection {
+    fn stop(mut self, offset: usize, msg: impl Into<String>) -> Self {
+        self.error = Some(format!("at byte {offset}: {}", msg.into()));
+        self
+    }
+}
+
+/// Parse as much of a vault file as possible without a password.
+///
+/// Unlike [`read_vault`] this never fails: damaged files come back with
+/// whatever was readable and `error` saying where parsing stopped.  The
+/// HMAC is not (and cannot be) verified.
+pub fn inspect_vault(data: &[u8]) -> VaultInspection {
+    let mut out = VaultInspection {
+        file_size: data.len(),
+        ..VaultInspection::default()
+    };
+
+    let Some(magic) = data.get(0..4) else {
+        return out.stop(0, "file ends before the magic bytes");
+    };
+    out.magic_ok = Some(magic == MAGIC);
+    if magic != MAGIC {
+        return out.stop(0, "missing EVLT magic bytes");
+    }
+
+    let Some(&version) = data.get(4) else {
+        return out.stop(4, "file ends before the version byte");
+    };
+    out.version = Some(version);
+
+    let Some(len_bytes) = data.get(5..PREFIX_LEN) else {
+        return out.stop(5, "file ends inside the header length");
+    };
+    let header_len = u32::from_le_bytes([len_bytes[0], len_bytes[1], len_bytes[2], len_bytes[3]]);
+    let header_len = header_len as usize;
+    out.header_len = Some(header_len);
+    if header_len > MAX_HEADER_LEN {
+        return out.stop(
+            5,
+            format!("header length {header_len} exceeds maximum of {MAX_HEADER_LEN} bytes"),
+        );
+    }
+
+    let header_end = PREFIX_LEN + header_len;
+    let Some(header_bytes) = data.get(PREFIX_LEN..header_end) else {
+        return out.stop(
+            PREFIX_LEN,
+            format!(
+                "header length {header_len} runs past the end of the file ({} bytes)",
+                data.len()
+            ),
+        );
+    };
+    match serde_json::from_slice::<serde_json::Value>(header_bytes) {
+        Ok(serde_json::Value::Object(fields)) => out.header_fields = Some(fields),
+        Ok(_) => return out.stop(PREFIX_LEN, "header JSON is not an object"),
+        Err(e) => return out.stop(PREFIX_LEN, format!("header JSON: {e}")),
+    }
+    out.header_problem = match serde_json::from_slice::<VaultHeader>(header_bytes) {
+        Ok(mut header) => validate_header(&mut header).err().map(|e| e.to_string()),
+        Err(e) => Some(format!("header JSON: {e}")),
+    };
+
+    let rest = data.len() - header_end;
+    out.hmac_len = Some(rest.min(HMAC_LEN));
+    if rest < HMAC_LEN {
+        return out.stop(
+            header_end,
+            format!(
+                "only {rest} bytes after the header, expected at least {HMAC_LEN} for the HMAC"
+            ),
+        );
+    }
+
+    let secrets_bytes = &data[header_end..data.len() - HMAC_LEN];
+    match serde_json::from_slice::<Vec<serde_json::Value>>(secrets_bytes) {
+        Ok(secrets) => out.secrets_count = Some(secrets.len()),
+        Err(e) => return out.stop(header_end, format!("secrets JSON: {e}")),
+    }
+
+    out
+}
+
 /// Compute HMAC-SHA256 over header + secrets bytes.
 pub fn compute_hmac(hmac_key: &[u8], header_bytes: &[u8], secrets_bytes: &[u8]) -> Result<Vec<u8>> {
     let mut mac = Hmac::<Sha256>::new_from_slice(hmac_key)
@@ -274,11 +699,37 @@ pub fn verify_hmac(
 
 // ---------------------------------------------------------------------------
 // Serde helpers for base64-encoded Vec<u8> fields
+//
+// Writes are canonical: the standard alphabet, padded, no whitespace.
+// Reads also accept unpadded input and ASCII whitespace anywhere, since
+// vaults written by other tools differ in exactly those ways.  The HMAC
+// covers the bytes as stored, so accepting them changes nothing about
+// authentication; the next save rewrites them canonically.
 // ---------------------------------------------------------------------------
 
-use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::alphabet;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig, STANDARD as BASE64};
+use base64::engine::DecodePaddingMode;
 use base64::Engine;
 
+/// Standard alphabet, padding optional.
+const BASE64_LENIENT: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Decode standard base64 with or without padding, ignoring ASCII
+/// whitespace.
+pub fn decode_base64_lenient(s: &str) -> std::result::Result<Vec<u8>, base64::DecodeError> {
+    if s.bytes().any(|b| b.is_ascii_whitespace()) {
+        let compact: String = s.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+        BASE64_LENIENT.decode(compact)
+    } else {
+        BASE64_LENIENT.decode(s)
+    }
+}
+
+/// Serialize bytes as canonical base64 (padded, no whitespace).
 pub(crate) fn base64_encode<S>(data: &[u8], serializer: S) -> std::result::Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
@@ -287,10 +738,371 @@ where
     serializer.serialize_str(&encoded)
 }
 
+/// Deserialize bytes with [`decode_base64_lenient`].
 pub(crate) fn base64_decode<'de, D>(deserializer: D) -> std::result::Result<Vec<u8>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
     let s = String::deserialize(deserializer)?;
-    BASE64.decode(&s).map_err(serde::de::Error::custom)
+    decode_base64_lenient(&s).map_err(|e| serde::de::Error::custom(format!("invalid base64: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Assemble a vault file around a hand-written header JSON.
+    /// The HMAC is zeroed — `read_vault` does not verify it.
+    fn craft_vault(header_json: &str) -> Vec<u8> {
+        let header = header_json.as_bytes();
+        let mut buf = Vec::new();
+        buf.extend_from_slice(MAGIC);
+        buf.push(CURRENT_VERSION);
+        buf.extend_from_slice(&(header.len() as u32).to_le_bytes());
+        buf.extend_from_slice(header);
+        buf.extend_from_slice(b"[]");
+        buf.extend_from_slice(&[0u8; HMAC_LEN]);
+        buf
+    }
+
+    fn header_json(salt: &[u8], keyfile_hash: Option<&str>) -> String {
+        let kf = keyfile_hash.map_or(String::new(), |h| format!(r#","keyfile_hash":"{h}""#));
+        format!(
+            r#"{{"version":1,"salt":"{}","created_at":"2024-01-01T00:00:00Z","environment":"dev"{kf}}}"#,
+            BASE64.encode(salt)
+        )
+    }
+
+    fn read_crafted(bytes: &[u8]) -> Result<RawVault> {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("crafted.vault");
+        fs::write(&path, bytes).unwrap();
+        read_vault(&path)
+    }
+
+    fn assert_invalid(result: Result<RawVault>, needle: &str) {
+        match result {
+            Err(EnvVaultError::InvalidVaultFormat(msg)) => {
+                assert!(msg.contains(needle), "unexpected message: {msg}");
+            }
+            Err(e) => panic!("expected InvalidVaultFormat, got {e}"),
+            Ok(_) => panic!("expected InvalidVaultFormat, got Ok"),
+        }
+    }
+
+    #[test]
+    fn inspect_reads_every_section_of_a_valid_file() {
+        let bytes = craft_vault(&header_json(&[1u8; 32], None).replace(
+            r#""environment":"dev""#,
+            r#""environment":"dev","future_field":true"#,
+        ));
+        let info = inspect_vault(&bytes);
+
+        assert_eq!(info.file_size, bytes.len());
+        assert_eq!(info.magic_ok, Some(true));
+        assert_eq!(info.version, Some(CURRENT_VERSION));
+        let fields = info.header_fields.unwrap();
+        assert_eq!(fields["environment"], "dev");
+        assert_eq!(fields["future_field"], true);
+        assert!(info.header_problem.is_none());
+        assert_eq!(info.secrets_count, Some(0));
+        assert_eq!(info.hmac_len, Some(HMAC_LEN));
+        assert!(info.error.is_none());
+    }
+
+    #[test]
+    fn inspect_truncated_file_keeps_the_header() {
+        let mut bytes = craft_vault(&header_json(&[1u8; 32], None));
+        bytes.truncate(bytes.len() - HMAC_LEN + 4);
+        let info = inspect_vault(&bytes);
+
+        assert!(info.header_fields.is_some());
+        assert_eq!(info.hmac_len, Some(6));
+        assert!(info.secrets_count.is_none());
+        let error = info.error.unwrap();
+        assert!(error.contains("only 6 bytes after the header"), "{error}");
+
+        // Cut inside the header itself.
+        let info = inspect_vault(&bytes[..PREFIX_LEN + 10]);
+        assert_eq!(info.version, Some(CURRENT_VERSION));
+        assert!(info.header_fields.is_none());
+        let error = info.error.unwrap();
+        assert!(error.starts_with("at byte 9: header length"), "{error}");
+    }
+
+    #[test]
+    fn inspect_bad_magic_stops_at_byte_zero() {
+        let mut bytes = craft_vault(&header_json(&[1u8; 32], None));
+        bytes[0..4].copy_from_slice(b"NOPE");
+        let info = inspect_vault(&bytes);
+
+        assert_eq!(info.magic_ok, Some(false));
+        assert!(info.version.is_none());
+        assert_eq!(
+            info.error.as_deref(),
+            Some("at byte 0: missing EVLT magic bytes")
+        );
+    }
+
+    #[test]
+    fn inspect_reports_invalid_header_without_stopping() {
+        let info = inspect_vault(&craft_vault(&header_json(&[1u8; 8], None)));
+        assert!(info
+            .header_problem
+            .unwrap()
+            .contains("salt decoded to 8 bytes"));
+        assert_eq!(info.secrets_count, Some(0));
+    }
+
+    #[test]
+    fn read_header_stops_before_the_secrets() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("crafted.vault");
+        let hash = BASE64.encode([7u8; KEYFILE_HASH_LEN]);
+        let mut bytes = craft_vault(&header_json(&[1u8; 32], Some(&hash)));
+        // Garbage after the header does not matter to `read_header`.
+        bytes.truncate(bytes.len() - HMAC_LEN - 1);
+        fs::write(&path, &bytes).unwrap();
+
+        let header = read_header(&path).unwrap();
+        assert_eq!(header.environment, "dev");
+        assert_eq!(header.keyfile_hash.as_deref(), Some(hash.as_str()));
+        assert!(read_vault(&path).is_err());
+
+        fs::write(&path, b"EVLT").unwrap();
+        assert!(read_header(&path).is_err());
+    }
+
+    #[test]
+    fn well_formed_header_is_accepted() {
+        let hash = BASE64.encode([7u8; 32]);
+        let raw = read_crafted(&craft_vault(&header_json(&[1u8; 32], Some(&hash)))).unwrap();
+        assert_eq!(raw.header.salt.len(), 32);
+    }
+
+    #[test]
+    fn oversized_header_length_is_rejected() {
+        let mut bytes = craft_vault(&header_json(&[1u8; 32], None));
+        let huge = (MAX_HEADER_LEN as u32 + 1).to_le_bytes();
+        bytes[5..9].copy_from_slice(&huge);
+        bytes.resize(MAX_HEADER_LEN + 100, b' ');
+        assert_invalid(read_crafted(&bytes), "exceeds maximum");
+    }
+
+    fn write_crafted(dir: &tempfile::TempDir, bytes: &[u8]) -> std::path::PathBuf {
+        let path = dir.path().join("crafted.vault");
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    /// A crafted vault whose secrets section is `secrets` and whose HMAC
+    /// is all zeros (so it can never authenticate).
+    fn craft_with_secrets(secrets: &[u8]) -> Vec<u8> {
+        let header = header_json(&[1u8; 32], None);
+        let mut buf = craft_vault(&header);
+        buf.truncate(PREFIX_LEN + header.len());
+        buf.extend_from_slice(secrets);
+        buf.extend_from_slice(&[0u8; HMAC_LEN]);
+        buf
+    }
+
+    #[test]
+    fn oversized_secrets_section_is_rejected_before_parsing() {
+        let dir = tempfile::TempDir::new().unwrap();
+        // Not JSON at all: a parse attempt would report a JSON error.
+        let path = write_crafted(&dir, &craft_with_secrets(&[b'['; 4096]));
+
+        match read_vault_unverified(&path, 1024) {
+            Err(EnvVaultError::VaultTooLarge(msg)) => {
+                assert!(msg.contains("secrets section is 4096 bytes"), "{msg}");
+            }
+            Err(e) => panic!("expected VaultTooLarge, got {e}"),
+            Ok(_) => panic!("expected VaultTooLarge, got Ok"),
+        }
+    }
+
+    #[test]
+    fn files_far_over_the_limit_are_rejected_from_their_size() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = write_crafted(&dir, &craft_with_secrets(&vec![b'['; 64 * 1024]));
+
+        match read_vault_unverified(&path, 0) {
+            Err(EnvVaultError::VaultTooLarge(msg)) => {
+                assert!(msg.contains("-byte limit"), "{msg}");
+                assert!(msg.starts_with(&path.display().to_string()), "{msg}");
+            }
+            Err(e) => panic!("expected VaultTooLarge, got {e}"),
+            Ok(_) => panic!("expected VaultTooLarge, got Ok"),
+        }
+    }
+
+    #[test]
+    fn unverified_read_does_not_parse_secrets() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let garbage = [b'['; 4096];
+        let path = write_crafted(&dir, &craft_with_secrets(&garbage));
+
+        // The header is parsed; the secrets bytes are carried unparsed.
+        let unverified = read_vault_unverified(&path, max_secrets_len()).unwrap();
+        assert_eq!(unverified.header.environment, "dev");
+        assert_eq!(unverified.secrets_bytes, garbage);
+
+        // Parsing after (pretend) verification names the situation.
+        let err = unverified.into_verified().err().expect("bad JSON");
+        assert!(
+            err.to_string()
+                .contains("invalid even though the HMAC verified"),
+            "{err}"
+        );
+
+        // The password-free reader still parses eagerly.
+        assert_invalid(read_vault(&path), "secrets JSON");
+    }
+
+    #[test]
+    fn max_secrets_mb_saturates() {
+        // Only checks the arithmetic; the global is restored afterwards.
+        set_max_secrets_mb(u64::MAX);
+        assert_eq!(max_secrets_len(), usize::MAX);
+        set_max_secrets_mb(DEFAULT_MAX_SECRETS_MB);
+        assert_eq!(max_secrets_len(), 64 * MIB);
+    }
+
+    #[test]
+    fn legacy_header_defaults_to_32_byte_lengths() {
+        let raw = read_crafted(&craft_vault(&header_json(&[1u8; 32], None))).unwrap();
+        assert_eq!(raw.header.key_len, DEFAULT_KEY_LEN);
+        assert_eq!(raw.header.salt_len, DEFAULT_KEY_LEN);
+    }
+
+    #[test]
+    fn explicit_lengths_are_read() {
+        let json = header_json(&[1u8; 16], None).replace(
+            r#""environment":"dev""#,
+            r#""environment":"dev","key_len":64,"salt_len":16"#,
+        );
+        let raw = read_crafted(&craft_vault(&json)).unwrap();
+        assert_eq!(raw.header.key_len, 64);
+        assert_eq!(raw.header.salt_len, 16);
+    }
+
+    #[test]
+    fn salt_len_mismatch_is_rejected() {
+        let json = header_json(&[1u8; 16], None);
+        assert_invalid(
+            read_crafted(&craft_vault(&json)),
+            "salt decoded to 16 bytes, expected 32 (salt_len)",
+        );
+    }
+
+    #[test]
+    fn out_of_range_key_len_is_rejected() {
+        let json = header_json(&[1u8; 32], None).replace(
+            r#""environment":"dev""#,
+            r#""environment":"dev","key_len":4096"#,
+        );
+        assert_invalid(read_crafted(&craft_vault(&json)), "key_len is 4096");
+    }
+
+    #[test]
+    fn short_salt_is_rejected() {
+        let bytes = craft_vault(&header_json(&[1u8; 8], None));
+        assert_invalid(read_crafted(&bytes), "salt decoded to 8 bytes");
+    }
+
+    #[test]
+    fn oversized_salt_is_rejected() {
+        let bytes = craft_vault(&header_json(&[1u8; 4096], None));
+        assert_invalid(read_crafted(&bytes), "salt decoded to 4096 bytes");
+    }
+
+    #[test]
+    fn lenient_decode_accepts_padding_either_way_and_whitespace() {
+        let bytes = [0xfbu8, 0xff, 0x01, 0x02];
+        let canonical = BASE64.encode(bytes);
+        assert_eq!(canonical, "+/8BAg==");
+        for encoded in ["+/8BAg==", "+/8BAg", " +/8B\nAg=\t= ", "+/8B\r\nAg"] {
+            assert_eq!(
+                decode_base64_lenient(encoded).unwrap(),
+                bytes,
+                "{encoded:?}"
+            );
+        }
+        // Still the standard alphabet, and nothing else.
+        assert!(decode_base64_lenient("-_8BAg").is_err());
+        assert!(decode_base64_lenient("+/8BA").is_err());
+    }
+
+    #[test]
+    fn externally_crafted_header_is_read_leniently_and_written_canonically() {
+        let salt = BASE64.encode([1u8; 32]);
+        let (head, tail) = salt.trim_end_matches('=').split_at(20);
+        let hash = BASE64.encode([7u8; 32]);
+        let json = format!(
+            r#"{{"version":1,"salt":"{head}\n  {tail}","created_at":"2024-01-01T00:00:00Z","environment":"dev","keyfile_hash":"{}"}}"#,
+            hash.trim_end_matches('=')
+        );
+
+        let raw = read_crafted(&craft_vault(&json)).unwrap();
+        assert_eq!(raw.header.salt, [1u8; 32]);
+        assert_eq!(raw.header.keyfile_hash.as_deref(), Some(hash.as_str()));
+
+        let written: serde_json::Value = serde_json::to_value(&raw.header).unwrap();
+        assert_eq!(written["salt"], salt.as_str());
+        assert_eq!(written["keyfile_hash"], hash.as_str());
+    }
+
+    #[test]
+    fn salt_length_errors_name_both_lengths() {
+        let json = header_json(&[1u8; 31], None);
+        assert_invalid(
+            read_crafted(&craft_vault(&json)),
+            "salt decoded to 31 bytes, expected 32",
+        );
+    }
+
+    #[test]
+    fn undecodable_salt_is_reported_as_base64() {
+        let json = header_json(&[1u8; 32], None).replacen(r#""salt":""#, r#""salt":"!"#, 1);
+        assert_invalid(read_crafted(&craft_vault(&json)), "invalid base64");
+    }
+
+    #[test]
+    fn garbage_keyfile_hash_is_rejected() {
+        let bytes = craft_vault(&header_json(&[1u8; 32], Some("not base64!!")));
+        assert_invalid(read_crafted(&bytes), "not valid base64");
+    }
+
+    #[test]
+    fn wrong_length_keyfile_hash_is_rejected() {
+        let hash = BASE64.encode([7u8; 16]);
+        let bytes = craft_vault(&header_json(&[1u8; 32], Some(&hash)));
+        assert_invalid(read_crafted(&bytes), "decodes to 16 bytes");
+    }
+
+    #[test]
+    fn oversized_keyfile_hash_is_rejected() {
+        let hash = "A".repeat(10_000);
+        let bytes = craft_vault(&header_json(&[1u8; 32], Some(&hash)));
+        assert_invalid(read_crafted(&bytes), "10000 characters");
+    }
+
+    #[test]
+    fn failed_write_leaves_the_old_file_byte_identical() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("dev.vault");
+        let before = craft_vault(&header_json(&[1u8; 32], None));
+        fs::write(&path, &before).unwrap();
+
+        // A writer that gets half the bytes out, then runs out of disk.
+        let result = write_atomic(&path, b"replacement contents", |tmp, bytes| {
+            fs::write(tmp, &bytes[..bytes.len() / 2])?;
+            Err(std::io::Error::new(std::io::ErrorKind::Other, "disk full"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(fs::read(&path).unwrap(), before);
+        let leftovers: Vec<_> = fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(leftovers.len(), 1, "temp file was not cleaned up");
+    }
 }
@@ -2,14 +2,16 @@
 //!
 //! This module provides:
 //! - `Secret` and `SecretMetadata` types (`secret`)
+//! - Clock-skew checks for stored timestamps (`clock`)
 //! - Binary vault file format with HMAC integrity (`format`)
 //! - High-level `VaultStore` for creating, opening, and managing vaults (`store`)
 
+pub mod clock;
 pub mod format;
 pub mod secret;
 pub mod store;
 
 // Re-export the most commonly used items.
 pub use format::{StoredArgon2Params, VaultHeader};
-pub use secret::{Secret, SecretMetadata};
+pub use secret::{Secret, SecretEncoding, SecretMetadata};
 pub use store::VaultStore;
@@ -4,10 +4,32 @@
 //! and creation/update timestamps.  The `encrypted_value` field uses
 //! custom serde helpers so it serializes as a base64 string in JSON
 //! rather than a raw byte array.
+//!
+//! An optional `encoding` hint records how the plaintext should be
+//! interpreted (base64, hex).  It is metadata only — the value is
+//! encrypted and stored exactly as given either way.
+//!
+//! `access_count` and `last_accessed` are only written when access
+//! tracking is on (`[telemetry] track_access = true`); vaults that never
+//! enable it keep the original layout.
+//!
+//! `description` and `tags` are free-form notes set with `set
+//! --description` and `set --tag`; like the encoding hint they are stored
+//! in plaintext, so they must not contain anything secret.
+//!
+//! `order` is the position given with `set --order`; `export --sort
+//! declared` and `edit --sort declared` list ordered secrets first.
+//!
+//! `chunk_size` marks a value written with `VaultStore::set_secret_reader`:
+//! its ciphertext is a STREAM of chunks (see
+//! `crypto::encryption::encrypt_stream`) rather than one nonce + ciphertext.
 
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::errors::{EnvVaultError, Result};
+
 // Re-use the base64 serde helpers from format.rs (no duplication).
 use super::format::{base64_decode, base64_encode};
 
@@ -27,15 +49,253 @@ pub struct Secret {
 
     /// When this secret was last updated.
     pub updated_at: DateTime<Utc>,
+
+    /// How the plaintext is encoded (`base64`, `hex`), if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+
+    /// How many times the value has been read, if access tracking is on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_count: Option<u64>,
+
+    /// When the value was last read, if access tracking is on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_accessed: Option<DateTime<Utc>>,
+
+    /// When the value should be rotated, if set with `set --expires`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+
+    /// Plaintext bytes per chunk if the value was encrypted as a stream.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunk_size: Option<u32>,
+
+    /// What the secret is for, if set with `set --description`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Labels set with `set --tag`, sorted and without duplicates.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Position among the vault's secrets, if set with `set --order`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<u32>,
 }
 
 /// Lightweight metadata about a secret (no encrypted value).
 ///
 /// Returned by `VaultStore::list_secrets` so callers can display
 /// secret names and timestamps without touching any ciphertext.
-#[derive(Debug, Clone)]
+/// Serializes to the objects of `list --json`: RFC 3339 timestamps, and
+/// unset optional fields left out.
+#[derive(Debug, Clone, Serialize)]
 pub struct SecretMetadata {
     pub name: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_accessed: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<u32>,
+}
+
+impl SecretMetadata {
+    /// Whether the secret's expiry has passed at `now`.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        is_expired(self.expires_at, now)
+    }
+
+    /// Whether the secret carries `tag`.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+/// Check and normalize `set --tag` values: each must be non-empty and
+/// free of whitespace and commas.  The result is sorted and deduplicated.
+pub fn normalize_tags<S: AsRef<str>>(tags: &[S]) -> Result<Vec<String>> {
+    let mut normalized = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.as_ref().trim();
+        if tag.is_empty() || tag.contains(|c: char| c.is_whitespace() || c == ',') {
+            return Err(EnvVaultError::CommandFailed(format!(
+                "invalid tag '{tag}' — tags must be non-empty, without spaces or commas"
+            )));
+        }
+        normalized.push(tag.to_string());
+    }
+    normalized.sort();
+    normalized.dedup();
+    Ok(normalized)
+}
+
+/// Whether an expiry of `expires_at` has passed at `now`.  A secret
+/// expires at that instant, not a moment later; no expiry never passes.
+pub fn is_expired(expires_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    expires_at.is_some_and(|at| at <= now)
+}
+
+/// Encoding hint for a secret's plaintext value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretEncoding {
+    Base64,
+    Hex,
+}
+
+impl SecretEncoding {
+    /// Parse an `--encoding` value.  `raw` means "no hint" and yields `None`.
+    pub fn parse(value: &str) -> Result<Option<Self>> {
+        match value.to_lowercase().as_str() {
+            "raw" => Ok(None),
+            "base64" => Ok(Some(Self::Base64)),
+            "hex" => Ok(Some(Self::Hex)),
+            other => Err(EnvVaultError::CommandFailed(format!(
+                "unknown encoding '{other}' — supported: raw, base64, hex"
+            ))),
+        }
+    }
+
+    /// Name stored in the vault.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Base64 => "base64",
+            Self::Hex => "hex",
+        }
+    }
+
+    /// Decode a value stored with this encoding.
+    pub fn decode(self, value: &str) -> Result<Vec<u8>> {
+        let value = value.trim();
+        let decoded = match self {
+            Self::Base64 => BASE64.decode(value).ok(),
+            Self::Hex => decode_hex(value),
+        };
+        decoded.ok_or_else(|| {
+            EnvVaultError::CommandFailed(format!("value is not valid {}", self.as_str()))
+        })
+    }
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if value.len() % 2 != 0 {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoding_parse() {
+        assert_eq!(SecretEncoding::parse("raw").unwrap(), None);
+        assert_eq!(
+            SecretEncoding::parse("Base64").unwrap(),
+            Some(SecretEncoding::Base64)
+        );
+        assert_eq!(
+            SecretEncoding::parse("hex").unwrap(),
+            Some(SecretEncoding::Hex)
+        );
+        assert!(SecretEncoding::parse("utf16").is_err());
+    }
+
+    #[test]
+    fn encoding_decode() {
+        assert_eq!(SecretEncoding::Base64.decode("aGk=").unwrap(), b"hi");
+        assert_eq!(SecretEncoding::Hex.decode("00ff10").unwrap(), [0, 255, 16]);
+        assert_eq!(
+            SecretEncoding::Hex.decode("DEADbeef\n").unwrap(),
+            [0xde, 0xad, 0xbe, 0xef]
+        );
+        assert!(SecretEncoding::Hex.decode("abc").is_err());
+        assert!(SecretEncoding::Hex.decode("zz").is_err());
+        assert!(SecretEncoding::Base64.decode("not base64!").is_err());
+    }
+
+    #[test]
+    fn encoding_is_omitted_when_unset() {
+        let secret = Secret {
+            name: "A".into(),
+            encrypted_value: vec![1, 2, 3],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            encoding: None,
+            access_count: None,
+            last_accessed: None,
+            expires_at: None,
+            chunk_size: None,
+            description: None,
+            tags: Vec::new(),
+            order: None,
+        };
+        let json = serde_json::to_string(&secret).unwrap();
+        assert!(!json.contains("encoding"), "{json}");
+        assert!(!json.contains("description"), "{json}");
+        assert!(!json.contains("tags"), "{json}");
+        assert!(!json.contains("order"), "{json}");
+        assert!(!json.contains("access"), "{json}");
+        assert!(!json.contains("expires"), "{json}");
+        assert!(!json.contains("chunk_size"), "{json}");
+
+        let parsed: Secret = serde_json::from_str(&json).unwrap();
+        assert!(parsed.encoding.is_none());
+    }
+
+    #[test]
+    fn secrets_from_older_vaults_have_no_expiry() {
+        let json = r#"{"name":"A","encrypted_value":"AQID","created_at":"2024-01-01T00:00:00Z","updated_at":"2024-01-01T00:00:00Z"}"#;
+        let parsed: Secret = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.expires_at, None);
+        assert_eq!(parsed.description, None);
+        assert!(parsed.tags.is_empty());
+        assert_eq!(parsed.order, None);
+
+        let mut with_expiry = parsed.clone();
+        with_expiry.expires_at = Some("2024-02-01T00:00:00Z".parse().unwrap());
+        let json = serde_json::to_string(&with_expiry).unwrap();
+        assert!(
+            json.contains(r#""expires_at":"2024-02-01T00:00:00Z""#),
+            "{json}"
+        );
+        let parsed: Secret = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.expires_at, with_expiry.expires_at);
+    }
+
+    #[test]
+    fn expiry_passes_at_the_expiry_instant() {
+        let at: DateTime<Utc> = "2024-02-01T00:00:00Z".parse().unwrap();
+        let second = chrono::Duration::seconds(1);
+        assert!(!is_expired(Some(at), at - second));
+        assert!(is_expired(Some(at), at));
+        assert!(is_expired(Some(at), at + second));
+        assert!(!is_expired(None, at));
+    }
+
+    #[test]
+    fn tags_are_sorted_deduplicated_and_checked() {
+        assert_eq!(
+            normalize_tags(&["payments", " db", "payments"]).unwrap(),
+            ["db", "payments"]
+        );
+        assert!(normalize_tags::<&str>(&[]).unwrap().is_empty());
+        assert!(normalize_tags(&[""]).is_err());
+        assert!(normalize_tags(&["two words"]).is_err());
+        assert!(normalize_tags(&["a,b"]).is_err());
+    }
 }
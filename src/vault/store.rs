@@ -5,19 +5,30 @@
 //! like `store.set_secret("DB_URL", "postgres://...")`.
 
 use std::collections::HashMap;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
-use chrono::Utc;
-use zeroize::Zeroize;
+use chrono::{DateTime, Duration, Utc};
+use zeroize::{Zeroize, Zeroizing};
 
-use crate::crypto::encryption::{decrypt, encrypt};
-use crate::crypto::kdf::{derive_master_key_with_params, generate_salt, Argon2Params};
+use crate::crypto::encryption::{
+    decrypt_stream, decrypt_with_aad, encrypt_stream, encrypt_with_aad, Cipher, STREAM_CHUNK_LEN,
+};
+use crate::crypto::kdf::{
+    derive_master_key_with_len, derive_master_key_with_params, generate_salt, Argon2Params,
+};
 use crate::crypto::keyfile;
 use crate::crypto::keys::MasterKey;
 use crate::errors::{EnvVaultError, Result};
+use crate::progress;
+use crate::timings;
 
-use super::format::{self, StoredArgon2Params, VaultHeader, CURRENT_VERSION};
-use super::secret::{Secret, SecretMetadata};
+use super::clock;
+use super::format::{
+    self, StoredArgon2Params, VaultHeader, AAD_VERSION, CURRENT_VERSION, DEFAULT_KEY_LEN,
+};
+use super::secret::{Secret, SecretEncoding, SecretMetadata};
 
 /// The main vault handle.  Create one with `VaultStore::create` or
 /// `VaultStore::open`, then use its methods to manage secrets.
@@ -33,6 +44,19 @@ pub struct VaultStore {
 
     /// The derived master key (zeroized on drop).
     master_key: MasterKey,
+
+    /// Cipher for secret values, from the header.
+    cipher: Cipher,
+
+    /// Set by `open_readonly`; mutating methods refuse to run.
+    readonly: bool,
+
+    /// Whether reads are counted (see [`VaultStore::set_track_access`]).
+    track_access: bool,
+
+    /// Reads since the last save: name -> (count, last read).  Behind a
+    /// mutex because `get_secret` takes `&self`.
+    pending_access: Mutex<HashMap<String, (u64, DateTime<Utc>)>>,
 }
 
 impl VaultStore {
@@ -57,12 +81,62 @@ impl VaultStore {
         environment: &str,
         argon2_params: Option<&Argon2Params>,
         keyfile_bytes: Option<&[u8]>,
+    ) -> Result<Self> {
+        Self::create_with_cipher(
+            path,
+            password,
+            environment,
+            argon2_params,
+            keyfile_bytes,
+            Cipher::default(),
+        )
+    }
+
+    /// Create a brand-new vault like [`VaultStore::create`], encrypting
+    /// secret values with `cipher` instead of the default AES-256-GCM.
+    ///
+    /// The cipher is recorded in the header, so `open` needs no hint.
+    pub fn create_with_cipher(
+        path: &Path,
+        password: &[u8],
+        environment: &str,
+        argon2_params: Option<&Argon2Params>,
+        keyfile_bytes: Option<&[u8]>,
+        cipher: Cipher,
+    ) -> Result<Self> {
+        let mut store = Self::create_staged(
+            path,
+            password,
+            environment,
+            argon2_params,
+            keyfile_bytes,
+            cipher,
+        )?;
+        store.save()?;
+        Ok(store)
+    }
+
+    /// Set up a brand-new vault like [`VaultStore::create_with_cipher`]
+    /// without writing anything: the file appears with the first `save`.
+    ///
+    /// Cross-vault commands stage every secret of the target in memory
+    /// this way, so a failure before or during that one save leaves no
+    /// half-filled vault behind.
+    pub fn create_staged(
+        path: &Path,
+        password: &[u8],
+        environment: &str,
+        argon2_params: Option<&Argon2Params>,
+        keyfile_bytes: Option<&[u8]>,
+        cipher: Cipher,
     ) -> Result<Self> {
         if path.exists() {
             return Err(EnvVaultError::VaultAlreadyExists(path.to_path_buf()));
         }
 
-        // 1. Generate a random salt.
+        // 1. Generate a random salt.  Callers cannot supply one: a shared
+        //    salt lets one precomputed table attack every vault built from
+        //    it (see "Deterministic vault files" in ROADMAP.md).
         let salt = generate_salt();
 
         // 2. Resolve Argon2 params (explicit or defaults).
@@ -92,22 +166,25 @@ impl VaultStore {
                 parallelism: effective_params.parallelism,
             }),
             keyfile_hash: kf_hash,
+            key_len: DEFAULT_KEY_LEN,
+            salt_len: salt.len(),
+            cipher: Some(cipher.as_str().to_string()),
+            rotated_at: None,
         };
 
         // 5. Start with an empty secrets map.
         let secrets = HashMap::new();
 
-        let mut store = Self {
+        Ok(Self {
             path: path.to_path_buf(),
             header,
             secrets,
             master_key,
-        };
-
-        // 6. Persist the empty vault to disk.
-        store.save()?;
-
-        Ok(store)
+            cipher,
+            readonly: false,
+            track_access: false,
+            pending_access: Mutex::default(),
+        })
     }
 
     /// Open an existing vault file, verifying its integrity.
@@ -120,45 +197,17 @@ impl VaultStore {
     /// provided. If the vault has no keyfile requirement, the parameter
     /// is ignored.
     pub fn open(path: &Path, password: &[u8], keyfile_bytes: Option<&[u8]>) -> Result<Self> {
-        // 1. Read the binary vault file (raw bytes preserved).
-        let raw = format::read_vault(path)?;
+        // 1. Read the binary vault file (raw bytes preserved).  Only the
+        //    header is parsed; the secrets JSON waits for the HMAC.
+        let raw = format::read_vault_unverified(path, format::max_secrets_len())?;
 
-        // 2. Validate keyfile requirement.
-        //    If the vault header has a keyfile_hash, a keyfile is required.
-        if let Some(ref expected_hash) = raw.header.keyfile_hash {
-            match keyfile_bytes {
-                Some(kf) => keyfile::verify_keyfile_hash(kf, expected_hash)?,
-                None => {
-                    return Err(EnvVaultError::KeyfileError(
-                        "this vault requires a keyfile — use --keyfile <path>".into(),
-                    ));
-                }
-            }
-        }
-
-        // 3. Combine password with keyfile (if provided) and derive master key.
-        let mut effective_password = match keyfile_bytes {
-            Some(kf) => keyfile::combine_password_keyfile(password, kf)?,
-            None => password.to_vec(),
-        };
-
-        // 4. Derive the master key using the stored Argon2 params.
-        //    Fall back to defaults for v0.1.0 vaults without stored params.
-        let stored = raw.header.argon2_params.unwrap_or_default();
-        let params = Argon2Params {
-            memory_kib: stored.memory_kib,
-            iterations: stored.iterations,
-            parallelism: stored.parallelism,
-        };
-        let mut master_bytes =
-            derive_master_key_with_params(&effective_password, &raw.header.salt, &params)?;
-        effective_password.zeroize();
-        let master_key = MasterKey::new(master_bytes);
-        master_bytes.zeroize();
+        // 2-4. Check the keyfile and derive the master key.
+        let master_key = Self::derive_master_key(&raw.header, password, keyfile_bytes)?;
 
-        // 3. Verify the HMAC over the *original raw bytes* from disk.
+        // 5. Verify the HMAC over the *original raw bytes* from disk.
         //    This avoids the re-serialization round-trip bug where
         //    serde_json might produce different byte output.
+        let verify = timings::phase(timings::HMAC_VERIFY);
         let mut hmac_key = master_key.derive_hmac_key()?;
         format::verify_hmac(
             &hmac_key,
@@ -167,8 +216,10 @@ impl VaultStore {
             &raw.stored_hmac,
         )?;
         hmac_key.zeroize();
+        drop(verify);
 
-        // 4. Build the in-memory map.
+        // 6. Parse the now-authenticated secrets and build the map.
+        let raw = raw.into_verified()?;
         let secrets: HashMap<String, Secret> = raw
             .secrets
             .into_iter()
@@ -177,23 +228,90 @@ impl VaultStore {
 
         Ok(Self {
             path: path.to_path_buf(),
+            cipher: raw.header.cipher()?,
             header: raw.header,
             secrets,
             master_key,
+            readonly: false,
+            track_access: false,
+            pending_access: Mutex::default(),
         })
     }
 
+    /// Derive the master key of a vault with `header` from `password`
+    /// and, if the vault requires one, `keyfile_bytes`.
+    ///
+    /// This is the first half of [`VaultStore::open`]; `verify` uses it
+    /// to check the HMAC of a file without decrypting anything.
+    pub fn derive_master_key(
+        header: &VaultHeader,
+        password: &[u8],
+        keyfile_bytes: Option<&[u8]>,
+    ) -> Result<MasterKey> {
+        // 1. Validate keyfile requirement.
+        //    If the vault header has a keyfile_hash, a keyfile is required.
+        if let Some(ref expected_hash) = header.keyfile_hash {
+            match keyfile_bytes {
+                Some(kf) => keyfile::verify_keyfile_hash(kf, expected_hash)?,
+                None => {
+                    return Err(EnvVaultError::KeyfileError(
+                        "this vault requires a keyfile — use --keyfile <path>".into(),
+                    ));
+                }
+            }
+        }
+
+        // 2. Combine password with keyfile (if provided).
+        let mut effective_password = match keyfile_bytes {
+            Some(kf) => keyfile::combine_password_keyfile(password, kf)?,
+            None => password.to_vec(),
+        };
+
+        // 3. Derive the master key using the stored Argon2 params.
+        //    Fall back to defaults for v0.1.0 vaults without stored params.
+        let params = Argon2Params::from(header.argon2_params.unwrap_or_default());
+        let kdf = timings::phase(timings::KDF);
+        let mut kdf_progress = progress::phase(progress::DERIVE_KEY, 1);
+        let master_bytes =
+            derive_master_key_with_len(&effective_password, &header.salt, &params, header.key_len)?;
+        kdf_progress.inc();
+        drop(kdf_progress);
+        drop(kdf);
+        effective_password.zeroize();
+        Ok(MasterKey::from_slice(&master_bytes))
+    }
+
+    /// Open an existing vault like [`VaultStore::open`], but refuse all
+    /// writes.
+    ///
+    /// `set_secret`, `delete_secret`, and `save` on the returned store fail
+    /// with `CommandFailed("read-only")` before touching memory or disk.
+    pub fn open_readonly(
+        path: &Path,
+        password: &[u8],
+        keyfile_bytes: Option<&[u8]>,
+    ) -> Result<Self> {
+        let mut store = Self::open(path, password, keyfile_bytes)?;
+        store.readonly = true;
+        Ok(store)
+    }
+
     /// Build a `VaultStore` from pre-constructed parts.
     ///
     /// Used by `rotate-key` to create a new store with a new master key
-    /// without writing to disk first.
-    pub fn from_parts(path: PathBuf, header: VaultHeader, master_key: MasterKey) -> Self {
-        Self {
+    /// without writing to disk first.  Secrets are encrypted with the
+    /// header's cipher; an unrecognized one is an error.
+    pub fn from_parts(path: PathBuf, header: VaultHeader, master_key: MasterKey) -> Result<Self> {
+        Ok(Self {
             path,
+            cipher: header.cipher()?,
             header,
             secrets: HashMap::new(),
             master_key,
-        }
+            readonly: false,
+            track_access: false,
+            pending_access: Mutex::default(),
+        })
     }
 
     // ------------------------------------------------------------------
@@ -206,42 +324,201 @@ impl VaultStore {
     /// from the master key + secret name.  The per-secret key is
     /// zeroized immediately after use.
     pub fn set_secret(&mut self, name: &str, plaintext_value: &str) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_secret_name(name)?;
 
         // Derive a unique encryption key for this secret name.
         let mut secret_key = self.master_key.derive_secret_key(name)?;
 
         // Encrypt the plaintext value.
-        let encrypted_value = encrypt(&secret_key, plaintext_value.as_bytes());
+        let encrypted_value = self.seal_value(name, &secret_key, plaintext_value.as_bytes(), None);
 
         // Zeroize the per-secret key immediately — we no longer need it.
         secret_key.zeroize();
 
         let encrypted_value = encrypted_value?;
 
+        self.insert_encrypted(name, encrypted_value, None);
+        Ok(())
+    }
+
+    /// Add or update a secret whose value is read from `reader`, without
+    /// holding the whole plaintext in memory.
+    ///
+    /// The value is encrypted as a stream of fixed-size chunks and marked
+    /// as such in the vault; its ciphertext is kept in memory and in the
+    /// vault file like any other.  Metadata is kept as by
+    /// [`VaultStore::set_secret`].  Returns the number of bytes read.
+    pub fn set_secret_reader(&mut self, name: &str, reader: impl Read) -> Result<u64> {
+        self.ensure_writable()?;
+        Self::validate_secret_name(name)?;
+
+        let mut secret_key = self.master_key.derive_secret_key(name)?;
+        let mut encrypted_value = Vec::new();
+        let read = encrypt_stream(
+            self.cipher,
+            &secret_key,
+            STREAM_CHUNK_LEN,
+            self.aad(name),
+            reader,
+            &mut encrypted_value,
+        );
+        secret_key.zeroize();
+        let read = read?;
+
+        self.insert_encrypted(name, encrypted_value, Some(STREAM_CHUNK_LEN as u32));
+        Ok(read)
+    }
+
+    /// Store an encrypted value under `name`, keeping the creation time,
+    /// encoding hint, access stats, expiry, description, tags, and order of
+    /// the secret it replaces.
+    fn insert_encrypted(&mut self, name: &str, encrypted_value: Vec<u8>, chunk_size: Option<u32>) {
         let now = Utc::now();
 
-        // If the secret already exists, preserve the original created_at.
-        let created_at = self
-            .secrets
-            .get(name)
-            .map_or(now, |existing| existing.created_at);
+        // If the secret already exists, preserve the original created_at
+        // and encoding hint.
+        let existing = self.secrets.get(name);
+        let created_at = existing.map_or(now, |existing| existing.created_at);
+        let encoding = existing.and_then(|existing| existing.encoding.clone());
+        let access_count = existing.and_then(|existing| existing.access_count);
+        let last_accessed = existing.and_then(|existing| existing.last_accessed);
+        let expires_at = existing.and_then(|existing| existing.expires_at);
+        let description = existing.and_then(|existing| existing.description.clone());
+        let tags = existing.map_or_else(Vec::new, |existing| existing.tags.clone());
+        let order = existing.and_then(|existing| existing.order);
 
         let secret = Secret {
             name: name.to_string(),
             encrypted_value,
             created_at,
             updated_at: now,
+            encoding,
+            access_count,
+            last_accessed,
+            expires_at,
+            chunk_size,
+            description,
+            tags,
+            order,
         };
 
         self.secrets.insert(name.to_string(), secret);
+    }
+
+    /// Add or update a secret like [`VaultStore::set_secret`] and set its
+    /// expiry; `None` clears it.
+    ///
+    /// `set_secret` alone keeps whatever expiry the secret already had.
+    pub fn set_secret_with_expiry(
+        &mut self,
+        name: &str,
+        plaintext_value: &str,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        self.set_secret(name, plaintext_value)?;
+        if let Some(secret) = self.secrets.get_mut(name) {
+            secret.expires_at = expires_at;
+        }
+        Ok(())
+    }
+
+    /// Add or replace a secret, taking its timestamps, encoding hint,
+    /// access stats, description, tags, and order from `meta` instead of
+    /// stamping them with "now".
+    ///
+    /// For flows that rebuild a vault from another one (`rotate-key`,
+    /// `env clone`): the value is encrypted under this store's key, but
+    /// when it was introduced and last changed carries over.  Use
+    /// [`VaultStore::copy_secret_from`] to also keep the stored layout of
+    /// streamed values.
+    pub fn set_secret_with_metadata(
+        &mut self,
+        meta: &SecretMetadata,
+        plaintext_value: &str,
+    ) -> Result<()> {
+        self.insert_with_metadata(meta, plaintext_value.as_bytes(), None)
+    }
+
+    /// Re-encrypt `meta.name` from `source` under this store's key, with
+    /// the metadata in `meta` (see [`VaultStore::set_secret_with_metadata`]).
+    ///
+    /// The value is copied as bytes, so it need not be UTF-8, and a value
+    /// written with [`VaultStore::set_secret_reader`] stays chunked.
+    pub fn copy_secret_from(&mut self, source: &VaultStore, meta: &SecretMetadata) -> Result<()> {
+        let secret = source
+            .secrets
+            .get(&meta.name)
+            .ok_or_else(|| EnvVaultError::SecretNotFound(meta.name.clone()))?;
+
+        let decrypt = timings::phase(timings::DECRYPT_ALL);
+        let mut source_key = source.master_key.derive_secret_key(&meta.name)?;
+        let plaintext = source.open_value(secret, &source_key);
+        source_key.zeroize();
+        let plaintext = Zeroizing::new(plaintext?);
+        drop(decrypt);
+
+        self.insert_with_metadata(meta, &plaintext, secret.chunk_size)
+    }
+
+    fn insert_with_metadata(
+        &mut self,
+        meta: &SecretMetadata,
+        plaintext: &[u8],
+        chunk_size: Option<u32>,
+    ) -> Result<()> {
+        self.ensure_writable()?;
+        Self::validate_secret_name(&meta.name)?;
+
+        let mut secret_key = self.master_key.derive_secret_key(&meta.name)?;
+        let encrypted_value = self.seal_value(&meta.name, &secret_key, plaintext, chunk_size);
+        secret_key.zeroize();
+        let encrypted_value = encrypted_value?;
+
+        let secret = Secret {
+            name: meta.name.clone(),
+            encrypted_value,
+            created_at: meta.created_at,
+            updated_at: meta.updated_at,
+            encoding: meta.encoding.clone(),
+            access_count: meta.access_count,
+            last_accessed: meta.last_accessed,
+            expires_at: meta.expires_at,
+            chunk_size,
+            description: meta.description.clone(),
+            tags: meta.tags.clone(),
+            order: meta.order,
+        };
+
+        self.secrets.insert(meta.name.clone(), secret);
         Ok(())
     }
 
     /// Decrypt and return the plaintext value of a secret.
     ///
-    /// The per-secret key is zeroized after decryption.
+    /// The per-secret key is zeroized after decryption.  Fails, naming the
+    /// secret, if the value is not UTF-8 (binary data stored with
+    /// [`VaultStore::set_secret_reader`]); read those with
+    /// [`VaultStore::get_secret_bytes`] instead.
     pub fn get_secret(&self, name: &str) -> Result<String> {
+        let mut plaintext_bytes = self.get_secret_bytes(name)?;
+
+        // Take the bytes out of the wrapper rather than copying them; on
+        // error, zeroize the bytes inside the error before discarding.
+        String::from_utf8(std::mem::take(&mut *plaintext_bytes)).map_err(|e| {
+            let mut bad_bytes = e.into_bytes();
+            bad_bytes.zeroize();
+            EnvVaultError::SerializationError(format!(
+                "secret '{name}' holds binary data (not valid UTF-8) and cannot be read as text"
+            ))
+        })
+    }
+
+    /// Decrypt and return the raw bytes of a secret's value, UTF-8 or not.
+    ///
+    /// The bytes are zeroized on drop.  Counts as a read like
+    /// [`VaultStore::get_secret`].
+    pub fn get_secret_bytes(&self, name: &str) -> Result<Zeroizing<Vec<u8>>> {
         Self::validate_secret_name(name)?;
         let secret = self
             .secrets
@@ -249,20 +526,213 @@ impl VaultStore {
             .ok_or_else(|| EnvVaultError::SecretNotFound(name.to_string()))?;
 
         let mut secret_key = self.master_key.derive_secret_key(name)?;
-        let plaintext_bytes = decrypt(&secret_key, &secret.encrypted_value)?;
+        let plaintext_bytes = self.open_value(secret, &secret_key);
         secret_key.zeroize();
+        let plaintext_bytes = Zeroizing::new(plaintext_bytes?);
 
-        // Convert to String via from_utf8 which takes ownership (no clone).
-        // On error, zeroize the bytes inside the error before discarding.
-        String::from_utf8(plaintext_bytes).map_err(|e| {
-            let mut bad_bytes = e.into_bytes();
-            bad_bytes.zeroize();
-            EnvVaultError::SerializationError("secret value is not valid UTF-8".to_string())
-        })
+        if self.track_access {
+            self.note_access(name);
+        }
+        Ok(plaintext_bytes)
+    }
+
+    /// Decrypt a secret's value into `writer`, a chunk at a time for values
+    /// stored with [`VaultStore::set_secret_reader`].
+    ///
+    /// The value need not be UTF-8.  If decryption fails part-way,
+    /// `writer` may already hold some of the plaintext and must be
+    /// discarded.  Returns the number of bytes written.
+    pub fn get_secret_writer(&self, name: &str, mut writer: impl Write) -> Result<u64> {
+        Self::validate_secret_name(name)?;
+        let secret = self
+            .secrets
+            .get(name)
+            .ok_or_else(|| EnvVaultError::SecretNotFound(name.to_string()))?;
+
+        let mut secret_key = self.master_key.derive_secret_key(name)?;
+        let written = match secret.chunk_size {
+            Some(chunk_size) => decrypt_stream(
+                self.cipher,
+                &secret_key,
+                chunk_size as usize,
+                self.aad(name),
+                secret.encrypted_value.as_slice(),
+                &mut writer,
+            ),
+            None => decrypt_with_aad(
+                self.cipher,
+                &secret_key,
+                &secret.encrypted_value,
+                self.aad(name),
+            )
+            .and_then(|mut plaintext| {
+                let written = writer
+                    .write_all(&plaintext)
+                    .map(|()| plaintext.len() as u64);
+                plaintext.zeroize();
+                Ok(written?)
+            }),
+        };
+        secret_key.zeroize();
+        let written = written?;
+
+        if self.track_access {
+            self.note_access(name);
+        }
+        Ok(written)
+    }
+
+    /// Associated data binding a value to the secret `name`: the name
+    /// itself from format version 2 on, nothing in version 1 vaults.
+    fn aad<'a>(&self, name: &'a str) -> &'a [u8] {
+        if self.header.version >= AAD_VERSION {
+            name.as_bytes()
+        } else {
+            &[]
+        }
+    }
+
+    /// Decrypt a stored value, whether it was encrypted in one piece or
+    /// as a stream.
+    fn open_value(&self, secret: &Secret, secret_key: &[u8]) -> Result<Vec<u8>> {
+        let aad = self.aad(&secret.name);
+        match secret.chunk_size {
+            Some(chunk_size) => {
+                let mut plaintext = Vec::new();
+                let result = decrypt_stream(
+                    self.cipher,
+                    secret_key,
+                    chunk_size as usize,
+                    aad,
+                    secret.encrypted_value.as_slice(),
+                    &mut plaintext,
+                );
+                if let Err(e) = result {
+                    plaintext.zeroize();
+                    return Err(e);
+                }
+                Ok(plaintext)
+            }
+            None => decrypt_with_aad(self.cipher, secret_key, &secret.encrypted_value, aad),
+        }
+    }
+
+    /// Encrypt `plaintext` as the value of `name` in the layout given by
+    /// `chunk_size`.
+    fn seal_value(
+        &self,
+        name: &str,
+        secret_key: &[u8],
+        plaintext: &[u8],
+        chunk_size: Option<u32>,
+    ) -> Result<Vec<u8>> {
+        let aad = self.aad(name);
+        match chunk_size {
+            Some(chunk_size) => {
+                let mut sealed = Vec::new();
+                encrypt_stream(
+                    self.cipher,
+                    secret_key,
+                    chunk_size as usize,
+                    aad,
+                    plaintext,
+                    &mut sealed,
+                )?;
+                Ok(sealed)
+            }
+            None => encrypt_with_aad(self.cipher, secret_key, plaintext, aad),
+        }
+    }
+
+    /// Set or clear the encoding hint of an existing secret.
+    ///
+    /// Metadata only: the stored value is not re-encoded.
+    pub fn set_encoding(&mut self, name: &str, encoding: Option<SecretEncoding>) -> Result<()> {
+        self.ensure_writable()?;
+        let secret = self
+            .secrets
+            .get_mut(name)
+            .ok_or_else(|| EnvVaultError::SecretNotFound(name.to_string()))?;
+        secret.encoding = encoding.map(|e| e.as_str().to_string());
+        Ok(())
+    }
+
+    /// Set or clear the description of an existing secret.
+    pub fn set_description(&mut self, name: &str, description: Option<String>) -> Result<()> {
+        self.ensure_writable()?;
+        let secret = self
+            .secrets
+            .get_mut(name)
+            .ok_or_else(|| EnvVaultError::SecretNotFound(name.to_string()))?;
+        secret.description = description;
+        Ok(())
+    }
+
+    /// Replace the tags of an existing secret (normalized as by
+    /// [`normalize_tags`](super::secret::normalize_tags)).
+    pub fn set_tags<S: AsRef<str>>(&mut self, name: &str, tags: &[S]) -> Result<()> {
+        self.ensure_writable()?;
+        let tags = super::secret::normalize_tags(tags)?;
+        let secret = self
+            .secrets
+            .get_mut(name)
+            .ok_or_else(|| EnvVaultError::SecretNotFound(name.to_string()))?;
+        secret.tags = tags;
+        Ok(())
+    }
+
+    /// Set or clear the `set --order` position of an existing secret.
+    pub fn set_order(&mut self, name: &str, order: Option<u32>) -> Result<()> {
+        self.ensure_writable()?;
+        let secret = self
+            .secrets
+            .get_mut(name)
+            .ok_or_else(|| EnvVaultError::SecretNotFound(name.to_string()))?;
+        secret.order = order;
+        Ok(())
+    }
+
+    /// Secret names in declared order: secrets with an `order` first, by
+    /// position, then the rest in the order they were created.  Ties go
+    /// by name.
+    pub fn declared_order(&self) -> Vec<String> {
+        let mut secrets: Vec<&Secret> = self.secrets.values().collect();
+        secrets.sort_by(|a, b| {
+            (a.order.is_none(), a.order, a.created_at, &a.name).cmp(&(
+                b.order.is_none(),
+                b.order,
+                b.created_at,
+                &b.name,
+            ))
+        });
+        secrets.into_iter().map(|s| s.name.clone()).collect()
+    }
+
+    /// When a secret expires, if it has an expiry.
+    pub fn secret_expires_at(&self, name: &str) -> Option<DateTime<Utc>> {
+        self.secrets.get(name)?.expires_at
+    }
+
+    /// Names of secrets whose expiry has passed at `now`, sorted.
+    pub fn expired(&self, now: DateTime<Utc>) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .secrets
+            .values()
+            .filter(|s| super::secret::is_expired(s.expires_at, now))
+            .map(|s| s.name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// The encoding hint stored for a secret, if any.
+    pub fn secret_encoding(&self, name: &str) -> Option<&str> {
+        self.secrets.get(name)?.encoding.as_deref()
     }
 
     /// Remove a secret from the vault.
     pub fn delete_secret(&mut self, name: &str) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_secret_name(name)?;
         if self.secrets.remove(name).is_none() {
             return Err(EnvVaultError::SecretNotFound(name.to_string()));
@@ -270,6 +740,69 @@ impl VaultStore {
         Ok(())
     }
 
+    /// Rename a secret, keeping its value, `created_at`, and encoding hint.
+    ///
+    /// Per-secret keys are bound to the name, so the value is decrypted
+    /// and re-encrypted under the key derived for `new`.  Both keys are
+    /// zeroized after use.
+    ///
+    /// Fails with `SecretAlreadyExists` if `new` is taken; see
+    /// [`VaultStore::rename_secret_replacing`].
+    pub fn rename_secret(&mut self, old: &str, new: &str) -> Result<()> {
+        self.rename_secret_inner(old, new, false).map(|_| ())
+    }
+
+    /// Like [`VaultStore::rename_secret`], but an existing `new` is
+    /// replaced.  Returns whether one was.
+    pub fn rename_secret_replacing(&mut self, old: &str, new: &str) -> Result<bool> {
+        self.rename_secret_inner(old, new, true)
+    }
+
+    fn rename_secret_inner(&mut self, old: &str, new: &str, replace: bool) -> Result<bool> {
+        self.ensure_writable()?;
+        Self::validate_secret_name(old)?;
+        Self::validate_secret_name(new)?;
+        let secret = self
+            .secrets
+            .get(old)
+            .ok_or_else(|| EnvVaultError::SecretNotFound(old.to_string()))?;
+        // Renaming a secret onto itself is never a replacement.
+        let replaced = self.secrets.contains_key(new);
+        if replaced && (!replace || old == new) {
+            return Err(EnvVaultError::SecretAlreadyExists(new.to_string()));
+        }
+
+        let mut old_key = self.master_key.derive_secret_key(old)?;
+        let plaintext = self.open_value(secret, &old_key);
+        old_key.zeroize();
+        let mut plaintext = plaintext?;
+
+        let mut new_key = self.master_key.derive_secret_key(new)?;
+        let encrypted_value = self.seal_value(new, &new_key, &plaintext, secret.chunk_size);
+        new_key.zeroize();
+        plaintext.zeroize();
+        let encrypted_value = encrypted_value?;
+
+        let mut secret = self
+            .secrets
+            .remove(old)
+            .ok_or_else(|| EnvVaultError::SecretNotFound(old.to_string()))?;
+        secret.name = new.to_string();
+        secret.encrypted_value = encrypted_value;
+        secret.updated_at = Utc::now();
+        self.secrets.insert(new.to_string(), secret);
+
+        // Reads recorded under the old name follow the secret; those of a
+        // replaced secret are dropped with it.
+        if let Ok(mut pending) = self.pending_access.lock() {
+            pending.remove(new);
+            if let Some(entry) = pending.remove(old) {
+                pending.insert(new.to_string(), entry);
+            }
+        }
+        Ok(replaced)
+    }
+
     /// List metadata for all secrets, sorted by name.
     pub fn list_secrets(&self) -> Vec<SecretMetadata> {
         let mut list: Vec<SecretMetadata> = self
@@ -279,6 +812,13 @@ impl VaultStore {
                 name: s.name.clone(),
                 created_at: s.created_at,
                 updated_at: s.updated_at,
+                encoding: s.encoding.clone(),
+                access_count: s.access_count,
+                last_accessed: s.last_accessed,
+                expires_at: s.expires_at,
+                description: s.description.clone(),
+                tags: s.tags.clone(),
+                order: s.order,
             })
             .collect();
 
@@ -286,15 +826,89 @@ impl VaultStore {
         list
     }
 
+    /// The newest timestamp in the vault (header or any secret).
+    pub fn latest_timestamp(&self) -> DateTime<Utc> {
+        self.secrets
+            .values()
+            .flat_map(|s| [s.created_at, s.updated_at])
+            .fold(self.header.created_at, DateTime::max)
+    }
+
+    /// How far `now` is behind the vault's newest timestamp, if by more
+    /// than `tolerance` — a sign the system clock is wrong.
+    pub fn clock_skew(&self, now: DateTime<Utc>, tolerance: Duration) -> Option<Duration> {
+        clock::clock_behind(now, self.latest_timestamp(), tolerance)
+    }
+
+    /// When the master key was last set: the last `rotate-key`, or the
+    /// vault's creation if it was never rotated.
+    pub fn key_set_at(&self) -> DateTime<Utc> {
+        self.header.rotated_at.unwrap_or(self.header.created_at)
+    }
+
+    /// The master key's age in whole days, if it is at least
+    /// `rotate_after_days` old.  0 never reports.
+    pub fn rotation_due(&self, now: DateTime<Utc>, rotate_after_days: u64) -> Option<i64> {
+        let age = now.signed_duration_since(self.key_set_at()).num_days();
+        (rotate_after_days > 0 && u64::try_from(age).is_ok_and(|age| age >= rotate_after_days))
+            .then_some(age)
+    }
+
+    /// Names of secrets with a created or updated time later than `now`
+    /// by more than `tolerance`, sorted.
+    pub fn future_dated(&self, now: DateTime<Utc>, tolerance: Duration) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .secrets
+            .values()
+            .filter(|s| {
+                clock::is_future(s.created_at, now, tolerance)
+                    || clock::is_future(s.updated_at, now, tolerance)
+            })
+            .map(|s| s.name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Clamp every timestamp later than `now` (beyond `tolerance`) to
+    /// `now`, including the vault's own `created_at`.
+    ///
+    /// Returns how many timestamps were changed.  Call `save()` to persist.
+    pub fn clamp_future_timestamps(
+        &mut self,
+        now: DateTime<Utc>,
+        tolerance: Duration,
+    ) -> Result<usize> {
+        self.ensure_writable()?;
+
+        let mut fixed = 0;
+        let mut clamp = |ts: &mut DateTime<Utc>| {
+            if clock::is_future(*ts, now, tolerance) {
+                *ts = now;
+                fixed += 1;
+            }
+        };
+
+        clamp(&mut self.header.created_at);
+        for secret in self.secrets.values_mut() {
+            clamp(&mut secret.created_at);
+            clamp(&mut secret.updated_at);
+        }
+        Ok(fixed)
+    }
+
     /// Decrypt all secrets and return them as a name -> plaintext map.
     ///
     /// Used by the `run` command to inject secrets into a child process.
     pub fn get_all_secrets(&self) -> Result<HashMap<String, String>> {
+        let _decrypt = timings::phase(timings::DECRYPT_ALL);
+        let mut progress = progress::phase(progress::DECRYPT, self.secrets.len());
         let mut map = HashMap::with_capacity(self.secrets.len());
 
         for name in self.secrets.keys() {
             let value = self.get_secret(name)?;
             map.insert(name.clone(), value);
+            progress.inc();
         }
 
         Ok(map)
@@ -309,6 +923,10 @@ impl VaultStore {
     /// Computes a fresh HMAC over the header + secrets JSON and writes
     /// the full binary envelope via temp-file + rename.
     pub fn save(&mut self) -> Result<()> {
+        self.ensure_writable()?;
+        let mut progress = progress::phase(progress::SAVE, 1);
+        self.apply_pending_access();
+
         // Collect secrets into a sorted Vec for deterministic output.
         let mut secret_list: Vec<Secret> = self.secrets.values().cloned().collect();
         secret_list.sort_by(|a, b| a.name.cmp(&b.name));
@@ -317,14 +935,63 @@ impl VaultStore {
 
         format::write_vault(&self.path, &self.header, &secret_list, &hmac_key)?;
         hmac_key.zeroize();
+        progress.inc();
 
         Ok(())
     }
 
+    // ------------------------------------------------------------------
+    // Access tracking
+    // ------------------------------------------------------------------
+
+    /// Count reads from `get_secret` / `get_all_secrets` from now on.
+    ///
+    /// Counts are kept in memory and written to `access_count` /
+    /// `last_accessed` on the next `save`, so enabling this turns reads
+    /// into writes.  Read-only stores never track.
+    pub fn set_track_access(&mut self, enabled: bool) {
+        self.track_access = enabled && !self.readonly;
+    }
+
+    /// Whether there are counted reads that `save` has not written yet.
+    pub fn has_pending_access(&self) -> bool {
+        self.pending_access
+            .lock()
+            .map(|pending| !pending.is_empty())
+            .unwrap_or(false)
+    }
+
+    fn note_access(&self, name: &str) {
+        if let Ok(mut pending) = self.pending_access.lock() {
+            let entry = pending.entry(name.to_string()).or_insert((0, Utc::now()));
+            entry.0 += 1;
+            entry.1 = Utc::now();
+        }
+    }
+
+    /// Fold counted reads into the secrets before they are written.
+    fn apply_pending_access(&mut self) {
+        let pending = match self.pending_access.get_mut() {
+            Ok(pending) => std::mem::take(pending),
+            Err(poisoned) => std::mem::take(poisoned.into_inner()),
+        };
+        for (name, (count, at)) in pending {
+            if let Some(secret) = self.secrets.get_mut(&name) {
+                secret.access_count = Some(secret.access_count.unwrap_or(0) + count);
+                secret.last_accessed = Some(at);
+            }
+        }
+    }
+
     // ------------------------------------------------------------------
     // Accessors
     // ------------------------------------------------------------------
 
+    /// Whether this store was opened with `open_readonly`.
+    pub fn is_readonly(&self) -> bool {
+        self.readonly
+    }
+
     /// Returns the path to the vault file.
     pub fn path(&self) -> &Path {
         &self.path
@@ -345,6 +1012,18 @@ impl VaultStore {
         self.header.created_at
     }
 
+    /// Returns the binary format version recorded in the header.
+    pub fn format_version(&self) -> u8 {
+        self.header.version
+    }
+
+    /// Returns `true` if the header predates stored Argon2 params (v0.1.0
+    /// vaults), so the key is derived with the built-in defaults until the
+    /// next `rotate-key` records them.
+    pub fn kdf_params_implicit(&self) -> bool {
+        self.header.argon2_params.is_none()
+    }
+
     /// Returns `true` if the vault contains a secret with the given name.
     ///
     /// This is a metadata-only check — no decryption is performed.
@@ -359,15 +1038,28 @@ impl VaultStore {
         &self.header
     }
 
+    /// The cipher secret values are encrypted with.
+    pub fn cipher(&self) -> Cipher {
+        self.cipher
+    }
+
     // ------------------------------------------------------------------
     // Validation
     // ------------------------------------------------------------------
 
+    /// Refuse mutation on a store opened with `open_readonly`.
+    fn ensure_writable(&self) -> Result<()> {
+        if self.readonly {
+            return Err(EnvVaultError::CommandFailed("read-only".into()));
+        }
+        Ok(())
+    }
+
     /// Validate that a secret name is safe.
     ///
     /// Allowed: ASCII letters, digits, underscores, hyphens, periods.
     /// Must be non-empty and at most 256 characters.
-    fn validate_secret_name(name: &str) -> Result<()> {
+    pub fn validate_secret_name(name: &str) -> Result<()> {
         if name.is_empty() {
             return Err(EnvVaultError::CommandFailed(
                 "secret name cannot be empty".into(),
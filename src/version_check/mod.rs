@@ -2,10 +2,22 @@
 //!
 //! Behind the `version-check` feature flag. Caches results for 24 hours
 //! in `~/.config/envvault/version-check.json`. Never fails — returns `None`
-//! on any error.
+//! on any error, including a registry that does not answer within
+//! [`CONNECT_TIMEOUT`] / [`RESPONSE_TIMEOUT`].  [`lookup_latest`] returns
+//! the reason instead, for `version --verbose`.
+//!
+//! The registry can be swapped for a crates.io-compatible mirror with
+//! `$ENVVAULT_REGISTRY_URL` or `[update] registry_url` (project config
+//! first, then the global config).  `[update] check_url` replaces the whole
+//! endpoint with any URL that answers with the same JSON shape
+//! (`{"crate": {"max_version": "..."}}`).
+//!
+//! Requests go through `$HTTPS_PROXY` / `$HTTP_PROXY` (or `$ALL_PROXY`)
+//! when set, honouring `$NO_PROXY`.
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -13,11 +25,42 @@ use serde::{Deserialize, Serialize};
 /// How long to cache the version check result.
 const CACHE_TTL_HOURS: i64 = 24;
 
+/// Longest wait for the registry connection (TCP plus TLS handshake).
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Longest wait for each of the response headers and the response body.
+pub const RESPONSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Upper bound on the whole request, DNS lookup included.
+pub const TOTAL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Registry queried when no override is configured.
+pub const DEFAULT_REGISTRY_URL: &str = "https://crates.io";
+
+/// Environment variable that overrides the registry URL.
+pub const REGISTRY_URL_ENV: &str = "ENVVAULT_REGISTRY_URL";
+
+/// Whether this binary can query the registry at all.
+pub const ENABLED: bool = cfg!(feature = "version-check");
+
+/// Where the version check gets its JSON from; a seam for tests.
+trait Fetch {
+    fn get_json(&self, url: &str) -> Result<serde_json::Value, String>;
+}
+
 /// Cached version check result.
 #[derive(Serialize, Deserialize)]
 struct CachedVersion {
     latest: String,
     checked_at: DateTime<Utc>,
+    /// Registry (or `check_url`) the result came from (absent in caches
+    /// written before mirrors were supported, which always used crates.io).
+    #[serde(default = "default_registry")]
+    registry: String,
+}
+
+fn default_registry() -> String {
+    DEFAULT_REGISTRY_URL.to_string()
 }
 
 /// Check for the latest version of envvault on crates.io.
@@ -25,51 +68,151 @@ struct CachedVersion {
 /// Returns `Some(version_string)` if a newer version is available,
 /// or `None` if already up-to-date or on any error.
 pub fn check_latest_version(current: &str) -> Option<String> {
-    // Try cache first.
-    if let Some(cached) = read_cache() {
-        let age = Utc::now() - cached.checked_at;
-        if age.num_hours() < CACHE_TTL_HOURS {
-            return if cached.latest == current {
-                None
-            } else {
-                Some(cached.latest)
-            };
+    latest_version().filter(|latest| latest != current)
+}
+
+/// The latest published version, from the cache or the registry.
+///
+/// Returns `None` if it can't be determined (offline, or the
+/// `version-check` feature is disabled).
+pub fn latest_version() -> Option<String> {
+    lookup_latest(false).ok()
+}
+
+/// Like [`latest_version`], but says why the version could not be
+/// determined.  `refresh` skips the 24-hour cache.
+pub fn lookup_latest(refresh: bool) -> Result<String, String> {
+    let (url, source) = match check_url() {
+        Some(url) => (url.clone(), url),
+        None => {
+            let registry = registry_url();
+            (crate_endpoint(&registry), registry)
+        }
+    };
+    lookup_with(&HttpFetch, &url, &source, refresh, cache_path().as_deref())
+}
+
+/// Look up the latest version at `url`, using the cache at `cache` unless
+/// `refresh` is set.  Cached results only count if they came from `source`.
+fn lookup_with(
+    fetch: &dyn Fetch,
+    url: &str,
+    source: &str,
+    refresh: bool,
+    cache: Option<&Path>,
+) -> Result<String, String> {
+    if !refresh {
+        if let Some(cached) = cache.and_then(read_cache) {
+            let age = Utc::now() - cached.checked_at;
+            if age.num_hours() < CACHE_TTL_HOURS && cached.registry == source {
+                return Ok(cached.latest);
+            }
         }
     }
 
-    // Fetch from crates.io.
-    let latest = fetch_latest_version()?;
+    let latest = fetch_latest_version(fetch, url)?;
 
     // Cache the result (fire-and-forget).
-    let _ = write_cache(&latest);
-
-    if latest == current {
-        None
-    } else {
-        Some(latest)
+    if let Some(cache) = cache {
+        let _ = write_cache(cache, &latest, source);
     }
+
+    Ok(latest)
+}
+
+/// `[update] check_url` from the project config, then the global config.
+fn check_url() -> Option<String> {
+    let project = std::env::current_dir()
+        .ok()
+        .and_then(|dir| crate::config::Settings::load(&dir).ok())
+        .and_then(|settings| settings.update.check_url);
+    project
+        .or_else(|| crate::config::GlobalConfig::load().update.check_url)
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+}
+
+/// The registry to query: `$ENVVAULT_REGISTRY_URL`, then `[update]
+/// registry_url` from the project and global config, then crates.io.
+pub fn registry_url() -> String {
+    let project = std::env::current_dir()
+        .ok()
+        .and_then(|dir| crate::config::Settings::load(&dir).ok())
+        .and_then(|settings| settings.update.registry_url);
+    let global = crate::config::GlobalConfig::load().update.registry_url;
+
+    resolve_registry_url(std::env::var(REGISTRY_URL_ENV).ok(), project.or(global))
+}
+
+/// Pick the first non-empty override, falling back to crates.io.
+/// Trailing slashes are dropped so the API path can be appended.
+fn resolve_registry_url(env: Option<String>, config: Option<String>) -> String {
+    [env, config]
+        .into_iter()
+        .flatten()
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .find(|url| !url.is_empty())
+        .unwrap_or_else(default_registry)
+}
+
+/// crates.io API endpoint for envvault on `registry`.
+fn crate_endpoint(registry: &str) -> String {
+    format!("{registry}/api/v1/crates/envvault")
 }
 
-/// Fetch the latest version from crates.io API.
+/// HTTP agent with the timeouts above, so a hung network or registry
+/// makes the check fail fast instead of stalling `version`, and with the
+/// proxy from the environment.
 #[cfg(feature = "version-check")]
-fn fetch_latest_version() -> Option<String> {
-    let resp = ureq::get("https://crates.io/api/v1/crates/envvault")
-        .header(
-            "User-Agent",
-            &format!("envvault/{}", env!("CARGO_PKG_VERSION")),
-        )
-        .call()
-        .ok()?;
+fn agent() -> ureq::Agent {
+    ureq::Agent::config_builder()
+        .timeout_global(Some(TOTAL_TIMEOUT))
+        .timeout_connect(Some(CONNECT_TIMEOUT))
+        .timeout_recv_response(Some(RESPONSE_TIMEOUT))
+        .timeout_recv_body(Some(RESPONSE_TIMEOUT))
+        .proxy(ureq::Proxy::try_from_env())
+        .build()
+        .into()
+}
 
-    let body: serde_json::Value = resp.into_body().read_json().ok()?;
-    let version = body.get("crate")?.get("max_version")?.as_str()?.to_string();
+/// The real network, through [`agent`].
+struct HttpFetch;
 
-    Some(version)
+#[cfg(feature = "version-check")]
+impl Fetch for HttpFetch {
+    fn get_json(&self, url: &str) -> Result<serde_json::Value, String> {
+        let resp = agent()
+            .get(url)
+            .header(
+                "User-Agent",
+                &format!("envvault/{}", env!("CARGO_PKG_VERSION")),
+            )
+            .call()
+            .map_err(|e| format!("{url}: {e}"))?;
+        resp.into_body()
+            .read_json()
+            .map_err(|e| format!("{url}: invalid JSON: {e}"))
+    }
 }
 
 #[cfg(not(feature = "version-check"))]
-fn fetch_latest_version() -> Option<String> {
-    None
+impl Fetch for HttpFetch {
+    fn get_json(&self, _url: &str) -> Result<serde_json::Value, String> {
+        Err(
+            crate::errors::EnvVaultError::FeatureDisabled(crate::features::VERSION_CHECK)
+                .to_string(),
+        )
+    }
+}
+
+/// Fetch `url` and read the latest version from its crates API response.
+fn fetch_latest_version(fetch: &dyn Fetch, url: &str) -> Result<String, String> {
+    let body = fetch.get_json(url)?;
+    body.get("crate")
+        .and_then(|c| c.get("max_version"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| format!("{url}: response has no crate.max_version"))
 }
 
 /// Path to the cache file.
@@ -79,7 +222,7 @@ fn cache_path() -> Option<PathBuf> {
 }
 
 /// Get the envvault config directory.
-fn dirs_cache_path() -> Option<PathBuf> {
+pub(crate) fn dirs_cache_path() -> Option<PathBuf> {
     // Use $HOME/.config/envvault on all platforms.
     let home = std::env::var("HOME")
         .or_else(|_| std::env::var("USERPROFILE"))
@@ -88,17 +231,14 @@ fn dirs_cache_path() -> Option<PathBuf> {
     Some(path)
 }
 
-/// Read the cached version check.
-fn read_cache() -> Option<CachedVersion> {
-    let path = cache_path()?;
+/// Read the cached version check at `path`.
+fn read_cache(path: &Path) -> Option<CachedVersion> {
     let content = fs::read_to_string(path).ok()?;
     serde_json::from_str(&content).ok()
 }
 
-/// Write a version check result to cache.
-fn write_cache(version: &str) -> Option<()> {
-    let path = cache_path()?;
-
+/// Write a version check result to the cache at `path`.
+fn write_cache(path: &Path, version: &str, registry: &str) -> Option<()> {
     // Create the directory if needed.
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).ok()?;
@@ -107,6 +247,7 @@ fn write_cache(version: &str) -> Option<()> {
     let cached = CachedVersion {
         latest: version.to_string(),
         checked_at: Utc::now(),
+        registry: registry.to_string(),
     };
 
     let content = serde_json::to_string_pretty(&cached).ok()?;
@@ -118,6 +259,29 @@ fn write_cache(version: &str) -> Option<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::Cell;
+
+    /// Answers every request with `body`, counting the calls.
+    struct Canned {
+        body: Result<serde_json::Value, String>,
+        calls: Cell<usize>,
+    }
+
+    impl Canned {
+        fn new(body: Result<serde_json::Value, String>) -> Self {
+            Self {
+                body,
+                calls: Cell::new(0),
+            }
+        }
+    }
+
+    impl Fetch for Canned {
+        fn get_json(&self, _url: &str) -> Result<serde_json::Value, String> {
+            self.calls.set(self.calls.get() + 1);
+            self.body.clone()
+        }
+    }
 
     #[test]
     fn cache_roundtrip() {
@@ -128,6 +292,7 @@ mod tests {
         let cached = CachedVersion {
             latest: "1.2.3".to_string(),
             checked_at: Utc::now(),
+            registry: default_registry(),
         };
 
         let content = serde_json::to_string_pretty(&cached).unwrap();
@@ -139,14 +304,76 @@ mod tests {
     }
 
     #[test]
-    fn check_returns_none_without_feature() {
-        // Without the version-check feature, fetch always returns None.
+    fn check_fails_without_feature() {
+        // Without the version-check feature, fetch always fails.
         #[cfg(not(feature = "version-check"))]
         {
-            assert!(fetch_latest_version().is_none());
+            let url = crate_endpoint(DEFAULT_REGISTRY_URL);
+            assert!(fetch_latest_version(&HttpFetch, &url).is_err());
         }
     }
 
+    #[test]
+    fn lookup_caches_and_refresh_bypasses_the_cache() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = dir.path().join("version-check.json");
+        let fetch = Canned::new(Ok(serde_json::json!({"crate": {"max_version": "9.9.9"}})));
+        let url = "https://mirror.corp/envvault.json";
+
+        let lookup = |refresh| lookup_with(&fetch, url, url, refresh, Some(&cache));
+        assert_eq!(lookup(false).unwrap(), "9.9.9");
+        assert_eq!(lookup(false).unwrap(), "9.9.9");
+        assert_eq!(fetch.calls.get(), 1);
+        assert_eq!(lookup(true).unwrap(), "9.9.9");
+        assert_eq!(fetch.calls.get(), 2);
+
+        // A cache from another source doesn't count.
+        lookup_with(&fetch, url, "https://crates.io", false, Some(&cache)).unwrap();
+        assert_eq!(fetch.calls.get(), 3);
+    }
+
+    #[test]
+    fn lookup_errors_say_what_went_wrong() {
+        let url = "https://mirror.corp/envvault.json";
+        let offline = Canned::new(Err(format!("{url}: connection refused")));
+        let err = lookup_with(&offline, url, url, true, None).unwrap_err();
+        assert!(err.contains("connection refused"), "{err}");
+
+        let wrong_shape = Canned::new(Ok(serde_json::json!({"version": "1.0.0"})));
+        let err = lookup_with(&wrong_shape, url, url, true, None).unwrap_err();
+        assert!(err.contains("crate.max_version"), "{err}");
+    }
+
+    #[test]
+    fn registry_override_order() {
+        let mirror = || Some("https://mirror.corp/".to_string());
+        let config = || Some("https://config.corp".to_string());
+
+        assert_eq!(resolve_registry_url(None, None), DEFAULT_REGISTRY_URL);
+        assert_eq!(resolve_registry_url(None, config()), "https://config.corp");
+        assert_eq!(
+            resolve_registry_url(mirror(), config()),
+            "https://mirror.corp"
+        );
+        // An empty variable doesn't mask the config file.
+        assert_eq!(
+            resolve_registry_url(Some(String::new()), config()),
+            "https://config.corp"
+        );
+        assert_eq!(
+            crate_endpoint("https://mirror.corp"),
+            "https://mirror.corp/api/v1/crates/envvault"
+        );
+    }
+
+    #[test]
+    fn legacy_cache_is_attributed_to_crates_io() {
+        let cached: CachedVersion =
+            serde_json::from_str(r#"{"latest":"0.5.0","checked_at":"2024-01-01T00:00:00Z"}"#)
+                .unwrap();
+        assert_eq!(cached.registry, DEFAULT_REGISTRY_URL);
+    }
+
     #[test]
     fn cache_path_returns_some() {
         // cache_path depends on HOME being set, which it normally is.
@@ -161,4 +388,17 @@ mod tests {
             assert_eq!(actual, Some(expected));
         }
     }
+
+    #[cfg(feature = "version-check")]
+    #[test]
+    fn silent_registry_times_out() {
+        // Accepts connections (via the backlog) but never answers.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let registry = format!("http://{}", listener.local_addr().unwrap());
+
+        let started = std::time::Instant::now();
+        assert!(fetch_latest_version(&HttpFetch, &crate_endpoint(&registry)).is_err());
+        assert!(started.elapsed() < TOTAL_TIMEOUT + Duration::from_secs(1));
+        drop(listener);
+    }
 }
@@ -44,6 +44,37 @@ fn version_flag_shows_version() {
         .stdout(predicate::str::contains("envvault"));
 }
 
+#[test]
+fn long_version_includes_build_metadata() {
+    envvault()
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(env!("CARGO_PKG_VERSION")))
+        .stdout(predicate::str::contains("commit:"))
+        .stdout(predicate::str::contains("built:"))
+        .stdout(predicate::str::contains("target:"))
+        .stdout(predicate::str::contains(format!(
+            "format:   v{}",
+            envvault::vault::format::CURRENT_VERSION
+        )))
+        .stdout(predicate::str::contains("features:"));
+}
+
+#[test]
+fn version_json_is_machine_readable() {
+    let out = envvault().args(["version", "--json"]).output().unwrap();
+    assert!(out.status.success());
+    let info: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(
+        info["format_version"],
+        envvault::vault::format::CURRENT_VERSION
+    );
+    assert!(info["update_available"].is_boolean());
+    assert!(info["features"].is_array());
+}
+
 #[test]
 fn no_args_shows_help() {
     // Running with no subcommand should show an error or help.
@@ -58,15 +89,16 @@ fn get_on_missing_vault_fails() {
     let tmp = TempDir::new().unwrap();
 
     // Trying to get a secret from a non-existent vault should fail.
-    // We pipe "testpass" to stdin to avoid the interactive prompt hanging.
     envvault()
         .args([
+            "--stdin-password",
             "get",
             "MY_KEY",
             "--vault-dir",
             tmp.path().join(".envvault").to_str().unwrap(),
         ])
         .current_dir(tmp.path())
+        .env_remove("ENVVAULT_PASSWORD")
         .write_stdin("testpass\n")
         .assert()
         .failure();
@@ -114,3 +146,1699 @@ fn auth_help_shows_subcommands() {
         .stdout(predicate::str::contains("keyring"))
         .stdout(predicate::str::contains("keyfile-generate"));
 }
+
+#[test]
+fn dump_json_writes_only_json_to_stdout() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+
+    let mut store = envvault::vault::VaultStore::create(
+        &vault_dir.join("dev.vault"),
+        b"dump-password",
+        "dev",
+        None,
+        None,
+    )
+    .unwrap();
+    store.set_secret("DATABASE_URL", "postgres://db").unwrap();
+    store.set_secret("API_KEY", "multi\nline").unwrap();
+    store.save().unwrap();
+
+    let output = envvault()
+        .args(["dump", "--json"])
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", "dump-password")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["DATABASE_URL"], "postgres://db");
+    assert_eq!(parsed["API_KEY"], "multi\nline");
+}
+
+#[test]
+fn set_many_json_reads_what_dump_json_writes() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+
+    let mut store = envvault::vault::VaultStore::create(
+        &vault_dir.join("dev.vault"),
+        b"bulk-password",
+        "dev",
+        None,
+        None,
+    )
+    .unwrap();
+    store.set_secret("API_KEY", "old").unwrap();
+    store.save().unwrap();
+
+    let run = |args: &[&str], stdin: &str| {
+        envvault()
+            .args(args)
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", "bulk-password")
+            .write_stdin(stdin)
+            .output()
+            .unwrap()
+    };
+
+    let set = run(
+        &["set-many", "--json"],
+        r#"{"API_KEY":"sk-2","DATABASE_URL":"postgres://db","MULTI":"a\nb"}"#,
+    );
+    assert!(set.status.success());
+    assert!(String::from_utf8_lossy(&set.stdout).contains("2 added, 1 updated"));
+
+    let dump = run(&["dump", "--json"], "");
+    let parsed: serde_json::Value = serde_json::from_slice(&dump.stdout).unwrap();
+    assert_eq!(parsed["API_KEY"], "sk-2");
+    assert_eq!(parsed["DATABASE_URL"], "postgres://db");
+    assert_eq!(parsed["MULTI"], "a\nb");
+
+    // Feeding the dump back in changes nothing but is accepted.
+    let again = run(
+        &["set-many", "--json"],
+        &String::from_utf8_lossy(&dump.stdout),
+    );
+    assert!(again.status.success());
+
+    let invalid = run(&["set-many", "--json"], "[1, 2]");
+    assert!(!invalid.status.success());
+    assert!(String::from_utf8_lossy(&invalid.stderr).contains("invalid JSON"));
+
+    // `dump --json --with-metadata` output is refused, not stored as text.
+    let with_metadata = run(&["dump", "--json", "--with-metadata"], "");
+    let nested = run(
+        &["set-many", "--json"],
+        &String::from_utf8_lossy(&with_metadata.stdout),
+    );
+    assert!(!nested.status.success());
+    assert!(String::from_utf8_lossy(&nested.stderr).contains("'API_KEY' holds a nested JSON value"));
+    let dump = run(&["dump", "--json"], "");
+    let parsed: serde_json::Value = serde_json::from_slice(&dump.stdout).unwrap();
+    assert_eq!(parsed["API_KEY"], "sk-2");
+}
+
+#[test]
+fn export_k8s_checks_the_name_before_opening_the_vault() {
+    let tmp = TempDir::new().unwrap();
+
+    // No vault and no password: the bad name is reported first.
+    envvault()
+        .args(["export", "--format", "k8s", "--name", "Not_Valid"])
+        .current_dir(tmp.path())
+        .env_remove("ENVVAULT_PASSWORD")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--name 'Not_Valid'"));
+
+    envvault()
+        .args(["export", "--namespace", "prod"])
+        .current_dir(tmp.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("only work with the k8s format"));
+}
+
+#[test]
+#[ignore = "needs a docker daemon and the alpine image"]
+fn dotenv_docker_values_with_spaces_survive_docker_env_file() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+
+    let mut store = envvault::vault::VaultStore::create(
+        &vault_dir.join("dev.vault"),
+        b"docker-password",
+        "dev",
+        None,
+        None,
+    )
+    .unwrap();
+    store.set_secret("GREETING", "hello big world").unwrap();
+    store.save().unwrap();
+
+    let env_file = tmp.path().join("docker.env");
+    envvault()
+        .args(["export", "--format", "dotenv-docker", "--output"])
+        .arg(&env_file)
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", "docker-password")
+        .assert()
+        .success();
+
+    let output = std::process::Command::new("docker")
+        .args(["run", "--rm", "--env-file"])
+        .arg(&env_file)
+        .args(["alpine", "printenv", "GREETING"])
+        .output()
+        .expect("docker should be installed");
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"hello big world\n");
+}
+
+#[test]
+fn init_with_a_bad_env_file_cleans_up_and_can_be_rerun() {
+    let tmp = TempDir::new().unwrap();
+    let vault = tmp.path().join(".envvault").join("dev.vault");
+    std::fs::write(tmp.path().join(".env"), "# fixture\nGOOD=1\nBAD KEY=2\n").unwrap();
+    let init = |extra: &[&str]| {
+        envvault()
+            .args(["init", "--no-gitignore"])
+            .args(extra)
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", "init-password")
+            .output()
+            .unwrap()
+    };
+
+    // Without the flag (and no terminal to ask) the vault is kept.
+    let kept = init(&[]);
+    assert!(!kept.status.success());
+    let stderr = String::from_utf8_lossy(&kept.stderr);
+    assert!(
+        stderr.contains(".env:3: cannot import 'BAD KEY'"),
+        "{stderr}"
+    );
+    assert!(vault.exists());
+    std::fs::remove_file(&vault).unwrap();
+
+    let cleaned = init(&["--clean-on-failure"]);
+    assert!(!cleaned.status.success());
+    assert!(!vault.exists());
+
+    // The failure is audited with its phase.
+    #[cfg(feature = "audit-log")]
+    {
+        let audit = envvault::audit::AuditLog::open(&tmp.path().join(".envvault")).unwrap();
+        let entries = audit.query(1, None).unwrap();
+        assert_eq!(entries[0].operation, "init-failed");
+        let details = entries[0].details.as_deref().unwrap();
+        assert!(details.contains(r#""phase":"import""#), "{details}");
+        assert!(details.contains(r#""removed":true"#), "{details}");
+    }
+
+    // Fix the file and run init again.
+    std::fs::write(tmp.path().join(".env"), "GOOD=1\nBAD_KEY=2\n").unwrap();
+    assert!(init(&[]).status.success());
+    let dump = envvault()
+        .args(["dump", "--json"])
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", "init-password")
+        .output()
+        .unwrap();
+    let parsed: serde_json::Value = serde_json::from_slice(&dump.stdout).unwrap();
+    assert_eq!(parsed["BAD_KEY"], "2");
+}
+
+#[test]
+fn diff_against_file_compares_without_importing() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+
+    let mut store = envvault::vault::VaultStore::create(
+        &vault_dir.join("dev.vault"),
+        b"diff-password",
+        "dev",
+        None,
+        None,
+    )
+    .unwrap();
+    store.set_secret("SAME", "1").unwrap();
+    store.set_secret("CHANGED", "vault-value").unwrap();
+    store.set_secret("VAULT_ONLY", "v").unwrap();
+    store.save().unwrap();
+    std::fs::write(
+        tmp.path().join("other.env"),
+        "SAME=1\nCHANGED=file-value\nFILE_ONLY=f\n",
+    )
+    .unwrap();
+
+    let output = envvault()
+        .args(["diff", "--against-file", "other.env", "--show-values"])
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", "diff-password")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("dev vs other.env"), "{stdout}");
+    assert!(stdout.contains("+ FILE_ONLY = f"), "{stdout}");
+    assert!(stdout.contains("- VAULT_ONLY = v"), "{stdout}");
+    assert!(stdout.contains("vault-value"), "{stdout}");
+    assert!(stdout.contains("1 added, 1 removed, 1 changed, 1 unchanged"));
+
+    // Nothing was imported.
+    let store =
+        envvault::vault::VaultStore::open(&vault_dir.join("dev.vault"), b"diff-password", None)
+            .unwrap();
+    assert!(!store.contains_key("FILE_ONLY"));
+
+    envvault()
+        .args(["diff", "staging", "--against-file", "other.env"])
+        .current_dir(tmp.path())
+        .assert()
+        .failure();
+}
+
+#[test]
+fn import_merge_strategies() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    let vault = vault_dir.join("dev.vault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+
+    let mut store =
+        envvault::vault::VaultStore::create(&vault, b"merge-password", "dev", None, None).unwrap();
+    store.set_secret("EXISTING", "vault").unwrap();
+    store.save().unwrap();
+    std::fs::write(tmp.path().join("in.env"), "EXISTING=file\nNEW=1\n").unwrap();
+
+    let import = |strategy: &str| {
+        envvault()
+            .args(["import", "in.env", "--merge-strategy", strategy])
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", "merge-password")
+            .output()
+            .unwrap()
+    };
+    let value = |key: &str| {
+        envvault::vault::VaultStore::open(&vault, b"merge-password", None)
+            .unwrap()
+            .get_secret(key)
+            .ok()
+    };
+
+    // `fail` leaves the vault file byte-for-byte untouched.
+    let before = std::fs::read(&vault).unwrap();
+    let failed = import("fail");
+    assert!(!failed.status.success());
+    assert!(String::from_utf8_lossy(&failed.stderr).contains("EXISTING"));
+    assert_eq!(std::fs::read(&vault).unwrap(), before);
+
+    let skipped = import("skip");
+    assert!(skipped.status.success());
+    assert!(String::from_utf8_lossy(&skipped.stdout).contains("1 added, 0 overwritten, 1 skipped"));
+    assert_eq!(value("EXISTING").as_deref(), Some("vault"));
+    assert_eq!(value("NEW").as_deref(), Some("1"));
+
+    let overwritten = import("overwrite");
+    assert!(overwritten.status.success());
+    assert!(
+        String::from_utf8_lossy(&overwritten.stdout).contains("0 added, 2 overwritten, 0 skipped")
+    );
+    assert_eq!(value("EXISTING").as_deref(), Some("file"));
+
+    assert!(!import("merge").status.success());
+}
+
+#[test]
+fn rotate_key_and_export_with_a_binary_streamed_value() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+    let path = vault_dir.join("dev.vault");
+
+    let mut store =
+        envvault::vault::VaultStore::create(&path, b"binary-password", "dev", None, None).unwrap();
+    let blob: Vec<u8> = (0..100_000u32).map(|i| (i % 256) as u8).collect();
+    store.set_secret_reader("BLOB", blob.as_slice()).unwrap();
+    store.set_secret("API_KEY", "sk-123").unwrap();
+    store.save().unwrap();
+
+    envvault()
+        .args(["rotate-key", "--force"])
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", "binary-password")
+        .assert()
+        .success();
+
+    let store = envvault::vault::VaultStore::open(&path, b"binary-password", None).unwrap();
+    assert_eq!(*store.get_secret_bytes("BLOB").unwrap(), blob);
+    assert_eq!(store.get_secret("API_KEY").unwrap(), "sk-123");
+
+    // Text output cannot hold the value, and the error says which one.
+    envvault()
+        .arg("export")
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", "binary-password")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'BLOB'"))
+        .stderr(predicate::str::contains("binary data"));
+}
+
+#[test]
+fn list_json_writes_only_json_to_stdout() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+
+    let mut store = envvault::vault::VaultStore::create(
+        &vault_dir.join("dev.vault"),
+        b"list-password",
+        "dev",
+        None,
+        None,
+    )
+    .unwrap();
+    store.set_secret("DATABASE_URL", "postgres://db").unwrap();
+    store.set_secret("API_KEY", "sk-1").unwrap();
+    store.save().unwrap();
+
+    let list = |args: &[&str]| {
+        envvault()
+            .args(args)
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", "list-password")
+            .output()
+            .unwrap()
+    };
+
+    let output = list(&["list", "--json"]);
+    assert!(output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed[0]["name"], "API_KEY");
+    assert_eq!(parsed[1]["name"], "DATABASE_URL");
+    assert!(parsed[0]["updated_at"].is_string());
+    assert!(parsed[0].get("value").is_none());
+
+    let output = list(&["list", "--json", "--values"]);
+    assert!(output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed[1]["value"], "postgres://db");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--values"));
+}
+
+#[test]
+fn init_no_gitignore_leaves_gitignore_alone() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join(".gitignore"), "target/\n").unwrap();
+    std::fs::write(
+        tmp.path().join(".envvault.toml"),
+        "argon2_memory_kib = 8192\nargon2_iterations = 1\nargon2_parallelism = 1\n",
+    )
+    .unwrap();
+
+    envvault()
+        .args(["init", "--no-gitignore"])
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", "init-password")
+        .assert()
+        .success();
+
+    assert!(tmp.path().join(".envvault/dev.vault").exists());
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join(".gitignore")).unwrap(),
+        "target/\n"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn run_strips_envvault_password_from_child() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+
+    let mut store = envvault::vault::VaultStore::create(
+        &vault_dir.join("dev.vault"),
+        b"run-password",
+        "dev",
+        None,
+        None,
+    )
+    .unwrap();
+    store.set_secret("APP_TOKEN", "token-value").unwrap();
+    store.save().unwrap();
+
+    let script = "printenv ENVVAULT_PASSWORD || echo password-absent; printenv APP_TOKEN";
+
+    envvault()
+        .args(["run", "--", "sh", "-c", script])
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", "run-password")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("password-absent"))
+        .stdout(predicate::str::contains("run-password").not())
+        .stdout(predicate::str::contains("token-value"));
+
+    envvault()
+        .args(["run", "--keep-envvault-vars", "--", "sh", "-c", script])
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", "run-password")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("run-password"));
+}
+
+#[test]
+fn run_with_env_adds_a_prefixed_environment() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+
+    for (env, pairs) in [
+        (
+            "dev",
+            &[("DB_URL", "dev-db"), ("SHARED_REGION", "mine")][..],
+        ),
+        ("shared", &[("DB_URL", "shared-db"), ("REGION", "eu")][..]),
+    ] {
+        let mut store = envvault::vault::VaultStore::create(
+            &vault_dir.join(format!("{env}.vault")),
+            b"run-password",
+            env,
+            None,
+            None,
+        )
+        .unwrap();
+        for (key, value) in pairs {
+            store.set_secret(key, value).unwrap();
+        }
+        store.save().unwrap();
+    }
+
+    envvault()
+        .args([
+            "run",
+            "--with-env",
+            "shared:SHARED_",
+            "--",
+            "sh",
+            "-c",
+            "echo $DB_URL $SHARED_DB_URL $SHARED_REGION",
+        ])
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", "run-password")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("dev-db shared-db mine"))
+        .stderr(predicate::str::contains("SHARED_REGION"));
+
+    envvault()
+        .args(["run", "--with-env", "missing:X_", "--", "true"])
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", "run-password")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("missing"));
+}
+
+#[cfg(unix)]
+#[test]
+fn stdin_password_reads_only_the_first_line() {
+    let tmp = TempDir::new().unwrap();
+    let run = |args: &[&str], stdin: &str| {
+        envvault()
+            .arg("--stdin-password")
+            .args(args)
+            .current_dir(tmp.path())
+            .env_remove("ENVVAULT_PASSWORD")
+            .write_stdin(stdin)
+            .output()
+            .unwrap()
+    };
+
+    assert!(run(&["init", "--no-gitignore"], "stdin-password\n")
+        .status
+        .success());
+
+    // `set`: the password line comes first, the value is everything after it.
+    assert!(run(&["set", "API_KEY"], "stdin-password\nsk-live-1\n")
+        .status
+        .success());
+    assert_eq!(
+        run(&["get", "API_KEY"], "stdin-password\n").stdout,
+        b"sk-live-1\n"
+    );
+
+    // A password line with nothing after it is not an empty value.
+    let missing = run(&["set", "EMPTY"], "stdin-password\n");
+    assert!(!missing.status.success());
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("after the password line"));
+
+    // `run`: the child inherits the rest of stdin untouched.
+    let child = run(
+        &["run", "--", "sh", "-c", "cat; printenv API_KEY"],
+        "stdin-password\nfor the child\n",
+    );
+    assert!(child.status.success());
+    assert!(String::from_utf8_lossy(&child.stdout).ends_with("for the child\nsk-live-1\n"));
+
+    // The flag beats ENVVAULT_PASSWORD, and a wrong line is rejected.
+    let wrong = envvault()
+        .args(["--stdin-password", "get", "API_KEY"])
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", "stdin-password")
+        .write_stdin("not-the-password\n")
+        .output()
+        .unwrap();
+    assert!(!wrong.status.success());
+
+    // An empty first line is an error rather than an empty password.
+    let empty = run(&["get", "API_KEY"], "\n");
+    assert!(!empty.status.success());
+    assert!(String::from_utf8_lossy(&empty.stderr).contains("no password"));
+}
+
+#[cfg(unix)]
+#[test]
+fn run_secrets_dir_passes_files_not_values() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+
+    let mut store = envvault::vault::VaultStore::create(
+        &vault_dir.join("dev.vault"),
+        b"files-password",
+        "dev",
+        None,
+        None,
+    )
+    .unwrap();
+    store.set_secret("DB_PASS", "hunter2").unwrap();
+    store.save().unwrap();
+
+    let secrets_dir = tmp.path().join("run-secrets");
+    let script = "cat \"$DB_PASS_FILE\"; echo; printenv DB_PASS || echo value-absent";
+
+    envvault()
+        .args(["run", "--secrets-dir"])
+        .arg(&secrets_dir)
+        .args(["--", "sh", "-c", script])
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", "files-password")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("hunter2\nvalue-absent"));
+
+    // Files (and the directory envvault created) are gone afterwards.
+    assert!(!secrets_dir.exists());
+}
+
+#[test]
+fn search_values_regex_and_exit_status() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+
+    let mut store = envvault::vault::VaultStore::create(
+        &vault_dir.join("dev.vault"),
+        b"search-password",
+        "dev",
+        None,
+        None,
+    )
+    .unwrap();
+    store.set_secret("AWS_ACCESS_KEY", "akia-value").unwrap();
+    store.set_secret("GITHUB_TOKEN", "ghp-value").unwrap();
+    store.save().unwrap();
+
+    let run = |args: &[&str]| {
+        envvault()
+            .arg("search")
+            .args(args)
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", "search-password")
+            .output()
+            .unwrap()
+    };
+
+    // Metadata only by default.
+    let glob = run(&["aws_*"]);
+    assert!(glob.status.success());
+    let stdout = String::from_utf8_lossy(&glob.stdout);
+    assert!(stdout.contains("AWS_ACCESS_KEY") && !stdout.contains("akia-value"));
+
+    let values = run(&["--regex", "token$", "--values"]);
+    assert!(values.status.success());
+    let stdout = String::from_utf8_lossy(&values.stdout);
+    assert!(stdout.contains("ghp-value") && !stdout.contains("akia-value"));
+
+    // A plain pattern matches part of a name.
+    let substring = run(&["token"]);
+    assert!(substring.status.success());
+    assert!(String::from_utf8_lossy(&substring.stdout).contains("GITHUB_TOKEN"));
+
+    let none = run(&["NOPE_*"]);
+    assert!(!none.status.success());
+    assert!(String::from_utf8_lossy(&none.stderr).contains("no secrets matching"));
+
+    // A plain name also finds longer names; anchor it to test existence.
+    assert!(run(&["AWS_ACCESS"]).status.success());
+    assert!(!run(&["--regex", "^AWS_ACCESS$"]).status.success());
+    assert!(run(&["--regex", "^AWS_ACCESS_KEY$"]).status.success());
+}
+
+#[test]
+fn get_output_formats() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+
+    let mut store = envvault::vault::VaultStore::create(
+        &vault_dir.join("dev.vault"),
+        b"get-password",
+        "dev",
+        None,
+        None,
+    )
+    .unwrap();
+    store.set_secret("CERT", "line one\nline two\n").unwrap();
+    store.set_secret("API_KEY", "sk-12345").unwrap();
+    store.save().unwrap();
+
+    let get = |args: &[&str]| {
+        let output = envvault()
+            .arg("get")
+            .args(args)
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", "get-password")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        output.stdout
+    };
+
+    assert_eq!(get(&["CERT"]), b"line one\nline two\n\n");
+    assert_eq!(get(&["CERT", "-n"]), b"line one\nline two\n");
+    assert_eq!(get(&["CERT", "--print0"]), b"line one\nline two\n\0");
+
+    // Several keys: KEY=value lines for scripts, a masked table on request.
+    assert_eq!(
+        get(&["API_KEY", "CERT", "--print0"]),
+        b"API_KEY=sk-12345\0CERT=line one\nline two\n\0"
+    );
+    let table = String::from_utf8(get(&["API_KEY", "CERT", "--format", "table"])).unwrap();
+    assert!(table.contains("API_KEY"), "{table}");
+    assert!(!table.contains("sk-12345"), "{table}");
+    let table = String::from_utf8(get(&["API_KEY", "--format", "table", "--reveal"])).unwrap();
+    assert!(table.contains("sk-12345"), "{table}");
+}
+
+#[test]
+fn set_encoding_and_get_decode() {
+    let tmp = TempDir::new().unwrap();
+    let password = "encoding-password";
+    let run = |args: &[&str]| {
+        envvault()
+            .args(args)
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", password)
+            .output()
+            .unwrap()
+    };
+
+    assert!(run(&["init", "--no-gitignore"]).status.success());
+    assert!(run(&["set", "BLOB", "AP8Q", "-f", "--encoding", "base64"])
+        .status
+        .success());
+
+    let decoded = run(&["get", "BLOB", "--decode"]);
+    assert!(decoded.status.success());
+    assert_eq!(decoded.stdout, [0x00, 0xff, 0x10]);
+
+    // The stored value is untouched and the hint shows up in export.
+    assert_eq!(run(&["get", "BLOB"]).stdout, b"AP8Q\n");
+    let export = String::from_utf8(run(&["export"]).stdout).unwrap();
+    assert!(export.contains("# encoding: base64\nBLOB=AP8Q"), "{export}");
+
+    // Values that don't match the encoding are rejected.
+    assert!(!run(&["set", "BAD", "zz", "-f", "--encoding", "hex"])
+        .status
+        .success());
+    // Secrets without a hint can't be decoded.
+    assert!(run(&["set", "PLAIN", "x", "-f"]).status.success());
+    assert!(!run(&["get", "PLAIN", "--decode"]).status.success());
+}
+
+#[test]
+fn import_crlf_env_file_strips_carriage_returns() {
+    let tmp = TempDir::new().unwrap();
+    let run = |args: &[&str]| {
+        envvault()
+            .args(args)
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", "crlf-password")
+            .output()
+            .unwrap()
+    };
+
+    assert!(run(&["init", "--no-gitignore"]).status.success());
+    std::fs::write(
+        tmp.path().join("windows.env"),
+        "# from Windows\r\nDATABASE_URL=postgres://db/app\r\nQUOTED=\"a b\"\r\n",
+    )
+    .unwrap();
+
+    let import = run(&["import", "windows.env"]);
+    assert!(import.status.success());
+    let stderr = String::from_utf8_lossy(&import.stderr);
+    assert!(stderr.contains("CRLF"), "{stderr}");
+
+    assert_eq!(run(&["get", "DATABASE_URL"]).stdout, b"postgres://db/app\n");
+    assert_eq!(run(&["get", "QUOTED"]).stdout, b"a b\n");
+}
+
+#[test]
+fn import_yaml_keeps_multiline_values() {
+    let tmp = TempDir::new().unwrap();
+    let run = |args: &[&str]| {
+        envvault()
+            .args(args)
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", "yaml-password")
+            .output()
+            .unwrap()
+    };
+
+    assert!(run(&["init", "--no-gitignore"]).status.success());
+    std::fs::write(
+        tmp.path().join("config.yml"),
+        "PORT: 8080\nCERT: |\n  -----BEGIN CERT-----\n  MIIB\n  -----END CERT-----\nNESTED_OK: \"a: b\"\n",
+    )
+    .unwrap();
+
+    assert!(run(&["import", "config.yml"]).status.success());
+    assert_eq!(run(&["get", "PORT"]).stdout, b"8080\n");
+    assert_eq!(run(&["get", "NESTED_OK"]).stdout, b"a: b\n");
+    assert_eq!(
+        run(&["get", "CERT"]).stdout,
+        b"-----BEGIN CERT-----\nMIIB\n-----END CERT-----\n\n"
+    );
+
+    std::fs::write(tmp.path().join("nested.yaml"), "DB:\n  url: x\n").unwrap();
+    let nested = run(&["import", "nested.yaml"]);
+    assert!(!nested.status.success());
+    assert!(String::from_utf8_lossy(&nested.stderr).contains("'DB'"));
+}
+
+#[test]
+fn progress_json_reports_a_bulk_import() {
+    let tmp = TempDir::new().unwrap();
+    let run = |args: &[&str]| {
+        envvault()
+            .args(args)
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", "progress-password")
+            .output()
+            .unwrap()
+    };
+
+    assert!(run(&["init", "--no-gitignore"]).status.success());
+    let lines: String = (0..250).map(|i| format!("KEY_{i}=value{i}\n")).collect();
+    std::fs::write(tmp.path().join("bulk.env"), lines).unwrap();
+
+    let import = run(&["--progress-json", "import", "bulk.env"]);
+    assert!(import.status.success());
+    let events: Vec<serde_json::Value> = String::from_utf8_lossy(&import.stderr)
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert_eq!(events.first().unwrap()["event"], "start");
+    assert_eq!(events.first().unwrap()["command"], "import");
+    assert_eq!(events.last().unwrap()["event"], "done");
+    let import_progress: Vec<(u64, u64)> = events
+        .iter()
+        .filter(|e| e["event"] == "progress" && e["phase"] == "import")
+        .map(|e| (e["current"].as_u64().unwrap(), e["total"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(import_progress.first(), Some(&(0, 250)));
+    assert_eq!(import_progress.last(), Some(&(250, 250)));
+    assert!(import_progress.windows(2).all(|w| w[0].0 <= w[1].0));
+    for phase in ["derive-key", "save"] {
+        assert!(
+            events.iter().any(|e| e["phase"] == phase),
+            "no {phase} event"
+        );
+    }
+
+    // Failures end with an error event; without the flag nothing is JSON.
+    let missing = run(&["--progress-json", "import", "missing.env"]);
+    assert!(!missing.status.success());
+    let stderr = String::from_utf8_lossy(&missing.stderr);
+    let last: serde_json::Value =
+        serde_json::from_str(stderr.lines().rev().find(|l| l.starts_with('{')).unwrap()).unwrap();
+    assert_eq!(last["event"], "error");
+    assert!(last["message"].as_str().unwrap().contains("missing.env"));
+    let plain = run(&["import", "bulk.env"]);
+    assert!(!String::from_utf8_lossy(&plain.stderr).contains("\"event\""));
+}
+
+#[cfg(unix)]
+#[test]
+fn post_write_hook_runs_after_writes_and_never_blocks_them() {
+    let tmp = TempDir::new().unwrap();
+    let run = |args: &[&str]| {
+        envvault()
+            .args(args)
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", "hook-password")
+            .output()
+            .unwrap()
+    };
+
+    assert!(run(&["init", "--no-gitignore"]).status.success());
+    std::fs::write(
+        tmp.path().join(".envvault.toml"),
+        "[hooks]\npost_write = \"sh -c 'echo $ENVVAULT_OP/$ENVVAULT_ENV/${ENVVAULT_PASSWORD:-none} >> hook.log'\"\n",
+    )
+    .unwrap();
+
+    assert!(run(&["set", "API_KEY", "sk-1", "-f"]).status.success());
+    assert!(run(&["delete", "API_KEY", "-f"]).status.success());
+    // Reads don't trigger it.
+    let _ = run(&["list"]);
+
+    let log = std::fs::read_to_string(tmp.path().join("hook.log")).unwrap();
+    assert_eq!(log, "set/dev/none\ndelete/dev/none\n");
+
+    std::fs::write(
+        tmp.path().join(".envvault.toml"),
+        "[hooks]\npost_write = \"false\"\n",
+    )
+    .unwrap();
+    let set = run(&["set", "OTHER", "x", "-f"]);
+    assert!(set.status.success());
+    assert!(String::from_utf8_lossy(&set.stderr).contains("post_write hook failed"));
+    assert_eq!(run(&["get", "OTHER"]).stdout, b"x\n");
+}
+
+#[test]
+fn timings_flag_reports_every_vault_phase() {
+    let tmp = TempDir::new().unwrap();
+    let run = |args: &[&str]| {
+        envvault()
+            .args(args)
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", "timings-password")
+            .output()
+            .unwrap()
+    };
+
+    assert!(run(&["init", "--no-gitignore"]).status.success());
+    assert!(run(&["set", "API_KEY", "sk-1", "-f"]).status.success());
+
+    // Cloning opens, decrypts everything, and saves.
+    let clone = run(&["--timings", "env", "clone", "staging"]);
+    assert!(clone.status.success());
+    let stderr = String::from_utf8_lossy(&clone.stderr);
+    for label in [
+        "read file",
+        "JSON parse",
+        "key derivation",
+        "HMAC verify",
+        "decrypt secrets",
+        "serialize",
+        "HMAC sign",
+        "write file",
+        "total",
+    ] {
+        assert!(stderr.contains(label), "missing '{label}' in:\n{stderr}");
+    }
+
+    // Without the flag nothing is printed.
+    let quiet = run(&["get", "API_KEY"]);
+    assert!(!String::from_utf8_lossy(&quiet.stderr).contains("key derivation"));
+}
+
+#[test]
+fn commands_from_nested_dir_share_one_audit_db() {
+    let tmp = TempDir::new().unwrap();
+    let project = tmp.path().join("project");
+    let nested = project.join("src").join("deep");
+    std::fs::create_dir_all(&nested).unwrap();
+    std::fs::create_dir_all(project.join(".git")).unwrap();
+
+    let vault_dir = project.join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+    envvault::vault::VaultStore::create(
+        &vault_dir.join("dev.vault"),
+        b"nested-password",
+        "dev",
+        None,
+        None,
+    )
+    .unwrap();
+
+    for cwd in [&nested, &project] {
+        envvault()
+            .args(["set", "KEY", "value", "--force"])
+            .current_dir(cwd)
+            .env("ENVVAULT_PASSWORD", "nested-password")
+            .assert()
+            .success();
+    }
+
+    fn find_audit_dbs(dir: &std::path::Path, found: &mut Vec<std::path::PathBuf>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                find_audit_dbs(&path, found);
+            } else if path.file_name().is_some_and(|n| n == "audit.db") {
+                found.push(path);
+            }
+        }
+    }
+    let mut dbs = Vec::new();
+    find_audit_dbs(&project, &mut dbs);
+    assert_eq!(dbs, vec![vault_dir.join("audit.db")]);
+}
+
+#[cfg(feature = "audit-log")]
+#[test]
+fn audit_env_flag_limits_entries_to_that_environment() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+    let audit = envvault::audit::AuditLog::open(&vault_dir).unwrap();
+    audit.log("set", "dev", Some("DEV_ONLY_KEY"), None);
+    audit.log("set", "prod", Some("PROD_ONLY_KEY"), None);
+    drop(audit);
+
+    let run = |args: &[&str]| {
+        let out = envvault()
+            .args(args)
+            .current_dir(tmp.path())
+            .output()
+            .unwrap();
+        assert!(out.status.success(), "{args:?}");
+        String::from_utf8_lossy(&out.stdout).into_owned()
+    };
+
+    // No --env: every environment, even though `dev` is the default.
+    let all = run(&["audit"]);
+    assert!(
+        all.contains("DEV_ONLY_KEY") && all.contains("PROD_ONLY_KEY"),
+        "{all}"
+    );
+
+    let prod = run(&["audit", "--env", "prod"]);
+    assert!(prod.contains("PROD_ONLY_KEY"), "{prod}");
+    assert!(!prod.contains("DEV_ONLY_KEY"), "{prod}");
+
+    let dev = run(&["--env", "dev", "audit", "export"]);
+    assert!(dev.contains("DEV_ONLY_KEY"), "{dev}");
+    assert!(!dev.contains("PROD_ONLY_KEY"), "{dev}");
+}
+
+#[test]
+fn export_all_envs_writes_one_file_per_environment() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+    let params = envvault::devtools::fast_kdf_params();
+
+    // Two vaults share a password; the third needs a keyfile we don't pass.
+    let keyfile = [7u8; 32];
+    for (env, keyfile) in [
+        ("dev", None),
+        ("staging", None),
+        ("prod", Some(&keyfile[..])),
+    ] {
+        let path = vault_dir.join(format!("{env}.vault"));
+        let mut store = envvault::vault::VaultStore::create(
+            &path,
+            b"shared-password",
+            env,
+            Some(&params),
+            keyfile,
+        )
+        .unwrap();
+        store.set_secret("APP_ENV", env).unwrap();
+        store.set_secret("DB_URL", "postgres://db").unwrap();
+        store.save().unwrap();
+    }
+
+    let export = |extra: &[&str]| {
+        let mut args = vec!["export", "--all-envs", "--output-dir", "dist/env"];
+        args.extend_from_slice(extra);
+        envvault()
+            .args(&args)
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", "shared-password")
+            .output()
+            .unwrap()
+    };
+
+    let out = export(&[]);
+    assert!(out.status.success(), "{out:?}");
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stdout.contains("Exported 2 environment(s)"), "{stdout}");
+    assert!(stdout.contains("skipped (keyfile required)"), "{stdout}");
+    assert!(
+        stderr.contains("SKIPPED 1 keyfile-protected environment(s): prod"),
+        "{stderr}"
+    );
+
+    let dist = tmp.path().join("dist/env");
+    let dev = std::fs::read_to_string(dist.join("dev.env")).unwrap();
+    assert!(dev.contains("APP_ENV=dev"), "{dev}");
+    let staging = std::fs::read_to_string(dist.join("staging.env")).unwrap();
+    assert!(staging.contains("APP_ENV=staging"), "{staging}");
+    assert!(!dist.join("prod.env").exists());
+    assert!(std::fs::read_to_string(tmp.path().join(".gitignore"))
+        .unwrap()
+        .contains("dist/env/"));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(dist.join("dev.env"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    // Existing files are only replaced with --force.
+    let out = export(&["--format", "env"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8(out.stderr).unwrap().contains("--force"));
+    assert!(export(&["--force"]).status.success());
+
+    let out = export(&["--format", "json"]);
+    assert!(out.status.success(), "{out:?}");
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dist.join("dev.json")).unwrap()).unwrap();
+    assert_eq!(json["APP_ENV"], "dev");
+}
+
+#[test]
+fn onboard_defaults_sets_up_a_git_repo_and_is_idempotent() {
+    let tmp = TempDir::new().unwrap();
+    let project = tmp.path().join("project");
+    let home = tmp.path().join("home");
+    std::fs::create_dir_all(project.join(".git")).unwrap();
+    std::fs::write(
+        project.join(".env"),
+        "DATABASE_URL=postgres://db\nAPI_KEY=abc\n",
+    )
+    .unwrap();
+    std::fs::write(project.join(".env.example"), "DATABASE_URL=changeme\n").unwrap();
+
+    let run = |args: &[&str]| {
+        envvault()
+            .args(args)
+            .current_dir(&project)
+            .env("ENVVAULT_PASSWORD", "Onboard-Pass-123")
+            .env("HOME", &home)
+            .env("SHELL", "/bin/bash")
+            .output()
+            .unwrap()
+    };
+
+    let out = run(&["onboard", "--defaults"]);
+    assert!(out.status.success(), "{out:?}");
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("created dev"), "{stdout}");
+    assert!(stdout.contains("imported 2 from .env into dev"), "{stdout}");
+
+    assert!(project.join(".envvault.toml").exists());
+    assert!(project.join(".envvault/dev.vault").exists());
+    assert!(project.join(".git/hooks/pre-commit").exists());
+    assert!(home
+        .join(".local/share/bash-completion/completions/envvault")
+        .exists());
+    assert!(std::fs::read_to_string(project.join(".gitignore"))
+        .unwrap()
+        .contains(".envvault/"));
+    assert_eq!(run(&["get", "API_KEY"]).stdout, b"abc\n");
+
+    // Re-running changes nothing.
+    let out = run(&["onboard", "--defaults"]);
+    assert!(out.status.success(), "{out:?}");
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(!stdout.contains("| done"), "{stdout}");
+    assert!(stdout.contains("nothing new in .env"), "{stdout}");
+
+    // --defaults never prompts for a password.
+    let out = envvault()
+        .args(["onboard", "--defaults"])
+        .current_dir(tmp.path())
+        .env_remove("ENVVAULT_PASSWORD")
+        .env("HOME", &home)
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    assert!(String::from_utf8(out.stderr)
+        .unwrap()
+        .contains("ENVVAULT_PASSWORD"));
+
+    // A scripted password is held to the same minimum length as `init`.
+    let short = tmp.path().join("short");
+    std::fs::create_dir_all(&short).unwrap();
+    let out = envvault()
+        .args(["onboard", "--defaults"])
+        .current_dir(&short)
+        .env("ENVVAULT_PASSWORD", "abc")
+        .env("HOME", &home)
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    assert!(String::from_utf8(out.stderr)
+        .unwrap()
+        .contains("at least 8 characters"));
+    assert!(!short.join(".envvault/dev.vault").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn rotate_secret_updates_vault_only_after_hook_succeeds() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+    let params = envvault::devtools::fast_kdf_params();
+    let mut store = envvault::vault::VaultStore::create(
+        &vault_dir.join("dev.vault"),
+        b"rotate-password",
+        "dev",
+        Some(&params),
+        None,
+    )
+    .unwrap();
+    store.set_secret("DB_PASSWORD", "old-pw").unwrap();
+    store.save().unwrap();
+
+    let run = |args: &[&str]| {
+        envvault()
+            .args(args)
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", "rotate-password")
+            .output()
+            .unwrap()
+    };
+    let get = || run(&["get", "DB_PASSWORD"]).stdout;
+
+    // A failing hook leaves the vault unchanged.
+    let out = run(&[
+        "rotate-secret",
+        "DB_PASSWORD",
+        "--value-from",
+        "echo new-pw",
+        "--hook",
+        "exit 7",
+    ]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8(out.stderr)
+        .unwrap()
+        .contains("vault unchanged"));
+    assert_eq!(get(), b"old-pw\n");
+
+    // A succeeding hook sees both values, then the vault is updated.
+    let seen = tmp.path().join("seen");
+    let hook = format!(
+        "cat \"$ENVVAULT_OLD_VALUE_FILE\" \"$ENVVAULT_NEW_VALUE_FILE\" > '{}'",
+        seen.display()
+    );
+    let out = run(&[
+        "rotate-secret",
+        "DB_PASSWORD",
+        "--value-from",
+        "echo new-pw",
+        "--hook",
+        &hook,
+    ]);
+    assert!(out.status.success(), "{out:?}");
+    assert_eq!(std::fs::read_to_string(&seen).unwrap(), "old-pwnew-pw");
+    assert_eq!(get(), b"new-pw\n");
+
+    // Generated values replace the old one.
+    assert!(run(&[
+        "rotate-secret",
+        "DB_PASSWORD",
+        "--generate",
+        "--length",
+        "40"
+    ])
+    .status
+    .success());
+    let value = get();
+    assert_eq!(value.len(), 41);
+    assert!(value[..40].iter().all(u8::is_ascii_alphanumeric));
+
+    // Unknown keys fail before anything runs.
+    assert!(!run(&["rotate-secret", "MISSING", "--generate"])
+        .status
+        .success());
+}
+
+#[test]
+fn delete_glob_removes_every_match_in_one_save() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+    let vault = vault_dir.join("dev.vault");
+    let mut store =
+        envvault::vault::VaultStore::create(&vault, b"delete-password", "dev", None, None).unwrap();
+    for key in ["LEGACY_DB", "LEGACY_TOKEN", "KEEP_ME"] {
+        store.set_secret(key, "v").unwrap();
+    }
+    store.save().unwrap();
+
+    envvault()
+        .args(["delete", "LEGACY_*", "--force"])
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", "delete-password")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Deleted 2 secrets"));
+
+    let store = envvault::vault::VaultStore::open(&vault, b"delete-password", None).unwrap();
+    assert_eq!(store.secret_count(), 1);
+    assert!(store.contains_key("KEEP_ME"));
+
+    // One audit entry per deleted key.
+    let out = envvault()
+        .args(["audit", "export"])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    let audit = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        audit.contains("LEGACY_DB") && audit.contains("LEGACY_TOKEN"),
+        "{audit}"
+    );
+
+    envvault()
+        .args(["delete", "LEGACY_*", "--force"])
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", "delete-password")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "no secrets in dev match 'LEGACY_*'",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn password_file_unlocks_the_vault_and_must_be_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+    let mut store = envvault::vault::VaultStore::create(
+        &vault_dir.join("dev.vault"),
+        b"file-password",
+        "dev",
+        None,
+        None,
+    )
+    .unwrap();
+    store.set_secret("API_KEY", "from-file").unwrap();
+    store.save().unwrap();
+
+    let password_file = tmp.path().join("password");
+    std::fs::write(&password_file, "file-password\n").unwrap();
+    std::fs::set_permissions(&password_file, std::fs::Permissions::from_mode(0o600)).unwrap();
+
+    envvault()
+        .arg("--password-file")
+        .arg(&password_file)
+        .args(["get", "API_KEY"])
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", "not-the-password")
+        .assert()
+        .success()
+        .stdout("from-file\n");
+
+    envvault()
+        .args(["get", "API_KEY"])
+        .current_dir(tmp.path())
+        .env_remove("ENVVAULT_PASSWORD")
+        .env("ENVVAULT_PASSWORD_FILE", &password_file)
+        .assert()
+        .success()
+        .stdout("from-file\n");
+
+    std::fs::set_permissions(&password_file, std::fs::Permissions::from_mode(0o644)).unwrap();
+    envvault()
+        .arg("--password-file")
+        .arg(&password_file)
+        .args(["get", "API_KEY"])
+        .current_dir(tmp.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("chmod 600"));
+}
+
+#[cfg(unix)]
+#[test]
+fn onboard_defaults_reads_the_password_file() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = TempDir::new().unwrap();
+    let project = tmp.path().join("project");
+    std::fs::create_dir_all(project.join(".git")).unwrap();
+    std::fs::write(project.join(".env"), "API_KEY=abc\n").unwrap();
+    let password_file = tmp.path().join("password");
+    std::fs::write(&password_file, "Onboard-File-123\n").unwrap();
+    std::fs::set_permissions(&password_file, std::fs::Permissions::from_mode(0o600)).unwrap();
+
+    envvault()
+        .arg("--password-file")
+        .arg(&password_file)
+        .args(["onboard", "--defaults"])
+        .current_dir(&project)
+        .env_remove("ENVVAULT_PASSWORD")
+        .env("HOME", tmp.path().join("home"))
+        .env("SHELL", "/bin/bash")
+        .assert()
+        .success();
+
+    envvault()
+        .args(["get", "API_KEY"])
+        .current_dir(&project)
+        .env("ENVVAULT_PASSWORD", "Onboard-File-123")
+        .assert()
+        .success()
+        .stdout("abc\n");
+}
+
+#[test]
+fn env_clone_from_file_seeds_a_new_vault() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".envvault")).unwrap();
+    let run = |args: &[&str]| {
+        envvault()
+            .args(args)
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", "seed-password")
+            .output()
+            .unwrap()
+    };
+
+    std::fs::write(
+        tmp.path().join("staging.env"),
+        "API_KEY=sk-staging\nDB_URL=postgres://staging\n",
+    )
+    .unwrap();
+    std::fs::write(tmp.path().join("empty.json"), "{}").unwrap();
+
+    // No source environment is needed.
+    let clone = run(&["env", "clone", "staging", "--from-file", "staging.env"]);
+    assert!(clone.status.success(), "{clone:?}");
+    assert_eq!(
+        run(&["--env", "staging", "get", "DB_URL"]).stdout,
+        b"postgres://staging\n"
+    );
+
+    // An empty file creates nothing.
+    let empty = run(&["env", "clone", "qa", "--from-file", "empty.json"]);
+    assert!(!empty.status.success());
+    assert!(!tmp.path().join(".envvault").join("qa.vault").exists());
+
+    // The target must still be new.
+    let again = run(&["env", "clone", "staging", "--from-file", "staging.env"]);
+    assert!(!again.status.success());
+}
+
+#[test]
+fn verify_reports_each_vault_and_fails_on_corruption() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+    for env in ["dev", "prod"] {
+        envvault::vault::VaultStore::create(
+            &vault_dir.join(format!("{env}.vault")),
+            b"verify-password",
+            env,
+            None,
+            None,
+        )
+        .unwrap();
+    }
+
+    // Structure only, no password.
+    envvault()
+        .arg("verify")
+        .current_dir(tmp.path())
+        .env_remove("ENVVAULT_PASSWORD")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("skipped (no password)"));
+
+    envvault()
+        .arg("verify")
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", "verify-password")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PASS").and(predicate::str::contains("skipped").not()));
+
+    // Truncate prod: it fails, dev alone still passes.
+    let prod = vault_dir.join("prod.vault");
+    let bytes = std::fs::read(&prod).unwrap();
+    std::fs::write(&prod, &bytes[..bytes.len() / 2]).unwrap();
+    envvault()
+        .arg("verify")
+        .current_dir(tmp.path())
+        .env_remove("ENVVAULT_PASSWORD")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("FAIL"))
+        .stderr(predicate::str::contains("1 of 2"));
+    envvault()
+        .args(["--env", "dev", "verify"])
+        .current_dir(tmp.path())
+        .env_remove("ENVVAULT_PASSWORD")
+        .assert()
+        .success();
+}
+
+#[test]
+fn env_clone_that_fails_to_save_writes_nothing_and_logs_the_abort() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".envvault")).unwrap();
+    std::fs::write(tmp.path().join("seed.env"), "API_KEY=sk-seed\n").unwrap();
+    // No secrets section fits in 0 MiB, so the one save fails.
+    std::fs::write(tmp.path().join(".envvault.toml"), "max_secrets_mb = 0\n").unwrap();
+
+    envvault()
+        .args(["env", "clone", "staging", "--from-file", "seed.env"])
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", "abort-password")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("nothing was applied"));
+
+    let entries: Vec<_> = std::fs::read_dir(tmp.path().join(".envvault"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| !name.starts_with("audit.db"))
+        .collect();
+    assert!(entries.is_empty(), "left behind: {entries:?}");
+
+    let out = envvault()
+        .args(["audit", "export"])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    let audit = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        audit.contains("plan") && audit.contains("aborted") && !audit.contains("applied"),
+        "{audit}"
+    );
+}
+
+#[test]
+fn set_description_and_tags_show_up_in_list() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+    envvault::vault::VaultStore::create(&vault_dir.join("dev.vault"), b"tag-pw", "dev", None, None)
+        .unwrap();
+    let run = |args: &[&str]| {
+        envvault()
+            .args(args)
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", "tag-pw")
+            .output()
+            .unwrap()
+    };
+
+    let set = run(&[
+        "set",
+        "STRIPE_KEY",
+        "sk_1",
+        "-f",
+        "--description",
+        "Stripe live key",
+        "--tag",
+        "payments",
+    ]);
+    assert!(set.status.success(), "{set:?}");
+    assert!(run(&["set", "DB_URL", "postgres://db", "-f"])
+        .status
+        .success());
+    // Updating the value alone keeps the notes.
+    assert!(run(&["set", "STRIPE_KEY", "sk_2", "-f"]).status.success());
+
+    let table = run(&["list", "--tag", "payments"]);
+    assert!(table.status.success());
+    let stdout = String::from_utf8_lossy(&table.stdout);
+    assert!(stdout.contains("Stripe live key"), "{stdout}");
+    assert!(!stdout.contains("DB_URL"), "{stdout}");
+
+    let json = run(&["list", "--json"]);
+    let parsed: serde_json::Value = serde_json::from_slice(&json.stdout).unwrap();
+    assert_eq!(parsed[1]["name"], "STRIPE_KEY");
+    assert_eq!(parsed[1]["tags"], serde_json::json!(["payments"]));
+    assert!(parsed[0].get("description").is_none());
+}
+
+#[test]
+fn run_check_only_reports_missing_secrets_without_running() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+    let mut store = envvault::vault::VaultStore::create(
+        &vault_dir.join("dev.vault"),
+        b"check-pw",
+        "dev",
+        None,
+        None,
+    )
+    .unwrap();
+    store.set_secret("DB_URL", "postgres://db").unwrap();
+    store.save().unwrap();
+    let marker = tmp.path().join("ran");
+    let run = |keys: &str| {
+        envvault()
+            .args(["run", "--check-only", keys, "--", "touch"])
+            .arg(&marker)
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", "check-pw")
+            .output()
+            .unwrap()
+    };
+
+    let ok = run("DB_URL");
+    assert!(ok.status.success(), "{ok:?}");
+
+    let missing = run("DB_URL,STRIPE_KEY,REDIS_URL");
+    assert!(!missing.status.success());
+    let stderr = String::from_utf8_lossy(&missing.stderr);
+    assert!(stderr.contains("STRIPE_KEY, REDIS_URL"), "{stderr}");
+
+    // The command never runs.
+    assert!(!marker.exists());
+}
+
+#[test]
+fn export_sort_declared_follows_set_order() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+    envvault::vault::VaultStore::create(
+        &vault_dir.join("dev.vault"),
+        b"order-pw",
+        "dev",
+        None,
+        None,
+    )
+    .unwrap();
+    let run = |args: &[&str]| {
+        envvault()
+            .args(args)
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", "order-pw")
+            .output()
+            .unwrap()
+    };
+
+    assert!(
+        run(&["set", "APP_URL", "https://app", "-f", "--order", "2"])
+            .status
+            .success()
+    );
+    assert!(run(&["set", "DB_HOST", "db", "-f", "--order", "1"])
+        .status
+        .success());
+
+    let by_name = run(&["export"]);
+    assert_eq!(
+        String::from_utf8_lossy(&by_name.stdout),
+        "APP_URL=https://app\nDB_HOST=db\n"
+    );
+    let declared = run(&["export", "--sort", "declared"]);
+    assert!(declared.status.success(), "{declared:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&declared.stdout),
+        "DB_HOST=db\nAPP_URL=https://app\n"
+    );
+}
+
+#[test]
+fn key_policy_blocks_bad_names_unless_no_policy() {
+    let tmp = TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    std::fs::create_dir_all(&vault_dir).unwrap();
+    envvault::vault::VaultStore::create(
+        &vault_dir.join("dev.vault"),
+        b"policy-pw",
+        "dev",
+        None,
+        None,
+    )
+    .unwrap();
+    std::fs::write(
+        tmp.path().join(".envvault.toml"),
+        "[key_policy]\npattern = \"^[A-Z][A-Z0-9_]*$\"\nrequired_prefixes = [\"DB_\"]\n",
+    )
+    .unwrap();
+    let run = |args: &[&str]| {
+        envvault()
+            .args(args)
+            .current_dir(tmp.path())
+            .env("ENVVAULT_PASSWORD", "policy-pw")
+            .output()
+            .unwrap()
+    };
+
+    assert!(run(&["set", "DB_HOST", "db", "-f"]).status.success());
+
+    let rejected = run(&["set", "api_key", "x", "-f"]);
+    assert!(!rejected.status.success());
+    let stderr = String::from_utf8_lossy(&rejected.stderr);
+    assert!(stderr.contains("api_key"), "{stderr}");
+    assert!(stderr.contains("--no-policy"), "{stderr}");
+
+    // A bulk import lists every offending name and writes nothing.
+    std::fs::write(
+        tmp.path().join("bulk.env"),
+        "DB_USER=u\nREDIS_URL=r\nstripe=s\n",
+    )
+    .unwrap();
+    let import = run(&["import", "bulk.env"]);
+    assert!(!import.status.success());
+    let stderr = String::from_utf8_lossy(&import.stderr);
+    assert!(stderr.contains("2 key(s)"), "{stderr}");
+    assert!(
+        stderr.contains("REDIS_URL") && stderr.contains("stripe"),
+        "{stderr}"
+    );
+
+    assert!(run(&["set", "api_key", "x", "-f", "--no-policy"])
+        .status
+        .success());
+    let check = run(&["list", "--policy-violations"]);
+    assert!(!check.status.success());
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert!(stdout.contains("api_key"), "{stdout}");
+    assert!(!stdout.contains("DB_HOST"), "{stdout}");
+
+    assert!(run(&["rename", "api_key", "DB_API_KEY"]).status.success());
+    assert!(run(&["list", "--policy-violations"]).status.success());
+}
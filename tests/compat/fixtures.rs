@@ -0,0 +1,271 @@
+//! The historical vault layouts and the generator for their checked-in
+//! files.
+//!
+//! Each [`Fixture`] writes its header and secrets JSON with exactly the
+//! fields the format had at that point, then encrypts and signs them the
+//! way envvault always has.  Salts and nonces are random, so regenerating
+//! changes the bytes but not what the files prove.
+
+use std::path::PathBuf;
+
+use envvault::crypto::encryption::encrypt_stream;
+use envvault::crypto::keys::MasterKey;
+use envvault::crypto::{
+    combine_password_keyfile, derive_master_key_with_len, encrypt_with_aad, generate_salt,
+    hash_keyfile, Argon2Params, Cipher,
+};
+use envvault::vault::format::compute_hmac;
+use serde_json::{json, Map, Value};
+
+/// Password of every fixture.
+pub const PASSWORD: &[u8] = b"compat-password";
+
+/// Keyfile of the fixtures that need one, next to the vaults.
+pub const KEYFILE: &str = "compat.keyfile";
+
+/// Plaintext bytes per chunk of the streamed secret.
+pub const STREAM_CHUNK_LEN: usize = 16;
+
+/// One historical layout, named after what it added to the one before.
+pub struct Fixture {
+    /// File stem in the fixtures directory.
+    pub name: &'static str,
+    /// `argon2_params` is stored; without it (v0.1.0) the key comes from
+    /// the default params.
+    pub argon2_params: bool,
+    /// `keyfile_hash` is stored and the keyfile is mixed into the password.
+    pub keyfile: bool,
+    /// `key_len` and `salt_len` are stored, with a 16-byte salt; without
+    /// them both are 32.
+    pub lengths: bool,
+    /// `cipher` is stored; without it values are AES-256-GCM.
+    pub cipher: Option<Cipher>,
+    /// Secrets carry the optional metadata (`encoding`, access stats,
+    /// `expires_at`, a streamed value) and the header has `rotated_at`.
+    pub secret_metadata: bool,
+    /// Format version 2: every value authenticates its secret name as
+    /// associated data.  Earlier layouts are version 1.
+    pub aad: bool,
+}
+
+impl Fixture {
+    /// The format version written in the prefix and header.
+    pub fn version(&self) -> u8 {
+        if self.aad {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+pub const FIXTURES: &[Fixture] = &[
+    Fixture {
+        name: "v0.1.0",
+        argon2_params: false,
+        keyfile: false,
+        lengths: false,
+        cipher: None,
+        secret_metadata: false,
+        aad: false,
+    },
+    Fixture {
+        name: "argon2-params",
+        argon2_params: true,
+        keyfile: false,
+        lengths: false,
+        cipher: None,
+        secret_metadata: false,
+        aad: false,
+    },
+    Fixture {
+        name: "keyfile",
+        argon2_params: true,
+        keyfile: true,
+        lengths: false,
+        cipher: None,
+        secret_metadata: false,
+        aad: false,
+    },
+    Fixture {
+        name: "key-salt-len",
+        argon2_params: true,
+        keyfile: false,
+        lengths: true,
+        cipher: None,
+        secret_metadata: false,
+        aad: false,
+    },
+    Fixture {
+        name: "chacha20",
+        argon2_params: true,
+        keyfile: false,
+        lengths: true,
+        cipher: Some(Cipher::ChaCha20Poly1305),
+        secret_metadata: false,
+        aad: false,
+    },
+    Fixture {
+        name: "secret-metadata",
+        argon2_params: true,
+        keyfile: false,
+        lengths: true,
+        cipher: Some(Cipher::Aes256Gcm),
+        secret_metadata: true,
+        aad: false,
+    },
+    Fixture {
+        name: "secret-aad",
+        argon2_params: true,
+        keyfile: false,
+        lengths: true,
+        cipher: Some(Cipher::Aes256Gcm),
+        secret_metadata: true,
+        aad: true,
+    },
+];
+
+/// Directory holding the checked-in fixtures.
+pub fn dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/compat/fixtures")
+}
+
+/// Contents of [`KEYFILE`].
+pub fn keyfile_bytes() -> Vec<u8> {
+    (0u8..32)
+        .map(|b| b.wrapping_mul(37).wrapping_add(11))
+        .collect()
+}
+
+/// Name and value of every secret in `fixture`.
+pub fn expected_secrets(fixture: &Fixture) -> Vec<(&'static str, &'static str)> {
+    let mut secrets = vec![
+        ("API_KEY", "compat-api-key"),
+        ("DATABASE_URL", "postgres://compat@localhost/app"),
+    ];
+    if fixture.secret_metadata {
+        secrets.push(("CERT_B64", "aGVsbG8gY29tcGF0"));
+        secrets.push(("STREAMED", "a value longer than one stream chunk"));
+    }
+    secrets
+}
+
+/// Argon2 params stored in the fixtures that store them.
+fn stored_params() -> Argon2Params {
+    Argon2Params {
+        memory_kib: envvault::crypto::kdf::MIN_MEMORY_KIB,
+        iterations: 1,
+        parallelism: 1,
+    }
+}
+
+/// The vault file bytes for `fixture`.
+pub fn build(fixture: &Fixture) -> Vec<u8> {
+    let salt_len = if fixture.lengths { 16 } else { 32 };
+    let salt = generate_salt()[..salt_len].to_vec();
+    let params = if fixture.argon2_params {
+        stored_params()
+    } else {
+        Argon2Params::default()
+    };
+
+    let mut header = Map::new();
+    header.insert("version".into(), json!(fixture.version()));
+    header.insert("salt".into(), json!(b64(&salt)));
+    header.insert("created_at".into(), json!("2024-01-15T09:30:00Z"));
+    header.insert("environment".into(), json!("dev"));
+    if fixture.argon2_params {
+        header.insert(
+            "argon2_params".into(),
+            json!({
+                "memory_kib": params.memory_kib,
+                "iterations": params.iterations,
+                "parallelism": params.parallelism,
+            }),
+        );
+    }
+    if fixture.keyfile {
+        header.insert("keyfile_hash".into(), json!(hash_keyfile(&keyfile_bytes())));
+    }
+    if fixture.lengths {
+        header.insert("key_len".into(), json!(32));
+        header.insert("salt_len".into(), json!(salt_len));
+    }
+    if let Some(cipher) = fixture.cipher {
+        header.insert("cipher".into(), json!(cipher.as_str()));
+    }
+    if fixture.secret_metadata {
+        header.insert("rotated_at".into(), json!("2024-06-01T12:00:00Z"));
+    }
+
+    let password = if fixture.keyfile {
+        combine_password_keyfile(PASSWORD, &keyfile_bytes()).unwrap()
+    } else {
+        PASSWORD.to_vec()
+    };
+    let master = derive_master_key_with_len(&password, &salt, &params, 32).unwrap();
+    let master = MasterKey::from_slice(&master);
+    let cipher = fixture.cipher.unwrap_or_default();
+
+    let secrets: Vec<Value> = expected_secrets(fixture)
+        .into_iter()
+        .map(|(name, value)| {
+            let key = master.derive_secret_key(name).unwrap();
+            let aad = if fixture.aad { name.as_bytes() } else { b"" };
+            let mut secret = Map::new();
+            secret.insert("name".into(), json!(name));
+            let ciphertext = if name == "STREAMED" {
+                let mut sealed = Vec::new();
+                encrypt_stream(
+                    cipher,
+                    &key,
+                    STREAM_CHUNK_LEN,
+                    aad,
+                    value.as_bytes(),
+                    &mut sealed,
+                )
+                .unwrap();
+                secret.insert("chunk_size".into(), json!(STREAM_CHUNK_LEN));
+                sealed
+            } else {
+                encrypt_with_aad(cipher, &key, value.as_bytes(), aad).unwrap()
+            };
+            secret.insert("encrypted_value".into(), json!(b64(&ciphertext)));
+            secret.insert("created_at".into(), json!("2024-01-15T09:31:00Z"));
+            secret.insert("updated_at".into(), json!("2024-02-01T10:00:00Z"));
+            if fixture.secret_metadata {
+                if name == "CERT_B64" {
+                    secret.insert("encoding".into(), json!("base64"));
+                }
+                if name == "API_KEY" {
+                    secret.insert("access_count".into(), json!(3));
+                    secret.insert("last_accessed".into(), json!("2024-05-20T08:00:00Z"));
+                    secret.insert("expires_at".into(), json!("2030-01-01T00:00:00Z"));
+                }
+            }
+            Value::Object(secret)
+        })
+        .collect();
+
+    let header_bytes = serde_json::to_vec(&Value::Object(header)).unwrap();
+    let secrets_bytes = serde_json::to_vec(&secrets).unwrap();
+    let hmac = compute_hmac(
+        &master.derive_hmac_key().unwrap(),
+        &header_bytes,
+        &secrets_bytes,
+    )
+    .unwrap();
+
+    let mut file = b"EVLT".to_vec();
+    file.push(fixture.version());
+    file.extend_from_slice(&(header_bytes.len() as u32).to_le_bytes());
+    file.extend_from_slice(&header_bytes);
+    file.extend_from_slice(&secrets_bytes);
+    file.extend_from_slice(&hmac);
+    file
+}
+
+fn b64(bytes: &[u8]) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
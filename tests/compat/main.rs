@@ -0,0 +1,168 @@
+//! Backward compatibility: every historical vault layout keeps opening.
+//!
+//! Each format addition (`argon2_params`, `keyfile_hash`, `key_len` /
+//! `salt_len`, `cipher`, per-secret metadata) relies on serde defaults for
+//! files written before it; format version 2 added name-bound values.  `fixtures/` holds one checked-in vault per
+//! layout (see `fixtures.rs`); these tests open each with its known
+//! password, read every secret, and check that `save()` rewrites it in the
+//! current layout without losing anything.  `save()` keeps the format
+//! version: only `rotate-key` re-encrypts a version 1 vault as version 2.
+//!
+//! Never regenerate a fixture to make a failing test pass: the files are
+//! the contract.  To add a layout, add a `Fixture` and run
+//! `cargo test --test compat -- --ignored regenerate_fixtures`, which
+//! writes only files that do not exist yet.
+
+mod fixtures;
+
+use std::fs;
+
+use envvault::vault::format;
+use envvault::vault::VaultStore;
+use fixtures::{Fixture, FIXTURES, KEYFILE, PASSWORD};
+use serde_json::Value;
+
+fn open(path: &std::path::Path, fixture: &Fixture) -> VaultStore {
+    let keyfile = fixture
+        .keyfile
+        .then(|| fs::read(fixtures::dir().join(KEYFILE)).unwrap());
+    VaultStore::open(path, PASSWORD, keyfile.as_deref())
+        .unwrap_or_else(|e| panic!("{}: {e}", fixture.name))
+}
+
+fn assert_secrets(store: &VaultStore, fixture: &Fixture) {
+    let expected = fixtures::expected_secrets(fixture);
+    assert_eq!(store.secret_count(), expected.len(), "{}", fixture.name);
+    for (name, value) in expected {
+        assert_eq!(store.get_secret(name).unwrap(), value, "{}", fixture.name);
+    }
+}
+
+/// Every stored secret field, as JSON, in name order.
+fn raw_secrets(path: &std::path::Path) -> Vec<Value> {
+    let mut secrets = format::read_vault(path).unwrap().secrets;
+    secrets.sort_by(|a, b| a.name.cmp(&b.name));
+    secrets
+        .iter()
+        .map(|s| serde_json::to_value(s).unwrap())
+        .collect()
+}
+
+#[test]
+fn every_fixture_opens_reads_and_upgrades_losslessly() {
+    for fixture in FIXTURES {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("dev.vault");
+        fs::copy(
+            fixtures::dir().join(format!("{}.vault", fixture.name)),
+            &path,
+        )
+        .unwrap();
+
+        let mut store = open(&path, fixture);
+        assert_eq!(
+            store.format_version(),
+            fixture.version(),
+            "{}",
+            fixture.name
+        );
+        assert_eq!(store.environment(), "dev");
+        assert_eq!(
+            store.kdf_params_implicit(),
+            !fixture.argon2_params,
+            "{}",
+            fixture.name
+        );
+        assert_eq!(store.cipher(), fixture.cipher.unwrap_or_default());
+        assert_secrets(&store, fixture);
+
+        let created_at = store.created_at();
+        let key_set_at = store.key_set_at();
+        let secrets_before = raw_secrets(&path);
+        store.save().unwrap();
+
+        let store = open(&path, fixture);
+        assert_secrets(&store, fixture);
+        assert_eq!(store.created_at(), created_at, "{}", fixture.name);
+        assert_eq!(store.key_set_at(), key_set_at, "{}", fixture.name);
+        assert_eq!(store.kdf_params_implicit(), !fixture.argon2_params);
+        assert_eq!(raw_secrets(&path), secrets_before, "{}", fixture.name);
+
+        // The rewritten header spells out what used to be implied.
+        let header = format::read_header(&path).unwrap();
+        assert_eq!(header.version, fixture.version(), "{}", fixture.name);
+        assert_eq!(header.key_len, 32, "{}", fixture.name);
+        assert_eq!(
+            header.salt_len,
+            if fixture.lengths { 16 } else { 32 },
+            "{}",
+            fixture.name
+        );
+    }
+}
+
+#[test]
+fn secret_metadata_fixture_keeps_optional_fields() {
+    let fixture = FIXTURES.iter().find(|f| f.secret_metadata).unwrap();
+    let path = fixtures::dir().join(format!("{}.vault", fixture.name));
+    let store = open(&path, fixture);
+
+    let meta = store.list_secrets();
+    let api = meta.iter().find(|m| m.name == "API_KEY").unwrap();
+    assert_eq!(api.access_count, Some(3));
+    assert!(api.last_accessed.is_some() && api.expires_at.is_some());
+    assert_eq!(store.secret_encoding("CERT_B64"), Some("base64"));
+    assert_ne!(store.key_set_at(), store.created_at());
+
+    let mut streamed = Vec::new();
+    store.get_secret_writer("STREAMED", &mut streamed).unwrap();
+    assert_eq!(streamed, b"a value longer than one stream chunk");
+}
+
+#[test]
+fn rotate_key_keeps_the_streamed_fixture_value_chunked() {
+    let fixture = FIXTURES.iter().find(|f| f.secret_metadata).unwrap();
+    let tmp = tempfile::TempDir::new().unwrap();
+    let vault_dir = tmp.path().join(".envvault");
+    fs::create_dir_all(&vault_dir).unwrap();
+    let path = vault_dir.join("dev.vault");
+    fs::copy(
+        fixtures::dir().join(format!("{}.vault", fixture.name)),
+        &path,
+    )
+    .unwrap();
+
+    #[allow(deprecated)]
+    assert_cmd::Command::cargo_bin("envvault")
+        .unwrap()
+        .args(["rotate-key", "--force"])
+        .current_dir(tmp.path())
+        .env("ENVVAULT_PASSWORD", std::str::from_utf8(PASSWORD).unwrap())
+        .assert()
+        .success();
+
+    let store = open(&path, fixture);
+    assert_secrets(&store, fixture);
+    let streamed = raw_secrets(&path)
+        .into_iter()
+        .find(|s| s["name"] == "STREAMED")
+        .unwrap();
+    assert_eq!(streamed["chunk_size"], fixtures::STREAM_CHUNK_LEN);
+}
+
+#[test]
+#[ignore = "writes fixture files; run to add a new layout"]
+fn regenerate_fixtures() {
+    let dir = fixtures::dir();
+    fs::create_dir_all(&dir).unwrap();
+    let keyfile = dir.join(KEYFILE);
+    if !keyfile.exists() {
+        fs::write(&keyfile, fixtures::keyfile_bytes()).unwrap();
+    }
+    for fixture in FIXTURES {
+        let path = dir.join(format!("{}.vault", fixture.name));
+        if !path.exists() {
+            fs::write(&path, fixtures::build(fixture)).unwrap();
+        }
+    }
+}
@@ -1,7 +1,17 @@
 //! Integration tests for the EnvVault crypto module.
 
+use envvault::crypto::encryption::{
+    decrypt_stream, encrypt_stream, MAX_STREAM_CHUNK_LEN, STREAM_NONCE_PREFIX_LEN,
+};
+use envvault::crypto::kdf::{
+    calibrate, calibrate_with, validate_params, Argon2Params, MAX_ITERATIONS, MAX_MEMORY_KIB,
+    MAX_PARALLELISM, MIN_MEMORY_KIB,
+};
 use envvault::crypto::keys::{derive_hmac_key, derive_secret_key, MasterKey};
-use envvault::crypto::{decrypt, derive_master_key, encrypt, generate_salt};
+use envvault::crypto::{
+    decrypt, decrypt_with, decrypt_with_aad, derive_master_key, encrypt, encrypt_with,
+    encrypt_with_aad, generate_salt, Cipher,
+};
 
 // ---------------------------------------------------------------------------
 // Encryption round-trip
@@ -72,6 +82,90 @@ fn decrypt_with_corrupted_ciphertext_fails() {
     assert!(result.is_err(), "corrupted ciphertext must fail auth check");
 }
 
+#[test]
+fn encrypt_with_roundtrip_for_both_ciphers() {
+    let key = [0x5Au8; 32];
+    let plaintext = b"STRIPE_KEY=sk_live_abc";
+
+    for cipher in [Cipher::Aes256Gcm, Cipher::ChaCha20Poly1305] {
+        let ciphertext = encrypt_with(cipher, &key, plaintext).expect("encrypt");
+        // Same layout for both: 12-byte nonce + ciphertext + 16-byte tag.
+        assert_eq!(ciphertext.len(), 12 + plaintext.len() + 16);
+        let recovered = decrypt_with(cipher, &key, &ciphertext).expect("decrypt");
+        assert_eq!(recovered, plaintext, "{}", cipher.as_str());
+    }
+}
+
+#[test]
+fn ciphers_do_not_decrypt_each_other() {
+    let key = [0x5Au8; 32];
+    let chacha = encrypt_with(Cipher::ChaCha20Poly1305, &key, b"value").unwrap();
+    assert!(decrypt(&key, &chacha).is_err());
+
+    // `encrypt` is AES-256-GCM.
+    let aes = encrypt(&key, b"value").unwrap();
+    assert!(decrypt_with(Cipher::ChaCha20Poly1305, &key, &aes).is_err());
+    assert_eq!(
+        decrypt_with(Cipher::Aes256Gcm, &key, &aes).unwrap(),
+        b"value"
+    );
+}
+
+#[test]
+fn associated_data_must_match_to_decrypt() {
+    let key = [0x5Au8; 32];
+    for cipher in [Cipher::Aes256Gcm, Cipher::ChaCha20Poly1305] {
+        let sealed = encrypt_with_aad(cipher, &key, b"hunter2", b"ADMIN_TOKEN").unwrap();
+        assert_eq!(
+            decrypt_with_aad(cipher, &key, &sealed, b"ADMIN_TOKEN").unwrap(),
+            b"hunter2"
+        );
+        assert!(decrypt_with_aad(cipher, &key, &sealed, b"DEBUG_FLAG").is_err());
+        assert!(decrypt_with(cipher, &key, &sealed).is_err());
+
+        // Empty associated data is the same as none.
+        let plain = encrypt_with(cipher, &key, b"hunter2").unwrap();
+        assert_eq!(
+            decrypt_with_aad(cipher, &key, &plain, b"").unwrap(),
+            b"hunter2"
+        );
+    }
+
+    let mut sealed = Vec::new();
+    encrypt_stream(
+        Cipher::Aes256Gcm,
+        &key,
+        16,
+        b"ADMIN_TOKEN",
+        &[9u8; 40][..],
+        &mut sealed,
+    )
+    .unwrap();
+    let open = |aad: &[u8]| {
+        decrypt_stream(
+            Cipher::Aes256Gcm,
+            &key,
+            16,
+            aad,
+            sealed.as_slice(),
+            Vec::new(),
+        )
+    };
+    assert!(open(b"ADMIN_TOKEN").is_ok());
+    assert!(open(b"DEBUG_FLAG").is_err());
+}
+
+#[test]
+fn cipher_names_parse() {
+    assert_eq!(Cipher::parse("aes-256-gcm").unwrap(), Cipher::Aes256Gcm);
+    assert_eq!(
+        Cipher::parse("ChaCha20-Poly1305").unwrap(),
+        Cipher::ChaCha20Poly1305
+    );
+    assert!(Cipher::parse("des").is_err());
+    assert_eq!(Cipher::default(), Cipher::Aes256Gcm);
+}
+
 // ---------------------------------------------------------------------------
 // Key derivation (Argon2id)
 // ---------------------------------------------------------------------------
@@ -197,3 +291,194 @@ fn full_crypto_pipeline() {
     let recovered = decrypt(&secret_key, &ciphertext).expect("decrypt");
     assert_eq!(recovered, plaintext.to_vec());
 }
+
+// ---------------------------------------------------------------------------
+// Argon2 parameter checks
+// ---------------------------------------------------------------------------
+
+#[test]
+fn default_params_are_not_below_recommended() {
+    assert!(Argon2Params::default().below_recommended().is_empty());
+}
+
+#[test]
+fn below_recommended_warning_matrix() {
+    let defaults = Argon2Params::default();
+    let cases = [
+        (defaults.memory_kib / 2, defaults.iterations, 4, 1),
+        (defaults.memory_kib, defaults.iterations - 1, 4, 1),
+        (defaults.memory_kib / 2, 1, 4, 2),
+        // Fewer lanes alone is not a weakness.
+        (defaults.memory_kib, defaults.iterations, 1, 0),
+        // Stronger than the defaults.
+        (defaults.memory_kib * 4, defaults.iterations + 2, 8, 0),
+    ];
+
+    for (memory_kib, iterations, parallelism, expected) in cases {
+        let params = Argon2Params {
+            memory_kib,
+            iterations,
+            parallelism,
+        };
+        assert_eq!(
+            params.below_recommended().len(),
+            expected,
+            "unexpected shortfalls for {params}"
+        );
+    }
+}
+
+#[test]
+fn validate_params_rejects_absurd_values() {
+    let defaults = Argon2Params::default();
+    assert!(validate_params(&defaults).is_ok());
+
+    for params in [
+        Argon2Params {
+            memory_kib: MAX_MEMORY_KIB + 1,
+            ..defaults
+        },
+        Argon2Params {
+            iterations: MAX_ITERATIONS + 1,
+            ..defaults
+        },
+        Argon2Params {
+            parallelism: MAX_PARALLELISM + 1,
+            ..defaults
+        },
+    ] {
+        let err = validate_params(&params).unwrap_err().to_string();
+        assert!(err.contains("at most"), "{err}");
+    }
+}
+
+#[test]
+fn argon2_params_display() {
+    assert_eq!(
+        Argon2Params::default().to_string(),
+        "Argon2id m=65536 KiB, t=3, p=4"
+    );
+}
+
+#[test]
+fn calibrate_never_goes_below_the_minimums() {
+    let params = calibrate(std::time::Duration::ZERO);
+    assert!(validate_params(&params).is_ok(), "{params}");
+    assert_eq!(params.memory_kib, MIN_MEMORY_KIB);
+    assert_eq!(params.iterations, Argon2Params::default().iterations);
+}
+
+#[test]
+fn calibrate_longer_targets_never_give_weaker_params() {
+    use std::time::Duration;
+
+    // A machine where 1 MiB of one pass costs 1 ms.
+    let cost = |p: &Argon2Params| {
+        Duration::from_micros(u64::from(p.memory_kib) * u64::from(p.iterations) * 1000 / 1024)
+    };
+
+    let mut previous = calibrate_with(Duration::ZERO, cost);
+    assert_eq!(previous.memory_kib, MIN_MEMORY_KIB);
+    for ms in [
+        1, 10, 24, 25, 100, 250, 500, 1_000, 10_000, 100_000, 1_000_000,
+    ] {
+        let params = calibrate_with(Duration::from_millis(ms), cost);
+        assert!(validate_params(&params).is_ok(), "{params}");
+        assert!(
+            params.memory_kib >= previous.memory_kib && params.iterations >= previous.iterations,
+            "{ms} ms gave {params}, weaker than {previous}"
+        );
+        assert!(params.iterations == 3 || params.memory_kib == MAX_MEMORY_KIB);
+        previous = params;
+    }
+    // 500 ms at 3 passes is ~166 MiB on this machine.
+    let half_second = calibrate_with(Duration::from_millis(500), cost);
+    assert_eq!(half_second.memory_kib, 166 * 1024);
+}
+
+// ---------------------------------------------------------------------------
+// Streaming encryption
+// ---------------------------------------------------------------------------
+
+fn stream_round_trip(cipher: Cipher, chunk_len: usize, plaintext: &[u8]) -> Vec<u8> {
+    let key = [0x5Au8; 32];
+    let mut sealed = Vec::new();
+    let read = encrypt_stream(cipher, &key, chunk_len, b"", plaintext, &mut sealed).unwrap();
+    assert_eq!(read, plaintext.len() as u64);
+
+    let mut opened = Vec::new();
+    let written =
+        decrypt_stream(cipher, &key, chunk_len, b"", sealed.as_slice(), &mut opened).unwrap();
+    assert_eq!(written, plaintext.len() as u64);
+    assert_eq!(opened, plaintext);
+    sealed
+}
+
+#[test]
+fn stream_round_trips_on_and_around_chunk_boundaries() {
+    let data: Vec<u8> = (0..=255u8).cycle().take(3 * 16 + 5).collect();
+    for cipher in [Cipher::Aes256Gcm, Cipher::ChaCha20Poly1305] {
+        for len in [0, 1, 15, 16, 17, 32, 48, data.len()] {
+            let sealed = stream_round_trip(cipher, 16, &data[..len]);
+            // Prefix plus a tag per chunk; an exact multiple has no empty
+            // trailing chunk, and empty input is one empty chunk.
+            let chunks = ((len + 15) / 16).max(1);
+            assert_eq!(sealed.len(), STREAM_NONCE_PREFIX_LEN + len + 16 * chunks);
+        }
+    }
+}
+
+#[test]
+fn stream_rejects_truncated_reordered_and_wrong_key() {
+    let key = [0x5Au8; 32];
+    let data = [7u8; 40];
+    let mut sealed = Vec::new();
+    encrypt_stream(Cipher::Aes256Gcm, &key, 16, b"", &data[..], &mut sealed).unwrap();
+    let open = |input: &[u8], key: &[u8]| {
+        decrypt_stream(Cipher::Aes256Gcm, key, 16, b"", input, &mut Vec::new())
+    };
+    assert!(open(&sealed, &key).is_ok());
+
+    // Dropping the last chunk leaves a full chunk that is not marked last.
+    let whole_chunks = STREAM_NONCE_PREFIX_LEN + 2 * (16 + 16);
+    assert!(open(&sealed[..whole_chunks], &key).is_err());
+    assert!(open(&sealed[..STREAM_NONCE_PREFIX_LEN], &key).is_err());
+    assert!(open(&sealed[..3], &key).is_err());
+
+    // Swapping the first two chunks breaks their counters.
+    let mut swapped = sealed.clone();
+    let (first, second) = (STREAM_NONCE_PREFIX_LEN, STREAM_NONCE_PREFIX_LEN + 32);
+    let chunk: Vec<u8> = swapped[first..second].to_vec();
+    swapped.copy_within(second..second + 32, first);
+    swapped[second..second + 32].copy_from_slice(&chunk);
+    assert!(open(&swapped, &key).is_err());
+
+    assert!(open(&sealed, &[0x5Bu8; 32]).is_err());
+    // A one-piece ciphertext is not a stream.
+    assert!(open(&encrypt(&key, &data).unwrap(), &key).is_err());
+}
+
+#[test]
+fn stream_chunk_length_is_bounded() {
+    let key = [0u8; 32];
+    for chunk_len in [0, MAX_STREAM_CHUNK_LEN + 1] {
+        assert!(encrypt_stream(
+            Cipher::Aes256Gcm,
+            &key,
+            chunk_len,
+            b"",
+            &b"x"[..],
+            Vec::new()
+        )
+        .is_err());
+        assert!(decrypt_stream(
+            Cipher::Aes256Gcm,
+            &key,
+            chunk_len,
+            b"",
+            &b"x"[..],
+            Vec::new()
+        )
+        .is_err());
+    }
+}
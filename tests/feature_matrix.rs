@@ -0,0 +1,48 @@
+//! Build matrix for the cargo features.
+//!
+//! `cargo check`s every supported feature combination, lib, binary, and
+//! tests, in a target directory of its own.  That takes a few minutes, so
+//! the test is ignored by default; CI runs it with
+//! `cargo test --test feature_matrix -- --ignored`.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Feature sets checked with `--no-default-features --features <set>`.
+const COMBINATIONS: &[&str] = &[
+    "",
+    "cli",
+    "audit-log",
+    "keyring-store",
+    "version-check",
+    "clipboard",
+    "dev-tools",
+    "full",
+    "full,dev-tools",
+];
+
+#[test]
+#[ignore = "runs cargo check once per feature combination"]
+fn every_feature_combination_builds() {
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("feature-matrix");
+
+    let failed: Vec<&str> = COMBINATIONS
+        .iter()
+        .copied()
+        .filter(|features| {
+            let status = Command::new(env!("CARGO"))
+                .current_dir(env!("CARGO_MANIFEST_DIR"))
+                .args(["check", "--all-targets", "--no-default-features"])
+                .args(["--features", features])
+                .env("CARGO_TARGET_DIR", &target_dir)
+                .status()
+                .expect("cargo should run");
+            !status.success()
+        })
+        .collect();
+
+    assert!(
+        failed.is_empty(),
+        "failed to build with features {failed:?}"
+    );
+}
@@ -250,9 +250,13 @@ fn from_parts_creates_empty_vault() {
             parallelism: fast_params.parallelism,
         }),
         keyfile_hash: None,
+        key_len: 32,
+        salt_len: salt.len(),
+        cipher: None,
+        rotated_at: None,
     };
 
-    let mut store = VaultStore::from_parts(path.clone(), header, master_key);
+    let mut store = VaultStore::from_parts(path.clone(), header, master_key).unwrap();
     store.set_secret("KEY", "value").unwrap();
     store.save().unwrap();
 
@@ -262,6 +266,110 @@ fn from_parts_creates_empty_vault() {
     assert_eq!(store2.environment(), "test");
 }
 
+#[test]
+fn open_honors_stored_key_len() {
+    use envvault::crypto::kdf::{derive_master_key_with_len, generate_salt};
+    use envvault::crypto::keys::MasterKey;
+    use envvault::vault::format::{StoredArgon2Params, VaultHeader, CURRENT_VERSION};
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("key-len.vault");
+    let password = b"key-len-test";
+    let salt = generate_salt();
+
+    let fast_params = Argon2Params {
+        memory_kib: 8_192,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    let master_bytes = derive_master_key_with_len(password, &salt, &fast_params, 64).unwrap();
+    let master_key = MasterKey::from_slice(&master_bytes);
+
+    let header = VaultHeader {
+        version: CURRENT_VERSION,
+        salt: salt.to_vec(),
+        created_at: chrono::Utc::now(),
+        environment: "test".to_string(),
+        argon2_params: Some(StoredArgon2Params {
+            memory_kib: fast_params.memory_kib,
+            iterations: fast_params.iterations,
+            parallelism: fast_params.parallelism,
+        }),
+        keyfile_hash: None,
+        key_len: 64,
+        salt_len: salt.len(),
+        cipher: None,
+        rotated_at: None,
+    };
+
+    let mut store = VaultStore::from_parts(path.clone(), header, master_key).unwrap();
+    store.set_secret("KEY", "value").unwrap();
+    store.save().unwrap();
+
+    // Opening must derive a 64-byte key, or the HMAC check fails.
+    let reopened = VaultStore::open(&path, password, None).unwrap();
+    assert_eq!(reopened.header().key_len, 64);
+    assert_eq!(reopened.get_secret("KEY").unwrap(), "value");
+}
+
+#[test]
+fn vault_without_cipher_field_opens_as_aes() {
+    use envvault::crypto::kdf::{derive_master_key_with_params, generate_salt};
+    use envvault::crypto::keys::MasterKey;
+    use envvault::crypto::{decrypt, Cipher};
+    use envvault::vault::format::{self, StoredArgon2Params, VaultHeader};
+
+    let (_dir, path) = vault_path();
+    let password = b"pre-cipher-vault";
+    let salt = generate_salt();
+    let fast_params = Argon2Params {
+        memory_kib: 8_192,
+        iterations: 1,
+        parallelism: 1,
+    };
+    let master_bytes = derive_master_key_with_params(password, &salt, &fast_params).unwrap();
+
+    // A header as written before the cipher was recorded (format v1,
+    // so values carry no associated data).
+    let header = VaultHeader {
+        version: 1,
+        salt: salt.to_vec(),
+        created_at: chrono::Utc::now(),
+        environment: "dev".to_string(),
+        argon2_params: Some(StoredArgon2Params {
+            memory_kib: fast_params.memory_kib,
+            iterations: fast_params.iterations,
+            parallelism: fast_params.parallelism,
+        }),
+        keyfile_hash: None,
+        key_len: 32,
+        salt_len: salt.len(),
+        cipher: None,
+        rotated_at: None,
+    };
+    let mut store =
+        VaultStore::from_parts(path.clone(), header, MasterKey::new(master_bytes)).unwrap();
+    store.set_secret("KEY", "value").unwrap();
+    store.save().unwrap();
+
+    let raw = format::read_vault(&path).unwrap();
+    assert!(!String::from_utf8_lossy(&raw.header_bytes).contains("cipher"));
+
+    // The stored value is plain AES-256-GCM under the per-secret key.
+    let secret_key = MasterKey::new(master_bytes)
+        .derive_secret_key("KEY")
+        .unwrap();
+    assert_eq!(
+        decrypt(&secret_key, &raw.secrets[0].encrypted_value).unwrap(),
+        b"value"
+    );
+
+    let reopened = VaultStore::open(&path, password, None).unwrap();
+    assert_eq!(reopened.cipher(), Cipher::Aes256Gcm);
+    assert_eq!(reopened.get_secret("KEY").unwrap(), "value");
+}
+
 // ---------------------------------------------------------------------------
 // Rotate key using from_parts (exercises the real code path)
 // ---------------------------------------------------------------------------
@@ -309,12 +417,18 @@ fn rotate_key_via_from_parts() {
             parallelism: fast_params.parallelism,
         }),
         keyfile_hash: store.header().keyfile_hash.clone(),
+        key_len: 32,
+        salt_len: new_salt.len(),
+        cipher: store.header().cipher.clone(),
+        rotated_at: None,
     };
 
     // Create new store via from_parts and re-encrypt all secrets.
-    let mut new_store = VaultStore::from_parts(path.clone(), new_header, new_master_key);
-    for (name, value) in &secrets {
-        new_store.set_secret(name, value).unwrap();
+    let mut new_store = VaultStore::from_parts(path.clone(), new_header, new_master_key).unwrap();
+    for meta in store.list_secrets() {
+        new_store
+            .set_secret_with_metadata(&meta, &secrets[&meta.name])
+            .unwrap();
     }
     new_store.save().unwrap();
 
@@ -325,6 +439,16 @@ fn rotate_key_via_from_parts() {
     let reopened = VaultStore::open(&path, new_password, None).unwrap();
     assert_eq!(reopened.get_secret("DB_URL").unwrap(), "postgres://old");
     assert_eq!(reopened.get_secret("TOKEN").unwrap(), "secret-123");
+
+    // Values did not change, so neither did their timestamps.
+    let before = store.list_secrets();
+    let after = reopened.list_secrets();
+    assert_eq!(before.len(), after.len());
+    for (b, a) in before.iter().zip(&after) {
+        assert_eq!(b.name, a.name);
+        assert_eq!(b.created_at, a.created_at);
+        assert_eq!(b.updated_at, a.updated_at);
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -475,18 +599,32 @@ fn rotate_preserves_keyfile_hash() {
             parallelism: fast_params.parallelism,
         }),
         keyfile_hash: store.header().keyfile_hash.clone(),
+        key_len: 32,
+        salt_len: new_salt.len(),
+        cipher: store.header().cipher.clone(),
+        rotated_at: None,
     };
 
-    let mut new_store = VaultStore::from_parts(vault.clone(), new_header, new_master_key);
-    for (name, value) in &secrets {
-        new_store.set_secret(name, value).unwrap();
+    let mut new_store = VaultStore::from_parts(vault.clone(), new_header, new_master_key).unwrap();
+    for meta in store.list_secrets() {
+        new_store
+            .set_secret_with_metadata(&meta, &secrets[&meta.name])
+            .unwrap();
     }
     new_store.save().unwrap();
 
-    // Verify keyfile_hash is preserved after rotation.
+    // Verify keyfile_hash and timestamps are preserved after rotation.
     let reopened = VaultStore::open(&vault, new_password, Some(&kf_bytes)).unwrap();
     assert_eq!(reopened.header().keyfile_hash, original_kf_hash);
     assert_eq!(reopened.get_secret("KEY").unwrap(), "val");
+    assert_eq!(
+        reopened.list_secrets()[0].created_at,
+        store.list_secrets()[0].created_at
+    );
+    assert_eq!(
+        reopened.list_secrets()[0].updated_at,
+        store.list_secrets()[0].updated_at
+    );
 
     // Opening without keyfile must still fail.
     assert!(VaultStore::open(&vault, new_password, None).is_err());
@@ -5,6 +5,7 @@
 
 use std::fs;
 
+use envvault::devtools::{corrupt_vault, generate_vault, CorruptMode, GenVaultOptions};
 use envvault::vault::VaultStore;
 use tempfile::TempDir;
 
@@ -109,21 +110,20 @@ fn export_import_roundtrip_via_api() {
 // Vault corruption scenarios
 // ---------------------------------------------------------------------------
 
+/// Helper: generate a small fixture vault and corrupt it in place.
+fn corrupted_vault(dir: &TempDir, mode: CorruptMode) -> std::path::PathBuf {
+    let path = vault_path(dir, &mode.to_string());
+    generate_vault(&path, &GenVaultOptions::default()).unwrap();
+    corrupt_vault(&path, mode).unwrap();
+    path
+}
+
 #[test]
 fn truncated_vault_file_rejected() {
     let dir = TempDir::new().unwrap();
-    let path = vault_path(&dir, "truncated");
-    let pw = b"truncate-pw";
+    let path = corrupted_vault(&dir, CorruptMode::Truncate);
 
-    let mut store = VaultStore::create(&path, pw, "dev", None, None).unwrap();
-    store.set_secret("KEY", "value").unwrap();
-    store.save().unwrap();
-
-    // Truncate the file to just the magic bytes.
-    let data = fs::read(&path).unwrap();
-    fs::write(&path, &data[..8]).unwrap();
-
-    let result = VaultStore::open(&path, pw, None);
+    let result = VaultStore::open(&path, b"testpass", None);
     assert!(result.is_err(), "truncated vault must be rejected");
 }
 
@@ -142,60 +142,28 @@ fn empty_file_rejected() {
 #[test]
 fn wrong_magic_bytes_rejected() {
     let dir = TempDir::new().unwrap();
-    let path = vault_path(&dir, "bad-magic");
-    let pw = b"magic-pw";
-
-    let mut store = VaultStore::create(&path, pw, "dev", None, None).unwrap();
-    store.save().unwrap();
+    let path = corrupted_vault(&dir, CorruptMode::Magic);
 
-    // Overwrite magic bytes.
-    let mut data = fs::read(&path).unwrap();
-    data[0] = b'X';
-    data[1] = b'Y';
-    fs::write(&path, &data).unwrap();
-
-    let result = VaultStore::open(&path, pw, None);
+    let result = VaultStore::open(&path, b"testpass", None);
     assert!(result.is_err(), "wrong magic bytes must be rejected");
 }
 
 #[test]
 fn corrupted_hmac_detected() {
     let dir = TempDir::new().unwrap();
-    let path = vault_path(&dir, "bad-hmac");
-    let pw = b"hmac-pw";
-
-    let mut store = VaultStore::create(&path, pw, "dev", None, None).unwrap();
-    store.set_secret("KEY", "value").unwrap();
-    store.save().unwrap();
-
-    // Flip the last byte (part of the HMAC tag).
-    let mut data = fs::read(&path).unwrap();
-    let last = data.len() - 1;
-    data[last] ^= 0xFF;
-    fs::write(&path, &data).unwrap();
+    let path = corrupted_vault(&dir, CorruptMode::Hmac);
 
-    let result = VaultStore::open(&path, pw, None);
+    let result = VaultStore::open(&path, b"testpass", None);
     assert!(result.is_err(), "corrupted HMAC must be rejected");
 }
 
 #[test]
 fn corrupted_header_json_detected() {
     let dir = TempDir::new().unwrap();
-    let path = vault_path(&dir, "bad-header");
-    let pw = b"header-pw";
+    let path = corrupted_vault(&dir, CorruptMode::Header);
 
-    let mut store = VaultStore::create(&path, pw, "dev", None, None).unwrap();
-    store.save().unwrap();
-
-    // Flip a byte in the header JSON region (byte 10 = inside the header JSON).
-    let mut data = fs::read(&path).unwrap();
-    if data.len() > 15 {
-        data[12] ^= 0xFF;
-        fs::write(&path, &data).unwrap();
-
-        let result = VaultStore::open(&path, pw, None);
-        assert!(result.is_err(), "corrupted header must be rejected");
-    }
+    let result = VaultStore::open(&path, b"testpass", None);
+    assert!(result.is_err(), "corrupted header must be rejected");
 }
 
 // ---------------------------------------------------------------------------
@@ -307,6 +275,20 @@ QUOTED_HASH="value # not a comment"
     assert_eq!(parsed.len(), 5);
 }
 
+#[test]
+fn edit_parse_crlf_buffer() {
+    use envvault::cli::commands::edit::parse_edited_content;
+
+    // What Notepad saves.
+    let content = "# EnvVault — edit secrets below\r\n\r\nDB_URL=postgres://localhost/db\r\nAPI_KEY=\"sk 123\"\r\nLAST=x";
+
+    let parsed = parse_edited_content(content);
+    assert_eq!(parsed["DB_URL"], "postgres://localhost/db");
+    assert_eq!(parsed["API_KEY"], "sk 123");
+    assert_eq!(parsed["LAST"], "x");
+    assert!(parsed.values().all(|v| !v.contains('\r')));
+}
+
 // ---------------------------------------------------------------------------
 // Audit log integration test
 // ---------------------------------------------------------------------------
@@ -134,17 +134,17 @@ fn import_skip_existing_preserves_original() {
 
 #[test]
 fn run_only_filter() {
-    use envvault::cli::commands::run::filter_secrets;
+    use envvault::cli::commands::run::select_secrets;
     use std::collections::HashMap;
 
-    let mut secrets = HashMap::from([
+    let secrets = HashMap::from([
         ("DB_URL".into(), "pg://localhost".into()),
         ("API_KEY".into(), "sk-test".into()),
         ("SECRET".into(), "shh".into()),
     ]);
 
     let only = vec!["DB_URL".to_string(), "API_KEY".to_string()];
-    filter_secrets(&mut secrets, Some(&only), None);
+    let secrets = select_secrets(secrets, Some(&only), None);
 
     assert_eq!(secrets.len(), 2);
     assert!(secrets.contains_key("DB_URL"));
@@ -154,17 +154,17 @@ fn run_only_filter() {
 
 #[test]
 fn run_exclude_filter() {
-    use envvault::cli::commands::run::filter_secrets;
+    use envvault::cli::commands::run::select_secrets;
     use std::collections::HashMap;
 
-    let mut secrets = HashMap::from([
+    let secrets = HashMap::from([
         ("DB_URL".into(), "pg://localhost".into()),
         ("API_KEY".into(), "sk-test".into()),
         ("SECRET".into(), "shh".into()),
     ]);
 
     let exclude = vec!["SECRET".to_string()];
-    filter_secrets(&mut secrets, None, Some(&exclude));
+    let secrets = select_secrets(secrets, None, Some(&exclude));
 
     assert_eq!(secrets.len(), 2);
     assert!(!secrets.contains_key("SECRET"));
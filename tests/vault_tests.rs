@@ -2,7 +2,7 @@
 
 use std::fs;
 
-use envvault::vault::VaultStore;
+use envvault::vault::{SecretEncoding, VaultStore};
 use tempfile::TempDir;
 
 /// Helper: create a temporary vault file path inside a fresh temp dir.
@@ -39,6 +39,69 @@ fn create_vault_and_reopen() {
     assert_eq!(value, "postgres://localhost/db");
 }
 
+#[test]
+fn chacha20_vault_roundtrip() {
+    use envvault::crypto::Cipher;
+
+    let (_dir, path) = vault_path();
+    let password = b"chacha-password";
+
+    let mut store = VaultStore::create_with_cipher(
+        &path,
+        password,
+        "dev",
+        None,
+        None,
+        Cipher::ChaCha20Poly1305,
+    )
+    .unwrap();
+    store.set_secret("DB_URL", "postgres://chacha").unwrap();
+    store.rename_secret("DB_URL", "DATABASE_URL").unwrap();
+    store.save().unwrap();
+
+    // The cipher comes from the header; open needs no hint.
+    let reopened = VaultStore::open(&path, password, None).unwrap();
+    assert_eq!(reopened.cipher(), Cipher::ChaCha20Poly1305);
+    assert_eq!(
+        reopened.header().cipher.as_deref(),
+        Some("chacha20-poly1305")
+    );
+    assert_eq!(
+        reopened.get_secret("DATABASE_URL").unwrap(),
+        "postgres://chacha"
+    );
+}
+
+#[test]
+fn new_vaults_record_aes_cipher() {
+    use envvault::crypto::Cipher;
+
+    let (_dir, path) = vault_path();
+    let store = VaultStore::create(&path, b"aes-password", "dev", None, None).unwrap();
+    assert_eq!(store.cipher(), Cipher::Aes256Gcm);
+    assert_eq!(store.header().cipher.as_deref(), Some("aes-256-gcm"));
+}
+
+#[test]
+fn unknown_cipher_in_header_is_rejected() {
+    let (_dir, path) = vault_path();
+    VaultStore::create(&path, b"cipher-password", "dev", None, None).unwrap();
+
+    // Rename the cipher in place; parsing fails before the HMAC check.
+    let mut data = fs::read(&path).unwrap();
+    let at = data
+        .windows(11)
+        .position(|w| w == b"aes-256-gcm")
+        .expect("cipher in header");
+    data[at..at + 11].copy_from_slice(b"aes-128-xyz");
+    fs::write(&path, &data).unwrap();
+
+    let err = VaultStore::open(&path, b"cipher-password", None)
+        .err()
+        .expect("open must fail");
+    assert!(err.to_string().contains("unknown cipher"), "{err}");
+}
+
 // ---------------------------------------------------------------------------
 // Set and get secret round-trip
 // ---------------------------------------------------------------------------
@@ -109,6 +172,203 @@ fn list_secrets_returns_sorted_metadata() {
     assert_eq!(list[2].name, "ZEBRA");
 }
 
+// ---------------------------------------------------------------------------
+// Read-only open refuses writes
+// ---------------------------------------------------------------------------
+
+#[test]
+fn open_readonly_refuses_all_writes() {
+    let (_dir, path) = vault_path();
+    let password = b"readonly-pw";
+
+    let mut store = VaultStore::create(&path, password, "dev", None, None).unwrap();
+    store.set_secret("KEY", "value").unwrap();
+    store.save().unwrap();
+    let before = fs::read(&path).unwrap();
+
+    let mut ro = VaultStore::open_readonly(&path, password, None).unwrap();
+    assert!(ro.is_readonly());
+    assert_eq!(ro.get_secret("KEY").unwrap(), "value");
+
+    for err in [
+        ro.set_secret("KEY", "changed").unwrap_err(),
+        ro.set_secret("NEW", "value").unwrap_err(),
+        ro.delete_secret("KEY").unwrap_err(),
+        ro.rename_secret("KEY", "RENAMED").unwrap_err(),
+        ro.save().unwrap_err(),
+    ] {
+        assert!(err.to_string().contains("read-only"), "{err}");
+    }
+
+    // Nothing changed in memory or on disk.
+    assert_eq!(ro.get_secret("KEY").unwrap(), "value");
+    assert_eq!(ro.secret_count(), 1);
+    assert_eq!(fs::read(&path).unwrap(), before);
+}
+
+#[test]
+fn encoding_hint_round_trips_and_survives_updates() {
+    let (_dir, path) = vault_path();
+    let password = b"encoding-pw";
+
+    let mut store = VaultStore::create(&path, password, "dev", None, None).unwrap();
+    store.set_secret("CERT", "aGk=").unwrap();
+    store.set_secret("PLAIN", "x").unwrap();
+    store
+        .set_encoding("CERT", Some(SecretEncoding::Base64))
+        .unwrap();
+    assert!(store.set_encoding("MISSING", None).is_err());
+    store.save().unwrap();
+
+    let mut store = VaultStore::open(&path, password, None).unwrap();
+    assert_eq!(store.secret_encoding("CERT"), Some("base64"));
+    assert_eq!(store.secret_encoding("PLAIN"), None);
+    // The value itself is stored as given.
+    assert_eq!(store.get_secret("CERT").unwrap(), "aGk=");
+
+    // Updating the value keeps the hint; clearing it removes it.
+    store.set_secret("CERT", "aGV5").unwrap();
+    assert_eq!(store.secret_encoding("CERT"), Some("base64"));
+    store.set_encoding("CERT", None).unwrap();
+    assert_eq!(store.secret_encoding("CERT"), None);
+}
+
+#[test]
+fn description_and_tags_survive_updates_and_rebuilds() {
+    let (dir, path) = vault_path();
+    let password = b"notes-pw";
+
+    let mut store = VaultStore::create(&path, password, "dev", None, None).unwrap();
+    store.set_secret("STRIPE_KEY", "sk_1").unwrap();
+    store
+        .set_description("STRIPE_KEY", Some("Stripe live key".into()))
+        .unwrap();
+    store
+        .set_tags("STRIPE_KEY", &["payments", "prod", "payments"])
+        .unwrap();
+    assert!(store.set_tags("STRIPE_KEY", &["two words"]).is_err());
+    assert!(store.set_description("MISSING", None).is_err());
+    store.save().unwrap();
+
+    // A value update keeps both.
+    let mut store = VaultStore::open(&path, password, None).unwrap();
+    store.set_secret("STRIPE_KEY", "sk_2").unwrap();
+    let meta = store.list_secrets().remove(0);
+    assert_eq!(meta.description.as_deref(), Some("Stripe live key"));
+    assert_eq!(meta.tags, ["payments", "prod"]);
+    assert!(meta.has_tag("prod"));
+
+    // So does rebuilding the vault under a new key, as rotate-key does.
+    let rotated_path = dir.path().join("rotated.vault");
+    let mut rotated =
+        VaultStore::create(&rotated_path, b"new-pw", "dev", Some(&cheap_params()), None).unwrap();
+    rotated
+        .set_secret_with_metadata(&meta, &store.get_secret("STRIPE_KEY").unwrap())
+        .unwrap();
+    rotated.save().unwrap();
+    let reopened = VaultStore::open(&rotated_path, b"new-pw", None).unwrap();
+    let meta = reopened.list_secrets().remove(0);
+    assert_eq!(meta.description.as_deref(), Some("Stripe live key"));
+    assert_eq!(meta.tags, ["payments", "prod"]);
+}
+
+#[test]
+fn declared_order_puts_positions_first_then_insertion_order() {
+    let (_dir, path) = vault_path();
+    let password = b"order-pw";
+
+    let mut store = VaultStore::create(&path, password, "dev", None, None).unwrap();
+    for name in ["ZETA", "ALPHA", "MIDDLE", "BETA"] {
+        store.set_secret(name, "x").unwrap();
+        // Distinct creation times, so insertion order is unambiguous.
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+    assert_eq!(store.declared_order(), ["ZETA", "ALPHA", "MIDDLE", "BETA"]);
+
+    store.set_order("BETA", Some(1)).unwrap();
+    store.set_order("MIDDLE", Some(2)).unwrap();
+    assert!(store.set_order("MISSING", Some(3)).is_err());
+    store.save().unwrap();
+
+    // Positions survive reopening and value updates.
+    let mut store = VaultStore::open(&path, password, None).unwrap();
+    store.set_secret("BETA", "y").unwrap();
+    assert_eq!(store.declared_order(), ["BETA", "MIDDLE", "ZETA", "ALPHA"]);
+
+    store.set_order("BETA", None).unwrap();
+    assert_eq!(store.declared_order(), ["MIDDLE", "ZETA", "ALPHA", "BETA"]);
+}
+
+#[test]
+fn access_counts_are_opt_in_and_persist_on_save() {
+    let (_dir, path) = vault_path();
+    let password = b"access-pw";
+
+    let mut store = VaultStore::create(&path, password, "dev", None, None).unwrap();
+    store.set_secret("USED", "1").unwrap();
+    store.set_secret("IDLE", "2").unwrap();
+    store.save().unwrap();
+
+    // Reads are not counted unless tracking is on.
+    let store = VaultStore::open(&path, password, None).unwrap();
+    store.get_secret("USED").unwrap();
+    assert!(!store.has_pending_access());
+
+    let mut store = VaultStore::open(&path, password, None).unwrap();
+    store.set_track_access(true);
+    store.get_secret("USED").unwrap();
+    store.get_all_secrets().unwrap();
+    assert!(store.has_pending_access());
+    store.save().unwrap();
+    assert!(!store.has_pending_access());
+
+    let store = VaultStore::open(&path, password, None).unwrap();
+    let meta = store.list_secrets();
+    let used = meta.iter().find(|m| m.name == "USED").unwrap();
+    let idle = meta.iter().find(|m| m.name == "IDLE").unwrap();
+    assert_eq!(used.access_count, Some(2));
+    assert!(used.last_accessed.is_some());
+    assert_eq!(idle.access_count, Some(1));
+
+    // Read-only stores never track.
+    let mut store = VaultStore::open_readonly(&path, password, None).unwrap();
+    store.set_track_access(true);
+    store.get_secret("USED").unwrap();
+    assert!(!store.has_pending_access());
+}
+
+#[test]
+fn clock_skew_and_future_timestamps_use_injected_now() {
+    use chrono::{Duration, Utc};
+
+    let (_dir, path) = vault_path();
+    let password = b"clock-pw";
+    let mut store = VaultStore::create(&path, password, "dev", None, None).unwrap();
+    store.set_secret("A", "1").unwrap();
+    store.set_secret("B", "2").unwrap();
+
+    let tolerance = Duration::minutes(5);
+    let now = Utc::now();
+    assert_eq!(store.clock_skew(now, tolerance), None);
+    assert!(store.future_dated(now, tolerance).is_empty());
+
+    // Seen from a clock a day behind, everything is in the future.
+    let behind = now - Duration::days(1);
+    let skew = store.clock_skew(behind, tolerance).unwrap();
+    assert!(skew > Duration::hours(23), "{skew}");
+    assert_eq!(store.future_dated(behind, tolerance), ["A", "B"]);
+
+    // Clamping fixes the header and both timestamps of each secret.
+    assert_eq!(store.clamp_future_timestamps(behind, tolerance).unwrap(), 5);
+    assert_eq!(store.latest_timestamp(), behind);
+    assert!(store.future_dated(behind, tolerance).is_empty());
+    store.save().unwrap();
+
+    let store = VaultStore::open(&path, password, None).unwrap();
+    assert_eq!(store.created_at(), behind);
+    assert_eq!(store.get_secret("A").unwrap(), "1");
+}
+
 // ---------------------------------------------------------------------------
 // Delete secret
 // ---------------------------------------------------------------------------
@@ -137,6 +397,92 @@ fn delete_secret_removes_it() {
     assert_eq!(store.get_secret("TO_KEEP").unwrap(), "stay");
 }
 
+#[test]
+fn rename_secret_re_encrypts_under_new_name() {
+    let (_dir, path) = vault_path();
+    let password = b"rename-pw";
+
+    let mut store = VaultStore::create(&path, password, "dev", None, None).unwrap();
+    store
+        .set_secret("DB_URL", "postgres://localhost/db")
+        .unwrap();
+    store.set_secret("OTHER", "x").unwrap();
+    let before = store.list_secrets();
+    let created_before = before
+        .iter()
+        .find(|m| m.name == "DB_URL")
+        .unwrap()
+        .created_at;
+
+    store.rename_secret("DB_URL", "DATABASE_URL").unwrap();
+    store.save().unwrap();
+
+    // The value decrypts under the new name after a reopen.
+    let store = VaultStore::open(&path, password, None).unwrap();
+    assert_eq!(
+        store.get_secret("DATABASE_URL").unwrap(),
+        "postgres://localhost/db"
+    );
+    assert!(matches!(
+        store.get_secret("DB_URL"),
+        Err(envvault::errors::EnvVaultError::SecretNotFound(_))
+    ));
+
+    let meta = store.list_secrets();
+    let renamed = meta.iter().find(|m| m.name == "DATABASE_URL").unwrap();
+    assert_eq!(renamed.created_at, created_before);
+    assert!(renamed.updated_at >= created_before);
+}
+
+#[test]
+fn rename_secret_rejects_missing_colliding_and_invalid_names() {
+    let (_dir, path) = vault_path();
+    let mut store = VaultStore::create(&path, b"rename-pw", "dev", None, None).unwrap();
+    store.set_secret("A", "1").unwrap();
+    store.set_secret("B", "2").unwrap();
+
+    assert!(matches!(
+        store.rename_secret("MISSING", "C"),
+        Err(envvault::errors::EnvVaultError::SecretNotFound(_))
+    ));
+    assert!(matches!(
+        store.rename_secret("A", "B"),
+        Err(envvault::errors::EnvVaultError::SecretAlreadyExists(_))
+    ));
+    assert!(store.rename_secret("A", "BAD NAME").is_err());
+
+    // Nothing changed.
+    assert_eq!(store.get_secret("A").unwrap(), "1");
+    assert_eq!(store.get_secret("B").unwrap(), "2");
+}
+
+#[test]
+fn rename_secret_replacing_overwrites_the_target() {
+    let (_dir, path) = vault_path();
+    let mut store = VaultStore::create(&path, b"rename-pw", "dev", None, None).unwrap();
+    store.set_secret("DB_URL", "postgres://new").unwrap();
+    store.set_secret("DATABASE_URL", "postgres://old").unwrap();
+    let created = store
+        .list_secrets()
+        .into_iter()
+        .find(|m| m.name == "DB_URL")
+        .unwrap()
+        .created_at;
+
+    assert!(store
+        .rename_secret_replacing("DB_URL", "DATABASE_URL")
+        .unwrap());
+    assert!(!store.contains_key("DB_URL"));
+    assert_eq!(store.get_secret("DATABASE_URL").unwrap(), "postgres://new");
+    let meta = store.list_secrets();
+    assert_eq!(meta.len(), 1);
+    assert_eq!(meta[0].created_at, created);
+
+    // A free name is not a replacement; the name itself still is not free.
+    assert!(!store.rename_secret_replacing("DATABASE_URL", "DB").unwrap());
+    assert!(store.rename_secret_replacing("DB", "DB").is_err());
+}
+
 // ---------------------------------------------------------------------------
 // Get all secrets (for `run` command)
 // ---------------------------------------------------------------------------
@@ -199,6 +545,73 @@ fn tampered_file_detected() {
     assert!(result.is_err(), "tampered vault must be rejected");
 }
 
+#[test]
+fn swapped_ciphertexts_are_detected_even_with_a_valid_hmac() {
+    use envvault::vault::format::{self, CURRENT_VERSION};
+
+    let (_dir, path) = vault_path();
+    let password = b"swap-pw";
+    let mut store =
+        VaultStore::create(&path, password, "dev", Some(&cheap_params()), None).unwrap();
+    assert_eq!(store.format_version(), CURRENT_VERSION);
+    store.set_secret("ADMIN_TOKEN", "root-token").unwrap();
+    store.set_secret("DEBUG_FLAG", "false").unwrap();
+    store.save().unwrap();
+
+    // Move ADMIN_TOKEN's ciphertext into DEBUG_FLAG and back.
+    let raw = format::read_vault(&path).unwrap();
+    let mut secrets = raw.secrets.clone();
+    let (a, b) = (
+        secrets[0].encrypted_value.clone(),
+        secrets[1].encrypted_value.clone(),
+    );
+    secrets[0].encrypted_value = b;
+    secrets[1].encrypted_value = a;
+
+    // Without the key the HMAC gives it away...
+    let original = fs::read(&path).unwrap();
+    let hmac_key = VaultStore::derive_master_key(&raw.header, password, None)
+        .unwrap()
+        .derive_hmac_key()
+        .unwrap();
+    format::write_vault(&path, &raw.header, &secrets, &[0u8; 32]).unwrap();
+    assert!(VaultStore::open(&path, password, None).is_err());
+
+    // ...and even re-signed, neither value decrypts under the other name.
+    format::write_vault(&path, &raw.header, &secrets, &hmac_key).unwrap();
+    assert_ne!(fs::read(&path).unwrap(), original);
+    let store = VaultStore::open(&path, password, None).unwrap();
+    assert!(store.get_secret("ADMIN_TOKEN").is_err());
+    assert!(store.get_secret("DEBUG_FLAG").is_err());
+}
+
+#[test]
+fn unauthenticated_secrets_are_not_parsed() {
+    let (_dir, path) = vault_path();
+    let mut store = VaultStore::create(&path, b"unparsed-pw", "dev", None, None).unwrap();
+    store.set_secret("KEY", "value").unwrap();
+    store.save().unwrap();
+
+    // Swap the secrets section for 1 MiB of non-JSON, keeping the header
+    // and the (now wrong) HMAC.
+    let data = fs::read(&path).unwrap();
+    let header_len = u32::from_le_bytes(data[5..9].try_into().unwrap()) as usize;
+    let header_end = 9 + header_len;
+    let mut crafted = data[..header_end].to_vec();
+    crafted.extend(std::iter::repeat(b'[').take(1024 * 1024));
+    crafted.extend_from_slice(&data[data.len() - 32..]);
+    fs::write(&path, &crafted).unwrap();
+
+    // Had the secrets been parsed first, this would be a JSON error.
+    let err = VaultStore::open(&path, b"unparsed-pw", None)
+        .err()
+        .expect("open must fail");
+    assert!(
+        matches!(err, envvault::errors::EnvVaultError::HmacMismatch),
+        "{err}"
+    );
+}
+
 // ---------------------------------------------------------------------------
 // Vault already exists error
 // ---------------------------------------------------------------------------
@@ -238,3 +651,217 @@ fn get_nonexistent_secret_fails() {
     let result = store.get_secret("DOES_NOT_EXIST");
     assert!(result.is_err());
 }
+
+// ---------------------------------------------------------------------------
+// Vaults written by other tools
+// ---------------------------------------------------------------------------
+
+/// Minimum Argon2 cost, so the test can re-derive the HMAC key cheaply.
+fn cheap_params() -> envvault::crypto::Argon2Params {
+    envvault::crypto::Argon2Params {
+        memory_kib: envvault::crypto::kdf::MIN_MEMORY_KIB,
+        iterations: 1,
+        parallelism: 1,
+    }
+}
+
+#[test]
+fn unpadded_base64_with_whitespace_opens_and_saves_canonically() {
+    use envvault::crypto::{derive_hmac_key, kdf::derive_master_key_with_len};
+    use envvault::vault::format::compute_hmac;
+
+    let (_dir, path) = vault_path();
+    let password = b"other-tool";
+    let params = cheap_params();
+    let mut store = VaultStore::create(&path, password, "dev", Some(&params), None).unwrap();
+    store.set_secret("DB_URL", "postgres://db").unwrap();
+    store.save().unwrap();
+    let salt = store.header().salt.clone();
+
+    // Rewrite every base64 field the way a sloppier writer might, then
+    // re-authenticate the file as that writer would.
+    let bytes = fs::read(&path).unwrap();
+    let header_len = u32::from_le_bytes(bytes[5..9].try_into().unwrap()) as usize;
+    let header_end = 9 + header_len;
+    let mut header: serde_json::Value = serde_json::from_slice(&bytes[9..header_end]).unwrap();
+    let mut secrets: serde_json::Value =
+        serde_json::from_slice(&bytes[header_end..bytes.len() - 32]).unwrap();
+
+    let canonical_salt = header["salt"].as_str().unwrap().to_string();
+    assert!(canonical_salt.ends_with('='));
+    let unpadded = canonical_salt.trim_end_matches('=');
+    header["salt"] = format!("{}\n{}", &unpadded[..16], &unpadded[16..]).into();
+    for secret in secrets.as_array_mut().unwrap() {
+        let value = secret["encrypted_value"].as_str().unwrap();
+        secret["encrypted_value"] = value.trim_end_matches('=').to_string().into();
+    }
+
+    let header_bytes = serde_json::to_vec(&header).unwrap();
+    let secrets_bytes = serde_json::to_vec(&secrets).unwrap();
+    let master = derive_master_key_with_len(password, &salt, &params, 32).unwrap();
+    let hmac = compute_hmac(
+        &derive_hmac_key(&master).unwrap(),
+        &header_bytes,
+        &secrets_bytes,
+    )
+    .unwrap();
+    let mut crafted = bytes[..5].to_vec();
+    crafted.extend_from_slice(&(header_bytes.len() as u32).to_le_bytes());
+    crafted.extend_from_slice(&header_bytes);
+    crafted.extend_from_slice(&secrets_bytes);
+    crafted.extend_from_slice(&hmac);
+    fs::write(&path, &crafted).unwrap();
+
+    // Read leniently...
+    let mut store = VaultStore::open(&path, password, None).expect("lenient open");
+    assert_eq!(store.get_secret("DB_URL").unwrap(), "postgres://db");
+    assert_eq!(store.header().salt, salt);
+
+    // ...write strictly.
+    store.save().unwrap();
+    let header = envvault::vault::format::read_header(&path).unwrap();
+    let rewritten = serde_json::to_value(&header).unwrap();
+    assert_eq!(rewritten["salt"], canonical_salt.as_str());
+    let reopened = VaultStore::open(&path, password, None).unwrap();
+    assert_eq!(reopened.get_secret("DB_URL").unwrap(), "postgres://db");
+}
+
+// ---------------------------------------------------------------------------
+// Expiry
+// ---------------------------------------------------------------------------
+
+#[test]
+fn expiry_is_kept_across_updates_and_reopens() {
+    let (_dir, path) = vault_path();
+    let password = b"expiry";
+    let mut store =
+        VaultStore::create(&path, password, "dev", Some(&cheap_params()), None).unwrap();
+
+    let now = chrono::Utc::now();
+    let past = now - chrono::Duration::days(1);
+    let future = now + chrono::Duration::days(30);
+    store
+        .set_secret_with_expiry("OLD_TOKEN", "a", Some(past))
+        .unwrap();
+    store
+        .set_secret_with_expiry("NEW_TOKEN", "b", Some(future))
+        .unwrap();
+    store.set_secret("PLAIN", "c").unwrap();
+    assert_eq!(store.expired(now), ["OLD_TOKEN"]);
+
+    // A plain update keeps the expiry; an explicit None clears it.
+    store.set_secret("NEW_TOKEN", "b2").unwrap();
+    assert_eq!(store.secret_expires_at("NEW_TOKEN"), Some(future));
+    store.save().unwrap();
+
+    let mut store = VaultStore::open(&path, password, None).unwrap();
+    assert_eq!(store.secret_expires_at("OLD_TOKEN"), Some(past));
+    assert_eq!(store.secret_expires_at("PLAIN"), None);
+    let meta = store.list_secrets();
+    assert!(meta
+        .iter()
+        .find(|m| m.name == "OLD_TOKEN")
+        .unwrap()
+        .is_expired(now));
+
+    store
+        .set_secret_with_expiry("OLD_TOKEN", "rotated", None)
+        .unwrap();
+    assert!(store.expired(now).is_empty());
+}
+
+#[test]
+fn rotation_is_due_once_the_key_is_old_enough() {
+    let (_dir, path) = vault_path();
+    let store = VaultStore::create(&path, b"rotate", "dev", Some(&cheap_params()), None).unwrap();
+
+    // Never rotated: the key dates from creation.
+    assert_eq!(store.key_set_at(), store.created_at());
+    let now = store.created_at();
+    assert_eq!(store.rotation_due(now, 90), None);
+    assert_eq!(
+        store.rotation_due(now + chrono::Duration::days(89), 90),
+        None
+    );
+    assert_eq!(
+        store.rotation_due(now + chrono::Duration::days(120), 90),
+        Some(120)
+    );
+    // 0 disables the check.
+    assert_eq!(
+        store.rotation_due(now + chrono::Duration::days(120), 0),
+        None
+    );
+}
+
+#[test]
+fn streamed_values_round_trip_and_survive_rename() {
+    let (_dir, path) = vault_path();
+    let password = b"stream";
+    let mut store =
+        VaultStore::create(&path, password, "dev", Some(&cheap_params()), None).unwrap();
+
+    // Larger than one chunk, and not UTF-8.
+    let blob: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+    let read = store
+        .set_secret_reader("BIG_BLOB", blob.as_slice())
+        .unwrap();
+    assert_eq!(read, blob.len() as u64);
+    store.set_secret_reader("SMALL", &b"hello"[..]).unwrap();
+    store.save().unwrap();
+
+    let raw = fs::read(&path).unwrap();
+    assert!(String::from_utf8_lossy(&raw).contains("\"chunk_size\""));
+
+    let mut store = VaultStore::open(&path, password, None).unwrap();
+    let mut out = Vec::new();
+    store.get_secret_writer("BIG_BLOB", &mut out).unwrap();
+    assert_eq!(out, blob);
+    // Streamed values are readable through the usual API too.
+    assert_eq!(store.get_secret("SMALL").unwrap(), "hello");
+
+    store.rename_secret("BIG_BLOB", "RENAMED_BLOB").unwrap();
+    let mut out = Vec::new();
+    store.get_secret_writer("RENAMED_BLOB", &mut out).unwrap();
+    assert_eq!(out, blob);
+
+    // One-piece values can be written out as well.
+    store.set_secret("PLAIN", "value").unwrap();
+    let mut out = Vec::new();
+    assert_eq!(store.get_secret_writer("PLAIN", &mut out).unwrap(), 5);
+    assert_eq!(out, b"value");
+}
+
+#[test]
+fn binary_values_are_named_in_text_reads_and_copied_as_bytes() {
+    let (dir, path) = vault_path();
+    let mut store =
+        VaultStore::create(&path, b"binary", "dev", Some(&cheap_params()), None).unwrap();
+    let blob: Vec<u8> = (0..100_000u32).map(|i| (i % 256) as u8).collect();
+    store.set_secret_reader("BLOB", blob.as_slice()).unwrap();
+    store.set_secret("TEXT", "plain").unwrap();
+
+    let err = store.get_all_secrets().unwrap_err().to_string();
+    assert!(err.contains("'BLOB'") && err.contains("binary"), "{err}");
+    assert_eq!(*store.get_secret_bytes("BLOB").unwrap(), blob);
+
+    let copy_path = dir.path().join("copy.vault");
+    let mut copy =
+        VaultStore::create(&copy_path, b"other", "dev", Some(&cheap_params()), None).unwrap();
+    for meta in store.list_secrets() {
+        copy.copy_secret_from(&store, &meta).unwrap();
+    }
+    copy.save().unwrap();
+
+    let copy = VaultStore::open(&copy_path, b"other", None).unwrap();
+    assert_eq!(*copy.get_secret_bytes("BLOB").unwrap(), blob);
+    assert_eq!(copy.get_secret("TEXT").unwrap(), "plain");
+    let chunked: Vec<_> = envvault::vault::format::read_vault(&copy_path)
+        .unwrap()
+        .secrets
+        .into_iter()
+        .filter(|s| s.chunk_size.is_some())
+        .map(|s| s.name)
+        .collect();
+    assert_eq!(chunked, ["BLOB"]);
+}